@@ -32,6 +32,9 @@ pub struct config {
     // The clang executable
     clang_path: Option<Path>,
 
+    // The rustdoc executable
+    rustdoc_path: Option<Path>,
+
     // The llvm binaries path
     llvm_bin_path: Option<Path>,
 
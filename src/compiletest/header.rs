@@ -28,6 +28,11 @@ pub struct TestProps {
     debugger_cmds: ~[~str],
     // Lines to check if they appear in the expected debugger output
     check_lines: ~[~str],
+    // Whether to run the test through rustdoc instead of rustc
+    check_rustdoc: bool,
+    // The kind of library to build this file as when it's an auxiliary
+    // crate (`lib`, `rlib` or `staticlib`), `lib` if unset
+    crate_type: Option<~str>,
 }
 
 // Load any test directives embedded in the file
@@ -39,6 +44,8 @@ pub fn load_props(testfile: &Path) -> TestProps {
     let mut pp_exact = None;
     let mut debugger_cmds = ~[];
     let mut check_lines = ~[];
+    let mut check_rustdoc = false;
+    let mut crate_type = None;
     iter_header(testfile, |ln| {
         match parse_error_pattern(ln) {
           Some(ep) => error_patterns.push(ep),
@@ -73,6 +80,14 @@ pub fn load_props(testfile: &Path) -> TestProps {
             None => ()
         };
 
+        if !check_rustdoc {
+            check_rustdoc = parse_name_directive(ln, "check-rustdoc");
+        }
+
+        if crate_type.is_none() {
+            crate_type = parse_crate_type(ln);
+        }
+
         true
     });
     return TestProps {
@@ -82,7 +97,9 @@ pub fn load_props(testfile: &Path) -> TestProps {
         aux_builds: aux_builds,
         exec_env: exec_env,
         debugger_cmds: debugger_cmds,
-        check_lines: check_lines
+        check_lines: check_lines,
+        check_rustdoc: check_rustdoc,
+        crate_type: crate_type,
     };
 }
 
@@ -130,6 +147,10 @@ fn parse_aux_build(line: &str) -> Option<~str> {
     parse_name_value_directive(line, ~"aux-build")
 }
 
+fn parse_crate_type(line: &str) -> Option<~str> {
+    parse_name_value_directive(line, ~"crate-type")
+}
+
 fn parse_compile_flags(line: &str) -> Option<~str> {
     parse_name_value_directive(line, ~"compile-flags")
 }
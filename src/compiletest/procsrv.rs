@@ -11,6 +11,7 @@
 use std::os;
 use std::run;
 use std::str;
+use std::io::process;
 use std::io::process::ProcessExit;
 
 #[cfg(target_os = "win32")]
@@ -49,18 +50,24 @@ pub fn run(lib_path: &str,
            input: Option<~str>) -> Result {
 
     let env = env + target_env(lib_path, prog);
-    let mut process = run::Process::new(prog, args, run::ProcessOptions {
-        env: Some(env),
-        dir: None,
-        in_fd: None,
-        out_fd: None,
-        err_fd: None
-    });
+    let io = [process::CreatePipe(true, false),
+              process::CreatePipe(false, true),
+              process::CreatePipe(false, true)];
+    let mut process = process::Process::new(process::ProcessConfig {
+        program: prog,
+        args: args,
+        env: Some(env.as_slice()),
+        cwd: None,
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    }).expect(format!("failed to exec `{}`", prog));
 
     for input in input.iter() {
-        process.input().write(input.as_bytes());
+        process.io[0].get_mut_ref().write(input.as_bytes());
     }
-    let output = process.finish_with_output();
+    let output = process.wait_with_output(None);
 
     Result {
         status: output.status,
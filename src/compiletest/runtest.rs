@@ -60,7 +60,11 @@ pub fn run_metrics(config: config, testfile: ~str, mm: &mut MetricMap) {
 }
 
 fn run_cfail_test(config: &config, props: &TestProps, testfile: &Path) {
-    let ProcRes = compile_test(config, props, testfile);
+    let ProcRes = if props.check_rustdoc {
+        document(config, props, testfile)
+    } else {
+        compile_test(config, props, testfile)
+    };
 
     if ProcRes.status.success() {
         fatal_ProcRes(~"compile-fail test compiled successfully!", &ProcRes);
@@ -647,6 +651,28 @@ fn compile_test_(config: &config, props: &TestProps,
     compose_and_run_compiler(config, props, testfile, args, None)
 }
 
+// Runs the test through rustdoc, which reports the compiler's errors on its own
+// behalf, instead of compiling it.
+fn document(config: &config, props: &TestProps, testfile: &Path) -> ProcRes {
+    let rustdoc = match config.rustdoc_path {
+        Some(ref rustdoc) => rustdoc.clone(),
+        None => fatal(~"check-rustdoc test run without --rustdoc-path")
+    };
+    let out_dir = output_base_name(config, testfile).with_extension("doc");
+    ensure_dir(&out_dir);
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let mut args = ~[testfile.as_str().unwrap().to_owned(),
+                     ~"-o", out_dir.as_str().unwrap().to_owned(),
+                     ~"-L", config.build_base.as_str().unwrap().to_owned()];
+    args.push_all_move(split_maybe_args(&props.compile_flags));
+    let ProcArgs = ProcArgs {
+        prog: rustdoc.as_str().unwrap().to_owned(),
+        args: args
+    };
+    compose_and_run(config, testfile, ProcArgs, ~[],
+                    config.compile_lib_path, None)
+}
+
 fn exec_compiled_test(config: &config, props: &TestProps,
                       testfile: &Path) -> ProcRes {
 
@@ -688,8 +714,10 @@ fn compose_and_run_compiler(
 
     for rel_ab in props.aux_builds.iter() {
         let abs_ab = config.aux_base.join(rel_ab.as_slice());
+        let aux_props = load_props(&abs_ab);
+        let crate_type = ~"--" + aux_props.crate_type.unwrap_or(~"lib");
         let aux_args =
-            make_compile_args(config, props, ~[~"--lib"] + extra_link_args,
+            make_compile_args(config, props, ~[crate_type] + extra_link_args,
                               |a,b| make_lib_name(a, b, testfile), &abs_ab);
         let auxres = compose_and_run(config, &abs_ab, aux_args, ~[],
                                      config.compile_lib_path, None);
@@ -0,0 +1,48 @@
+# Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# Runs one of the Makefile-driven tests in src/test/run-make:
+#
+#     maketest.py <test dir> <rustc> <scratch dir> <cc> <lib dir>
+#
+# The test's Makefile is run with RUSTC, CC and TMPDIR set, and with the
+# compiler's libraries on the dynamic library search path so that both rustc
+# and the programs it builds can find them.
+
+import os
+import subprocess
+import sys
+
+def putenv(name, value):
+    if os.name == 'nt':
+        value = value.replace('\\', '/')
+    os.putenv(name, value)
+
+(test_dir, rustc, tmpdir, cc, lib_dir) = sys.argv[1:6]
+
+putenv('RUSTC', os.path.abspath(rustc))
+putenv('TMPDIR', os.path.abspath(tmpdir))
+putenv('CC', cc)
+lib_dir = os.path.abspath(lib_dir)
+for var in ['LD_LIBRARY_PATH', 'DYLD_LIBRARY_PATH']:
+    putenv(var, os.pathsep.join(filter(None, [lib_dir, os.getenv(var)])))
+
+proc = subprocess.Popen(['make', '-C', test_dir],
+                        stdout=subprocess.PIPE,
+                        stderr=subprocess.PIPE)
+out, err = proc.communicate()
+
+if proc.returncode != 0:
+    print 'run-make test failed: ' + test_dir
+    print '---- stdout ----'
+    print out
+    print '---- stderr ----'
+    print err
+    sys.exit(1)
@@ -721,11 +721,12 @@ mod tests {
     fn test_rw_arc_poison_wr() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.write(|one| {
                 assert_eq!(*one, 2);
             })
         };
+        assert!(result.is_err());
         arc.read(|one| {
             assert_eq!(*one, 1);
         })
@@ -735,11 +736,12 @@ mod tests {
     fn test_rw_arc_poison_ww() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.write(|one| {
                 assert_eq!(*one, 2);
             })
         };
+        assert!(result.is_err());
         arc.write(|one| {
             assert_eq!(*one, 1);
         })
@@ -748,13 +750,14 @@ mod tests {
     fn test_rw_arc_poison_dw() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.write_downgrade(|mut write_mode| {
                 write_mode.write(|one| {
                     assert_eq!(*one, 2);
                 })
             })
         };
+        assert!(result.is_err());
         arc.write(|one| {
             assert_eq!(*one, 1);
         })
@@ -763,11 +766,12 @@ mod tests {
     fn test_rw_arc_no_poison_rr() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.read(|one| {
                 assert_eq!(*one, 2);
             })
         };
+        assert!(result.is_err());
         arc.read(|one| {
             assert_eq!(*one, 1);
         })
@@ -776,11 +780,12 @@ mod tests {
     fn test_rw_arc_no_poison_rw() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.read(|one| {
                 assert_eq!(*one, 2);
             })
         };
+        assert!(result.is_err());
         arc.write(|one| {
             assert_eq!(*one, 1);
         })
@@ -789,7 +794,7 @@ mod tests {
     fn test_rw_arc_no_poison_dr() {
         let arc = RWArc::new(1);
         let arc2 = arc.clone();
-        do task::try {
+        let result = do task::try {
             arc2.write_downgrade(|write_mode| {
                 let read_mode = arc2.downgrade(write_mode);
                 read_mode.read(|one| {
@@ -797,6 +802,7 @@ mod tests {
                 })
             })
         };
+        assert!(result.is_err());
         arc.write(|one| {
             assert_eq!(*one, 1);
         })
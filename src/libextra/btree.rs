@@ -108,14 +108,6 @@ enum Node<K, V>{
 //Node functions/methods
 impl<K: Clone + TotalOrd, V: Clone> Node<K, V>{
 
-    ///Differentiates between leaf and branch nodes.
-    fn is_leaf(&self) -> bool{
-        match self{
-            &LeafNode(*) => true,
-            &BranchNode(*) => false
-        }
-    }
-
     ///Creates a new leaf node given a vector of elements.
     fn new_leaf(vec: ~[LeafElt<K, V>]) -> Node<K,V>{
         LeafNode(Leaf::new(vec))
@@ -1983,6 +1983,7 @@ mod tests {
     }
     fn check_err<T: Decodable<Decoder>>(to_parse: &'static str, expected_error: &str) {
         use std::task;
+        use std::task::FailurePayload;
         let res = do task::try {
             // either fails in `decode` (which is what we want), or
             // returns Some(error_message)/None if the string was
@@ -2001,10 +2002,11 @@ mod tests {
             Ok(None) => fail!("`{}` parsed & decoded ok, expecting error `{}`",
                               to_parse, expected_error),
             Err(e) => {
-                let err = e.as_ref::<~str>().unwrap();
+                let payload = e.as_ref::<FailurePayload>().unwrap();
+                let err = payload.msg_str().unwrap();
                 assert!(err.contains(expected_error),
                         "`{}` errored incorrectly, found `{}` expecting `{}`",
-                        to_parse, *err, expected_error);
+                        to_parse, err, expected_error);
             }
         }
     }
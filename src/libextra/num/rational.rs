@@ -99,12 +99,6 @@ impl<T: Clone + Integer + Ord>
         }
     }
 
-    /// Return a `reduce`d copy of self.
-    fn reduced(&self) -> Ratio<T> {
-        let mut ret = self.clone();
-        ret.reduce();
-        ret
-    }
 }
 
 /* Comparisons */
@@ -711,7 +711,7 @@ impl<D:Decoder,T:Decodable<D>> Decodable<D> for RingBuf<T> {
 
 impl<
     E: Encoder,
-    K: Encodable<E> + Hash + IterBytes + Eq,
+    K: Encodable<E> + Hash + Eq,
     V: Encodable<E>
 > Encodable<E> for HashMap<K, V> {
     fn encode(&self, e: &mut E) {
@@ -728,7 +728,7 @@ impl<
 
 impl<
     D: Decoder,
-    K: Decodable<D> + Hash + IterBytes + Eq,
+    K: Decodable<D> + Hash + Eq,
     V: Decodable<D>
 > Decodable<D> for HashMap<K, V> {
     fn decode(d: &mut D) -> HashMap<K, V> {
@@ -746,7 +746,7 @@ impl<
 
 impl<
     S: Encoder,
-    T: Encodable<S> + Hash + IterBytes + Eq
+    T: Encodable<S> + Hash + Eq
 > Encodable<S> for HashSet<T> {
     fn encode(&self, s: &mut S) {
         s.emit_seq(self.len(), |s| {
@@ -761,7 +761,7 @@ impl<
 
 impl<
     D: Decoder,
-    T: Decodable<D> + Hash + IterBytes + Eq
+    T: Decodable<D> + Hash + Eq
 > Decodable<D> for HashSet<T> {
     fn decode(d: &mut D) -> HashSet<T> {
         d.read_seq(|d, len| {
@@ -885,9 +885,10 @@ mod tests {
     fn test_mutex_cond_no_waiter() {
         let m = Mutex::new();
         let m2 = m.clone();
-        do task::try {
+        let result = do task::try {
             m.lock_cond(|_x| { })
         };
+        assert!(result.is_ok());
         m2.lock_cond(|cond| {
             assert!(!cond.signal());
         })
@@ -16,7 +16,7 @@ use std::io::{Reader, Seek};
 use std::io::mem::BufReader;
 use std::cmp::Eq;
 use std::hashmap::HashMap;
-use std::to_bytes;
+use std::hash::{Hash, Hasher};
 use std::uint;
 
 #[deriving(Clone, Eq)]
@@ -336,16 +336,6 @@ fn split_char_first(s: &str, c: char) -> (~str, ~str) {
     }
 }
 
-fn userinfo_from_str(uinfo: &str) -> UserInfo {
-    let (user, p) = split_char_first(uinfo, ':');
-    let pass = if p.is_empty() {
-        None
-    } else {
-        Some(p)
-    };
-    return UserInfo::new(user, pass);
-}
-
 fn userinfo_to_str(userinfo: &UserInfo) -> ~str {
     match userinfo.pass {
         Some(ref pass) => format!("{}:{}@", userinfo.user, *pass),
@@ -734,9 +724,9 @@ impl ToStr for Url {
     }
 }
 
-impl IterBytes for Url {
-    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
-        self.to_str().iter_bytes(lsb0, f)
+impl Hash for Url {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_str().hash(state)
     }
 }
 
@@ -17,6 +17,7 @@ use lib::llvm::ModuleRef;
 use lib;
 use metadata::common::LinkMeta;
 use metadata::{encoder, cstore, filesearch};
+use metadata::loader::METADATA_FILENAME;
 use middle::trans::context::CrateContext;
 use middle::trans::common::gensym_name;
 use middle::ty;
@@ -24,14 +25,16 @@ use util::ppaux;
 
 use std::c_str::ToCStr;
 use std::char;
-use std::hash::Streaming;
-use std::hash;
+use std::hash::{Hasher, SipState};
 use std::os::consts::{macos, freebsd, linux, android, win32};
 use std::ptr;
 use std::run;
 use std::str;
 use std::vec;
+use std::io;
 use std::io::fs;
+use std::io::File;
+use extra::tempfile::TempDir;
 use syntax::abi;
 use syntax::ast;
 use syntax::ast_map::{path, path_mod, path_name, path_pretty_name};
@@ -208,18 +211,69 @@ pub mod write {
     use driver::session::Session;
     use driver::session;
     use lib::llvm::llvm;
-    use lib::llvm::{ModuleRef, ContextRef};
+    use lib::llvm::{ModuleRef, ContextRef, TargetMachineRef, MemoryBufferRef};
+    use lib::llvm::False;
     use lib;
+    use metadata::common::LinkMeta;
 
+    use extra::arc::Arc;
     use std::c_str::ToCStr;
+    use std::comm::oneshot;
+    use std::io::fs;
     use std::libc::{c_uint, c_int};
     use std::path::Path;
+    use std::ptr;
     use std::run;
     use std::str;
+    use std::task;
+    use std::task::FailurePayload;
+    use std::vec;
+
+    // Same as the stack rustc itself runs on, see `rustc::monitor`
+    static CODEGEN_STACK_SIZE: uint = 20000000; // 20MB
+
+    /// The parts of the session needed to optimize a module and write it
+    /// out, in a form that can be sent to another task.
+    struct CodegenOptions {
+        target_triple: ~str,
+        target_cpu: ~str,
+        target_feature: ~str,
+        opt_level: lib::llvm::CodeGenOptLevel,
+        use_softfp: bool,
+        verify: bool,
+        lint: bool,
+        prepopulate_passes: bool,
+        custom_passes: ~[~str],
+        save_temps: bool,
+    }
+
+    impl CodegenOptions {
+        fn new(sess: Session) -> CodegenOptions {
+            CodegenOptions {
+                target_triple: sess.targ_cfg.target_strs.target_triple.clone(),
+                target_cpu: sess.opts.target_cpu.clone(),
+                target_feature: sess.opts.target_feature.clone(),
+                opt_level: match sess.opts.optimize {
+                    session::No => lib::llvm::CodeGenLevelNone,
+                    session::Less => lib::llvm::CodeGenLevelLess,
+                    session::Default => lib::llvm::CodeGenLevelDefault,
+                    session::Aggressive => lib::llvm::CodeGenLevelAggressive,
+                },
+                use_softfp: sess.opts.debugging_opts & session::use_softfp != 0,
+                verify: !sess.no_verify(),
+                lint: sess.lint_llvm(),
+                prepopulate_passes: !sess.no_prepopulate_passes(),
+                custom_passes: sess.opts.custom_passes.clone(),
+                save_temps: sess.opts.save_temps,
+            }
+        }
+    }
 
     pub fn run_passes(sess: Session,
                       llcx: ContextRef,
                       llmod: ModuleRef,
+                      link_meta: LinkMeta,
+                      codegen_units: &[(~str, uint)],
                       output_type: output_type,
                       output: &Path) {
         unsafe {
@@ -255,66 +309,23 @@ pub mod write {
 
             configure_llvm(sess);
 
-            let OptLevel = match sess.opts.optimize {
-              session::No => lib::llvm::CodeGenLevelNone,
-              session::Less => lib::llvm::CodeGenLevelLess,
-              session::Default => lib::llvm::CodeGenLevelDefault,
-              session::Aggressive => lib::llvm::CodeGenLevelAggressive,
-            };
-            let use_softfp = sess.opts.debugging_opts & session::use_softfp != 0;
-
-            let tm = sess.targ_cfg.target_strs.target_triple.with_c_str(|T| {
-                sess.opts.target_cpu.with_c_str(|CPU| {
-                    sess.opts.target_feature.with_c_str(|Features| {
-                        llvm::LLVMRustCreateTargetMachine(
-                            T, CPU, Features,
-                            lib::llvm::CodeModelDefault,
-                            lib::llvm::RelocPIC,
-                            OptLevel,
-                            true,
-                            use_softfp
-                        )
-                    })
-                })
-            });
+            let opts = CodegenOptions::new(sess);
 
-            // Create the two optimizing pass managers. These mirror what clang
-            // does, and are by populated by LLVM's default PassManagerBuilder.
-            // Each manager has a different set of passes, but they also share
-            // some common passes.
-            let fpm = llvm::LLVMCreateFunctionPassManagerForModule(llmod);
-            let mpm = llvm::LLVMCreatePassManager();
-
-            // If we're verifying or linting, add them to the function pass
-            // manager.
-            let addpass = |pass: &str| {
-                pass.with_c_str(|s| llvm::LLVMRustAddPass(fpm, s))
-            };
-            if !sess.no_verify() { assert!(addpass("verify")); }
-            if sess.lint_llvm()  { assert!(addpass("lint"));   }
-
-            if !sess.no_prepopulate_passes() {
-                llvm::LLVMRustAddAnalysisPasses(tm, fpm, llmod);
-                llvm::LLVMRustAddAnalysisPasses(tm, mpm, llmod);
-                populate_llvm_passes(fpm, mpm, llmod, OptLevel);
+            // Splitting the module only pays off when producing object code,
+            // which is where LLVM spends most of its time.
+            if sess.opts.codegen_units > 1 && !sess.opts.jit &&
+                (output_type == output_type_object || output_type == output_type_exe) {
+                run_passes_partitioned(sess, opts, llcx, llmod, link_meta,
+                                       codegen_units, output);
+                if sess.time_llvm_passes() { llvm::LLVMRustPrintPassTimings(); }
+                return;
             }
 
-            for pass in sess.opts.custom_passes.iter() {
-                pass.with_c_str(|s| {
-                    if !llvm::LLVMRustAddPass(mpm, s) {
-                        sess.warn(format!("Unknown pass {}, ignoring", *pass));
-                    }
-                })
+            let tm = create_target_machine(&opts);
+            for pass in optimize(&opts, tm, llmod).iter() {
+                sess.warn(format!("Unknown pass {}, ignoring", *pass));
             }
 
-            // Finally, run the actual optimization passes
-            llvm::LLVMRustRunFunctionPassManager(fpm, llmod);
-            llvm::LLVMRunPassManager(mpm, llmod);
-
-            // Deallocate managers that we're now done with
-            llvm::LLVMDisposePassManager(fpm);
-            llvm::LLVMDisposePassManager(mpm);
-
             if sess.opts.save_temps {
                 output.with_extension("bc").with_c_str(|buf| {
                     llvm::LLVMWriteBitcodeToFile(llmod, buf);
@@ -367,6 +378,243 @@ pub mod write {
         }
     }
 
+    unsafe fn create_target_machine(opts: &CodegenOptions) -> TargetMachineRef {
+        opts.target_triple.with_c_str(|T| {
+            opts.target_cpu.with_c_str(|CPU| {
+                opts.target_feature.with_c_str(|Features| {
+                    llvm::LLVMRustCreateTargetMachine(
+                        T, CPU, Features,
+                        lib::llvm::CodeModelDefault,
+                        lib::llvm::RelocPIC,
+                        opts.opt_level,
+                        true,
+                        opts.use_softfp
+                    )
+                })
+            })
+        })
+    }
+
+    // Runs the optimization passes over the module, returning the names of
+    // any custom passes LLVM didn't recognize.
+    unsafe fn optimize(opts: &CodegenOptions, tm: TargetMachineRef,
+                       llmod: ModuleRef) -> ~[~str] {
+        // Create the two optimizing pass managers. These mirror what clang
+        // does, and are by populated by LLVM's default PassManagerBuilder.
+        // Each manager has a different set of passes, but they also share
+        // some common passes.
+        let fpm = llvm::LLVMCreateFunctionPassManagerForModule(llmod);
+        let mpm = llvm::LLVMCreatePassManager();
+
+        // If we're verifying or linting, add them to the function pass
+        // manager.
+        let addpass = |pass: &str| {
+            pass.with_c_str(|s| llvm::LLVMRustAddPass(fpm, s))
+        };
+        if opts.verify { assert!(addpass("verify")); }
+        if opts.lint   { assert!(addpass("lint"));   }
+
+        if opts.prepopulate_passes {
+            llvm::LLVMRustAddAnalysisPasses(tm, fpm, llmod);
+            llvm::LLVMRustAddAnalysisPasses(tm, mpm, llmod);
+            populate_llvm_passes(fpm, mpm, llmod, opts.opt_level);
+        }
+
+        let mut unknown = ~[];
+        for pass in opts.custom_passes.iter() {
+            pass.with_c_str(|s| {
+                if !llvm::LLVMRustAddPass(mpm, s) {
+                    unknown.push(pass.clone());
+                }
+            })
+        }
+
+        // Finally, run the actual optimization passes
+        llvm::LLVMRustRunFunctionPassManager(fpm, llmod);
+        llvm::LLVMRunPassManager(mpm, llmod);
+
+        // Deallocate managers that we're now done with
+        llvm::LLVMDisposePassManager(fpm);
+        llvm::LLVMDisposePassManager(mpm);
+
+        unknown
+    }
+
+    /**
+     * Optimize and emit the crate as `sess.opts.codegen_units` separate
+     * LLVM modules, each on its own thread, then combine the resulting
+     * objects into the single object file the linker expects.
+     *
+     * Translation assigns every function and static of the crate to a unit
+     * (see `trans::base::assign_codegen_unit`), and `LLVMRustSplitModule`
+     * cuts the crate's module into the units along those lines, handing
+     * each back as bitcode so that it can be read into a context of its
+     * own: contexts can't be shared between threads. Monomorphizations,
+     * glue and other internal helpers are copied into every unit that uses
+     * them, and `#[inline]` functions are available for inlining in all of
+     * them. Internal symbols which are still referenced across units are
+     * promoted to hidden external symbols, renamed after the crate so that
+     * they can't clash with those of any other crate.
+     */
+    unsafe fn run_passes_partitioned(sess: Session,
+                                     opts: CodegenOptions,
+                                     llcx: ContextRef,
+                                     llmod: ModuleRef,
+                                     link_meta: LinkMeta,
+                                     codegen_units: &[(~str, uint)],
+                                     output: &Path) {
+        let units = sess.opts.codegen_units;
+
+        let names = codegen_units.map(|&(ref name, _)| name.to_c_str());
+        let name_ptrs = names.map(|name| name.with_ref(|p| p));
+        let unit_of = codegen_units.map(|&(_, unit)| unit as c_uint);
+        let suffix = format!(".{}.{}", link_meta.name, link_meta.extras_hash);
+        let mut buffers = vec::from_elem(units, ptr::null());
+        suffix.with_c_str(|suffix| {
+            llvm::LLVMRustSplitModule(llmod, units as c_uint,
+                                      vec::raw::to_ptr(name_ptrs),
+                                      vec::raw::to_ptr(unit_of),
+                                      names.len() as c_uint,
+                                      suffix,
+                                      vec::raw::to_mut_ptr(buffers));
+        });
+        llvm::LLVMDisposeModule(llmod);
+        llvm::LLVMContextDispose(llcx);
+
+        let opts = Arc::new(opts);
+        let mut objects = ~[];
+        let mut pending = ~[];
+        for (index, &llbuf) in buffers.iter().enumerate() {
+            let object = output.with_extension(format!("{}.o", index));
+            objects.push(object.clone());
+
+            let opts = opts.clone();
+            let (port, chan) = oneshot();
+            let mut builder = task::task();
+            builder.sched_mode(task::SingleThreaded);
+            // LLVM needs about as much stack as the rest of rustc does
+            builder.opts.stack_size = Some(CODEGEN_STACK_SIZE);
+            // A unit which fails is reported as an error below, it mustn't
+            // take the compiler's own task down with it
+            builder.unwatched();
+            let result = builder.future_result();
+            builder.spawn(proc() {
+                chan.send(codegen_unit(opts.get(), llbuf, &object));
+            });
+            pending.push((port, result));
+        }
+
+        let mut failed = false;
+        for (index, (port, result)) in pending.move_iter().enumerate() {
+            let result = match result.recv() {
+                Ok(()) => port.recv(),
+                Err(cause) => {
+                    let msg = cause.as_ref::<FailurePayload>()
+                                   .and_then(|p| p.msg_str())
+                                   .unwrap_or("the codegen task failed");
+                    Err(msg.to_owned())
+                }
+            };
+            match result {
+                Ok(unknown_passes) => {
+                    // Every unit sees the same passes, only report them once
+                    if index == 0 {
+                        for pass in unknown_passes.iter() {
+                            sess.warn(format!("Unknown pass {}, ignoring", *pass));
+                        }
+                    }
+                }
+                Err(msg) => {
+                    sess.err(format!("codegen unit {} failed: {}", index, msg));
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            sess.abort_if_errors();
+        }
+
+        link_objects(sess, objects, output);
+
+        if !sess.opts.save_temps {
+            for object in objects.iter() {
+                fs::unlink(object);
+            }
+        }
+    }
+
+    // Optimizes and emits a single codegen unit, given as bitcode. This runs
+    // on its own thread and so can't touch the session.
+    fn codegen_unit(opts: &CodegenOptions, llbuf: MemoryBufferRef,
+                    output: &Path) -> Result<~[~str], ~str> {
+        unsafe {
+            let llcx = llvm::LLVMContextCreate();
+            let mut llmod = ptr::null();
+            let mut msg = ptr::null();
+            let failed = llvm::LLVMParseBitcodeInContext(llcx, llbuf,
+                                                         &mut llmod,
+                                                         &mut msg) != False;
+            llvm::LLVMDisposeMemoryBuffer(llbuf);
+            if failed {
+                llvm::LLVMContextDispose(llcx);
+                return Err(format!("couldn't read the unit's bitcode: {}",
+                                   str::raw::from_c_str(msg)));
+            }
+
+            let tm = create_target_machine(opts);
+            let unknown_passes = optimize(opts, tm, llmod);
+
+            if opts.save_temps {
+                output.with_extension("bc").with_c_str(|buf| {
+                    llvm::LLVMWriteBitcodeToFile(llmod, buf);
+                })
+            }
+
+            let cpm = llvm::LLVMCreatePassManager();
+            llvm::LLVMRustAddAnalysisPasses(tm, cpm, llmod);
+            llvm::LLVMRustAddLibraryInfo(cpm, llmod);
+            let written = output.with_c_str(|output| {
+                llvm::LLVMRustWriteOutputFile(tm, cpm, llmod, output,
+                                              lib::llvm::ObjectFile)
+            });
+            llvm::LLVMDisposePassManager(cpm);
+
+            llvm::LLVMRustDisposeTargetMachine(tm);
+            llvm::LLVMDisposeModule(llmod);
+            llvm::LLVMContextDispose(llcx);
+
+            if written {
+                Ok(unknown_passes)
+            } else {
+                Err(format!("couldn't write {}", output.display()))
+            }
+        }
+    }
+
+    // Combines the objects of all codegen units into one relocatable object.
+    fn link_objects(sess: Session, objects: &[Path], output: &Path) {
+        let cc_prog = super::get_cc_prog(sess);
+
+        // FIXME (#9639): This needs to handle non-utf8 paths
+        let mut cc_args = ~[
+            ~"-nostdlib",
+            ~"-r",
+            ~"-o", output.as_str().unwrap().to_owned()];
+        for object in objects.iter() {
+            cc_args.push(object.as_str().unwrap().to_owned());
+        }
+
+        let prog = run::process_output(cc_prog, cc_args);
+
+        if !prog.status.success() {
+            sess.err(format!("linking with `{}` failed: {}", cc_prog, prog.status));
+            sess.note(format!("{} arguments: {}",
+                        cc_prog, cc_args.connect(" ")));
+            sess.note(str::from_utf8(prog.error + prog.output));
+            sess.abort_if_errors();
+        }
+    }
+
     pub fn run_assembler(sess: Session, assembly: &Path, object: &Path) {
         let cc_prog = super::get_cc_prog(sess);
 
@@ -511,7 +759,7 @@ pub mod write {
 pub fn build_link_meta(sess: Session,
                        c: &ast::Crate,
                        output: &Path,
-                       symbol_hasher: &mut hash::State)
+                       symbol_hasher: &mut SipState)
                        -> LinkMeta {
     struct ProvidedMetas {
         name: Option<@str>,
@@ -546,7 +794,7 @@ pub fn build_link_meta(sess: Session,
     }
 
     // This calculates CMH as defined above
-    fn crate_meta_extras_hash(symbol_hasher: &mut hash::State,
+    fn crate_meta_extras_hash(symbol_hasher: &mut SipState,
                               cmh_items: ~[@ast::MetaItem],
                               dep_hashes: ~[@str],
                               pkg_id: Option<@str>) -> @str {
@@ -560,7 +808,7 @@ pub fn build_link_meta(sess: Session,
 
         let cmh_items = attr::sort_meta_items(cmh_items);
 
-        fn hash(symbol_hasher: &mut hash::State, m: &@ast::MetaItem) {
+        fn hash(symbol_hasher: &mut SipState, m: &@ast::MetaItem) {
             match m.node {
               ast::MetaNameValue(key, value) => {
                 write_string(symbol_hasher, len_and_str(key));
@@ -668,14 +916,14 @@ pub fn build_link_meta(sess: Session,
     }
 }
 
-pub fn truncated_hash_result(symbol_hasher: &mut hash::State) -> ~str {
-    symbol_hasher.result_str()
+pub fn truncated_hash_result(symbol_hasher: &mut SipState) -> ~str {
+    format!("{:016x}", symbol_hasher.finish())
 }
 
 
 // This calculates STH for a symbol, as defined above
 pub fn symbol_hash(tcx: ty::ctxt,
-                   symbol_hasher: &mut hash::State,
+                   symbol_hasher: &mut SipState,
                    t: ty::t,
                    link_meta: LinkMeta) -> @str {
     // NB: do *not* use abbrevs here as we want the symbol names
@@ -888,6 +1136,48 @@ pub fn output_dll_filename(os: abi::Os, lm: LinkMeta) -> ~str {
     format!("{}{}-{}-{}{}", dll_prefix, lm.name, lm.extras_hash, lm.vers, dll_suffix)
 }
 
+pub fn output_rlib_filename(lm: LinkMeta) -> ~str {
+    format!("lib{}-{}-{}.rlib", lm.name, lm.extras_hash, lm.vers)
+}
+
+pub fn output_staticlib_filename(lm: LinkMeta) -> ~str {
+    format!("lib{}.a", lm.name)
+}
+
+/// The path of the file `link_binary` produces for the crate being
+/// compiled, given the output filename requested on the command line.
+pub fn output_artifact_filename(sess: Session, out_filename: &Path,
+                                lm: LinkMeta) -> Path {
+    match *sess.output_crate_type {
+        session::rlib_crate => {
+            out_filename.with_filename(output_rlib_filename(lm))
+        }
+        session::staticlib_crate => {
+            out_filename.with_filename(output_staticlib_filename(lm))
+        }
+        _ if *sess.building_library => {
+            out_filename.with_filename(output_dll_filename(sess.targ_cfg.os, lm))
+        }
+        _ => out_filename.clone()
+    }
+}
+
+pub fn get_ar_prog(sess: Session) -> ~str {
+    match sess.targ_cfg.os {
+        abi::OsAndroid =>
+            match &sess.opts.android_cross_path {
+                &Some(ref path) => {
+                    format!("{}/bin/arm-linux-androideabi-ar", *path)
+                }
+                &None => {
+                    sess.fatal("need Android NDK path for building \
+                                static libraries (--android-cross-path)")
+                }
+            },
+        _ => ~"ar"
+    }
+}
+
 pub fn get_cc_prog(sess: Session) -> ~str {
     // In the future, FreeBSD will use clang as default compiler.
     // It would be flexible to use cc (system's default C compiler)
@@ -914,29 +1204,165 @@ pub fn get_cc_prog(sess: Session) -> ~str {
     }
 }
 
-// If the user wants an exe generated we need to invoke
-// cc to link the object file with some libs
+// Produces the final artifact for the crate: an executable or dylib via cc,
+// or an archive via ar for the static library flavors
 pub fn link_binary(sess: Session,
                    obj_filename: &Path,
                    out_filename: &Path,
-                   lm: LinkMeta) {
+                   lm: LinkMeta,
+                   metadata: &[u8]) {
+    match *sess.output_crate_type {
+        session::rlib_crate => {
+            link_rlib(sess, obj_filename, out_filename, lm, metadata)
+        }
+        session::staticlib_crate => {
+            link_staticlib(sess, obj_filename, out_filename, lm)
+        }
+        _ => link_natively(sess, obj_filename, out_filename, lm)
+    }
 
-    let cc_prog = get_cc_prog(sess);
-    // The invocations of cc share some flags across platforms
+    // Remove the temporary object file if we aren't saving temps
+    if !sess.opts.save_temps {
+        fs::unlink(obj_filename);
+    }
+}
 
-    let output = if *sess.building_library {
-        let long_libname = output_dll_filename(sess.targ_cfg.os, lm);
-        debug!("link_meta.name:  {}", lm.name);
-        debug!("long_libname: {}", long_libname);
-        debug!("out_filename: {}", out_filename.display());
-        let out_dirname = out_filename.dir_path();
-        debug!("dirname(out_filename): {}", out_dirname.display());
+// An rlib is the crate's object file plus its encoded metadata as a separate
+// member, so that the loader doesn't need to understand object files inside
+// archives. Linkers skip the metadata member since it defines no symbols.
+fn link_rlib(sess: Session,
+             obj_filename: &Path,
+             out_filename: &Path,
+             lm: LinkMeta,
+             metadata: &[u8]) {
+    let output = output_artifact_filename(sess, out_filename, lm);
+    debug!("output: {}", output.display());
+    check_writeable(sess, obj_filename, &output);
 
-        out_filename.with_filename(long_libname)
-    } else {
-        out_filename.clone()
+    let tmpdir = match TempDir::new("rustc") {
+        Some(tmpdir) => tmpdir,
+        None => sess.fatal("couldn't create a temporary directory")
     };
+    let metadata_file = tmpdir.path().join(METADATA_FILENAME);
+    File::create(&metadata_file).write(metadata);
 
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    run_ar(sess, "crs", &output, [obj_filename.as_str().unwrap().to_owned(),
+                                  metadata_file.as_str().unwrap().to_owned()],
+           None);
+}
+
+// A staticlib bundles the crate's object with the objects of every rlib it
+// depends on. Dynamic dependencies (which includes libstd, since it's only
+// built as a dylib), native libraries, and the runtime itself (librustrt and
+// libmorestack) can't be bundled, so the linker flags which pull them in are
+// printed for the user to pass to their linker along with the archive.
+fn link_staticlib(sess: Session,
+                  obj_filename: &Path,
+                  out_filename: &Path,
+                  lm: LinkMeta) {
+    let output = output_artifact_filename(sess, out_filename, lm);
+    debug!("output: {}", output.display());
+    check_writeable(sess, obj_filename, &output);
+
+    let tmpdir = match TempDir::new("rustc") {
+        Some(tmpdir) => tmpdir,
+        None => sess.fatal("couldn't create a temporary directory")
+    };
+
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let lib_path = sess.filesearch.get_target_lib_path();
+    let mut members = ~[obj_filename.as_str().unwrap().to_owned()];
+    let mut unbundled = ~[~"-L" + lib_path.as_str().unwrap()];
+    let crates = cstore::get_used_crate_files(sess.cstore);
+    for (i, cratepath) in crates.iter().enumerate() {
+        if cratepath.extension_str() != Some("rlib") {
+            let dir = cratepath.dirname_str().unwrap();
+            if !dir.is_empty() { unbundled.push("-L" + dir); }
+            let libarg = unlib(sess.targ_cfg,
+                               cratepath.filestem_str().unwrap().to_owned());
+            unbundled.push("-l" + libarg);
+            continue;
+        }
+
+        // Each rlib is extracted into its own directory so that members with
+        // the same name in different crates don't clobber each other.
+        let dir = tmpdir.path().join(format!("{}", i));
+        fs::mkdir(&dir, io::UserRWX);
+        run_ar(sess, "x", cratepath, [], Some(&dir));
+        for member in fs::readdir(&dir).iter() {
+            if member.filename_str() == Some(METADATA_FILENAME) { continue; }
+            members.push(member.as_str().unwrap().to_owned());
+        }
+    }
+    for l in cstore::get_used_libraries(sess.cstore).iter() {
+        unbundled.push(~"-l" + *l);
+    }
+    unbundled.push_all([~"-lmorestack", ~"-lrustrt"]);
+    // See link_args for why these are spelled out
+    match sess.targ_cfg.os {
+        abi::OsLinux => unbundled.push_all([~"-lrt", ~"-ldl", ~"-lm"]),
+        abi::OsAndroid => unbundled.push_all([~"-ldl", ~"-llog", ~"-lm"]),
+        abi::OsFreebsd => unbundled.push_all([~"-pthread", ~"-lrt"]),
+        _ => {}
+    }
+
+    run_ar(sess, "crs", &output, members, None);
+
+    sess.note(format!("link against {} with: {}",
+                      output.display(), unbundled.connect(" ")));
+}
+
+fn unlib(config: @session::config, stem: ~str) -> ~str {
+    if stem.starts_with("lib") &&
+        config.os != abi::OsWin32 {
+        stem.slice(3, stem.len()).to_owned()
+    } else {
+        stem
+    }
+}
+
+// Runs ar with the given operation on an archive, aborting on failure
+fn run_ar(sess: Session, op: &str, archive: &Path, members: &[~str],
+          cwd: Option<&Path>) {
+    let ar_prog = get_ar_prog(sess);
+
+    // ar only ever adds to an existing archive
+    if op.contains_char('c') && archive.exists() {
+        fs::unlink(archive);
+    }
+
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let mut args = ~[op.to_owned(), archive.as_str().unwrap().to_owned()];
+    args.push_all(members);
+    debug!("{} {}", ar_prog, args.connect(" "));
+
+    let mut prog = run::Process::new(ar_prog, args, run::ProcessOptions {
+        dir: cwd,
+        .. run::ProcessOptions::new()
+    });
+    let output = prog.finish_with_output();
+    if !output.status.success() {
+        sess.err(format!("`{}` failed: {}", ar_prog, output.status));
+        sess.note(format!("{} arguments: {}", ar_prog, args.connect(" ")));
+        sess.note(str::from_utf8(output.error + output.output));
+        sess.abort_if_errors();
+    }
+}
+
+// If the user wants an exe or dylib generated we need to invoke
+// cc to link the object file with some libs
+fn link_natively(sess: Session,
+                 obj_filename: &Path,
+                 out_filename: &Path,
+                 lm: LinkMeta) {
+
+    let cc_prog = get_cc_prog(sess);
+    // The invocations of cc share some flags across platforms
+
+    debug!("link_meta.name:  {}", lm.name);
+    debug!("out_filename: {}", out_filename.display());
+    let output = output_artifact_filename(sess, out_filename, lm);
     debug!("output: {}", output.display());
     let cc_args = link_args(sess, obj_filename, out_filename, lm);
     debug!("{} link args: {}", cc_prog, cc_args.connect(" "));
@@ -959,18 +1385,12 @@ pub fn link_binary(sess: Session,
     // symbols
     if sess.targ_cfg.os == abi::OsMacos && sess.opts.debuginfo {
         // FIXME (#9639): This needs to handle non-utf8 paths
-        run::process_status("dsymutil", [output.as_str().unwrap().to_owned()]);
-    }
-
-    // Remove the temporary object file if we aren't saving temps
-    if !sess.opts.save_temps {
-        fs::unlink(obj_filename);
+        let _ = run::process_status("dsymutil",
+                                    [output.as_str().unwrap().to_owned()]);
     }
 }
 
 fn is_writeable(p: &Path) -> bool {
-    use std::io;
-
     !p.exists() ||
         (match io::result(|| p.stat()) {
             Err(*) => false,
@@ -978,22 +1398,25 @@ fn is_writeable(p: &Path) -> bool {
         })
 }
 
+fn check_writeable(sess: Session, obj_filename: &Path, output: &Path) {
+    let obj_is_writeable = is_writeable(obj_filename);
+    let out_is_writeable = is_writeable(output);
+    if !out_is_writeable {
+        sess.fatal(format!("Output file {} is not writeable -- check its permissions.",
+                           output.display()));
+    }
+    else if !obj_is_writeable {
+        sess.fatal(format!("Object file {} is not writeable -- check its permissions.",
+                           obj_filename.display()));
+    }
+}
+
 pub fn link_args(sess: Session,
                  obj_filename: &Path,
                  out_filename: &Path,
                  lm:LinkMeta) -> ~[~str] {
 
     // Converts a library file-stem into a cc -l argument
-    fn unlib(config: @session::config, stem: ~str) -> ~str {
-        if stem.starts_with("lib") &&
-            config.os != abi::OsWin32 {
-            stem.slice(3, stem.len()).to_owned()
-        } else {
-            stem
-        }
-    }
-
-
     let output = if *sess.building_library {
         let long_libname = output_dll_filename(sess.targ_cfg.os, lm);
         out_filename.with_filename(long_libname)
@@ -1005,16 +1428,7 @@ pub fn link_args(sess: Session,
     // Mac, FreeBSD, and Windows system linkers check this already --
     // however, the Linux linker will happily overwrite a read-only file.
     // We should be consistent.
-    let obj_is_writeable = is_writeable(obj_filename);
-    let out_is_writeable = is_writeable(&output);
-    if !out_is_writeable {
-        sess.fatal(format!("Output file {} is not writeable -- check its permissions.",
-                           output.display()));
-    }
-    else if !obj_is_writeable {
-        sess.fatal(format!("Object file {} is not writeable -- check its permissions.",
-                           obj_filename.display()));
-    }
+    check_writeable(sess, obj_filename, &output);
 
     // The default library location, we need this to find the runtime.
     // The location of crates will be determined as needed.
@@ -0,0 +1,284 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+ * A persistent cache of compiler outputs, enabled with `--cache-dir`.
+ *
+ * Every output file gets its own entry in the cache directory. An entry
+ * holds a copy of the artifact produced by the last successful compilation
+ * and a fingerprint of everything that went into producing it:
+ *
+ *  - the source of every file loaded into the codemap, which covers the
+ *    crate's modules and anything pulled in with `include!` or
+ *    `include_str!`, and the contents of files pulled in with
+ *    `include_bin!`,
+ *  - the hashes of all crates linked against, the same hashes that feed
+ *    into the crate's `LinkMeta`,
+ *  - the native libraries linked against, the library search paths and the
+ *    linker arguments, along with the contents of any of those libraries
+ *    which can be found in the search paths,
+ *  - the compiler version, the target, and the options that affect code
+ *    generation.
+ *
+ * The fingerprint is computed after analysis. If it matches the one stored
+ * in the entry, the cached artifact is copied into place and translation,
+ * the LLVM passes and linking are skipped.
+ *
+ * FIXME: nothing finer-grained than a whole crate is reused yet, and native
+ * libraries which the linker finds in its default search paths only count
+ * by name.
+ */
+
+use back::link;
+use driver::driver::OutputFilenames;
+use driver::session::Session;
+use metadata::cstore;
+
+use extra::sort;
+use std::hash::{Hasher, SipState};
+use std::io;
+use std::io::File;
+use std::io::fs;
+use std::str;
+use syntax::abi;
+use syntax::parse::token;
+use syntax::print::pprust;
+
+static FINGERPRINT_FILENAME: &'static str = "fingerprint";
+static ARTIFACT_FILENAME: &'static str = "artifact";
+
+pub struct CacheEntry {
+    /// The directory holding this entry
+    dir: Path,
+    /// Fingerprint of the current compilation
+    fingerprint: ~str,
+}
+
+/// Find the cache entry for the outputs of the current compilation, or
+/// `None` if no cache directory was given or the outputs can't be cached.
+/// Must be called after analysis, once the crate's dependencies are known.
+pub fn find_entry(sess: Session,
+                  outputs: &OutputFilenames) -> Option<CacheEntry> {
+    let cache_dir = match sess.opts.cache_dir {
+        Some(ref dir) => dir,
+        None => return None
+    };
+    if sess.opts.jit || sess.opts.output_type == link::output_type_none {
+        return None;
+    }
+
+    // Entries are keyed on the requested output file, so that building the
+    // same crate to several places (or as several kinds of output) doesn't
+    // thrash a single entry.
+    let output = artifact_base(sess, outputs);
+    let mut state = SipState::new(0, 0);
+    hash_str(&mut state, output.display().to_str());
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let stem = output.filestem_str().unwrap_or("rust_out");
+    let dir = cache_dir.join(format!("{}-{:016x}", stem, state.finish()));
+
+    Some(CacheEntry {
+        dir: dir,
+        fingerprint: fingerprint(sess),
+    })
+}
+
+/// Copy the cached artifact into place if the entry was produced from the
+/// same inputs as the current compilation, returning where it was put.
+pub fn restore(sess: Session, entry: &CacheEntry,
+               outputs: &OutputFilenames) -> Option<Path> {
+    fn read(path: &Path) -> Option<~str> {
+        if !path.exists() {
+            return None;
+        }
+        match io::result(|| File::open(path).read_to_end()) {
+            Ok(bytes) => str::from_utf8_owned_opt(bytes),
+            Err(*) => None
+        }
+    }
+
+    if read(&entry.dir.join(FINGERPRINT_FILENAME)) != Some(entry.fingerprint.clone()) {
+        debug!("cache miss for {}", entry.dir.display());
+        return None;
+    }
+    let name = match read(&entry.dir.join(ARTIFACT_FILENAME)) {
+        Some(name) => name,
+        None => return None
+    };
+
+    let cached = entry.dir.join(name.as_slice());
+    let output = artifact_base(sess, outputs).with_filename(name);
+    debug!("cache hit, copying {} to {}", cached.display(), output.display());
+    match io::result(|| fs::copy(&cached, &output)) {
+        Ok(()) => Some(output),
+        Err(*) => None
+    }
+}
+
+/// Record the artifact just produced in the cache entry. Failing to do so
+/// only produces a warning; the compilation itself has succeeded.
+pub fn store(sess: Session, entry: &CacheEntry, artifact: &Path) {
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let name = artifact.filename_str().unwrap();
+
+    let result = io::result(|| {
+        if entry.dir.exists() {
+            fs::rmdir_recursive(&entry.dir);
+        }
+        fs::mkdir_recursive(&entry.dir, io::UserRWX);
+        fs::copy(artifact, &entry.dir.join(name));
+        File::create(&entry.dir.join(ARTIFACT_FILENAME)).write(name.as_bytes());
+        // The fingerprint goes last, so that an interrupted write leaves an
+        // entry that never matches.
+        File::create(&entry.dir.join(FINGERPRINT_FILENAME))
+            .write(entry.fingerprint.as_bytes());
+    });
+    match result {
+        Ok(()) => {}
+        Err(e) => {
+            sess.warn(format!("couldn't write {} to the compilation cache: {}",
+                              artifact.display(), e.desc));
+        }
+    }
+}
+
+// Executables and libraries are named after the requested output, other
+// kinds of output are written in place of the object file.
+fn artifact_base<'a>(sess: Session, outputs: &'a OutputFilenames) -> &'a Path {
+    if sess.opts.output_type == link::output_type_exe {
+        &outputs.out_filename
+    } else {
+        &outputs.obj_filename
+    }
+}
+
+fn hash_str<H: Hasher>(state: &mut H, s: &str) {
+    state.write(format!("{}_{}", s.len(), s).as_bytes());
+}
+
+fn hash_bytes<H: Hasher>(state: &mut H, bytes: &[u8]) {
+    state.write(format!("{}_", bytes.len()).as_bytes());
+    state.write(bytes);
+}
+
+// Native libraries are named by the crates (with `#[link_args]`) as well as on
+// the command line, and they can change without any Rust source changing.
+fn hash_native_libraries(sess: Session, state: &mut SipState) {
+    let cstore = sess.cstore;
+    let mut search_paths = sess.opts.addl_lib_search_paths.iter()
+                               .map(|p| p.clone()).collect::<~[Path]>();
+    // The search paths are kept in a set, put them into some fixed order
+    search_paths = sort::merge_sort(search_paths, |a, b| {
+        a.as_vec() <= b.as_vec()
+    });
+    for path in search_paths.iter() {
+        hash_str(state, path.display().to_str());
+    }
+
+    let mut libs = ~[];
+    for lib in cstore::get_used_libraries(cstore).iter() {
+        hash_str(state, *lib);
+        libs.push(lib.to_owned());
+    }
+    for arg in cstore::get_used_link_args(cstore).iter() {
+        hash_str(state, *arg);
+        for word in arg.split(' ') {
+            if word.starts_with("-l") && word.len() > 2 {
+                libs.push(word.slice_from(2).to_owned());
+            }
+        }
+    }
+    for arg in sess.opts.linker_args.iter() {
+        if arg.starts_with("-l") && arg.len() > 2 {
+            libs.push(arg.slice_from(2).to_owned());
+        }
+    }
+
+    // Whichever of the libraries the linker would find in the search paths
+    // are hashed by content, so that rebuilding one of them is noticed.
+    for lib in libs.iter() {
+        for dir in search_paths.iter() {
+            for name in native_library_names(sess, *lib).iter() {
+                let file = dir.join(name.as_slice());
+                if !file.exists() { continue; }
+                match io::result(|| File::open(&file).read_to_end()) {
+                    Ok(bytes) => {
+                        hash_str(state, file.display().to_str());
+                        hash_bytes(state, bytes);
+                    }
+                    Err(*) => {}
+                }
+            }
+        }
+    }
+}
+
+// The file names the linker looks for when asked for library `lib`
+fn native_library_names(sess: Session, lib: &str) -> ~[~str] {
+    match sess.targ_cfg.os {
+        abi::OsWin32 => ~[format!("{}.lib", lib), format!("lib{}.a", lib),
+                          format!("{}.dll", lib)],
+        abi::OsMacos => ~[format!("lib{}.a", lib), format!("lib{}.dylib", lib)],
+        _ => ~[format!("lib{}.a", lib), format!("lib{}.so", lib)],
+    }
+}
+
+fn fingerprint(sess: Session) -> ~str {
+    let mut state = SipState::new(0, 0);
+    let opts = sess.opts;
+
+    hash_str(&mut state, option_env!("CFG_VERSION").unwrap_or("unknown version"));
+    hash_str(&mut state, opts.target_triple);
+    hash_str(&mut state, opts.target_cpu);
+    hash_str(&mut state, opts.target_feature);
+    hash_str(&mut state, format!("{:?}", *sess.output_crate_type));
+    hash_str(&mut state, format!("{:?}", opts.output_type));
+    hash_str(&mut state, format!("{:?}", opts.optimize));
+    hash_str(&mut state, format!("{}", opts.is_static));
+    hash_str(&mut state, format!("{}", opts.gc));
+    hash_str(&mut state, format!("{}", opts.debuginfo));
+    hash_str(&mut state, format!("{}", opts.extra_debuginfo));
+    hash_str(&mut state, format!("{}", opts.test));
+    hash_str(&mut state, format!("{}", opts.debugging_opts));
+    hash_str(&mut state, format!("{}", opts.codegen_units));
+    for pass in opts.custom_passes.iter() {
+        hash_str(&mut state, *pass);
+    }
+    for arg in opts.llvm_args.iter() {
+        hash_str(&mut state, *arg);
+    }
+    for linker in opts.linker.iter() {
+        hash_str(&mut state, *linker);
+    }
+    for arg in opts.linker_args.iter() {
+        hash_str(&mut state, *arg);
+    }
+    for mi in opts.cfg.iter() {
+        hash_str(&mut state, pprust::meta_item_to_str(*mi,
+                                                      token::get_ident_interner()));
+    }
+
+    for dep_hash in cstore::get_dep_hashes(sess.cstore).iter() {
+        hash_str(&mut state, *dep_hash);
+    }
+
+    for fm in sess.codemap.files.iter() {
+        hash_str(&mut state, fm.name);
+        hash_str(&mut state, fm.src);
+    }
+    for &(name, contents) in sess.codemap.binary_files.iter() {
+        hash_str(&mut state, name);
+        hash_bytes(&mut state, contents);
+    }
+
+    hash_native_libraries(sess, &mut state);
+
+    format!("{:016x}", state.finish())
+}
@@ -11,6 +11,7 @@
 
 use back::link;
 use back::{arm, x86, x86_64, mips};
+use driver::cache;
 use driver::session::{Aggressive};
 use driver::session::{Session, Session_, No, Less, Default};
 use driver::session;
@@ -20,7 +21,7 @@ use lib::llvm::{ContextRef, ModuleRef};
 use metadata::common::LinkMeta;
 use metadata::{creader, cstore, filesearch};
 use metadata;
-use middle::{trans, freevars, kind, ty, typeck, lint, astencode, reachable};
+use middle::{trans, freevars, kind, ty, typeck, lint, astencode, reachable, dead};
 use middle;
 use util::common::time;
 use util::ppaux;
@@ -28,6 +29,7 @@ use util::ppaux;
 use std::hashmap::{HashMap,HashSet};
 use std::io;
 use std::io::fs;
+use std::io::File;
 use std::io::mem::MemReader;
 use std::os;
 use std::vec;
@@ -164,8 +166,15 @@ pub fn phase_2_configure_and_expand(sess: Session,
                                     mut crate: ast::Crate) -> ast::Crate {
     let time_passes = sess.time_passes();
 
-    *sess.building_library = session::building_library(sess.opts.crate_type,
-                                                       &crate, sess.opts.test);
+    let crate_type = match session::output_crate_type(sess.opts.crate_type,
+                                                      &crate, sess.opts.test) {
+        // -Z static used to mean "leave an object behind for static
+        // linking"; an rlib is the archive form of that.
+        session::lib_crate if sess.opts.is_static => session::rlib_crate,
+        t => t
+    };
+    *sess.output_crate_type = crate_type;
+    *sess.building_library = crate_type != session::bin_crate;
 
     time(time_passes, "gated feature checking", (), |_|
          front::feature_gate::check_crate(sess, &crate));
@@ -185,8 +194,11 @@ pub fn phase_2_configure_and_expand(sess: Session,
     crate = time(time_passes, "configuration 1", crate, |crate|
                  front::config::strip_unconfigured_items(crate));
 
+    let mut loader = creader::Loader::new(sess);
     crate = time(time_passes, "expansion", crate, |crate|
-                 syntax::ext::expand::expand_crate(sess.parse_sess, cfg.clone(),
+                 syntax::ext::expand::expand_crate(sess.parse_sess,
+                                                   &mut loader,
+                                                   cfg.clone(),
                                                    crate));
 
     // strip again, in case expansion added anything with a #[cfg].
@@ -309,6 +321,10 @@ pub fn phase_3_run_analysis_passes(sess: Session,
         time(time_passes, "reachability checking", (), |_|
              reachable::find_reachable(ty_cx, method_map, &exported_items));
 
+    time(time_passes, "death checking", (), |_|
+         dead::check_crate(ty_cx, method_map,
+                           &exported_items, reachable_map, crate));
+
     time(time_passes, "lint checking", (), |_|
          lint::check_crate(ty_cx, &exported_items, crate));
 
@@ -330,7 +346,12 @@ pub fn phase_3_run_analysis_passes(sess: Session,
 pub struct CrateTranslation {
     context: ContextRef,
     module: ModuleRef,
-    link: LinkMeta
+    link: LinkMeta,
+    // The encoded crate metadata, empty unless building a library
+    metadata: ~[u8],
+    // The codegen unit which each function and static defined by the crate
+    // belongs to, by symbol. Empty unless there is more than one unit.
+    codegen_units: ~[(~str, uint)]
 }
 
 /// Run the translation phase to LLVM, after which the AST and analysis can
@@ -364,6 +385,8 @@ pub fn phase_5_run_llvm_passes(sess: Session,
             link::write::run_passes(sess,
                                     trans.context,
                                     trans.module,
+                                    trans.link,
+                                    trans.codegen_units,
                                     output_type,
                                     &asm_filename));
 
@@ -378,6 +401,8 @@ pub fn phase_5_run_llvm_passes(sess: Session,
             link::write::run_passes(sess,
                                     trans.context,
                                     trans.module,
+                                    trans.link,
+                                    trans.codegen_units,
                                     sess.opts.output_type,
                                     &outputs.obj_filename));
     }
@@ -392,7 +417,8 @@ pub fn phase_6_link_output(sess: Session,
          link::link_binary(sess,
                            &outputs.obj_filename,
                            &outputs.out_filename,
-                           trans.link));
+                           trans.link,
+                           trans.metadata));
 }
 
 pub fn stop_after_phase_3(sess: Session) -> bool {
@@ -417,11 +443,6 @@ pub fn stop_after_phase_5(sess: Session) -> bool {
         return true;
     }
 
-    if sess.opts.is_static && *sess.building_library {
-        debug!("building static library, returning early from compile_input");
-        return true;
-    }
-
     if sess.opts.jit {
         debug!("running JIT, returning early from compile_input");
         return true;
@@ -434,7 +455,7 @@ pub fn compile_input(sess: Session, cfg: ast::CrateConfig, input: &input,
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
-    let (outputs, trans) = {
+    let (outputs, trans, cache_entry) = {
         let expanded_crate = {
             let crate = phase_1_parse_input(sess, cfg.clone(), input);
             if stop_after_phase_1(sess) { return; }
@@ -444,13 +465,88 @@ pub fn compile_input(sess: Session, cfg: ast::CrateConfig, input: &input,
         if stop_after_phase_3(sess) { return; }
         let outputs = build_output_filenames(input, outdir, output,
                                              expanded_crate.attrs, sess);
+        let cache_entry = cache::find_entry(sess, outputs);
+        match cache_entry {
+            Some(ref entry) => {
+                let restored = time(sess.time_passes(), "restoring cached output", (),
+                                    |_| cache::restore(sess, entry, outputs));
+                match restored {
+                    Some(artifact) => {
+                        write_out_deps(sess, outputs, &artifact);
+                        return;
+                    }
+                    None => ()
+                }
+            }
+            None => ()
+        }
         let trans = phase_4_translate_to_llvm(sess, expanded_crate,
                                               &analysis, outputs);
-        (outputs, trans)
+        (outputs, trans, cache_entry)
     };
     phase_5_run_llvm_passes(sess, &trans, outputs);
-    if stop_after_phase_5(sess) { return; }
-    phase_6_link_output(sess, &trans, outputs);
+    if sess.opts.jit { return; }
+    if !stop_after_phase_5(sess) {
+        phase_6_link_output(sess, &trans, outputs);
+    }
+
+    let artifact = output_artifact(sess, outputs, trans.link);
+    write_out_deps(sess, outputs, &artifact);
+    match cache_entry {
+        Some(ref entry) => cache::store(sess, entry, &artifact),
+        None => ()
+    }
+}
+
+/// The file a compilation leaves behind: the linked executable or library,
+/// or whatever was written in place of the object file when not linking.
+pub fn output_artifact(sess: Session, outputs: &OutputFilenames,
+                       lm: LinkMeta) -> Path {
+    if sess.opts.output_type == link::output_type_exe {
+        link::output_artifact_filename(sess, &outputs.out_filename, lm)
+    } else {
+        outputs.obj_filename.clone()
+    }
+}
+
+/// Write a Makefile rule naming every source file and crate the artifact
+/// was built from, if `--dep-info` was given.
+pub fn write_out_deps(sess: Session, outputs: &OutputFilenames,
+                      artifact: &Path) {
+    let deps_filename = match sess.opts.write_dependency_info {
+        (true, Some(ref filename)) => filename.clone(),
+        (true, None) => outputs.out_filename.with_extension("d"),
+        (false, _) => return
+    };
+
+    // Files whose names are in angle brackets, like `<std-macros>`, don't
+    // exist on disk.
+    let mut files: ~[~str] = ~[];
+    for fm in sess.codemap.files.iter() {
+        if !fm.name.starts_with("<") && !files.iter().any(|f| f.as_slice() == fm.name.as_slice()) {
+            files.push(fm.name.to_owned());
+        }
+    }
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    for crate_file in cstore::get_used_crate_files(sess.cstore).iter() {
+        files.push(crate_file.as_str().unwrap().to_owned());
+    }
+
+    // Make needs spaces in file names escaped
+    fn escape(s: &str) -> ~str { s.replace(" ", "\\ ") }
+
+    let rule = format!("{}: {}\n", escape(artifact.as_str().unwrap()),
+                       files.map(|f| escape(*f)).connect(" "));
+    let result = io::result(|| {
+        File::create(&deps_filename).write(rule.as_bytes());
+    });
+    match result {
+        Ok(()) => {}
+        Err(e) => {
+            sess.fatal(format!("error writing dependencies to `{}`: {}",
+                               deps_filename.display(), e.desc));
+        }
+    }
 }
 
 struct IdentifiedAnnotation {
@@ -517,19 +613,6 @@ pub fn pretty_print_input(sess: Session,
                           cfg: ast::CrateConfig,
                           input: &input,
                           ppm: PpMode) {
-    fn ann_typed_post(tcx: ty::ctxt, node: pprust::ann_node) {
-        match node {
-          pprust::node_expr(s, expr) => {
-            pp::space(s.s);
-            pp::word(s.s, "as");
-            pp::space(s.s);
-            pp::word(s.s, ppaux::ty_to_str(tcx, ty::expr_ty(tcx, expr)));
-            pprust::pclose(s);
-          }
-          _ => ()
-        }
-    }
-
     let crate = phase_1_parse_input(sess, cfg.clone(), input);
 
     let (crate, is_expanded) = match ppm {
@@ -654,11 +737,20 @@ pub fn build_session_options(binary: @str,
                              -> @session::options {
     let crate_type = if matches.opt_present("lib") {
         session::lib_crate
+    } else if matches.opt_present("staticlib") {
+        session::staticlib_crate
+    } else if matches.opt_present("rlib") {
+        session::rlib_crate
     } else if matches.opt_present("bin") {
         session::bin_crate
     } else {
         session::unknown_crate
     };
+    match matches.opt_str("error-format") {
+        None => {}
+        Some(ref f) if *f == ~"human" || *f == ~"json" => {}
+        Some(f) => early_error(demitter, format!("unknown error format: `{}`", f))
+    }
     let parse_only = matches.opt_present("parse-only");
     let no_trans = matches.opt_present("no-trans");
 
@@ -763,6 +855,16 @@ pub fn build_session_options(binary: @str,
     let cfg = parse_cfgspecs(matches.opt_strs("cfg"), demitter);
     let test = matches.opt_present("test");
     let android_cross_path = matches.opt_str("android-cross-path");
+    let cache_dir = matches.opt_str("cache-dir").map(|s| Path::new(s));
+    let write_dependency_info = (matches.opt_present("dep-info"),
+                                 matches.opt_str("dep-info").map(|p| Path::new(p)));
+    let codegen_units = match matches.opt_str("codegen-units") {
+        None => 1,
+        Some(s) => match from_str::<uint>(s) {
+            Some(n) if n > 0 => n,
+            _ => early_error(demitter, "--codegen-units needs a positive integer")
+        }
+    };
 
     let custom_passes = match matches.opt_str("passes") {
         None => ~[],
@@ -807,7 +909,10 @@ pub fn build_session_options(binary: @str,
         parse_only: parse_only,
         no_trans: no_trans,
         debugging_opts: debugging_opts,
-        android_cross_path: android_cross_path
+        android_cross_path: android_cross_path,
+        cache_dir: cache_dir,
+        codegen_units: codegen_units,
+        write_dependency_info: write_dependency_info
     };
     return sopts;
 }
@@ -847,6 +952,7 @@ pub fn build_session_(sopts: @session::options,
         span_diagnostic: span_diagnostic_handler,
         filesearch: filesearch,
         building_library: @mut false,
+        output_crate_type: @mut session::bin_crate,
         working_dir: os::getcwd(),
         lints: @mut HashMap::new(),
         node_id: @mut 1
@@ -873,11 +979,23 @@ pub fn optgroups() -> ~[getopts::groups::OptGroup] {
  ~[
   optflag("",  "bin", "Compile an executable crate (default)"),
   optflag("c", "",    "Compile and assemble, but do not link"),
+  optopt("", "cache-dir",
+                        "Reuse the output of an earlier compilation from
+                          <dir> when neither the sources nor the
+                          dependencies changed", "DIR"),
   optmulti("", "cfg", "Configure the compilation
                           environment", "SPEC"),
+  optopt("", "codegen-units",
+                        "Split the crate into N LLVM modules that are
+                          optimized and compiled in parallel", "N"),
+  optflagopt("", "dep-info",
+                        "Output dependency info to <filename> after compiling", "FILENAME"),
   optflag("",  "emit-llvm",
                         "Produce an LLVM assembly file if used with -S option;
                          produce an LLVM bitcode file otherwise"),
+  optopt("", "error-format",
+                        "Print diagnostics for humans (the default) or
+                          as one JSON object per line", "human|json"),
   optflag("h", "help","Display this message"),
   optmulti("L", "",   "Add a directory to the library search path",
                               "PATH"),
@@ -910,10 +1028,15 @@ pub fn optgroups() -> ~[getopts::groups::OptGroup] {
                           typed (crates expanded, with type annotations),
                           or identified (fully parenthesized,
                           AST nodes and blocks with IDs)", "TYPE"),
+  optflag("", "rlib", "Compile a static Rust library crate"),
   optflag("S", "",    "Compile only; do not assemble or link"),
   optflag("", "save-temps",
                         "Write intermediate files (.bc, .opt.bc, .o)
                           in addition to normal output"),
+  optflag("", "staticlib",
+                        "Compile a native static library of the crate and
+                          its rlib dependencies; the flags for linking its
+                          other dependencies and the runtime are printed"),
   optopt("", "sysroot",
                         "Override the system root", "PATH"),
   optflag("", "test", "Build a test harness"),
@@ -957,9 +1080,7 @@ pub fn build_output_filenames(input: &input,
     let obj_path;
     let out_path;
     let sopts = sess.opts;
-    let stop_after_codegen =
-        sopts.output_type != link::output_type_exe ||
-            sopts.is_static && *sess.building_library;
+    let stop_after_codegen = sopts.output_type != link::output_type_exe;
 
     let obj_suffix =
         match sopts.output_type {
@@ -1004,7 +1125,11 @@ pub fn build_output_filenames(input: &input,
           }
 
           if *sess.building_library {
-              out_path = dirpath.join(os::dll_filename(stem));
+              out_path = match *sess.output_crate_type {
+                  session::staticlib_crate => dirpath.join(format!("lib{}.a", stem)),
+                  session::rlib_crate => dirpath.join(format!("lib{}.rlib", stem)),
+                  _ => dirpath.join(os::dll_filename(stem))
+              };
               obj_path = {
                   let mut p = dirpath.join(stem);
                   p.set_extension(obj_suffix);
@@ -1104,4 +1229,33 @@ mod test {
         assert!(test_items.next().is_some());
         assert!(test_items.next().is_none());
     }
+
+    #[test]
+    fn test_cache_dir() {
+        let matches =
+            &match getopts([~"--cache-dir", ~"build/cache"], optgroups()) {
+              Ok(m) => m,
+              Err(f) => fail!("test_cache_dir: {}", f.to_err_msg())
+            };
+        let sessopts = build_session_options(
+            @"rustc",
+            matches,
+            @diagnostic::DefaultEmitter as @diagnostic::Emitter);
+        assert_eq!(sessopts.cache_dir, Some(Path::new("build/cache")));
+    }
+
+    #[test]
+    fn test_dep_info() {
+        let matches =
+            &match getopts([~"--dep-info=out/foo.d"], optgroups()) {
+              Ok(m) => m,
+              Err(f) => fail!("test_dep_info: {}", f.to_err_msg())
+            };
+        let sessopts = build_session_options(
+            @"rustc",
+            matches,
+            @diagnostic::DefaultEmitter as @diagnostic::Emitter);
+        assert_eq!(sessopts.write_dependency_info,
+                   (true, Some(Path::new("out/foo.d"))));
+    }
 }
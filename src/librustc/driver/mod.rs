@@ -10,5 +10,6 @@
 
 pub use syntax::diagnostic;
 
+pub mod cache;
 pub mod driver;
 pub mod session;
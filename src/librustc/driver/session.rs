@@ -30,10 +30,15 @@ use syntax;
 
 use std::hashmap::{HashMap,HashSet};
 
-#[deriving(Clone)]
+#[deriving(Clone, Eq)]
 pub enum crate_type {
     bin_crate,
+    // A dynamic library with embedded metadata
     lib_crate,
+    // A self-contained native archive for consumption by non-Rust linkers
+    staticlib_crate,
+    // A Rust archive of object code and metadata for static linking
+    rlib_crate,
     unknown_crate,
 }
 
@@ -176,6 +181,15 @@ pub struct options {
     no_trans: bool,
     debugging_opts: uint,
     android_cross_path: Option<~str>,
+    // Directory holding cached outputs of previous compilations, see
+    // driver::cache
+    cache_dir: Option<Path>,
+    // Number of LLVM modules the crate is split into for optimization and
+    // code generation, see back::link::write
+    codegen_units: uint,
+    // Whether to write a Makefile rule with the inputs of the compilation,
+    // and where to, if not next to the output
+    write_dependency_info: (bool, Option<Path>),
 }
 
 pub struct crate_metadata {
@@ -206,6 +220,9 @@ pub struct Session_ {
     span_diagnostic: @mut diagnostic::span_handler,
     filesearch: @filesearch::FileSearch,
     building_library: @mut bool,
+    // The kind of artifact being produced, never `unknown_crate` once the
+    // crate attributes have been read
+    output_crate_type: @mut crate_type,
     working_dir: Path,
     lints: @mut HashMap<ast::NodeId, ~[(lint::lint, codemap::Span, ~str)]>,
     node_id: @mut ast::NodeId,
@@ -241,6 +258,12 @@ impl Session_ {
     pub fn warn(&self, msg: &str) {
         self.span_diagnostic.handler().warn(msg)
     }
+    pub fn span_lint_warn(&self, sp: Span, msg: &str, lint: &str) {
+        self.span_diagnostic.span_lint(sp, msg, diagnostic::warning, lint)
+    }
+    pub fn span_lint_err(&self, sp: Span, msg: &str, lint: &str) {
+        self.span_diagnostic.span_lint(sp, msg, diagnostic::error, lint)
+    }
     pub fn span_note(&self, sp: Span, msg: &str) {
         self.span_diagnostic.span_note(sp, msg)
     }
@@ -383,6 +406,9 @@ pub fn basic_options() -> @options {
         no_trans: false,
         debugging_opts: 0u,
         android_cross_path: None,
+        cache_dir: None,
+        codegen_units: 1,
+        write_dependency_info: (false, None),
     }
 }
 
@@ -394,21 +420,30 @@ pub fn expect<T:Clone>(sess: Session, opt: Option<T>, msg: || -> ~str) -> T {
 pub fn building_library(req_crate_type: crate_type,
                         crate: &ast::Crate,
                         testing: bool) -> bool {
+    output_crate_type(req_crate_type, crate, testing) != bin_crate
+}
+
+/// Resolves the kind of artifact to produce from the command line request
+/// and the `crate_type` attribute of the crate.
+pub fn output_crate_type(req_crate_type: crate_type,
+                         crate: &ast::Crate,
+                         testing: bool) -> crate_type {
     match req_crate_type {
-      bin_crate => false,
-      lib_crate => true,
       unknown_crate => {
         if testing {
-            false
+            bin_crate
         } else {
             match syntax::attr::first_attr_value_str_by_name(
                 crate.attrs,
                 "crate_type") {
-              Some(s) => "lib" == s,
-              _ => false
+              Some(s) if "lib" == s => lib_crate,
+              Some(s) if "staticlib" == s => staticlib_crate,
+              Some(s) if "rlib" == s => rlib_crate,
+              _ => bin_crate
             }
         }
       }
+      t => t
     }
 }
 
@@ -427,6 +462,7 @@ pub fn sess_os_to_meta_os(os: abi::Os) -> metadata::loader::Os {
 #[cfg(test)]
 mod test {
     use driver::session::{bin_crate, building_library, lib_crate};
+    use driver::session::{output_crate_type, rlib_crate, staticlib_crate};
     use driver::session::{unknown_crate};
 
     use syntax::ast;
@@ -453,6 +489,15 @@ mod test {
         }
     }
 
+    fn make_crate_of_type(t: @str) -> @ast::Crate {
+        @ast::Crate {
+            module: ast::_mod { view_items: ~[], items: ~[] },
+            attrs: ~[make_crate_type_attr(t)],
+            config: ~[],
+            span: codemap::dummy_sp(),
+        }
+    }
+
     #[test]
     fn bin_crate_type_attr_results_in_bin_output() {
         let crate = make_crate(true, false);
@@ -494,4 +539,23 @@ mod test {
         let crate = make_crate(false, false);
         assert!(building_library(lib_crate, crate, true));
     }
+
+    #[test]
+    fn static_crate_type_attrs_result_in_lib_output() {
+        let crate = make_crate_of_type(@"staticlib");
+        assert!(building_library(unknown_crate, crate, false));
+        assert_eq!(output_crate_type(unknown_crate, crate, false), staticlib_crate);
+
+        let crate = make_crate_of_type(@"rlib");
+        assert!(building_library(unknown_crate, crate, false));
+        assert_eq!(output_crate_type(unknown_crate, crate, false), rlib_crate);
+    }
+
+    #[test]
+    fn static_options_override_lib_crate_type() {
+        let crate = make_crate(false, true);
+        assert_eq!(output_crate_type(staticlib_crate, crate, false), staticlib_crate);
+        assert_eq!(output_crate_type(rlib_crate, crate, false), rlib_crate);
+        assert!(building_library(rlib_crate, crate, true));
+    }
 }
@@ -76,6 +76,7 @@ pub mod middle {
     pub mod entry;
     pub mod effect;
     pub mod reachable;
+    pub mod dead;
     pub mod graph;
     pub mod cfg;
 }
@@ -294,7 +295,8 @@ pub enum monitor_msg {
 }
 
 struct RustcEmitter {
-    ch_capture: comm::SharedChan<monitor_msg>
+    ch_capture: comm::SharedChan<monitor_msg>,
+    inner: @diagnostic::Emitter
 }
 
 impl diagnostic::Emitter for RustcEmitter {
@@ -306,7 +308,39 @@ impl diagnostic::Emitter for RustcEmitter {
             self.ch_capture.send(fatal)
         }
 
-        diagnostic::DefaultEmitter.emit(cmsp, msg, lvl)
+        self.inner.emit(cmsp, msg, lvl)
+    }
+
+    fn emit_lint(&self,
+                 cmsp: Option<(@codemap::CodeMap, codemap::Span)>,
+                 msg: &str,
+                 lvl: diagnostic::level,
+                 lint: &str) {
+        if lvl == diagnostic::fatal {
+            self.ch_capture.send(fatal)
+        }
+
+        self.inner.emit_lint(cmsp, msg, lvl, lint)
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+// The emitter has to exist before the command line is parsed, so the
+// diagnostic format is picked out of the raw arguments. Bad values are
+// reported by build_session_options.
+fn json_diagnostics(args: &[~str]) -> bool {
+    args.iter().any(|a| a == &~"--error-format=json") ||
+        args.windows(2).any(|w| w[0] == ~"--error-format" && w[1] == ~"json")
+}
+
+fn mk_emitter(json: bool) -> @diagnostic::Emitter {
+    if json {
+        @diagnostic::JsonEmitter::new() as @diagnostic::Emitter
+    } else {
+        @diagnostic::DefaultEmitter as @diagnostic::Emitter
     }
 }
 
@@ -322,7 +356,7 @@ diagnostic emitter which records when we hit a fatal error. If the task
 fails without recording a fatal error then we've encountered a compiler
 bug and need to present an error.
 */
-pub fn monitor(f: proc(@diagnostic::Emitter)) {
+pub fn monitor(json: bool, f: proc(@diagnostic::Emitter)) {
     use std::comm::*;
 
     // XXX: This is a hack for newsched since it doesn't support split stacks.
@@ -351,17 +385,24 @@ pub fn monitor(f: proc(@diagnostic::Emitter)) {
         // go through this function.
         let demitter = @RustcEmitter {
             ch_capture: ch.clone(),
+            inner: mk_emitter(json),
         } as @diagnostic::Emitter;
 
+        // Diagnostics the emitter is holding on to are written out even if
+        // the compiler fails without reporting a fatal error.
         struct finally {
             ch: SharedChan<monitor_msg>,
+            emitter: @diagnostic::Emitter,
         }
 
         impl Drop for finally {
-            fn drop(&mut self) { self.ch.send(done); }
+            fn drop(&mut self) {
+                self.emitter.flush();
+                self.ch.send(done);
+            }
         }
 
-        let _finally = finally { ch: ch };
+        let _finally = finally { ch: ch, emitter: demitter };
 
         f(demitter);
 
@@ -373,10 +414,10 @@ pub fn monitor(f: proc(@diagnostic::Emitter)) {
         result::Err(_) => {
             // Task failed without emitting a fatal diagnostic
             if p.recv() == done {
-                diagnostic::DefaultEmitter.emit(
-                    None,
-                    diagnostic::ice_msg("unexpected failure"),
-                    diagnostic::error);
+                let emitter = mk_emitter(json);
+                emitter.emit(None,
+                             diagnostic::ice_msg("unexpected failure"),
+                             diagnostic::error);
 
                 let xs = [
                     ~"the compiler hit an unexpected failure path. \
@@ -386,10 +427,9 @@ pub fn monitor(f: proc(@diagnostic::Emitter)) {
                      to github.com/mozilla/rust/issues"
                 ];
                 for note in xs.iter() {
-                    diagnostic::DefaultEmitter.emit(None,
-                                                    *note,
-                                                    diagnostic::note)
+                    emitter.emit(None, *note, diagnostic::note)
                 }
+                emitter.flush();
             }
             // Fail so the process returns a failure code
             fail!();
@@ -403,6 +443,6 @@ pub fn main() {
 
 pub fn main_args(args: &[~str]) -> int {
     let owned_args = args.to_owned();
-    monitor(proc(demitter) run_compiler(owned_args, demitter));
+    monitor(json_diagnostics(args), proc(demitter) run_compiler(owned_args, demitter));
     0
 }
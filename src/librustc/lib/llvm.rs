@@ -1468,6 +1468,27 @@ pub mod llvm {
             call. */
         pub fn LLVMRustGetLastError() -> *c_char;
 
+        /** Reads a module from a bitcode buffer into the given context.
+            Returns true on failure, setting OutMessage. */
+        pub fn LLVMParseBitcodeInContext(C: ContextRef,
+                                         MemBuf: MemoryBufferRef,
+                                         OutModule: *mut ModuleRef,
+                                         OutMessage: *mut *c_char)
+                                         -> Bool;
+
+        /** Splits a crate's module into `Count` codegen units, writing
+            the bitcode of each to `OutBuffers`. `Names` and `Units` say
+            which unit each of `NumNames` definitions belongs to, and
+            `Suffix` is appended to the names of the internal symbols which
+            have to be promoted so that other units can refer to them. */
+        pub fn LLVMRustSplitModule(M: ModuleRef,
+                                   Count: c_uint,
+                                   Names: **c_char,
+                                   Units: *c_uint,
+                                   NumNames: c_uint,
+                                   Suffix: *c_char,
+                                   OutBuffers: *mut MemoryBufferRef);
+
         /** Prepare the JIT. Returns a memory manager that can load crates. */
         pub fn LLVMRustPrepareJIT(__morestack: *()) -> *();
 
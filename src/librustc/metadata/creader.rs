@@ -11,6 +11,8 @@
 //! Validates all used crates and extern libraries and loads their metadata
 
 
+use driver::session::Session;
+use driver::session;
 use metadata::cstore;
 use metadata::decoder;
 use metadata::filesearch::FileSearch;
@@ -23,6 +25,7 @@ use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::codemap::{Span, dummy_sp};
 use syntax::diagnostic::span_handler;
+use syntax::ext::base::{CrateLoader, MacroCrate};
 use syntax::parse::token;
 use syntax::parse::token::ident_interner;
 use syntax::visit;
@@ -136,7 +139,9 @@ fn visit_crate(e: &Env, c: &ast::Crate) {
     }
 }
 
-fn visit_view_item(e: @mut Env, i: &ast::view_item) {
+// The name and link metas of the crate an `extern mod` refers to
+fn extern_mod_metas(diag: @mut span_handler, i: &ast::view_item)
+                    -> Option<(@str, ~[@ast::MetaItem], ast::NodeId)> {
     match i.node {
       ast::view_item_extern_mod(ident, path_opt, ref meta_items, id) => {
           let ident = token::ident_to_str(&ident);
@@ -146,7 +151,7 @@ fn visit_view_item(e: @mut Env, i: &ast::view_item) {
                   let p_path = Path::new(p);
                   match p_path.filestem_str() {
                       None|Some("") =>
-                          e.diag.span_bug(i.span, "Bad package path in `extern mod` item"),
+                          diag.span_bug(i.span, "Bad package path in `extern mod` item"),
                       Some(s) =>
                           vec::append(
                               ~[attr::mk_name_value_item_str(@"package_id", p),
@@ -155,6 +160,15 @@ fn visit_view_item(e: @mut Env, i: &ast::view_item) {
                   }
             }
           };
+          Some((ident, meta_items, id))
+      }
+      _ => None
+  }
+}
+
+fn visit_view_item(e: @mut Env, i: &ast::view_item) {
+    match extern_mod_metas(e.diag, i) {
+      Some((ident, meta_items, id)) => {
           debug!("resolving extern mod stmt. ident: {:?}, meta: {:?}",
                  ident, meta_items);
           let cnum = resolve_crate(e,
@@ -164,7 +178,7 @@ fn visit_view_item(e: @mut Env, i: &ast::view_item) {
                                    i.span);
           cstore::add_extern_mod_stmt_cnum(e.cstore, id, cnum);
       }
-      _ => ()
+      None => ()
   }
 }
 
@@ -354,3 +368,47 @@ fn resolve_crate_deps(e: @mut Env, cdata: @~[u8]) -> cstore::cnum_map {
     }
     return @mut cnum_map;
 }
+
+/// Finds the crates named by `#[phase(syntax)] extern mod`s while the crate is
+/// being expanded. Those crates are only opened to get at their syntax
+/// extensions and are not recorded in the crate store, so they don't get
+/// linked unless they're also wanted at link time.
+pub struct Loader {
+    priv sess: Session,
+}
+
+impl Loader {
+    pub fn new(sess: Session) -> Loader {
+        Loader { sess: sess }
+    }
+}
+
+impl CrateLoader for Loader {
+    fn load_crate(&mut self, crate: &ast::view_item) -> MacroCrate {
+        let diag = self.sess.diagnostic();
+        let (ident, metas) = match extern_mod_metas(diag, crate) {
+            Some((ident, metas, _)) => (ident, metas),
+            None => diag.span_fatal(crate.span, "only `extern mod`s can \
+                                                 provide syntax extensions")
+        };
+        let load_ctxt = loader::Context {
+            diag: diag,
+            filesearch: self.sess.filesearch,
+            span: crate.span,
+            ident: ident,
+            metas: metas_with_ident(ident, metas),
+            hash: @"",
+            os: session::sess_os_to_meta_os(self.sess.targ_cfg.os),
+            // Syntax extensions have to be loaded into the compiler
+            is_static: false,
+            intr: token::get_ident_interner()
+        };
+        let (lib, data) = loader::load_library_crate(&load_ctxt);
+        MacroCrate {
+            lib: Path::new(lib),
+            registrar: decoder::get_macro_registrar_fn(data).map(|id| {
+                decoder::get_symbol(data, id)
+            })
+        }
+    }
+}
@@ -26,6 +26,7 @@ use middle::typeck;
 use middle::astencode::vtable_decoder_helpers;
 
 use std::at_vec;
+use std::hash;
 use std::u64;
 use std::io;
 use std::io::extensions::u64_from_be_bytes;
@@ -84,7 +85,7 @@ pub fn maybe_find_item(item_id: ast::NodeId, items: ebml::Doc) -> Option<ebml::D
     }
     lookup_hash(items,
                 |a| eq_item(a, item_id),
-                (item_id as i64).hash())
+                hash::hash(&(item_id as i64)))
 }
 
 fn find_item(item_id: ast::NodeId, items: ebml::Doc) -> ebml::Doc {
@@ -1259,28 +1260,6 @@ pub fn get_item_visibility(cdata: Cmd, id: ast::NodeId)
     item_visibility(lookup_item(id, cdata.data))
 }
 
-fn family_has_type_params(fam: Family) -> bool {
-    match fam {
-      ImmStatic | ForeignType | Mod | ForeignMod | PublicField | PrivateField
-      | ForeignFn | MutStatic => false,
-      _           => true
-    }
-}
-
-fn family_names_type(fam: Family) -> bool {
-    match fam { Type | Mod | Trait => true, _ => false }
-}
-
-fn read_path(d: ebml::Doc) -> (~str, uint) {
-    reader::with_doc_data(d, |desc| {
-        let pos = u64_from_be_bytes(desc, 0u, 4u) as uint;
-        let pathbytes = desc.slice(4u, desc.len());
-        let path = str::from_utf8(pathbytes);
-
-        (path, pos)
-    })
-}
-
 fn describe_def(items: ebml::Doc, id: ast::DefId) -> ~str {
     if id.crate != ast::LOCAL_CRATE { return ~"external"; }
     let it = match maybe_find_item(id.node, items) {
@@ -1370,15 +1349,6 @@ fn get_attributes(md: ebml::Doc) -> ~[ast::Attribute] {
     return attrs;
 }
 
-fn list_meta_items(intr: @ident_interner,
-                   meta_items: ebml::Doc,
-                   out: @mut io::Writer) {
-    let r = get_meta_items(meta_items);
-    for mi in r.iter() {
-        write!(out, "{}\n", pprust::meta_item_to_str(*mi, intr));
-    }
-}
-
 fn list_crate_attributes(intr: @ident_interner, md: ebml::Doc, hash: &str,
                          out: @mut io::Writer) {
     write!(out, "=Crate Attributes ({})=\n", hash);
@@ -1441,6 +1411,13 @@ pub fn get_crate_hash(data: @~[u8]) -> @str {
     hashdoc.as_str_slice().to_managed()
 }
 
+pub fn get_macro_registrar_fn(data: @~[u8]) -> Option<ast::NodeId> {
+    let cratedoc = reader::Doc(data);
+    reader::maybe_get_doc(cratedoc, tag_macro_registrar_fn).map(|doc| {
+        reader::doc_as_u32(doc) as ast::NodeId
+    })
+}
+
 pub fn get_crate_vers(data: @~[u8]) -> @str {
     let attrs = decoder::get_crate_attributes(data);
     let linkage_attrs = attr::find_linkage_metas(attrs);
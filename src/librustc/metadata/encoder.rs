@@ -21,6 +21,7 @@ use middle::ty;
 use middle::typeck;
 use middle;
 
+use std::hash;
 use std::hashmap::{HashMap, HashSet};
 use std::io::{Writer, Seek, Decorator};
 use std::io::mem::MemWriter;
@@ -1391,13 +1392,13 @@ fn encode_info_for_items(ecx: &EncodeContext,
 
 // Path and definition ID indexing
 
-fn create_index<T:Clone + Hash + IterBytes + 'static>(
+fn create_index<T:Clone + Hash + 'static>(
                 index: ~[entry<T>])
                 -> ~[@~[entry<T>]] {
     let mut buckets: ~[@mut ~[entry<T>]] = ~[];
     for _ in range(0u, 256u) { buckets.push(@mut ~[]); };
     for elt in index.iter() {
-        let h = elt.val.hash() as uint;
+        let h = hash::hash(&elt.val) as uint;
         buckets[h % 256].push((*elt).clone());
     }
 
@@ -1441,10 +1442,6 @@ fn encode_index<T:'static>(
     ebml_w.end_tag();
 }
 
-fn write_str(writer: @mut MemWriter, s: ~str) {
-    writer.write(s.as_bytes());
-}
-
 fn write_i64(writer: @mut MemWriter, &n: &i64) {
     let wr: &mut MemWriter = writer;
     assert!(n < 0x7fff_ffff);
@@ -1633,6 +1630,25 @@ fn encode_lang_items(ecx: &EncodeContext, ebml_w: &mut writer::Encoder) {
     ebml_w.end_tag();   // tag_lang_items
 }
 
+// Records the node id of the crate's `#[macro_registrar]` function, which is
+// only looked for among the items at the crate root
+fn encode_macro_registrar_fn(crate: &Crate, ebml_w: &mut writer::Encoder) {
+    let registrar = crate.module.items.iter().find(|item| {
+        match item.node {
+            item_fn(*) => attr::contains_name(item.attrs, "macro_registrar"),
+            _ => false
+        }
+    });
+    for item in registrar.iter() {
+        ebml_w.start_tag(tag_macro_registrar_fn);
+        {
+            let wr: &mut MemWriter = ebml_w.writer;
+            wr.write_be_u32(item.id as u32);
+        }
+        ebml_w.end_tag();
+    }
+}
+
 struct ImplVisitor<'self> {
     ecx: &'self EncodeContext<'self>,
     ebml_w: &'self mut writer::Encoder,
@@ -1806,6 +1822,8 @@ pub fn encode_metadata(parms: EncodeParams, crate: &Crate) -> ~[u8] {
     encode_lang_items(&ecx, &mut ebml_w);
     ecx.stats.lang_item_bytes = wr.tell() - i;
 
+    encode_macro_registrar_fn(crate, &mut ebml_w);
+
     // Encode the def IDs of impls, for coherence checking.
     i = wr.tell();
     encode_impls(&ecx, crate, &mut ebml_w);
@@ -26,14 +26,18 @@ use syntax::attr::AttrMetaMethods;
 use std::c_str::ToCStr;
 use std::cast;
 use std::io;
+use std::io::File;
 use std::num;
 use std::option;
 use std::os::consts::{macos, freebsd, linux, android, win32};
-use std::ptr;
 use std::str;
 use std::vec;
 use extra::flate;
 
+/// The name of the archive member holding the encoded metadata of an rlib.
+/// Kept under 16 bytes so that every `ar` stores it in the member header.
+pub static METADATA_FILENAME: &'static str = "rust.metadata";
+
 pub enum Os {
     OsMacos,
     OsWin32,
@@ -67,11 +71,23 @@ pub fn load_library_crate(cx: &Context) -> (~str, @~[u8]) {
 
 fn find_library_crate(cx: &Context) -> Option<(~str, @~[u8])> {
     attr::require_unique_names(cx.diag, cx.metas);
-    find_library_crate_aux(cx, libname(cx), cx.filesearch)
+    // Prefer the requested flavor of library, but fall back to the other one
+    // so that crates only available as rlibs (or only as dylibs) still link.
+    let (first, second) = if cx.is_static {
+        (rlibname(), dylibname(cx))
+    } else {
+        (dylibname(cx), rlibname())
+    };
+    find_library_crate_aux(cx, first, cx.filesearch).or_else(|| {
+        find_library_crate_aux(cx, second, cx.filesearch)
+    })
+}
+
+fn rlibname() -> (~str, ~str) {
+    (~"lib", ~".rlib")
 }
 
-fn libname(cx: &Context) -> (~str, ~str) {
-    if cx.is_static { return (~"lib", ~".rlib"); }
+fn dylibname(cx: &Context) -> (~str, ~str) {
     let (dll_prefix, dll_suffix) = match cx.os {
         OsWin32 => (win32::DLL_PREFIX, win32::DLL_SUFFIX),
         OsMacos => (macos::DLL_PREFIX, macos::DLL_SUFFIX),
@@ -198,6 +214,9 @@ pub fn metadata_matches(extern_metas: &[@ast::MetaItem],
 
 fn get_metadata_section(os: Os,
                         filename: &Path) -> Option<@~[u8]> {
+    if filename.extension_str() == Some("rlib") {
+        return get_archive_metadata(filename);
+    }
     unsafe {
         let mb = filename.with_c_str(|buf| {
             llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(buf)
@@ -215,29 +234,10 @@ fn get_metadata_section(os: Os,
             if read_meta_section_name(os) == name {
                 let cbuf = llvm::LLVMGetSectionContents(si.llsi);
                 let csz = llvm::LLVMGetSectionSize(si.llsi) as uint;
-                let mut found = None;
                 let cvbuf: *u8 = cast::transmute(cbuf);
-                let vlen = encoder::metadata_encoding_version.len();
-                debug!("checking {} bytes of metadata-version stamp",
-                       vlen);
-                let minsz = num::min(vlen, csz);
-                let mut version_ok = false;
-                vec::raw::buf_as_slice(cvbuf, minsz, |buf0| {
-                    version_ok = (buf0 ==
-                                  encoder::metadata_encoding_version);
-                });
-                if !version_ok { return None; }
-
-                let cvbuf1 = ptr::offset(cvbuf, vlen as int);
-                debug!("inflating {} bytes of compressed metadata",
-                       csz - vlen);
-                vec::raw::buf_as_slice(cvbuf1, csz-vlen, |bytes| {
-                    let inflated = flate::inflate_bytes(bytes);
-                    found = Some(@(inflated));
+                return vec::raw::buf_as_slice(cvbuf, csz, |buf| {
+                    decode_metadata(buf)
                 });
-                if found != None {
-                    return found;
-                }
             }
             llvm::LLVMMoveToNextSection(si.llsi);
         }
@@ -245,6 +245,60 @@ fn get_metadata_section(os: Os,
     }
 }
 
+// Checks the version stamp of encoded metadata and inflates the rest
+fn decode_metadata(buf: &[u8]) -> Option<@~[u8]> {
+    let vlen = encoder::metadata_encoding_version.len();
+    debug!("checking {} bytes of metadata-version stamp", vlen);
+    let minsz = num::min(vlen, buf.len());
+    if buf.slice_to(minsz) != encoder::metadata_encoding_version {
+        return None;
+    }
+    debug!("inflating {} bytes of compressed metadata", buf.len() - vlen);
+    Some(@flate::inflate_bytes(buf.slice_from(vlen)))
+}
+
+// Finds the metadata member of an rlib. The archive is walked by hand; the
+// only member we care about has a short name, so neither the GNU nor the BSD
+// long name tables need to be understood.
+fn get_archive_metadata(filename: &Path) -> Option<@~[u8]> {
+    static MAGIC: &'static [u8] = bytes!("!<arch>\n");
+    static HEADER_LEN: uint = 60;
+
+    let data = match io::result(|| File::open(filename).map(|mut f| f.read_to_end())) {
+        Ok(Some(data)) => data,
+        _ => return None
+    };
+    if !data.starts_with(MAGIC) { return None; }
+
+    let mut pos = MAGIC.len();
+    while pos + HEADER_LEN <= data.len() {
+        let header = data.slice(pos, pos + HEADER_LEN);
+        let name = str::from_utf8_opt(header.slice_to(16));
+        let size = str::from_utf8_opt(header.slice(48, 58)).and_then(|s| {
+            from_str::<uint>(s.trim())
+        });
+        let size = match size {
+            Some(size) => size,
+            None => return None
+        };
+        let start = pos + HEADER_LEN;
+        if start + size > data.len() { return None; }
+
+        // GNU ar terminates names with a '/', BSD ar pads with spaces
+        match name {
+            Some(name) if name.trim_right().trim_right_chars(&'/') ==
+                          METADATA_FILENAME => {
+                debug!("get_archive_metadata: found {} in {}",
+                       METADATA_FILENAME, filename.display());
+                return decode_metadata(data.slice(start, start + size));
+            }
+            _ => {}
+        }
+        pos = start + size + size % 2;
+    }
+    None
+}
+
 pub fn meta_section_name(os: Os) -> &'static str {
     match os {
         OsMacos => "__DATA,__note.rustc",
@@ -216,7 +216,7 @@ pub type LoanMap = @mut HashMap<ast::NodeId, @Loan>;
 // used as part of evaluating `x[0]`. The entry with `derefs==1`
 // refers to the deref of the `x[0]` that occurs as part of the
 // auto-slice.
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub struct root_map_key {
     id: ast::NodeId,
     derefs: uint
@@ -276,13 +276,13 @@ pub struct Loan {
     span: Span,
 }
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub enum LoanPath {
     LpVar(ast::NodeId),               // `x` in doc.rs
     LpExtend(@LoanPath, mc::MutabilityCategory, LoanPathElem)
 }
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub enum LoanPathElem {
     LpDeref(mc::PointerKind),    // `*LV` in doc.rs
     LpInterior(mc::InteriorKind) // `LV.f` in doc.rs
@@ -0,0 +1,397 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// This implements the dead-code warning pass. It follows middle::reachable
+// closely. The idea is that all reachable symbols are live, everything
+// reachable from them is also live, and all other items are considered
+// dead. Exported items, the entry point, tests and lang items seed the
+// search.
+
+use middle::lint::dead_code;
+use middle::privacy;
+use middle::ty;
+use middle::typeck;
+
+use std::hashmap::HashSet;
+use syntax::ast;
+use syntax::ast_map;
+use syntax::ast_util::{def_id_of_def, is_local};
+use syntax::attr;
+use syntax::codemap;
+use syntax::parse::token;
+use syntax::visit::Visitor;
+use syntax::visit;
+
+// Any local node that may call something in its body block should be
+// explored. For example, if it's a live node_item that is a
+// function, then we should explore its block to check for codes that
+// may need to be marked as live.
+fn should_explore(tcx: ty::ctxt, def_id: ast::DefId) -> bool {
+    if !is_local(def_id) {
+        return false;
+    }
+    match tcx.items.find(&def_id.node) {
+        Some(&ast_map::node_item(*))
+        | Some(&ast_map::node_method(*))
+        | Some(&ast_map::node_foreign_item(*))
+        | Some(&ast_map::node_trait_method(*)) => true,
+        _ => false
+    }
+}
+
+struct MarkSymbolVisitor {
+    worklist: ~[ast::NodeId],
+    method_map: typeck::method_map,
+    tcx: ty::ctxt,
+    live_symbols: ~HashSet<ast::NodeId>,
+}
+
+impl MarkSymbolVisitor {
+    fn new(tcx: ty::ctxt,
+           method_map: typeck::method_map,
+           worklist: ~[ast::NodeId]) -> MarkSymbolVisitor {
+        MarkSymbolVisitor {
+            worklist: worklist,
+            method_map: method_map,
+            tcx: tcx,
+            live_symbols: ~HashSet::new(),
+        }
+    }
+
+    fn check_def_id(&mut self, def_id: ast::DefId) {
+        if !is_local(def_id) {
+            return;
+        }
+        // Variants and tuple-struct constructors have their own node ids;
+        // using one of them keeps the enclosing enum or struct alive.
+        match self.tcx.items.find(&def_id.node) {
+            Some(&ast_map::node_variant(_, item, _)) |
+            Some(&ast_map::node_struct_ctor(_, item, _)) => {
+                self.live_symbols.insert(item.id);
+            }
+            _ => ()
+        }
+        if should_explore(self.tcx, def_id) {
+            self.worklist.push(def_id.node);
+        }
+        self.live_symbols.insert(def_id.node);
+    }
+
+    fn lookup_and_handle_definition(&mut self, id: &ast::NodeId) {
+        let def = match self.tcx.def_map.find(id) {
+            Some(&def) => def,
+            None => return
+        };
+        let def_id = match def {
+            ast::DefPrimTy(*) | ast::DefTyParam(*) | ast::DefSelf(*) |
+            ast::DefSelfTy(*) | ast::DefLocal(*) | ast::DefArg(*) |
+            ast::DefBinding(*) | ast::DefUpvar(*) => return,
+            _ => def_id_of_def(def)
+        };
+        self.check_def_id(def_id);
+    }
+
+    fn lookup_and_handle_method(&mut self, id: &ast::NodeId,
+                                span: codemap::Span) {
+        match self.method_map.find(id) {
+            Some(&typeck::method_map_entry {
+                origin: typeck::method_static(def_id),
+                _
+            }) => self.check_def_id(def_id),
+            Some(_) => (),
+            None => {
+                self.tcx.sess.span_bug(span,
+                                       "method call expression not \
+                                        in method map?!")
+            }
+        }
+    }
+
+    fn mark_live_symbols(&mut self) {
+        let mut scanned = HashSet::new();
+        while self.worklist.len() > 0 {
+            let id = self.worklist.pop();
+            if scanned.contains(&id) {
+                continue
+            }
+            scanned.insert(id);
+
+            match self.tcx.items.find(&id) {
+                Some(node) => {
+                    self.live_symbols.insert(id);
+                    self.visit_node(node);
+                }
+                None => (),
+            }
+        }
+    }
+
+    fn visit_node(&mut self, node: &ast_map::ast_node) {
+        match *node {
+            ast_map::node_item(item, _) => {
+                visit::walk_item(self, item, ());
+            }
+            ast_map::node_trait_method(trait_method, _, _) => {
+                visit::walk_trait_method(self, trait_method, ());
+            }
+            ast_map::node_method(method, _, _) => {
+                visit::walk_block(self, &method.body, ());
+            }
+            ast_map::node_foreign_item(foreign_item, _, _, _) => {
+                visit::walk_foreign_item(self, foreign_item, ());
+            }
+            _ => ()
+        }
+    }
+}
+
+impl Visitor<()> for MarkSymbolVisitor {
+
+    fn visit_expr(&mut self, expr: @ast::Expr, _: ()) {
+        match expr.node {
+            ast::ExprPath(_) | ast::ExprStruct(*) => {
+                self.lookup_and_handle_definition(&expr.id);
+            }
+            ast::ExprMethodCall(*) => {
+                self.lookup_and_handle_method(&expr.id, expr.span);
+            }
+            _ => ()
+        }
+
+        visit::walk_expr(self, expr, ())
+    }
+
+    fn visit_pat(&mut self, pat: &ast::Pat, _: ()) {
+        match pat.node {
+            ast::PatEnum(*) | ast::PatIdent(*) | ast::PatStruct(*) => {
+                self.lookup_and_handle_definition(&pat.id);
+            }
+            _ => ()
+        }
+
+        visit::walk_pat(self, pat, ())
+    }
+
+    fn visit_ty(&mut self, typ: &ast::Ty, _: ()) {
+        match typ.node {
+            ast::ty_path(_, _, ref id) => {
+                self.lookup_and_handle_definition(id);
+            }
+            _ => ()
+        }
+
+        visit::walk_ty(self, typ, ())
+    }
+
+    fn visit_item(&mut self, _item: @ast::item, _: ()) {
+        // Do not recurse into items. These items will be added to the
+        // worklist and recursed into manually if necessary.
+    }
+}
+
+// This visitor is used to mark the implemented methods of a trait. Since we
+// can not be sure if such methods are live or dead, we simply mark them
+// as live. The same goes for test and benchmark functions, which are only
+// reached through the generated test harness.
+struct TraitMethodSeeder {
+    worklist: ~[ast::NodeId],
+}
+
+impl Visitor<()> for TraitMethodSeeder {
+    fn visit_item(&mut self, item: @ast::item, _: ()) {
+        match item.node {
+            ast::item_impl(_, Some(_), _, ref methods) => {
+                for method in methods.iter() {
+                    self.worklist.push(method.id);
+                }
+            }
+            ast::item_fn(*) if attr::contains_name(item.attrs, "test") ||
+                               attr::contains_name(item.attrs, "bench") => {
+                self.worklist.push(item.id);
+            }
+            _ => ()
+        }
+        visit::walk_item(self, item, ());
+    }
+}
+
+fn create_and_seed_worklist(tcx: ty::ctxt,
+                            exported_items: &privacy::ExportedItems,
+                            reachable_symbols: @mut HashSet<ast::NodeId>,
+                            crate: &ast::Crate) -> ~[ast::NodeId] {
+    let mut worklist = ~[];
+
+    // Preferably, we would only need to seed the worklist with reachable
+    // symbols. However, since the set of reachable symbols differs
+    // depending on whether a crate is built as bin or lib, and we want
+    // the warning to be consistent, we also seed the worklist with
+    // exported symbols.
+    for &id in exported_items.iter() {
+        worklist.push(id);
+    }
+    for &id in reachable_symbols.iter() {
+        worklist.push(id);
+    }
+
+    // Seed entry point
+    match *tcx.sess.entry_fn {
+        Some((id, _)) => worklist.push(id),
+        None => ()
+    }
+
+    // Seed lang items, which the compiler itself may call
+    for (_, item) in tcx.lang_items.items() {
+        match *item {
+            Some(def_id) if is_local(def_id) => worklist.push(def_id.node),
+            _ => ()
+        }
+    }
+
+    // Seed destructors, which are invoked implicitly
+    for (_, &dtor_id) in tcx.destructor_for_type.iter() {
+        if is_local(dtor_id) {
+            worklist.push(dtor_id.node);
+        }
+    }
+
+    // Seed implemented trait methods and tests
+    let mut trait_method_seeder = TraitMethodSeeder {
+        worklist: worklist
+    };
+    visit::walk_crate(&mut trait_method_seeder, crate, ());
+
+    return trait_method_seeder.worklist;
+}
+
+fn find_live(tcx: ty::ctxt,
+             method_map: typeck::method_map,
+             exported_items: &privacy::ExportedItems,
+             reachable_symbols: @mut HashSet<ast::NodeId>,
+             crate: &ast::Crate)
+             -> ~HashSet<ast::NodeId> {
+    let worklist = create_and_seed_worklist(tcx, exported_items,
+                                            reachable_symbols, crate);
+    let mut symbol_visitor = MarkSymbolVisitor::new(tcx, method_map,
+                                                    worklist);
+    symbol_visitor.mark_live_symbols();
+    symbol_visitor.live_symbols
+}
+
+fn should_warn(item: @ast::item) -> bool {
+    match item.node {
+        ast::item_static(*)
+        | ast::item_fn(*)
+        | ast::item_enum(*)
+        | ast::item_struct(*) => true,
+        _ => false
+    }
+}
+
+struct DeadVisitor {
+    tcx: ty::ctxt,
+    live_symbols: ~HashSet<ast::NodeId>,
+}
+
+impl DeadVisitor {
+    fn symbol_is_live(&self, id: ast::NodeId) -> bool {
+        self.live_symbols.contains(&id)
+    }
+
+    fn warn_dead_code(&mut self, id: ast::NodeId,
+                      span: codemap::Span, ident: ast::Ident) {
+        // Code generated by syntax extensions is not the user's to remove.
+        if span.expn_info.is_some() {
+            return;
+        }
+        self.tcx.sess.add_lint(dead_code, id, span,
+                               format!("code is never used: `{}`",
+                                       token::ident_to_str(&ident)));
+    }
+}
+
+impl Visitor<()> for DeadVisitor {
+    fn visit_item(&mut self, item: @ast::item, _: ()) {
+        if should_warn(item) && !self.symbol_is_live(item.id) {
+            self.warn_dead_code(item.id, item.span, item.ident);
+            // The variants of a dead enum are dead along with it, there's no
+            // need to report each of them as well.
+            match item.node {
+                ast::item_enum(*) => return,
+                _ => ()
+            }
+        }
+        visit::walk_item(self, item, ());
+    }
+
+    // A variant is live once it is named anywhere, be it to construct a value
+    // or in a pattern.
+    fn visit_variant(&mut self, variant: &ast::variant,
+                     generics: &ast::Generics, _: ()) {
+        if !self.symbol_is_live(variant.node.id) {
+            self.warn_dead_code(variant.node.id, variant.span,
+                                variant.node.name);
+        }
+        visit::walk_variant(self, variant, generics, ());
+    }
+
+    fn visit_fn(&mut self, fk: &visit::fn_kind,
+                _: &ast::fn_decl, block: &ast::Block,
+                span: codemap::Span, id: ast::NodeId, _: ()) {
+        // Have to warn method here because methods are not ast::item
+        match *fk {
+            visit::fk_method(ident, _, method) => {
+                if !self.symbol_is_live(id) && is_inherent_method(self.tcx,
+                                                                  method) {
+                    self.warn_dead_code(id, span, ident);
+                }
+            }
+            _ => ()
+        }
+        visit::walk_block(self, block, ());
+    }
+
+    // Overwrite so that we don't warn the trait method itself.
+    fn visit_trait_method(&mut self, trait_method: &ast::trait_method, _: ()) {
+        match *trait_method {
+            ast::provided(method) => visit::walk_block(self, &method.body, ()),
+            ast::required(_) => ()
+        }
+    }
+}
+
+// Methods of trait impls are always seeded as live, so only methods of
+// inherent impls can ever be reported.
+fn is_inherent_method(tcx: ty::ctxt, method: &ast::method) -> bool {
+    match tcx.items.find(&method.id) {
+        Some(&ast_map::node_method(_, impl_did, _)) if is_local(impl_did) => {
+            match tcx.items.find(&impl_did.node) {
+                Some(&ast_map::node_item(item, _)) => {
+                    match item.node {
+                        ast::item_impl(_, None, _, _) => true,
+                        _ => false
+                    }
+                }
+                _ => false
+            }
+        }
+        _ => false
+    }
+}
+
+pub fn check_crate(tcx: ty::ctxt,
+                   method_map: typeck::method_map,
+                   exported_items: &privacy::ExportedItems,
+                   reachable_symbols: @mut HashSet<ast::NodeId>,
+                   crate: &ast::Crate) {
+    let live_symbols = find_live(tcx, method_map, exported_items,
+                                 reachable_symbols, crate);
+    let mut visitor = DeadVisitor { tcx: tcx, live_symbols: live_symbols };
+    visit::walk_crate(&mut visitor, crate, ());
+}
@@ -182,17 +182,6 @@ fn with_appropriate_checker(cx: &Context,
         check_freevar_bounds(cx, fv.span, var_t, bounds, None);
     }
 
-    fn check_for_box(cx: &Context, fv: &freevar_entry, bounds: ty::BuiltinBounds) {
-        // all captured data must be owned
-        let id = ast_util::def_id_of_def(fv.def).node;
-        let var_t = ty::node_id_to_type(cx.tcx, id);
-
-        // check that only immutable variables are implicitly copied in
-        check_imm_free_var(cx, fv.def, fv.span);
-
-        check_freevar_bounds(cx, fv.span, var_t, bounds, None);
-    }
-
     fn check_for_block(cx: &Context, fv: &freevar_entry,
                        bounds: ty::BuiltinBounds, region: ty::Region) {
         let id = ast_util::def_id_of_def(fv.def).node;
@@ -421,20 +410,6 @@ pub fn check_trait_cast_bounds(cx: &Context, sp: Span, ty: ty::t,
     });
 }
 
-fn is_nullary_variant(cx: &Context, ex: @Expr) -> bool {
-    match ex.node {
-      ExprPath(_) => {
-        match cx.tcx.def_map.get_copy(&ex.id) {
-          DefVariant(edid, vdid, _) => {
-              ty::enum_variant_with_id(cx.tcx, edid, vdid).args.is_empty()
-          }
-          _ => false
-        }
-      }
-      _ => false
-    }
-}
-
 fn check_imm_free_var(cx: &Context, def: Def, sp: Span) {
     match def {
         DefLocal(_, BindByValue(MutMutable)) => {
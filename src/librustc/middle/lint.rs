@@ -69,6 +69,8 @@ pub enum lint {
     unnecessary_qualification,
     while_true,
     path_statement,
+    unused_must_use,
+    unused_result,
     unrecognized_lint,
     non_camel_case_types,
     non_uppercase_statics,
@@ -88,6 +90,7 @@ pub enum lint {
     dead_assignment,
     unused_mut,
     unnecessary_allocation,
+    dead_code,
 
     missing_doc,
     unreachable_code,
@@ -169,6 +172,20 @@ static lint_table: &'static [(&'static str, LintSpec)] = &[
         default: warn
      }),
 
+    ("unused_must_use",
+     LintSpec {
+        lint: unused_must_use,
+        desc: "unused result of a type flagged as #[must_use]",
+        default: warn
+    }),
+
+    ("unused_result",
+     LintSpec {
+        lint: unused_result,
+        desc: "unused result of an expression in a statement",
+        default: allow
+    }),
+
     ("unrecognized_lint",
      LintSpec {
         lint: unrecognized_lint,
@@ -282,6 +299,13 @@ static lint_table: &'static [(&'static str, LintSpec)] = &[
         default: warn
     }),
 
+    ("dead_code",
+     LintSpec {
+        lint: dead_code,
+        desc: "detect piece of code that will never be used",
+        default: warn
+    }),
+
     ("missing_doc",
      LintSpec {
         lint: missing_doc,
@@ -427,8 +451,12 @@ impl<'self> Context<'self> {
             }
         };
         match level {
-            warn =>          { self.tcx.sess.span_warn(span, msg); }
-            deny | forbid => { self.tcx.sess.span_err(span, msg);  }
+            warn => {
+                self.tcx.sess.span_lint_warn(span, msg, self.lint_to_str(lint));
+            }
+            deny | forbid => {
+                self.tcx.sess.span_lint_err(span, msg, self.lint_to_str(lint));
+            }
             allow => fail!(),
         }
 
@@ -830,14 +858,17 @@ static other_attrs: &'static [&'static str] = &[
     "deprecated", "experimental", "unstable", "stable", "locked", "frozen", //item stability
     "crate_map", "cfg", "doc", "export_name", "link_section", "no_freeze",
     "no_mangle", "no_send", "static_assert", "unsafe_no_drop_flag",
-    "packed", "simd", "repr", "deriving", "unsafe_destructor",
+    "packed", "simd", "repr", "deriving", "unsafe_destructor", "must_use",
 
     //mod-level
     "path", "link_name", "link_args", "nolink", "macro_escape", "no_implicit_prelude",
 
     // fn-level
     "test", "bench", "should_fail", "ignore", "inline", "lang", "main", "start",
-    "no_split_stack", "cold",
+    "no_split_stack", "cold", "macro_registrar",
+
+    // view-item-level
+    "phase",
 
     // internal attribute: bypass privacy inside items
     "!resolve_unexported",
@@ -900,6 +931,53 @@ fn check_path_statement(cx: &Context, s: &ast::Stmt) {
     }
 }
 
+fn check_unused_result(cx: &Context, s: &ast::Stmt) {
+    let expr = match s.node {
+        ast::StmtSemi(expr, _) => expr,
+        _ => return
+    };
+    let t = ty::expr_ty(cx.tcx, expr);
+    match ty::get(t).sty {
+        ty::ty_nil | ty::ty_bot => return,
+        _ => {}
+    }
+    match expr.node {
+        ast::ExprRet(*) => return,
+        _ => {}
+    }
+
+    let mut warned = false;
+    match ty::get(t).sty {
+        ty::ty_struct(did, _) |
+        ty::ty_enum(did, _) => {
+            if ast_util::is_local(did) {
+                match cx.tcx.items.get_copy(&did.node) {
+                    ast_map::node_item(it, _) => {
+                        if attr::contains_name(it.attrs, "must_use") {
+                            cx.span_lint(unused_must_use, s.span,
+                                         "unused result which must be used");
+                            warned = true;
+                        }
+                    }
+                    _ => {}
+                }
+            } else {
+                csearch::get_item_attrs(cx.tcx.cstore, did, |attrs| {
+                    if attr::contains_name(attrs, "must_use") {
+                        cx.span_lint(unused_must_use, s.span,
+                                     "unused result which must be used");
+                        warned = true;
+                    }
+                });
+            }
+        }
+        _ => {}
+    }
+    if !warned {
+        cx.span_lint(unused_result, s.span, "unused result");
+    }
+}
+
 fn check_item_non_camel_case_types(cx: &Context, it: &ast::item) {
     fn is_camel_case(cx: ty::ctxt, ident: ast::Ident) -> bool {
         let ident = cx.sess.str_of(ident);
@@ -1262,6 +1340,7 @@ impl<'self> Visitor<()> for Context<'self> {
 
     fn visit_stmt(&mut self, s: @ast::Stmt, _: ()) {
         check_path_statement(self, s);
+        check_unused_result(self, s);
 
         visit::walk_stmt(self, s, ());
     }
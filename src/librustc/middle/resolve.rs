@@ -52,12 +52,6 @@ type BindingMap = HashMap<Name,binding_info>;
 // Trait method resolution
 pub type TraitMap = HashMap<NodeId,@mut ~[DefId]>;
 
-// A summary of the generics on a trait.
-struct TraitGenerics {
-    has_lifetime: bool,
-    type_parameter_count: uint,
-}
-
 // This is the replacement export map. It maps a module to all of the exports
 // within.
 pub type ExportMap2 = @mut HashMap<NodeId, ~[Export2]>;
@@ -5435,39 +5429,6 @@ impl Resolver {
         return self.idents_to_str(idents.move_rev_iter().collect::<~[ast::Ident]>());
     }
 
-    fn dump_module(&mut self, module_: @mut Module) {
-        debug!("Dump of module `{}`:", self.module_to_str(module_));
-
-        debug!("Children:");
-        self.populate_module_if_necessary(module_);
-        for (&name, _) in module_.children.iter() {
-            debug!("* {}", interner_get(name));
-        }
-
-        debug!("Import resolutions:");
-        for (name, import_resolution) in module_.import_resolutions.iter() {
-            let value_repr;
-            match import_resolution.target_for_namespace(ValueNS) {
-                None => { value_repr = ~""; }
-                Some(_) => {
-                    value_repr = ~" value:?";
-                    // FIXME #4954
-                }
-            }
-
-            let type_repr;
-            match import_resolution.target_for_namespace(TypeNS) {
-                None => { type_repr = ~""; }
-                Some(_) => {
-                    type_repr = ~" type:?";
-                    // FIXME #4954
-                }
-            }
-
-            debug!("* {}:{}{}", interner_get(*name),
-                   value_repr, type_repr);
-        }
-    }
 }
 
 pub struct CrateMap {
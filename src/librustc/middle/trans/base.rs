@@ -68,9 +68,10 @@ use util::ppaux::{Repr, ty_to_str};
 use middle::trans::type_::Type;
 
 use std::c_str::ToCStr;
-use std::hash;
+use std::hash::SipState;
 use std::hashmap::HashMap;
 use std::libc::c_uint;
+use std::str;
 use std::vec;
 use std::local_data;
 use extra::time;
@@ -1989,6 +1990,30 @@ pub fn trans_fn(ccx: @mut CrateContext,
                   attrs,
                   output_type,
                   |_fcx| { });
+
+    // Monomorphizations aren't assigned a unit of their own, they are copied
+    // into whichever units use them.
+    if param_substs.is_none() {
+        assign_codegen_unit(ccx, llfndecl);
+    }
+}
+
+// Moves on to the next codegen unit, which the items translated from now on
+// belong to. Units are handed out one item at a time, so that the methods of
+// an impl stay together.
+fn next_codegen_unit(ccx: @mut CrateContext) {
+    ccx.codegen_unit = (ccx.codegen_unit + 1) % ccx.sess.opts.codegen_units;
+}
+
+// Records that the definition of `llval` belongs to the current codegen unit.
+// This is what back::link::write partitions the crate by.
+pub fn assign_codegen_unit(ccx: @mut CrateContext, llval: ValueRef) {
+    if ccx.sess.opts.codegen_units > 1 {
+        let name = unsafe {
+            str::raw::from_c_str(llvm::LLVMGetValueName(llval))
+        };
+        ccx.codegen_unit_symbols.push((name, ccx.codegen_unit));
+    }
 }
 
 fn insert_synthetic_type_entries(bcx: @mut Block,
@@ -2186,6 +2211,12 @@ pub fn trans_item(ccx: @mut CrateContext, item: &ast::item) {
         // tjc: ?
         _ => fail!("trans_item"),
     };
+    match item.node {
+        ast::item_fn(*) | ast::item_impl(*) | ast::item_static(*) => {
+            next_codegen_unit(ccx);
+        }
+        _ => {}
+    }
     match item.node {
       ast::item_fn(ref decl, purity, _abis, ref generics, ref body) => {
         if purity == ast::extern_fn  {
@@ -3074,15 +3105,16 @@ pub fn crate_ctxt_to_encode_parms<'r>(cx: &'r CrateContext, ie: encoder::encode_
         }
 }
 
-pub fn write_metadata(cx: &CrateContext, crate: &ast::Crate) {
-    if !*cx.sess.building_library { return; }
+pub fn write_metadata(cx: &CrateContext, crate: &ast::Crate) -> ~[u8] {
+    if !*cx.sess.building_library { return ~[]; }
 
     let encode_inlined_item: encoder::encode_inlined_item =
         |ecx, ebml_w, path, ii|
         astencode::encode_inlined_item(ecx, ebml_w, path, ii, cx.maps);
 
     let encode_parms = crate_ctxt_to_encode_parms(cx, encode_inlined_item);
-    let llmeta = C_bytes(encoder::encode_metadata(encode_parms, crate));
+    let metadata = encoder::encode_metadata(encode_parms, crate);
+    let llmeta = C_bytes(metadata);
     let llconst = C_struct([llmeta], false);
     let mut llglobal = "rust_metadata".with_c_str(|buf| {
         unsafe {
@@ -3104,6 +3136,7 @@ pub fn write_metadata(cx: &CrateContext, crate: &ast::Crate) {
         lib::llvm::SetLinkage(llvm_used, lib::llvm::AppendingLinkage);
         llvm::LLVMSetInitializer(llvm_used, C_array(t_ptr_i8, [llglobal]));
     }
+    metadata
 }
 
 // Writes the current ABI version into the crate.
@@ -3127,7 +3160,7 @@ pub fn trans_crate(sess: session::Session,
         sess.bug("couldn't enable multi-threaded LLVM");
     }
 
-    let mut symbol_hasher = hash::default_state();
+    let mut symbol_hasher = SipState::new(0, 0);
     let link_meta = link::build_link_meta(sess, &crate, output,
                                           &mut symbol_hasher);
 
@@ -3183,7 +3216,7 @@ pub fn trans_crate(sess: session::Session,
     }
 
     // Translate the metadata.
-    write_metadata(ccx, &crate);
+    let metadata = write_metadata(ccx, &crate);
     if ccx.sess.trans_stats() {
         println("--- trans stats ---");
         println!("n_static_tydescs: {}", ccx.stats.n_static_tydescs);
@@ -3217,10 +3250,13 @@ pub fn trans_crate(sess: session::Session,
     let llcx = ccx.llcx;
     let link_meta = ccx.link_meta;
     let llmod = ccx.llmod;
+    let codegen_units = ccx.codegen_unit_symbols.clone();
 
     return CrateTranslation {
         context: llcx,
         module: llmod,
-        link: link_meta
+        link: link_meta,
+        metadata: metadata,
+        codegen_units: codegen_units
     };
 }
@@ -645,5 +645,6 @@ pub fn trans_const(ccx: @mut CrateContext, m: ast::Mutability, id: ast::NodeId)
         if m != ast::MutMutable {
             llvm::LLVMSetGlobalConstant(g, True);
         }
+        base::assign_codegen_unit(ccx, g);
     }
 }
@@ -27,7 +27,7 @@ use middle::ty;
 use middle::trans::type_::Type;
 
 use std::c_str::ToCStr;
-use std::hash;
+use std::hash::SipState;
 use std::hashmap::{HashMap, HashSet};
 use std::local_data;
 use std::vec;
@@ -97,7 +97,7 @@ pub struct CrateContext {
      lltypes: HashMap<ty::t, Type>,
      llsizingtypes: HashMap<ty::t, Type>,
      adt_reprs: HashMap<ty::t, @adt::Repr>,
-     symbol_hasher: hash::State,
+     symbol_hasher: SipState,
      type_hashcodes: HashMap<ty::t, @str>,
      type_short_names: HashMap<ty::t, ~str>,
      all_llvm_symbols: HashSet<@str>,
@@ -115,7 +115,12 @@ pub struct CrateContext {
      // is not emitted by LLVM's GC pass when no functions use GC.
      uses_gc: bool,
      dbg_cx: Option<debuginfo::CrateDebugContext>,
-     do_not_commit_warning_issued: bool
+     do_not_commit_warning_issued: bool,
+     // The codegen unit which the items being translated belong to, and the
+     // symbols which have been assigned to a unit so far. Only used with
+     // more than one codegen unit.
+     codegen_unit: uint,
+     codegen_unit_symbols: ~[(~str, uint)]
 }
 
 impl CrateContext {
@@ -124,7 +129,7 @@ impl CrateContext {
                tcx: ty::ctxt,
                emap2: resolve::ExportMap2,
                maps: astencode::Maps,
-               symbol_hasher: hash::State,
+               symbol_hasher: SipState,
                link_meta: LinkMeta,
                reachable: @mut HashSet<ast::NodeId>)
                -> CrateContext {
@@ -231,7 +236,9 @@ impl CrateContext {
                   crate_map: crate_map,
                   uses_gc: false,
                   dbg_cx: dbg_cx,
-                  do_not_commit_warning_issued: false
+                  do_not_commit_warning_issued: false,
+                  codegen_unit: 0,
+                  codegen_unit_symbols: ~[]
             }
         }
     }
@@ -132,7 +132,7 @@ pub struct DatumBlock {
     datum: Datum,
 }
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub enum DatumMode {
     /// `val` is a pointer to the actual value (and thus has type *T).
     /// The argument indicates how to cancel cleanup of this datum if
@@ -155,7 +155,7 @@ impl DatumMode {
 }
 
 /// See `Datum cleanup styles` section at the head of this module.
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub enum DatumCleanup {
     RevokeClean,
     ZeroMem
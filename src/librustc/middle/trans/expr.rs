@@ -176,12 +176,6 @@ impl Dest {
     }
 }
 
-fn drop_and_cancel_clean(bcx: @mut Block, dat: Datum) -> @mut Block {
-    let bcx = dat.drop_val(bcx);
-    dat.cancel_clean(bcx);
-    return bcx;
-}
-
 pub fn trans_to_datum(bcx: @mut Block, expr: &ast::Expr) -> DatumBlock {
     debug!("trans_to_datum(expr={})", bcx.expr_to_str(expr));
 
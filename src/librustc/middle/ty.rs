@@ -34,7 +34,7 @@ use std::cmp;
 use std::hashmap::{HashMap, HashSet};
 use std::ops;
 use std::ptr::to_unsafe_ptr;
-use std::to_bytes;
+use std::hash::{Hash, Hasher};
 use std::to_str::ToStr;
 use std::vec;
 use syntax::ast::*;
@@ -58,7 +58,7 @@ pub static INITIAL_DISCRIMINANT_VALUE: Disr = 0;
 
 // Data types
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub struct field {
     ident: ast::Ident,
     mt: mt
@@ -130,13 +130,13 @@ pub struct Impl {
     methods: ~[@Method]
 }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct mt {
     ty: t,
     mutbl: ast::Mutability,
 }
 
-#[deriving(Clone, Eq, Encodable, Decodable, IterBytes, ToStr)]
+#[deriving(Clone, Eq, Encodable, Decodable, Hash, ToStr)]
 pub enum vstore {
     vstore_fixed(uint),
     vstore_uniq,
@@ -144,7 +144,7 @@ pub enum vstore {
     vstore_slice(Region)
 }
 
-#[deriving(Clone, Eq, IterBytes, Encodable, Decodable, ToStr)]
+#[deriving(Clone, Eq, Hash, Encodable, Decodable, ToStr)]
 pub enum TraitStore {
     BoxTraitStore,              // @Trait
     UniqTraitStore,             // ~Trait
@@ -167,7 +167,7 @@ pub struct field_ty {
 
 // Contains information needed to resolve types and (in the future) look up
 // the types of AST nodes.
-#[deriving(Eq,IterBytes)]
+#[deriving(Eq,Hash)]
 pub struct creader_cache_key {
     cnum: CrateNum,
     pos: uint,
@@ -194,12 +194,12 @@ impl cmp::Eq for intern_key {
     }
 }
 
-// NB: Do not replace this with #[deriving(IterBytes)], as above. (Figured
+// NB: Do not replace this with #[deriving(Hash)], as above. (Figured
 // this out the hard way.)
-impl to_bytes::IterBytes for intern_key {
-    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
+impl Hash for intern_key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
         unsafe {
-            (*self.sty).iter_bytes(lsb0, f)
+            (*self.sty).hash(state)
         }
     }
 }
@@ -429,14 +429,14 @@ pub fn type_has_regions(t: t) -> bool {
 }
 pub fn type_id(t: t) -> uint { get(t).id }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct BareFnTy {
     purity: ast::purity,
     abis: AbiSet,
     sig: FnSig
 }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct ClosureTy {
     purity: ast::purity,
     sigil: ast::Sigil,
@@ -458,7 +458,7 @@ pub struct ClosureTy {
  * - `output` is the return type.
  * - `variadic` indicates whether this is a varidic function. (only true for foreign fns)
  */
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct FnSig {
     binder_id: ast::NodeId,
     inputs: ~[t],
@@ -466,14 +466,14 @@ pub struct FnSig {
     variadic: bool
 }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct param_ty {
     idx: uint,
     def_id: DefId
 }
 
 /// Representation of regions:
-#[deriving(Clone, Eq, IterBytes, Encodable, Decodable, ToStr)]
+#[deriving(Clone, Eq, Hash, Encodable, Decodable, ToStr)]
 pub enum Region {
     // Region bound in a type or fn declaration which will be
     // substituted 'early' -- that is, at the same time when type
@@ -519,13 +519,13 @@ impl Region {
     }
 }
 
-#[deriving(Clone, Eq, TotalOrd, TotalEq, IterBytes, Encodable, Decodable, ToStr)]
+#[deriving(Clone, Eq, TotalOrd, TotalEq, Hash, Encodable, Decodable, ToStr)]
 pub struct FreeRegion {
     scope_id: NodeId,
     bound_region: BoundRegion
 }
 
-#[deriving(Clone, Eq, TotalEq, TotalOrd, IterBytes, Encodable, Decodable, ToStr)]
+#[deriving(Clone, Eq, TotalEq, TotalOrd, Hash, Encodable, Decodable, ToStr)]
 pub enum BoundRegion {
     /// An anonymous region parameter for a given fn (&T)
     BrAnon(uint),
@@ -544,7 +544,7 @@ pub enum BoundRegion {
  * Represents the values to use when substituting lifetime parameters.
  * If the value is `ErasedRegions`, then this subst is occurring during
  * trans, and all region parameters will be replaced with `ty::ReStatic`. */
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub enum RegionSubsts {
     ErasedRegions,
     NonerasedRegions(OptVec<ty::Region>)
@@ -567,7 +567,7 @@ pub enum RegionSubsts {
  * - `self_ty` is the type to which `self` should be remapped, if any.  The
  *   `self` type is rather funny in that it can only appear on traits and is
  *   always substituted away to the implementing type for a trait. */
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct substs {
     self_ty: Option<ty::t>,
     tps: ~[t],
@@ -622,7 +622,7 @@ mod primitives {
 
 // NB: If you change this, you'll probably want to change the corresponding
 // AST structure in libsyntax/ast.rs as well.
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub enum sty {
     ty_nil,
     ty_bot,
@@ -660,7 +660,7 @@ pub enum sty {
     ty_unboxed_vec(mt),
 }
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub struct TraitRef {
     def_id: DefId,
     substs: substs
@@ -722,7 +722,7 @@ pub enum type_err {
     terr_variadic_mismatch(expected_found<bool>)
 }
 
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, Hash)]
 pub struct ParamBounds {
     builtin_bounds: BuiltinBounds,
     trait_bounds: ~[@TraitRef]
@@ -730,7 +730,7 @@ pub struct ParamBounds {
 
 pub type BuiltinBounds = EnumSet<BuiltinBound>;
 
-#[deriving(Clone, Eq, IterBytes, ToStr)]
+#[deriving(Clone, Eq, Hash, ToStr)]
 #[repr(uint)]
 pub enum BuiltinBound {
     BoundStatic,
@@ -761,28 +761,28 @@ impl CLike for BuiltinBound {
     }
 }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct TyVid(uint);
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct IntVid(uint);
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub struct FloatVid(uint);
 
-#[deriving(Clone, Eq, Encodable, Decodable, IterBytes)]
+#[deriving(Clone, Eq, Encodable, Decodable, Hash)]
 pub struct RegionVid {
     id: uint
 }
 
-#[deriving(Clone, Eq, IterBytes)]
+#[deriving(Clone, Eq, Hash)]
 pub enum InferTy {
     TyVar(TyVid),
     IntVar(IntVid),
     FloatVar(FloatVid)
 }
 
-#[deriving(Clone, Encodable, Decodable, IterBytes, ToStr)]
+#[deriving(Clone, Encodable, Decodable, Hash, ToStr)]
 pub enum InferRegion {
     ReVar(RegionVid),
     ReSkolemized(uint, BoundRegion)
@@ -4565,7 +4565,7 @@ pub fn trait_of_method(tcx: ctxt, def_id: ast::DefId)
 /// Creates a hash of the type `t` which will be the same no matter what crate
 /// context it's calculated within. This is used by the `type_id` intrinsic.
 pub fn hash_crate_independent(tcx: ctxt, t: t, local_hash: @str) -> u64 {
-    use std::hash::{SipState, Streaming};
+    use std::hash::{Hasher, SipState};
     use metadata::cstore;
 
     let mut hash = SipState::new(0, 0);
@@ -4585,11 +4585,11 @@ pub fn hash_crate_independent(tcx: ctxt, t: t, local_hash: @str) -> u64 {
     };
     let vstore = |hash: &mut SipState, v: vstore| {
         match v {
-            vstore_fixed(_) => hash.input([0]),
-            vstore_uniq => hash.input([1]),
-            vstore_box => hash.input([2]),
+            vstore_fixed(_) => hash.write([0]),
+            vstore_uniq => hash.write([1]),
+            vstore_box => hash.write([2]),
             vstore_slice(r) => {
-                hash.input([3]);
+                hash.write([3]);
                 region(hash, r);
             }
         }
@@ -4600,70 +4600,70 @@ pub fn hash_crate_independent(tcx: ctxt, t: t, local_hash: @str) -> u64 {
         } else {
             cstore::get_crate_hash(tcx.sess.cstore, did.crate)
         };
-        hash.input(h.as_bytes());
+        hash.write(h.as_bytes());
         iter(hash, &did.node);
     };
     let mt = |hash: &mut SipState, mt: mt| {
         iter(hash, &mt.mutbl);
     };
-    fn iter<T: IterBytes>(hash: &mut SipState, t: &T) {
-        t.iter_bytes(true, |bytes| { hash.input(bytes); true });
+    fn iter<T: Hash>(hash: &mut SipState, t: &T) {
+        t.hash(hash);
     }
     ty::walk_ty(t, |t| {
         match ty::get(t).sty {
-            ty_nil => hash.input([0]),
-            ty_bot => hash.input([1]),
-            ty_bool => hash.input([2]),
-            ty_char => hash.input([3]),
+            ty_nil => hash.write([0]),
+            ty_bot => hash.write([1]),
+            ty_bool => hash.write([2]),
+            ty_char => hash.write([3]),
             ty_int(i) => {
-                hash.input([4]);
+                hash.write([4]);
                 iter(&mut hash, &i);
             }
             ty_uint(u) => {
-                hash.input([5]);
+                hash.write([5]);
                 iter(&mut hash, &u);
             }
             ty_float(f) => {
-                hash.input([6]);
+                hash.write([6]);
                 iter(&mut hash, &f);
             }
             ty_estr(v) => {
-                hash.input([7]);
+                hash.write([7]);
                 vstore(&mut hash, v);
             }
             ty_enum(d, _) => {
-                hash.input([8]);
+                hash.write([8]);
                 did(&mut hash, d);
             }
             ty_box(m) => {
-                hash.input([9]);
+                hash.write([9]);
                 mt(&mut hash, m);
             }
             ty_uniq(m) => {
-                hash.input([10]);
+                hash.write([10]);
                 mt(&mut hash, m);
             }
             ty_evec(m, v) => {
-                hash.input([11]);
+                hash.write([11]);
                 mt(&mut hash, m);
                 vstore(&mut hash, v);
             }
             ty_ptr(m) => {
-                hash.input([12]);
+                hash.write([12]);
                 mt(&mut hash, m);
             }
             ty_rptr(r, m) => {
-                hash.input([13]);
+                hash.write([13]);
                 region(&mut hash, r);
                 mt(&mut hash, m);
             }
             ty_bare_fn(ref b) => {
-                hash.input([14]);
+                hash.write([14]);
                 iter(&mut hash, &b.purity);
                 iter(&mut hash, &b.abis);
             }
             ty_closure(ref c) => {
-                hash.input([15]);
+                hash.write([15]);
                 iter(&mut hash, &c.purity);
                 iter(&mut hash, &c.sigil);
                 iter(&mut hash, &c.onceness);
@@ -4671,13 +4671,13 @@ pub fn hash_crate_independent(tcx: ctxt, t: t, local_hash: @str) -> u64 {
                 region(&mut hash, c.region);
             }
             ty_trait(d, _, store, m, bounds) => {
-                hash.input([17]);
+                hash.write([17]);
                 did(&mut hash, d);
                 match store {
-                    BoxTraitStore => hash.input([0]),
-                    UniqTraitStore => hash.input([1]),
+                    BoxTraitStore => hash.write([0]),
+                    UniqTraitStore => hash.write([1]),
                     RegionTraitStore(r) => {
-                        hash.input([2]);
+                        hash.write([2]);
                         region(&mut hash, r);
                     }
                 }
@@ -4685,38 +4685,38 @@ pub fn hash_crate_independent(tcx: ctxt, t: t, local_hash: @str) -> u64 {
                 iter(&mut hash, &bounds);
             }
             ty_struct(d, _) => {
-                hash.input([18]);
+                hash.write([18]);
                 did(&mut hash, d);
             }
             ty_tup(ref inner) => {
-                hash.input([19]);
+                hash.write([19]);
                 iter(&mut hash, &inner.len());
             }
             ty_param(p) => {
-                hash.input([20]);
+                hash.write([20]);
                 iter(&mut hash, &p.idx);
                 did(&mut hash, p.def_id);
             }
             ty_self(d) => {
-                hash.input([21]);
+                hash.write([21]);
                 did(&mut hash, d);
             }
             ty_infer(_) => unreachable!(),
-            ty_err => hash.input([23]),
-            ty_type => hash.input([24]),
-            ty_opaque_box => hash.input([25]),
+            ty_err => hash.write([23]),
+            ty_type => hash.write([24]),
+            ty_opaque_box => hash.write([25]),
             ty_opaque_closure_ptr(s) => {
-                hash.input([26]);
+                hash.write([26]);
                 iter(&mut hash, &s);
             }
             ty_unboxed_vec(m) => {
-                hash.input([27]);
+                hash.write([27]);
                 mt(&mut hash, m);
             }
         }
     });
 
-    hash.result_u64()
+    hash.finish()
 }
 
 impl Variance {
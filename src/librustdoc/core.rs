@@ -37,7 +37,8 @@ pub struct CrateAnalysis {
 
 /// Parses, resolves, and typechecks the given crate
 fn get_ast_and_resolve(cpath: &Path,
-                       libs: HashSet<Path>, cfgs: ~[~str]) -> (DocContext, CrateAnalysis) {
+                       libs: HashSet<Path>, cfgs: ~[~str],
+                       json_errors: bool) -> (DocContext, CrateAnalysis) {
     use syntax::codemap::dummy_spanned;
     use rustc::driver::driver::{file_input, build_configuration,
                                 phase_1_parse_input,
@@ -55,14 +56,18 @@ fn get_ast_and_resolve(cpath: &Path,
     };
 
 
-    let diagnostic_handler = syntax::diagnostic::mk_handler(None);
+    let emitter = if json_errors {
+        @diagnostic::JsonEmitter::new() as @diagnostic::Emitter
+    } else {
+        @diagnostic::DefaultEmitter as @diagnostic::Emitter
+    };
+    let diagnostic_handler = syntax::diagnostic::mk_handler(Some(emitter));
     let span_diagnostic_handler =
         syntax::diagnostic::mk_span_handler(diagnostic_handler, parsesess.cm);
 
     let sess = driver::driver::build_session_(sessopts,
                                               parsesess.cm,
-                                              @diagnostic::DefaultEmitter as
-                                                @diagnostic::Emitter,
+                                              emitter,
                                               span_diagnostic_handler);
 
     let mut cfg = build_configuration(sess);
@@ -75,14 +80,16 @@ fn get_ast_and_resolve(cpath: &Path,
     let driver::driver::CrateAnalysis {
         exported_items, ty_cx, _
     } = phase_3_run_analysis_passes(sess, &crate);
+    emitter.flush();
 
     debug!("crate: {:?}", crate);
     return (DocContext { crate: crate, tycx: ty_cx, sess: sess },
             CrateAnalysis { exported_items: exported_items });
 }
 
-pub fn run_core (libs: HashSet<Path>, cfgs: ~[~str], path: &Path) -> (clean::Crate, CrateAnalysis) {
-    let (ctxt, analysis) = get_ast_and_resolve(path, libs, cfgs);
+pub fn run_core (libs: HashSet<Path>, cfgs: ~[~str], json_errors: bool,
+                 path: &Path) -> (clean::Crate, CrateAnalysis) {
+    let (ctxt, analysis) = get_ast_and_resolve(path, libs, cfgs, json_errors);
     let ctxt = @ctxt;
     debug!("defmap:");
     for (k, v) in ctxt.tycx.def_map.iter() {
@@ -104,6 +104,8 @@ pub fn opts() -> ~[groups::OptGroup] {
         optmulti("", "plugins", "space separated list of plugins to also load",
                  "PLUGINS"),
         optflag("", "no-defaults", "don't run the default passes"),
+        optopt("", "error-format", "how to print compiler diagnostics",
+               "[human|json]"),
     ]
 }
 
@@ -168,6 +170,11 @@ fn acquire_input(matches: &getopts::Matches) -> Result<Output, ~str> {
         return Err(~"only one input file may be specified");
     }
 
+    match matches.opt_str("error-format") {
+        Some(~"human") | Some(~"json") | None => {}
+        Some(s) => return Err("unknown error format: " + s),
+    }
+
     let input = matches.free[0].as_slice();
     match matches.opt_str("r") {
         Some(~"rust") => Ok(rust_input(input, matches)),
@@ -197,10 +204,12 @@ fn rust_input(cratefile: &str, matches: &getopts::Matches) -> Output {
     let libs = Cell::new(matches.opt_strs("L").map(|s| Path::new(s.as_slice())));
     let cfgs = Cell::new(matches.opt_strs("cfg"));
     let cr = Cell::new(Path::new(cratefile));
+    let json_errors = matches.opt_str("error-format") == Some(~"json");
     info!("starting to run rustc");
     let (crate, analysis) = do std::task::try {
         let cr = cr.take();
-        core::run_core(libs.take().move_iter().collect(), cfgs.take(), &cr)
+        core::run_core(libs.take().move_iter().collect(), cfgs.take(),
+                       json_errors, &cr)
     }.unwrap();
     info!("finished with rustc");
     local_data::set(analysiskey, analysis);
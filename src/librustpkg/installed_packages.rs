@@ -31,8 +31,14 @@ pub fn list_installed_packages(f: |&PkgId| -> bool) -> bool  {
                 }
             }
         }
-        let libfiles = io::ignore_io_error(|| fs::readdir(&p.join("lib")));
-        for lib in libfiles.iter() {
+        // Plain files can't contain libraries, and their directory entries
+        // say as much without having to look inside of them.
+        let libdirs = io::ignore_io_error(|| {
+            fs::dir_entries(&p.join("lib")).filter(|entry| {
+                entry.kind != io::TypeFile
+            }).map(|entry| entry.path).collect::<~[Path]>()
+        });
+        for lib in libdirs.iter() {
             debug!("Full name: {}", lib.display());
             match has_library(lib) {
                 Some(basename) => {
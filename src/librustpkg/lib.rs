@@ -924,12 +924,3 @@ pub fn main_args(args: &[~str]) -> int {
     if result.is_err() { return COPY_FAILED_CODE; }
     return 0;
 }
-
-fn declare_package_script_dependency(prep: &mut workcache::Prep, pkg_src: &PkgSrc) {
-    match pkg_src.package_script_option() {
-        // FIXME (#9639): This needs to handle non-utf8 paths
-        Some(ref p) => prep.declare_input("file", p.as_str().unwrap(),
-                                      workcache_support::digest_file_with_date(p)),
-        None => ()
-    }
-}
@@ -10,8 +10,7 @@
 
 use version::{try_getting_version, try_getting_local_version,
               Version, NoVersion, split_version};
-use std::hash::Streaming;
-use std::hash;
+use std::hash::{Hasher, SipState};
 
 /// Path-fragment identifier of a package such as
 /// 'github.com/graydon/test'; path must be a relative
@@ -155,8 +154,8 @@ pub fn write<W: Writer>(writer: &mut W, string: &str) {
 }
 
 pub fn hash(data: ~str) -> ~str {
-    let hasher = &mut hash::default_state();
+    let hasher = &mut SipState::new(0, 0);
     write(hasher, data);
-    hasher.result_str()
+    format!("{:016x}", hasher.finish())
 }
 
@@ -56,8 +56,6 @@ pub struct PkgSrc {
     benchs: ~[Crate],
 }
 
-pub enum BuildSort { InPlace, Discovered }
-
 impl ToStr for PkgSrc {
     fn to_str(&self) -> ~str {
         format!("Package ID {} in start dir {} [workspaces = {} -> {}]",
@@ -321,11 +319,6 @@ impl PkgSrc {
         }
     }
 
-    /// True if the given path's stem is self's pkg ID's stem
-    fn stem_matches(&self, p: &Path) -> bool {
-        p.filestem().map_default(false, |p| { p == self.id.short_name.as_bytes() })
-    }
-
     pub fn push_crate(cs: &mut ~[Crate], prefix: uint, p: &Path) {
         let mut it = p.components().peekable();
         if prefix > 0 {
@@ -72,8 +72,21 @@ pub fn workspace_contains_package_id_(pkgid: &PkgId, workspace: &Path,
     if !src_dir.is_dir() { return None }
 
     let mut found = None;
-    for p in fs::walk_dir(&src_dir) {
-        if p.is_dir() {
+    let mut walker = fs::walk_dir(&src_dir);
+    loop {
+        let entry = match walker.next_entry() {
+            Some(entry) => entry,
+            None => break,
+        };
+        let p = entry.path;
+        // Directory entries know their own types, so only symlinks need to
+        // be stat'ed to find out whether they lead to a directory.
+        let is_dir = match entry.kind {
+            io::TypeDirectory => true,
+            io::TypeSymlink => p.is_dir(),
+            _ => false,
+        };
+        if is_dir {
             if p == src_dir.join(&pkgid.path) || {
                 let pf = p.filename_str();
                 pf.iter().any(|&g| {
@@ -11,7 +11,8 @@
 // Utils for working with version control repositories. Just git right now.
 
 use std::{run, str};
-use std::run::{ProcessOutput, ProcessOptions, Process};
+use std::run::ProcessOutput;
+use std::io::process;
 use std::io::fs;
 use extra::tempfile::TempDir;
 use version::*;
@@ -132,9 +133,23 @@ pub fn git_clone_url(source: &str, target: &Path, v: &Version) {
 }
 
 fn process_output_in_cwd(prog: &str, args: &[~str], cwd: &Path) -> ProcessOutput {
-    let mut prog = Process::new(prog, args, ProcessOptions{ dir: Some(cwd)
-                                ,..ProcessOptions::new()});
-    prog.finish_with_output()
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let io = [process::Ignored,
+              process::CreatePipe(false, true),
+              process::CreatePipe(false, true)];
+    let mut process = process::Process::new(process::ProcessConfig {
+        program: prog,
+        args: args,
+        env: None,
+        cwd: cwd.as_str(),
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    }).expect(format!("failed to exec `{}`", prog));
+    let process::ProcessOutput { status, output, error, _ } =
+        process.wait_with_output(None);
+    ProcessOutput { status: status, output: output, error: error }
 }
 
 pub fn is_git_dir(p: &Path) -> bool {
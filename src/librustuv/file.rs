@@ -15,7 +15,7 @@ use std::cast;
 use std::libc::{c_int, c_char, c_void, size_t};
 use std::libc;
 use std::rt::BlockedTask;
-use std::io::{FileStat, IoError};
+use std::io::{FileStat, FileType, IoError};
 use std::io;
 use std::rt::local::Local;
 use std::rt::rtio;
@@ -31,6 +31,12 @@ pub struct FsRequest {
     priv fired: bool,
 }
 
+// libuv can only list a directory all at once, and it doesn't report the types
+// of the entries, so they are left for the caller to stat if it needs them.
+pub struct DirListing {
+    priv paths: vec::MoveIterator<Path>,
+}
+
 pub struct FileWatcher {
     priv loop_: Loop,
     priv fd: c_int,
@@ -399,6 +405,18 @@ impl Drop for FileWatcher {
     }
 }
 
+impl DirListing {
+    pub fn new(paths: ~[Path]) -> DirListing {
+        DirListing { paths: paths.move_iter() }
+    }
+}
+
+impl rtio::RtioDirectory for DirListing {
+    fn next_entry(&mut self) -> Result<Option<(Path, FileType)>, IoError> {
+        Ok(self.paths.next().map(|p| (p, io::TypeUnknown)))
+    }
+}
+
 impl rtio::RtioFileStream for FileWatcher {
     fn read(&mut self, buf: &mut [u8]) -> Result<int, IoError> {
         self.base_read(buf, -1)
@@ -440,6 +458,8 @@ impl rtio::RtioFileStream for FileWatcher {
         let r = FsRequest::truncate(&self.loop_, self.fd, offset);
         r.map_err(uv_error_to_io_error)
     }
+
+    fn fd(&self) -> c_int { self.fd }
 }
 
 #[cfg(test)]
@@ -64,7 +64,7 @@ use std::vec;
 use std::io::IoError;
 
 pub use self::async::AsyncWatcher;
-pub use self::file::{FsRequest, FileWatcher};
+pub use self::file::{FsRequest, FileWatcher, DirListing};
 pub use self::idle::IdleWatcher;
 pub use self::net::{TcpWatcher, TcpListener, TcpAcceptor, UdpWatcher};
 pub use self::pipe::{PipeWatcher, PipeListener, PipeAcceptor};
@@ -92,6 +92,9 @@ pub mod pipe;
 pub mod tty;
 pub mod signal;
 pub mod stream;
+pub mod timeout;
+pub mod poll;
+pub mod sockopt;
 
 /// A type that wraps a uv handle
 pub trait UvHandle<T> {
@@ -343,6 +346,7 @@ pub fn uv_error_to_io_error(uverr: UvError) -> IoError {
             ENOTCONN => NotConnected,
             EPIPE => BrokenPipe,
             ECONNABORTED => ConnectionAborted,
+            ETIMEDOUT => TimedOut,
             err => {
                 uvdebug!("uverr.code {}", err as int);
                 // XXX: Need to map remaining uv error types
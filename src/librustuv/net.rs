@@ -14,6 +14,7 @@ use std::libc::{size_t, ssize_t, c_int, c_void, c_uint, c_char};
 use std::ptr;
 use std::rt::BlockedTask;
 use std::io::IoError;
+use std::io::net::BindOptions;
 use std::io::net::ip::{Ipv4Addr, Ipv6Addr, SocketAddr, IpAddr};
 use std::rt::local::Local;
 use std::rt::rtio;
@@ -22,10 +23,13 @@ use std::rt::tube::Tube;
 use std::str;
 use std::vec;
 
+use poll::{Poller, PeekedDatagram, take_peeked};
 use stream::StreamWatcher;
+use timeout::{Timeout, ConnectCtx};
 use super::{Loop, Request, UvError, Buf, status_to_io_result,
             uv_error_to_io_error, UvHandle, slice_to_uv_buf,
             wait_until_woken_after};
+use sockopt;
 use uvio::HomingIO;
 use uvll;
 use uvll::sockaddr;
@@ -34,6 +38,15 @@ use uvll::sockaddr;
 /// Generic functions related to dealing with sockaddr things
 ////////////////////////////////////////////////////////////////////////////////
 
+// libuv has no bindings for the sizes of socket buffers, so they are set
+// directly on the socket underlying a handle.
+fn set_buffer_size<T>(handle: *T, opt: c_int,
+                      bytes: uint) -> Result<(), IoError> {
+    sockopt::socket_of(handle).and_then(|fd| {
+        sockopt::setsockopt(fd, libc::SOL_SOCKET, opt, bytes as c_int)
+    })
+}
+
 fn socket_addr_as_sockaddr<T>(addr: SocketAddr, f: |*sockaddr| -> T) -> T {
     let malloc = match addr.ip {
         Ipv4Addr(*) => uvll::rust_malloc_ip4_addr,
@@ -158,6 +171,7 @@ pub struct TcpListener {
 pub struct TcpAcceptor {
     listener: ~TcpListener,
     priv incoming: Tube<Result<~rtio::RtioTcpStream, IoError>>,
+    priv timeout: Timeout,
 }
 
 // TCP watchers (clients/streams)
@@ -175,46 +189,20 @@ impl TcpWatcher {
         }
     }
 
-    pub fn connect(loop_: &mut Loop, address: SocketAddr)
-        -> Result<TcpWatcher, UvError>
+    pub fn connect(loop_: &mut Loop, address: SocketAddr,
+                   timeout: Option<u64>) -> Result<TcpWatcher, UvError>
     {
-        struct Ctx { status: c_int, task: Option<BlockedTask> }
-
         let tcp = TcpWatcher::new(loop_);
+        let mut cx = ConnectCtx::new(timeout);
         let ret = socket_addr_as_sockaddr(address, |addr| {
-            let mut req = Request::new(uvll::UV_CONNECT);
-            let result = unsafe {
-                uvll::uv_tcp_connect(req.handle, tcp.handle, addr,
-                                     connect_cb)
-            };
-            match result {
-                0 => {
-                    req.defuse(); // uv callback now owns this request
-                    let mut cx = Ctx { status: 0, task: None };
-                    wait_until_woken_after(&mut cx.task, || {
-                        req.set_data(&cx);
-                    });
-                    match cx.status {
-                        0 => Ok(()),
-                        n => Err(UvError(n)),
-                    }
-                }
-                n => Err(UvError(n))
-            }
+            cx.connect(tcp.handle, |req, cb| unsafe {
+                uvll::uv_tcp_connect(req, tcp.handle, addr, cb)
+            })
         });
 
-        return match ret {
+        match ret {
             Ok(()) => Ok(tcp),
             Err(e) => Err(e),
-        };
-
-        extern fn connect_cb(req: *uvll::uv_connect_t, status: c_int) {
-            let req = Request::wrap(req);
-            assert!(status != uvll::ECANCELED);
-            let cx: &mut Ctx = unsafe { req.get_data() };
-            cx.status = status;
-            let scheduler: ~Scheduler = Local::take();
-            scheduler.resume_blocked_task_immediately(cx.task.take_unwrap());
         }
     }
 }
@@ -274,6 +262,44 @@ impl rtio::RtioTcpStream for TcpWatcher {
             uvll::uv_tcp_keepalive(self.handle, 0 as c_int, 0 as c_uint)
         })
     }
+
+    fn close_write(&mut self) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        self.stream.shutdown().map_err(uv_error_to_io_error)
+    }
+
+    fn close_read(&mut self) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        self.stream.close_read()
+    }
+
+    fn poller(&mut self) -> Result<~rtio::RtioPoll, IoError> {
+        let _m = self.fire_homing_missile();
+        Ok(Poller::for_stream(&mut self.stream))
+    }
+
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        set_buffer_size(self.handle, libc::SO_RCVBUF, bytes)
+    }
+
+    fn set_send_buffer_size(&mut self, bytes: uint) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        set_buffer_size(self.handle, libc::SO_SNDBUF, bytes)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.set_read_timeout(timeout);
+        self.set_write_timeout(timeout);
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.stream.set_read_timeout(timeout);
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.stream.set_write_timeout(timeout);
+    }
 }
 
 impl UvHandle<uvll::uv_tcp_t> for TcpWatcher {
@@ -283,6 +309,7 @@ impl UvHandle<uvll::uv_tcp_t> for TcpWatcher {
 impl Drop for TcpWatcher {
     fn drop(&mut self) {
         let _m = self.fire_homing_missile();
+        self.stream.close_timeouts();
         self.close();
     }
 }
@@ -290,18 +317,22 @@ impl Drop for TcpWatcher {
 // TCP listeners (unbound servers)
 
 impl TcpListener {
-    pub fn bind(loop_: &mut Loop, address: SocketAddr)
-                -> Result<~TcpListener, UvError> {
+    fn new(loop_: &mut Loop) -> ~TcpListener {
         let handle = unsafe { uvll::malloc_handle(uvll::UV_TCP) };
         assert_eq!(unsafe {
             uvll::uv_tcp_init(loop_.handle, handle)
         }, 0);
-        let l = ~TcpListener {
+        ~TcpListener {
             home: get_handle_to_current_scheduler!(),
             handle: handle,
             closing_task: None,
             outgoing: Tube::new(),
-        };
+        }
+    }
+
+    pub fn bind(loop_: &mut Loop, address: SocketAddr)
+                -> Result<~TcpListener, UvError> {
+        let l = TcpListener::new(loop_);
         let res = socket_addr_as_sockaddr(address, |addr| unsafe {
             uvll::uv_tcp_bind(l.handle, addr)
         });
@@ -310,6 +341,39 @@ impl TcpListener {
             n => Err(UvError(n))
         };
     }
+
+    // libuv can't set options on a socket before binding it, so sockets which
+    // need any are created with the options applied, handed over to libuv, and
+    // only then bound (libuv binds the socket it was given).
+    pub fn bind_with_options(loop_: &mut Loop, address: SocketAddr,
+                             opts: BindOptions)
+        -> Result<~TcpListener, IoError>
+    {
+        if opts == BindOptions::new() {
+            return TcpListener::bind(loop_, address)
+                               .map_err(uv_error_to_io_error);
+        }
+        let fd = match sockopt::socket_with_options(address, libc::SOCK_STREAM,
+                                                    opts) {
+            Ok(fd) => fd,
+            Err(e) => return Err(e),
+        };
+        let l = TcpListener::new(loop_);
+        match unsafe { uvll::uv_tcp_open(l.handle, fd) } {
+            0 => {} // libuv now owns the socket
+            n => {
+                unsafe { sockopt::close(fd) }
+                return Err(uv_error_to_io_error(UvError(n)))
+            }
+        }
+        let res = socket_addr_as_sockaddr(address, |addr| unsafe {
+            uvll::uv_tcp_bind(l.handle, addr)
+        });
+        match res {
+            0 => Ok(l.install()),
+            n => Err(uv_error_to_io_error(UvError(n))),
+        }
+    }
 }
 
 impl HomingIO for TcpListener {
@@ -334,6 +398,7 @@ impl rtio::RtioTcpListener for TcpListener {
         let mut acceptor = ~TcpAcceptor {
             listener: self,
             incoming: incoming,
+            timeout: Timeout::new(),
         };
 
         let _m = acceptor.fire_homing_missile();
@@ -394,7 +459,20 @@ impl rtio::RtioSocket for TcpAcceptor {
 impl rtio::RtioTcpAcceptor for TcpAcceptor {
     fn accept(&mut self) -> Result<~rtio::RtioTcpStream, IoError> {
         let _m = self.fire_homing_missile();
-        self.incoming.recv()
+        let data = &mut self.listener.outgoing as
+            *mut Tube<Result<~rtio::RtioTcpStream, IoError>> as *c_void;
+        self.timeout.arm(self.listener.handle, data, accept_timed_out);
+        let ret = self.incoming.recv();
+        self.timeout.disarm();
+        return ret;
+
+        // Wakes up the acceptor by queueing up an error in place of the next
+        // connection.
+        fn accept_timed_out(data: *c_void) {
+            let outgoing: &mut Tube<Result<~rtio::RtioTcpStream, IoError>> =
+                unsafe { cast::transmute(data) };
+            outgoing.send(Err(uv_error_to_io_error(UvError(uvll::ETIMEDOUT))));
+        }
     }
 
     fn accept_simultaneously(&mut self) -> Result<(), IoError> {
@@ -410,6 +488,17 @@ impl rtio::RtioTcpAcceptor for TcpAcceptor {
             uvll::uv_tcp_simultaneous_accepts(self.listener.handle, 0)
         })
     }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.timeout.set(timeout);
+    }
+}
+
+impl Drop for TcpAcceptor {
+    fn drop(&mut self) {
+        let _m = self.fire_homing_missile();
+        self.timeout.close();
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -419,18 +508,32 @@ impl rtio::RtioTcpAcceptor for TcpAcceptor {
 pub struct UdpWatcher {
     handle: *uvll::uv_udp_t,
     home: SchedHandle,
+    priv read_timeout: Timeout,
+    priv write_timeout: Timeout,
+
+    // A datagram which a poller received while waiting for the socket to
+    // become readable, returned by the next recvfrom.
+    peeked: Option<PeekedDatagram>,
 }
 
 impl UdpWatcher {
-    pub fn bind(loop_: &Loop, address: SocketAddr)
-                -> Result<UdpWatcher, UvError> {
+    fn new(loop_: &Loop) -> UdpWatcher {
         let udp = UdpWatcher {
             handle: unsafe { uvll::malloc_handle(uvll::UV_UDP) },
             home: get_handle_to_current_scheduler!(),
+            read_timeout: Timeout::new(),
+            write_timeout: Timeout::new(),
+            peeked: None,
         };
         assert_eq!(unsafe {
             uvll::uv_udp_init(loop_.handle, udp.handle)
         }, 0);
+        udp
+    }
+
+    pub fn bind(loop_: &Loop, address: SocketAddr)
+                -> Result<UdpWatcher, UvError> {
+        let udp = UdpWatcher::new(loop_);
         let result = socket_addr_as_sockaddr(address, |addr| unsafe {
             uvll::uv_udp_bind(udp.handle, addr, 0u32)
         });
@@ -439,6 +542,37 @@ impl UdpWatcher {
             n => Err(UvError(n)),
         };
     }
+
+    // See TcpListener::bind_with_options
+    pub fn bind_with_options(loop_: &Loop, address: SocketAddr,
+                             opts: BindOptions)
+        -> Result<UdpWatcher, IoError>
+    {
+        if opts == BindOptions::new() {
+            return UdpWatcher::bind(loop_, address)
+                              .map_err(uv_error_to_io_error);
+        }
+        let fd = match sockopt::socket_with_options(address, libc::SOCK_DGRAM,
+                                                    opts) {
+            Ok(fd) => fd,
+            Err(e) => return Err(e),
+        };
+        let udp = UdpWatcher::new(loop_);
+        match unsafe { uvll::uv_udp_open(udp.handle, fd) } {
+            0 => {} // libuv now owns the socket
+            n => {
+                unsafe { sockopt::close(fd) }
+                return Err(uv_error_to_io_error(UvError(n)))
+            }
+        }
+        let result = socket_addr_as_sockaddr(address, |addr| unsafe {
+            uvll::uv_udp_bind(udp.handle, addr, 0u32)
+        });
+        match result {
+            0 => Ok(udp),
+            n => Err(uv_error_to_io_error(UvError(n))),
+        }
+    }
 }
 
 impl UvHandle<uvll::uv_udp_t> for UdpWatcher {
@@ -456,29 +590,71 @@ impl rtio::RtioSocket for UdpWatcher {
     }
 }
 
+// libuv names the interface by its address, and only knows how to do so for
+// IPv4 interfaces.
+fn set_membership(handle: *uvll::uv_udp_t, multi: IpAddr,
+                  interface: Option<IpAddr>,
+                  membership: uvll::uv_membership) -> Result<(), IoError> {
+    let interface = match interface {
+        Some(Ipv6Addr(*)) => return Err(sockopt::ipv6_interface_error()),
+        Some(interface) => Some(interface.to_str()),
+        None => None,
+    };
+    status_to_io_result(unsafe {
+        multi.to_str().with_c_str(|m_addr| {
+            match interface {
+                Some(ref interface) => {
+                    interface.with_c_str(|i_addr| {
+                        uvll::uv_udp_set_membership(handle, m_addr, i_addr,
+                                                    membership)
+                    })
+                }
+                None => {
+                    uvll::uv_udp_set_membership(handle, m_addr, ptr::null(),
+                                                membership)
+                }
+            }
+        })
+    })
+}
+
 impl rtio::RtioUdpSocket for UdpWatcher {
     fn recvfrom(&mut self, buf: &mut [u8])
         -> Result<(uint, SocketAddr), IoError>
     {
         struct Ctx {
+            handle: *uvll::uv_udp_t,
             task: Option<BlockedTask>,
             buf: Option<Buf>,
             result: Option<(ssize_t, Option<SocketAddr>)>,
         }
         let _m = self.fire_homing_missile();
 
+        match self.peeked.take() {
+            Some((Ok(data), addr)) => {
+                let (n, _) = take_peeked(data, buf, true);
+                return Ok((n, addr.unwrap()));
+            }
+            Some((Err(e), _)) => return Err(uv_error_to_io_error(e)),
+            None => {}
+        }
+
         let a = match unsafe {
             uvll::uv_udp_recv_start(self.handle, alloc_cb, recv_cb)
         } {
             0 => {
                 let mut cx = Ctx {
+                    handle: self.handle,
                     task: None,
                     buf: Some(slice_to_uv_buf(buf)),
                     result: None,
                 };
+                let data = &cx as *Ctx as *c_void;
+                self.read_timeout.arm(self.handle, data, recv_timed_out);
                 wait_until_woken_after(&mut cx.task, || {
                     unsafe { uvll::set_data_for_uv_handle(self.handle, &cx) }
                 });
+                self.read_timeout.disarm();
                 match cx.result.take_unwrap() {
                     (n, _) if n < 0 =>
                         Err(uv_error_to_io_error(UvError(n as c_int))),
@@ -531,15 +707,39 @@ impl rtio::RtioUdpSocket for UdpWatcher {
             let sched: ~Scheduler = Local::take();
             sched.resume_blocked_task_immediately(cx.task.take_unwrap());
         }
+
+        fn recv_timed_out(data: *c_void) {
+            let cx: &mut Ctx = unsafe { cast::transmute(data) };
+            unsafe { assert_eq!(uvll::uv_udp_recv_stop(cx.handle), 0) }
+            cx.result = Some((uvll::ETIMEDOUT as ssize_t, None));
+
+            let sched: ~Scheduler = Local::take();
+            sched.resume_blocked_task_immediately(cx.task.take_unwrap());
+        }
     }
 
     fn sendto(&mut self, buf: &[u8], dst: SocketAddr) -> Result<(), IoError> {
-        struct Ctx { task: Option<BlockedTask>, result: c_int }
+        // The context is heap allocated so that it can be handed over to
+        // send_cb if the send times out. libuv can't cancel a queued send, so
+        // in that case the context also keeps a copy of the data alive.
+        struct Ctx {
+            task: Option<BlockedTask>,
+            result: c_int,
+            data: Option<~[u8]>,
+        }
 
         let _m = self.fire_homing_missile();
 
         let mut req = Request::new(uvll::UV_UDP_SEND);
-        let buf = slice_to_uv_buf(buf);
+        let data = if self.write_timeout.is_set() {
+            Some(buf.to_owned())
+        } else {
+            None
+        };
+        let buf = match data {
+            Some(ref data) => slice_to_uv_buf(*data),
+            None => slice_to_uv_buf(buf),
+        };
         let result = socket_addr_as_sockaddr(dst, |dst| unsafe {
             uvll::uv_udp_send(req.handle, self.handle, [buf], dst, send_cb)
         });
@@ -547,10 +747,20 @@ impl rtio::RtioUdpSocket for UdpWatcher {
         return match result {
             0 => {
                 req.defuse(); // uv callback now owns this request
-                let mut cx = Ctx { task: None, result: 0 };
-                wait_until_woken_after(&mut cx.task, || {
-                    req.set_data(&cx);
-                });
+                let cx = ~Ctx { task: None, result: 0, data: data };
+                let cx: *mut Ctx = unsafe { cast::transmute(cx) };
+                self.write_timeout.arm(self.handle, cx as *c_void,
+                                       send_timed_out);
+                unsafe {
+                    wait_until_woken_after(&mut (*cx).task, || {
+                        req.set_data(cx);
+                    });
+                }
+                if self.write_timeout.disarm() {
+                    // The context now belongs to send_cb
+                    return Err(uv_error_to_io_error(UvError(uvll::ETIMEDOUT)));
+                }
+                let cx: ~Ctx = unsafe { cast::transmute(cx) };
                 match cx.result {
                     0 => Ok(()),
                     n => Err(uv_error_to_io_error(UvError(n)))
@@ -561,34 +771,43 @@ impl rtio::RtioUdpSocket for UdpWatcher {
 
         extern fn send_cb(req: *uvll::uv_udp_send_t, status: c_int) {
             let req = Request::wrap(req);
-            assert!(status != uvll::ECANCELED);
             let cx: &mut Ctx = unsafe { req.get_data() };
             cx.result = status;
 
+            match cx.task.take() {
+                Some(task) => {
+                    assert!(status != uvll::ECANCELED);
+                    let sched: ~Scheduler = Local::take();
+                    sched.resume_blocked_task_immediately(task);
+                }
+                // The sender timed out, so nobody is waiting on this context
+                None => { let _cx: ~Ctx = unsafe { cast::transmute(cx) }; }
+            }
+        }
+
+        fn send_timed_out(data: *c_void) {
+            let cx: &mut Ctx = unsafe { cast::transmute(data) };
             let sched: ~Scheduler = Local::take();
             sched.resume_blocked_task_immediately(cx.task.take_unwrap());
         }
     }
 
-    fn join_multicast(&mut self, multi: IpAddr) -> Result<(), IoError> {
+    fn join_multicast(&mut self, multi: IpAddr,
+                      interface: Option<IpAddr>) -> Result<(), IoError> {
         let _m = self.fire_homing_missile();
-        status_to_io_result(unsafe {
-            multi.to_str().with_c_str(|m_addr| {
-                uvll::uv_udp_set_membership(self.handle,
-                                            m_addr, ptr::null(),
-                                            uvll::UV_JOIN_GROUP)
-            })
-        })
+        set_membership(self.handle, multi, interface, uvll::UV_JOIN_GROUP)
     }
 
-    fn leave_multicast(&mut self, multi: IpAddr) -> Result<(), IoError> {
+    fn leave_multicast(&mut self, multi: IpAddr,
+                       interface: Option<IpAddr>) -> Result<(), IoError> {
         let _m = self.fire_homing_missile();
-        status_to_io_result(unsafe {
-            multi.to_str().with_c_str(|m_addr| {
-                uvll::uv_udp_set_membership(self.handle,
-                                            m_addr, ptr::null(),
-                                            uvll::UV_LEAVE_GROUP)
-            })
+        set_membership(self.handle, multi, interface, uvll::UV_LEAVE_GROUP)
+    }
+
+    fn multicast_interface(&mut self, interface: IpAddr) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        sockopt::socket_of(self.handle).and_then(|fd| {
+            sockopt::set_multicast_interface(fd, interface)
         })
     }
 
@@ -638,12 +857,42 @@ impl rtio::RtioUdpSocket for UdpWatcher {
                                        0 as c_int)
         })
     }
+
+    fn poller(&mut self) -> Result<~rtio::RtioPoll, IoError> {
+        let _m = self.fire_homing_missile();
+        Ok(Poller::for_udp(self))
+    }
+
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        set_buffer_size(self.handle, libc::SO_RCVBUF, bytes)
+    }
+
+    fn set_send_buffer_size(&mut self, bytes: uint) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        set_buffer_size(self.handle, libc::SO_SNDBUF, bytes)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.set_read_timeout(timeout);
+        self.set_write_timeout(timeout);
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout.set(timeout);
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.write_timeout.set(timeout);
+    }
 }
 
 impl Drop for UdpWatcher {
     fn drop(&mut self) {
         // Send ourselves home to close this handle (blocking while doing so).
         let _m = self.fire_homing_missile();
+        self.read_timeout.close();
+        self.write_timeout.close();
         self.close();
     }
 }
@@ -656,6 +905,8 @@ impl Drop for UdpWatcher {
 mod test {
     use std::cell::Cell;
     use std::comm::oneshot;
+    use std::io::{TimedOut, EndOfFile};
+    use std::io::net::BindOptions;
     use std::rt::test::*;
     use std::rt::rtio::{RtioTcpStream, RtioTcpListener, RtioTcpAcceptor,
                         RtioUdpSocket};
@@ -666,7 +917,7 @@ mod test {
 
     #[test]
     fn connect_close_ip4() {
-        match TcpWatcher::connect(local_loop(), next_test_ip4()) {
+        match TcpWatcher::connect(local_loop(), next_test_ip4(), None) {
             Ok(*) => fail!(),
             Err(e) => assert_eq!(e.name(), ~"ECONNREFUSED"),
         }
@@ -674,7 +925,7 @@ mod test {
 
     #[test]
     fn connect_close_ip6() {
-        match TcpWatcher::connect(local_loop(), next_test_ip6()) {
+        match TcpWatcher::connect(local_loop(), next_test_ip6(), None) {
             Ok(*) => fail!(),
             Err(e) => assert_eq!(e.name(), ~"ECONNREFUSED"),
         }
@@ -696,6 +947,28 @@ mod test {
         }
     }
 
+    #[test]
+    fn udp_bind_with_options() {
+        let addr = next_test_ip4();
+        let opts = BindOptions { reuse_address: true, .. BindOptions::new() };
+        let mut a = UdpWatcher::bind_with_options(local_loop(), addr,
+                                                  opts).unwrap();
+        let _b = UdpWatcher::bind_with_options(local_loop(), addr,
+                                               opts).unwrap();
+        a.set_recv_buffer_size(4096).unwrap();
+        a.set_send_buffer_size(4096).unwrap();
+    }
+
+    #[test]
+    fn tcp_bind_ipv6_only() {
+        let opts = BindOptions { ipv6_only: true, .. BindOptions::new() };
+        match TcpListener::bind_with_options(local_loop(), next_test_ip6(),
+                                             opts) {
+            Ok(*) => {}
+            Err(e) => fail!("{:?}", e),
+        }
+    }
+
     #[test]
     fn listen_ip4() {
         let (port, chan) = oneshot();
@@ -725,7 +998,7 @@ mod test {
         }
 
         port.recv();
-        let mut w = match TcpWatcher::connect(local_loop(), addr) {
+        let mut w = match TcpWatcher::connect(local_loop(), addr, None) {
             Ok(w) => w, Err(e) => fail!("{:?}", e)
         };
         match w.write([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]) {
@@ -762,7 +1035,7 @@ mod test {
         }
 
         port.recv();
-        let mut w = match TcpWatcher::connect(local_loop(), addr) {
+        let mut w = match TcpWatcher::connect(local_loop(), addr, None) {
             Ok(w) => w, Err(e) => fail!("{:?}", e)
         };
         match w.write([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]) {
@@ -770,6 +1043,86 @@ mod test {
         }
     }
 
+    #[test]
+    fn accept_timeout() {
+        let w = TcpListener::bind(local_loop(), next_test_ip4()).unwrap();
+        let mut w = w.listen().unwrap();
+        w.set_timeout(Some(10));
+        match w.accept() {
+            Ok(*) => fail!(),
+            Err(e) => assert_eq!(e.kind, TimedOut),
+        }
+    }
+
+    #[test]
+    fn read_timeout() {
+        let (port, chan) = oneshot();
+        let (timed_out_port, timed_out_chan) = oneshot();
+        let chan = Cell::new(chan);
+        let timed_out_chan = Cell::new(timed_out_chan);
+        let addr = next_test_ip4();
+
+        do spawn {
+            let w = TcpListener::bind(local_loop(), addr).unwrap();
+            let mut w = w.listen().unwrap();
+            chan.take().send(());
+            let mut stream = w.accept().unwrap();
+            let mut buf = [0u8, ..10];
+            stream.set_read_timeout(Some(10));
+            match stream.read(buf) {
+                Ok(*) => fail!(),
+                Err(e) => assert_eq!(e.kind, TimedOut),
+            }
+            timed_out_chan.take().send(());
+
+            // The stream is still perfectly usable after timing out
+            stream.set_read_timeout(None);
+            match stream.read(buf) {
+                Ok(1) => assert_eq!(buf[0], 1),
+                e => fail!("{:?}", e),
+            }
+        }
+
+        port.recv();
+        let mut w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
+        timed_out_port.recv();
+        w.write([1]).unwrap();
+    }
+
+    #[test]
+    fn shutdown() {
+        let (port, chan) = oneshot();
+        let chan = Cell::new(chan);
+        let addr = next_test_ip4();
+
+        do spawn {
+            let w = TcpListener::bind(local_loop(), addr).unwrap();
+            let mut w = w.listen().unwrap();
+            chan.take().send(());
+            let mut stream = w.accept().unwrap();
+            let mut buf = [0u8, ..10];
+            match stream.read(buf) {
+                Ok(1) => assert_eq!(buf[0], 1),
+                e => fail!("{:?}", e),
+            }
+            match stream.read(buf) {
+                Err(e) => assert_eq!(e.kind, EndOfFile),
+                e => fail!("{:?}", e),
+            }
+            stream.write([2]).unwrap();
+        }
+
+        port.recv();
+        let mut w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
+        w.write([1]).unwrap();
+        w.close_write().unwrap();
+        let mut buf = [0u8, ..10];
+        match w.read(buf) {
+            Ok(1) => assert_eq!(buf[0], 2),
+            e => fail!("{:?}", e),
+        }
+    }
+
     #[test]
     fn udp_recv_ip4() {
         let (port, chan) = oneshot();
@@ -861,7 +1214,7 @@ mod test {
 
         do spawn {
             port.take().recv();
-            let mut stream = TcpWatcher::connect(local_loop(), addr).unwrap();
+            let mut stream = TcpWatcher::connect(local_loop(), addr, None).unwrap();
             let mut buf = [0, .. 2048];
             let mut total_bytes_read = 0;
             while total_bytes_read < MAX {
@@ -1007,7 +1360,7 @@ mod test {
 
         do spawn {
             let port2 = port.take().recv();
-            let mut stream = TcpWatcher::connect(local_loop(), addr).unwrap();
+            let mut stream = TcpWatcher::connect(local_loop(), addr, None).unwrap();
             stream.write([0, 1, 2, 3, 4, 5, 6, 7]);
             stream.write([0, 1, 2, 3, 4, 5, 6, 7]);
             port2.recv();
@@ -1034,9 +1387,9 @@ mod test {
         }
 
         do task::spawn_sched(task::SingleThreaded) {
-            let mut stream = TcpWatcher::connect(local_loop(), addr);
+            let mut stream = TcpWatcher::connect(local_loop(), addr, None);
             while stream.is_err() {
-                stream = TcpWatcher::connect(local_loop(), addr);
+                stream = TcpWatcher::connect(local_loop(), addr, None);
             }
             stream.unwrap().write([0, 1, 2, 3, 4, 5, 6, 7]);
         }
@@ -1114,7 +1467,7 @@ mod test {
             let test_function: proc() = proc() {
                 let io = unsafe { local_io() };
                 let addr = next_test_ip4();
-                let maybe_socket = io.udp_bind(addr);
+                let maybe_socket = io.udp_bind(addr, BindOptions::new());
                 // this socket is bound to this event loop
                 assert!(maybe_socket.is_ok());
 
@@ -1181,7 +1534,7 @@ mod test {
             w.accept();
         }
         port.recv();
-        let _w = TcpWatcher::connect(local_loop(), addr).unwrap();
+        let _w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
         fail!();
     }
 
@@ -1246,7 +1599,7 @@ mod test {
         }
 
         port.recv();
-        let _w = TcpWatcher::connect(local_loop(), addr).unwrap();
+        let _w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
 
         fail!();
     }
@@ -1271,7 +1624,7 @@ mod test {
         }
 
         port.recv();
-        let _w = TcpWatcher::connect(local_loop(), addr).unwrap();
+        let _w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
         port.recv();
         fail!();
     }
@@ -10,16 +10,16 @@
 
 use std::c_str::CString;
 use std::libc;
-use std::rt::BlockedTask;
 use std::io::IoError;
 use std::rt::local::Local;
-use std::rt::rtio::{RtioPipe, RtioUnixListener, RtioUnixAcceptor};
+use std::rt::rtio::{RtioPipe, RtioUnixListener, RtioUnixAcceptor, RtioPoll};
 use std::rt::sched::{Scheduler, SchedHandle};
 use std::rt::tube::Tube;
 
+use poll::Poller;
 use stream::StreamWatcher;
-use super::{Loop, UvError, UvHandle, Request, uv_error_to_io_error,
-            wait_until_woken_after};
+use timeout::ConnectCtx;
+use super::{Loop, UvError, UvHandle, uv_error_to_io_error};
 use uvio::HomingIO;
 use uvll;
 
@@ -70,35 +70,21 @@ impl PipeWatcher {
         }
     }
 
-    pub fn connect(loop_: &Loop, name: &CString) -> Result<PipeWatcher, UvError>
+    pub fn connect(loop_: &Loop, name: &CString, timeout: Option<u64>)
+        -> Result<PipeWatcher, UvError>
     {
-        struct Ctx { task: Option<BlockedTask>, result: libc::c_int, }
-        let mut cx = Ctx { task: None, result: 0 };
-        let mut req = Request::new(uvll::UV_CONNECT);
         let pipe = PipeWatcher::new(loop_, false);
-
-        wait_until_woken_after(&mut cx.task, || {
+        let mut cx = ConnectCtx::new(timeout);
+        let ret = cx.connect(pipe.handle(), |req, cb| {
             unsafe {
-                uvll::uv_pipe_connect(req.handle,
-                                      pipe.handle(),
-                                      name.with_ref(|p| p),
-                                      connect_cb)
+                uvll::uv_pipe_connect(req, pipe.handle(),
+                                      name.with_ref(|p| p), cb)
             }
-            req.set_data(&cx);
-            req.defuse(); // uv callback now owns this request
+            0
         });
-        return match cx.result {
-            0 => Ok(pipe),
-            n => Err(UvError(n))
-        };
-
-        extern fn connect_cb(req: *uvll::uv_connect_t, status: libc::c_int) {;
-            let req = Request::wrap(req);
-            assert!(status != uvll::ECANCELED);
-            let cx: &mut Ctx = unsafe { req.get_data() };
-            cx.result = status;
-            let sched: ~Scheduler = Local::take();
-            sched.resume_blocked_task_immediately(cx.task.take_unwrap());
+        match ret {
+            Ok(()) => Ok(pipe),
+            Err(e) => Err(e),
         }
     }
 
@@ -122,6 +108,34 @@ impl RtioPipe for PipeWatcher {
         let _m = self.fire_homing_missile();
         self.stream.write(buf).map_err(uv_error_to_io_error)
     }
+
+    fn close_write(&mut self) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        self.stream.shutdown().map_err(uv_error_to_io_error)
+    }
+
+    fn close_read(&mut self) -> Result<(), IoError> {
+        let _m = self.fire_homing_missile();
+        self.stream.close_read()
+    }
+
+    fn poller(&mut self) -> Result<~RtioPoll, IoError> {
+        let _m = self.fire_homing_missile();
+        Ok(Poller::for_stream(&mut self.stream))
+    }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.set_read_timeout(timeout);
+        self.set_write_timeout(timeout);
+    }
+
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.stream.set_read_timeout(timeout);
+    }
+
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.stream.set_write_timeout(timeout);
+    }
 }
 
 impl HomingIO for PipeWatcher {
@@ -136,6 +150,7 @@ impl Drop for PipeWatcher {
     fn drop(&mut self) {
         if !self.defused {
             let _m = self.fire_homing_missile();
+            self.stream.close_timeouts();
             self.close();
         }
     }
@@ -245,7 +260,7 @@ mod tests {
 
     #[test]
     fn connect_err() {
-        match PipeWatcher::connect(local_loop(), &"path/to/nowhere".to_c_str()) {
+        match PipeWatcher::connect(local_loop(), &"path/to/nowhere".to_c_str(), None) {
             Ok(*) => fail!(),
             Err(*) => {}
         }
@@ -293,7 +308,7 @@ mod tests {
             assert!(client.write([2]).is_ok());
         }
         port.recv();
-        let mut c = PipeWatcher::connect(local_loop(), &path.to_c_str()).unwrap();
+        let mut c = PipeWatcher::connect(local_loop(), &path.to_c_str(), None).unwrap();
         assert!(c.write([1]).is_ok());
         let mut buf = [0];
         assert!(c.read(buf).unwrap() == 1);
@@ -314,7 +329,7 @@ mod tests {
             p.accept();
         }
         port.recv();
-        let _c = PipeWatcher::connect(local_loop(), &path.to_c_str()).unwrap();
+        let _c = PipeWatcher::connect(local_loop(), &path.to_c_str(), None).unwrap();
         fail!()
 
     }
@@ -0,0 +1,351 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cast;
+use std::cmp;
+use std::comm::{oneshot, PortOne, ChanOne, SendDeferred};
+use std::io::net::ip::SocketAddr;
+use std::io::poll::{Readiness, READABLE, NOT_READY};
+use std::libc::{c_int, c_uint, c_void, size_t, ssize_t};
+use std::rt::rtio::RtioPoll;
+use std::rt::sched::SchedHandle;
+use std::util;
+use std::vec;
+
+use net::{UdpWatcher, sockaddr_to_socket_addr};
+use stream::StreamWatcher;
+use timeout::Timeout;
+use super::{Buf, UvError, ForbidSwitch, slice_to_uv_buf};
+use uvio::HomingIO;
+use uvll;
+
+// libuv watches each socket through only one handle at a time, so the socket
+// of a stream or UDP handle can't also be given to a poll handle. Instead a
+// poller finds out that its handle is readable by reading from it, and keeps
+// what it read for the handle's next read. libuv queues writes instead of
+// blocking on them, so a handle is reported as writable right away (writing
+// to an object while polling it is forbidden by std::io::poll).
+pub struct Poller {
+    target: Target,
+    home: SchedHandle,
+    chan: Option<ChanOne<Readiness>>,
+    reading: bool,
+    buf: ~[u8],
+    timeout: Timeout,
+}
+
+enum Target {
+    StreamTarget(*mut StreamWatcher),
+    UdpTarget(*mut UdpWatcher),
+}
+
+// What a poller read from a stream, handed out by the stream's next read.
+pub type Peeked = Result<~[u8], UvError>;
+
+// As `Peeked`, along with where the datagram came from.
+pub type PeekedDatagram = (Result<~[u8], UvError>, Option<SocketAddr>);
+
+// Largest amount read at once while polling, which is enough for a datagram
+static BUF_SIZE: uint = 65536;
+
+impl Poller {
+    // Creates a poller reading through `stream`, which it must not outlive.
+    // This must be called from the stream's home.
+    pub fn for_stream(stream: &mut StreamWatcher) -> ~RtioPoll {
+        Poller::new(StreamTarget(stream as *mut StreamWatcher)) as ~RtioPoll
+    }
+
+    // As `for_stream`, for a UDP socket
+    pub fn for_udp(udp: &mut UdpWatcher) -> ~RtioPoll {
+        Poller::new(UdpTarget(udp as *mut UdpWatcher)) as ~RtioPoll
+    }
+
+    fn new(target: Target) -> ~Poller {
+        ~Poller {
+            target: target,
+            home: get_handle_to_current_scheduler!(),
+            chan: None,
+            reading: false,
+            buf: vec::from_elem(BUF_SIZE, 0u8),
+            timeout: Timeout::new(),
+        }
+    }
+
+    fn handle(&self) -> *c_void {
+        match self.target {
+            StreamTarget(s) => unsafe { (*s).handle as *c_void },
+            UdpTarget(u) => unsafe { (*u).handle as *c_void },
+        }
+    }
+
+    fn has_peeked(&self) -> bool {
+        match self.target {
+            StreamTarget(s) => unsafe { (*s).peeked.is_some() },
+            UdpTarget(u) => unsafe { (*u).peeked.is_some() },
+        }
+    }
+
+    fn start_reading(&mut self) -> c_int {
+        let handle = self.handle();
+        unsafe {
+            uvll::set_data_for_uv_handle(handle, self as *mut Poller);
+            match self.target {
+                StreamTarget(*) => {
+                    uvll::uv_read_start(handle as *uvll::uv_stream_t,
+                                        stream_alloc_cb, stream_read_cb)
+                }
+                UdpTarget(*) => {
+                    uvll::uv_udp_recv_start(handle as *uvll::uv_udp_t,
+                                            udp_alloc_cb, udp_recv_cb)
+                }
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        if self.reading {
+            let handle = self.handle();
+            assert_eq!(unsafe {
+                match self.target {
+                    StreamTarget(*) => {
+                        uvll::uv_read_stop(handle as *uvll::uv_stream_t)
+                    }
+                    UdpTarget(*) => {
+                        uvll::uv_udp_recv_stop(handle as *uvll::uv_udp_t)
+                    }
+                }
+            }, 0);
+            self.reading = false;
+        }
+        self.timeout.disarm();
+    }
+
+    fn ready(&mut self, ready: Readiness) {
+        self.stop();
+        match self.chan.take() {
+            Some(chan) => chan.send_deferred(ready),
+            None => {}
+        }
+    }
+
+    fn read(&self, nread: ssize_t) -> Result<~[u8], UvError> {
+        if nread < 0 {
+            Err(UvError(nread as c_int))
+        } else {
+            Ok(self.buf.slice_to(nread as uint).to_owned())
+        }
+    }
+}
+
+impl HomingIO for Poller {
+    fn home<'r>(&'r mut self) -> &'r mut SchedHandle { &mut self.home }
+}
+
+impl RtioPoll for Poller {
+    fn oneshot(&mut self, interest: Readiness,
+               timeout: Option<u64>) -> PortOne<Readiness> {
+        let (port, chan) = oneshot();
+
+        // As with timers, the previous channel must be dropped outside of the
+        // homing missile.
+        let _prev_chan = {
+            let _m = self.fire_homing_missile();
+            self.stop();
+            let mut ready = Readiness {
+                readable: interest.readable && self.has_peeked(),
+                writable: interest.writable,
+            };
+            if ready.is_empty() && interest.readable {
+                match self.start_reading() {
+                    0 => self.reading = true,
+                    // The handle is unusable, which the next read from it
+                    // will discover, so just report it as ready right away.
+                    _ => ready = interest,
+                }
+            }
+            if ready.is_empty() {
+                self.timeout.set(timeout);
+                let me = self as *mut Poller;
+                self.timeout.arm(self.handle(), me as *c_void, poll_timed_out);
+                util::replace(&mut self.chan, Some(chan))
+            } else {
+                chan.send_deferred(ready);
+                self.chan.take()
+            }
+        };
+        port
+    }
+
+    fn cancel(&mut self) {
+        let _prev_chan = {
+            let _m = self.fire_homing_missile();
+            self.stop();
+            self.chan.take()
+        };
+    }
+
+    fn fd(&self) -> Option<c_int> { None }
+}
+
+extern fn stream_alloc_cb(handle: *uvll::uv_stream_t, _hint: size_t,
+                          buf: *mut Buf) {
+    let p: &mut Poller = unsafe {
+        cast::transmute(uvll::get_data_for_uv_handle(handle))
+    };
+    unsafe { *buf = slice_to_uv_buf(p.buf); }
+}
+
+extern fn stream_read_cb(handle: *uvll::uv_stream_t, nread: ssize_t,
+                         _buf: *Buf) {
+    // Nothing could be read after all, so keep on waiting
+    if nread == 0 { return }
+    let _f = ForbidSwitch::new("poll callback can't switch");
+    let p: &mut Poller = unsafe {
+        cast::transmute(uvll::get_data_for_uv_handle(handle))
+    };
+    let peeked = p.read(nread);
+    match p.target {
+        StreamTarget(s) => unsafe { (*s).peeked = Some(peeked) },
+        UdpTarget(*) => unreachable!(),
+    }
+    p.ready(READABLE);
+}
+
+extern fn udp_alloc_cb(handle: *uvll::uv_udp_t, _hint: size_t,
+                       buf: *mut Buf) {
+    let p: &mut Poller = unsafe {
+        cast::transmute(uvll::get_data_for_uv_handle(handle))
+    };
+    unsafe { *buf = slice_to_uv_buf(p.buf); }
+}
+
+extern fn udp_recv_cb(handle: *uvll::uv_udp_t, nread: ssize_t, _buf: *Buf,
+                      addr: *uvll::sockaddr, _flags: c_uint) {
+    // Without an address nothing could be read after all (with one, this is
+    // an empty datagram), so keep on waiting.
+    if nread == 0 && addr.is_null() { return }
+    let _f = ForbidSwitch::new("poll callback can't switch");
+    let p: &mut Poller = unsafe {
+        cast::transmute(uvll::get_data_for_uv_handle(handle))
+    };
+    let addr = if addr.is_null() {
+        None
+    } else {
+        Some(sockaddr_to_socket_addr(addr))
+    };
+    let peeked = p.read(nread);
+    match p.target {
+        UdpTarget(u) => unsafe { (*u).peeked = Some((peeked, addr)) },
+        StreamTarget(*) => unreachable!(),
+    }
+    p.ready(READABLE);
+}
+
+fn poll_timed_out(data: *c_void) {
+    let p: &mut Poller = unsafe { cast::transmute(data) };
+    p.ready(NOT_READY);
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        let _chan = {
+            let _m = self.fire_homing_missile();
+            self.stop();
+            self.timeout.close();
+            self.chan.take()
+        };
+    }
+}
+
+// Hands out data which a poller read from a handle, returning how much of it
+// fit into `buf`. What doesn't fit is returned as well, unless `truncate` is
+// set (as for datagrams, the rest of which are discarded just as recvfrom(2)
+// would).
+pub fn take_peeked(data: ~[u8], buf: &mut [u8],
+                   truncate: bool) -> (uint, Option<~[u8]>) {
+    let n = cmp::min(buf.len(), data.len());
+    vec::bytes::copy_memory(buf, data, n);
+    if n < data.len() && !truncate {
+        (n, Some(data.slice_from(n).to_owned()))
+    } else {
+        (n, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::comm::oneshot;
+    use std::io::poll::{READABLE, WRITABLE, NOT_READY};
+    use std::rt::rtio::{RtioTcpStream, RtioTcpListener, RtioTcpAcceptor,
+                        RtioUdpSocket, RtioPoll};
+    use std::rt::test::next_test_ip4;
+
+    use net::{TcpListener, TcpWatcher, UdpWatcher};
+    use super::super::local_loop;
+
+    #[test]
+    fn tcp_readiness() {
+        let (port, chan) = oneshot();
+        let chan = Cell::new(chan);
+        let (go_port, go_chan) = oneshot();
+        let go_chan = Cell::new(go_chan);
+        let addr = next_test_ip4();
+
+        do spawn {
+            let w = TcpListener::bind(local_loop(), addr).unwrap();
+            let mut w = w.listen().unwrap();
+            chan.take().send(());
+            let mut stream = w.accept().unwrap();
+            let mut poller = stream.poller().unwrap();
+            assert_eq!(poller.oneshot(READABLE, Some(10)).recv(), NOT_READY);
+            go_chan.take().send(());
+            assert!(poller.oneshot(READABLE, None).recv().readable);
+            let mut buf = [0u8, ..10];
+            match stream.read(buf) {
+                Ok(1) => assert_eq!(buf[0], 1),
+                e => fail!("{:?}", e),
+            }
+        }
+
+        port.recv();
+        let mut w = TcpWatcher::connect(local_loop(), addr, None).unwrap();
+        let mut poller = w.poller().unwrap();
+        assert!(poller.oneshot(WRITABLE, None).recv().writable);
+        go_port.recv();
+        w.write([1]).unwrap();
+    }
+
+    #[test]
+    fn udp_readiness_keeps_the_datagram() {
+        let server_addr = next_test_ip4();
+        let client_addr = next_test_ip4();
+        let mut server = UdpWatcher::bind(local_loop(), server_addr).unwrap();
+        let mut client = UdpWatcher::bind(local_loop(), client_addr).unwrap();
+
+        {
+            let mut poller = server.poller().unwrap();
+            assert_eq!(poller.oneshot(READABLE, Some(10)).recv(), NOT_READY);
+            client.sendto([1, 2, 3], server_addr).unwrap();
+            assert!(poller.oneshot(READABLE, None).recv().readable);
+            // The datagram stays put until it's read
+            assert!(poller.oneshot(READABLE, Some(10)).recv().readable);
+        }
+
+        let mut buf = [0u8, ..2];
+        match server.recvfrom(buf) {
+            Ok((2, addr)) => {
+                assert_eq!(buf, [1, 2]);
+                assert_eq!(addr, client_addr);
+            }
+            e => fail!("{:?}", e),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use std::libc::c_int;
 use std::libc;
 use std::ptr;
 use std::rt::BlockedTask;
+use std::io;
 use std::io::IoError;
 use std::io::process::*;
 use std::rt::local::Local;
@@ -57,6 +58,11 @@ impl Process {
             }
         }
 
+        let mut flags = 0;
+        if config.uid.is_some() { flags |= uvll::PROCESS_SETUID; }
+        if config.gid.is_some() { flags |= uvll::PROCESS_SETGID; }
+        if config.detach { flags |= uvll::PROCESS_DETACHED; }
+
         let ret = with_argv(config.program, config.args, |argv| {
             with_env(config.env, |envp| {
                 let options = uvll::uv_process_options_t {
@@ -68,11 +74,11 @@ impl Process {
                         Some(ref cwd) => cwd.with_ref(|p| p),
                         None => ptr::null(),
                     },
-                    flags: 0,
+                    flags: flags as libc::c_uint,
                     stdio_count: stdio.len() as libc::c_int,
                     stdio: stdio.as_imm_buf(|p, _| p),
-                    uid: 0,
-                    gid: 0,
+                    uid: config.uid.unwrap_or(0) as uvll::uv_uid_t,
+                    gid: config.gid.unwrap_or(0) as uvll::uv_gid_t,
                 };
 
                 let handle = UvHandle::alloc(None::<Process>, uvll::UV_PROCESS);
@@ -199,7 +205,16 @@ impl RtioProcess for Process {
     }
 
     fn kill(&mut self, signal: int) -> Result<(), IoError> {
+        // The child is reaped by the event loop, which this is running on, so
+        // once it has exited its pid may already belong to another process.
         let _m = self.fire_homing_missile();
+        if self.exit_status.is_some() {
+            return Err(IoError {
+                kind: io::OtherIoError,
+                desc: "can't kill an exited process",
+                detail: None,
+            })
+        }
         match unsafe {
             uvll::uv_process_kill(self.handle, signal as libc::c_int)
         } {
@@ -224,6 +239,13 @@ impl RtioProcess for Process {
 
         self.exit_status.unwrap()
     }
+
+    fn try_wait(&mut self) -> Option<ProcessExit> {
+        // The exit status is filled in by the event loop, which may not have
+        // gotten around to reaping the child yet even if it has exited.
+        let _m = self.fire_homing_missile();
+        self.exit_status
+    }
 }
 
 impl Drop for Process {
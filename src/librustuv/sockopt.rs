@@ -0,0 +1,143 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Socket options and operations which libuv has no bindings for
+//!
+//! These work directly on the OS socket underlying a TCP, UDP or pipe handle,
+//! or on a socket which is created here and only then handed over to libuv.
+
+use std::cast;
+use std::io;
+use std::io::IoError;
+use std::io::net::BindOptions;
+use std::io::net::ip::{Ipv4Addr, Ipv6Addr, SocketAddr, IpAddr};
+use std::libc;
+use std::libc::c_int;
+use std::mem;
+use std::os;
+
+use super::{UvError, uv_error_to_io_error};
+use uvll;
+
+pub type sock_t = uvll::uv_os_sock_t;
+
+/// Fetches the OS socket underlying a TCP, UDP or pipe handle.
+pub fn socket_of<T>(handle: *T) -> Result<sock_t, IoError> {
+    let mut fd = 0 as uvll::uv_os_fd_t;
+    match unsafe { uvll::uv_fileno(handle as *uvll::uv_handle_t, &mut fd) } {
+        0 => Ok(fd as sock_t),
+        n => Err(uv_error_to_io_error(UvError(n))),
+    }
+}
+
+/// Creates an unbound socket of type `ty` (`SOCK_STREAM` or `SOCK_DGRAM`) for
+/// the family of `addr`, with the options in `opts` already applied. libuv
+/// can't set these options itself, as they have to be set before the socket is
+/// bound, so such sockets are opened by libuv afterwards and bound there.
+pub fn socket_with_options(addr: SocketAddr, ty: c_int,
+                           opts: BindOptions) -> Result<sock_t, IoError> {
+    let fam = match addr.ip {
+        Ipv4Addr(*) => libc::AF_INET,
+        Ipv6Addr(*) => libc::AF_INET6,
+    };
+    let fd = unsafe { libc::socket(fam, ty, 0) };
+    if fd == -1 as sock_t {
+        return Err(last_error())
+    }
+    let reuse = if opts.reuse_address {
+        setsockopt(fd, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1 as c_int)
+    } else {
+        Ok(())
+    };
+    let ret = reuse.and_then(|()| {
+        match addr.ip {
+            // The default varies between systems (and with sysctls), so it
+            // is always set one way or the other
+            Ipv6Addr(*) => {
+                setsockopt(fd, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY,
+                           opts.ipv6_only as c_int)
+            }
+            _ => Ok(()),
+        }
+    });
+    match ret {
+        Ok(()) => Ok(fd),
+        Err(e) => {
+            unsafe { close(fd) }
+            Err(e)
+        }
+    }
+}
+
+pub fn setsockopt<T>(fd: sock_t, opt: c_int, val: c_int,
+                     payload: T) -> Result<(), IoError> {
+    let ret = unsafe {
+        let payload = &payload as *T as *libc::c_void;
+        libc::setsockopt(fd, opt, val, payload,
+                         mem::size_of::<T>() as libc::socklen_t)
+    };
+    if ret == 0 {Ok(())} else {Err(last_error())}
+}
+
+/// Shuts down one or both halves of a connected socket (`how` is one of the
+/// `SHUT_*` constants).
+pub fn shutdown(fd: sock_t, how: c_int) -> Result<(), IoError> {
+    match unsafe { libc::shutdown(fd, how) } {
+        0 => Ok(()),
+        _ => Err(last_error()),
+    }
+}
+
+/// Selects the interface which multicast datagrams are sent out of.
+pub fn set_multicast_interface(fd: sock_t,
+                               interface: IpAddr) -> Result<(), IoError> {
+    match interface {
+        Ipv4Addr(a, b, c, d) => {
+            // the address is stored in network byte order, which is the order
+            // the octets are written in
+            let octets = [a, b, c, d];
+            let addr = libc::in_addr {
+                s_addr: unsafe { cast::transmute(octets) }
+            };
+            setsockopt(fd, libc::IPPROTO_IP, libc::IP_MULTICAST_IF, addr)
+        }
+        Ipv6Addr(*) => Err(ipv6_interface_error()),
+    }
+}
+
+// IPv6 selects interfaces by index rather than by address, so only IPv4
+// interfaces can be named.
+pub fn ipv6_interface_error() -> IoError {
+    IoError {
+        kind: io::OtherIoError,
+        desc: "multicast interfaces must be IPv4 addresses",
+        detail: None,
+    }
+}
+
+#[cfg(unix)]
+pub unsafe fn close(fd: sock_t) { let _ = libc::close(fd); }
+#[cfg(windows)]
+pub unsafe fn close(fd: sock_t) { let _ = libc::closesocket(fd); }
+
+// libuv's error codes are negated errnos on unix
+#[cfg(unix)]
+fn last_error() -> IoError {
+    uv_error_to_io_error(UvError(-os::errno() as c_int))
+}
+
+#[cfg(windows)]
+fn last_error() -> IoError {
+    IoError {
+        kind: io::OtherIoError,
+        desc: "socket operation failed",
+        detail: Some(os::last_os_error()),
+    }
+}
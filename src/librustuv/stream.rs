@@ -9,7 +9,9 @@
 // except according to those terms.
 
 use std::cast;
-use std::libc::{c_int, size_t, ssize_t};
+use std::io::IoError;
+use std::libc::{c_int, c_void, size_t, ssize_t};
+use std::libc;
 use std::ptr;
 use std::rt::BlockedTask;
 use std::rt::local::Local;
@@ -17,6 +19,9 @@ use std::rt::sched::Scheduler;
 
 use super::{UvError, Buf, slice_to_uv_buf, Request, wait_until_woken_after,
             ForbidUnwind};
+use poll::{Peeked, take_peeked};
+use sockopt;
+use timeout::Timeout;
 use uvll;
 
 // This is a helper structure which is intended to get embedded into other
@@ -31,9 +36,18 @@ pub struct StreamWatcher {
     // structure, but currently we don't have mappings for all the structures
     // defined in libuv, so we're foced to malloc this.
     priv last_write_req: Option<Request>,
+
+    // Time limits on individual reads and writes, unset by default.
+    priv read_timeout: Timeout,
+    priv write_timeout: Timeout,
+
+    // Data which a poller read while waiting for the stream to become
+    // readable, returned by the next read.
+    peeked: Option<Peeked>,
 }
 
 struct ReadContext {
+    handle: *uvll::uv_stream_t,
     buf: Option<Buf>,
     result: ssize_t,
     task: Option<BlockedTask>,
@@ -42,6 +56,9 @@ struct ReadContext {
 struct WriteContext {
     result: c_int,
     task: Option<BlockedTask>,
+    // Writes with a time limit keep their own copy of the data being written
+    // because libuv may still be using it after the writer has given up.
+    data: Option<~[u8]>,
 }
 
 impl StreamWatcher {
@@ -61,15 +78,44 @@ impl StreamWatcher {
         StreamWatcher {
             handle: stream,
             last_write_req: None,
+            read_timeout: Timeout::new(),
+            write_timeout: Timeout::new(),
+            peeked: None,
         }
     }
 
+    pub fn set_read_timeout(&mut self, ms: Option<u64>) {
+        self.read_timeout.set(ms);
+    }
+
+    pub fn set_write_timeout(&mut self, ms: Option<u64>) {
+        self.write_timeout.set(ms);
+    }
+
+    // Frees the timers backing any timeouts. Like closing the stream itself,
+    // this is the responsibility of the enclosing structure's destructor.
+    pub fn close_timeouts(&mut self) {
+        self.read_timeout.close();
+        self.write_timeout.close();
+    }
+
     pub fn read(&mut self, buf: &mut [u8]) -> Result<uint, UvError> {
+        match self.peeked.take() {
+            Some(Ok(data)) => {
+                let (n, rest) = take_peeked(data, buf, false);
+                self.peeked = rest.map(|rest| Ok(rest));
+                return Ok(n);
+            }
+            Some(Err(e)) => return Err(e),
+            None => {}
+        }
+
         // This read operation needs to get canceled on an unwind via libuv's
         // uv_read_stop function
         let _f = ForbidUnwind::new("stream read");
 
         let mut rcx = ReadContext {
+            handle: self.handle,
             buf: Some(slice_to_uv_buf(buf)),
             result: 0,
             task: None,
@@ -89,7 +135,10 @@ impl StreamWatcher {
             uvll::uv_read_start(self.handle, alloc_cb, read_cb)
         } {
             0 => {
+                let data = &rcx as *ReadContext as *c_void;
+                self.read_timeout.arm(self.handle, data, read_timed_out);
                 wait_until_woken_after(&mut rcx.task, || {});
+                self.read_timeout.disarm();
                 match rcx.result {
                     n if n < 0 => Err(UvError(n as c_int)),
                     n => Ok(n as uint),
@@ -105,6 +154,10 @@ impl StreamWatcher {
         // then the write request will be leaked.
         let _f = ForbidUnwind::new("stream write");
 
+        if self.write_timeout.is_set() {
+            return self.write_with_timeout(buf);
+        }
+
         // Prepare the write request, either using a cached one or allocating a
         // new one
         let mut req = match self.last_write_req.take() {
@@ -120,7 +173,7 @@ impl StreamWatcher {
                            write_cb)
         } {
             0 => {
-                let mut wcx = WriteContext { result: 0, task: None, };
+                let mut wcx = WriteContext { result: 0, task: None, data: None };
                 req.defuse(); // uv callback now owns this request
 
                 wait_until_woken_after(&mut wcx.task, || {
@@ -135,6 +188,96 @@ impl StreamWatcher {
             n => Err(UvError(n)),
         }
     }
+
+    // Shuts down the writing half of the stream once all pending writes have
+    // been flushed. The other end will see the end of the stream, but can keep
+    // on sending data to be read from this one.
+    pub fn shutdown(&mut self) -> Result<(), UvError> {
+        struct Ctx { task: Option<BlockedTask>, status: c_int }
+
+        let mut req = Request::new(uvll::UV_SHUTDOWN);
+        return match unsafe {
+            uvll::uv_shutdown(req.handle, self.handle, shutdown_cb)
+        } {
+            0 => {
+                req.defuse(); // uv callback now owns this request
+                let mut cx = Ctx { task: None, status: 0 };
+                wait_until_woken_after(&mut cx.task, || {
+                    req.set_data(&cx);
+                });
+                match cx.status {
+                    0 => Ok(()),
+                    n => Err(UvError(n)),
+                }
+            }
+            n => Err(UvError(n)),
+        };
+
+        extern fn shutdown_cb(req: *uvll::uv_shutdown_t, status: c_int) {
+            let req = Request::wrap(req);
+            assert!(status != uvll::ECANCELED);
+            let cx: &mut Ctx = unsafe { req.get_data() };
+            cx.status = status;
+            let sched: ~Scheduler = Local::take();
+            sched.resume_blocked_task_immediately(cx.task.take_unwrap());
+        }
+    }
+
+    // libuv has no way of shutting down the reading half of a stream, so this
+    // goes straight to the underlying socket. Streams which aren't sockets
+    // can't be shut down for reading at all.
+    pub fn close_read(&mut self) -> Result<(), IoError> {
+        sockopt::socket_of(self.handle).and_then(|fd| {
+            sockopt::shutdown(fd, libc::SHUT_RD)
+        })
+    }
+
+    // A write which is abandoned when it times out. libuv has no way of
+    // cancelling a queued write, so the request and its context are allocated
+    // such that they can be handed over to write_cb (which frees them) if the
+    // writing task stops waiting for it.
+    fn write_with_timeout(&mut self, buf: &[u8]) -> Result<(), UvError> {
+        let mut req = match self.last_write_req.take() {
+            Some(req) => req, None => Request::new(uvll::UV_WRITE),
+        };
+        req.set_data(ptr::null::<()>());
+
+        let data = buf.to_owned();
+        let uvbuf = slice_to_uv_buf(data);
+        match unsafe {
+            uvll::uv_write(req.handle, self.handle, [uvbuf], write_cb)
+        } {
+            0 => {
+                let wcx = ~WriteContext {
+                    result: 0,
+                    task: None,
+                    data: Some(data),
+                };
+                let wcx: *mut WriteContext = unsafe { cast::transmute(wcx) };
+                req.defuse(); // uv callback now owns this request
+
+                self.write_timeout.arm(self.handle, wcx as *c_void,
+                                       write_timed_out);
+                unsafe {
+                    wait_until_woken_after(&mut (*wcx).task, || {
+                        req.set_data(wcx);
+                    });
+                }
+                if self.write_timeout.disarm() {
+                    // The request (and the context) now belong to write_cb
+                    return Err(UvError(uvll::ETIMEDOUT));
+                }
+
+                let wcx: ~WriteContext = unsafe { cast::transmute(wcx) };
+                self.last_write_req = Some(Request::wrap(req.handle));
+                match wcx.result {
+                    0 => Ok(()),
+                    n => Err(UvError(n)),
+                }
+            }
+            n => Err(UvError(n)),
+        }
+    }
 }
 
 // This allocation callback expects to be invoked once and only once. It will
@@ -173,13 +316,41 @@ extern fn read_cb(handle: *uvll::uv_stream_t, nread: ssize_t, _buf: *Buf) {
 // away the error code as a result.
 extern fn write_cb(req: *uvll::uv_write_t, status: c_int) {
     let mut req = Request::wrap(req);
-    assert!(status != uvll::ECANCELED);
-    // Remember to not free the request because it is re-used between writes on
-    // the same stream.
     let wcx: &mut WriteContext = unsafe { req.get_data() };
     wcx.result = status;
-    req.defuse();
 
+    match wcx.task.take() {
+        Some(task) => {
+            assert!(status != uvll::ECANCELED);
+            // Remember to not free the request because it is re-used between
+            // writes on the same stream.
+            req.defuse();
+            let sched: ~Scheduler = Local::take();
+            sched.resume_blocked_task_immediately(task);
+        }
+        // The writer timed out and is no longer waiting (the stream may even
+        // have been closed since), so free the context along with the request.
+        None => {
+            let _wcx: ~WriteContext = unsafe { cast::transmute(wcx) };
+        }
+    }
+}
+
+// Invoked when a read times out. The read is stopped so that libuv will not
+// touch the buffer again once the reading task has been woken up.
+fn read_timed_out(data: *c_void) {
+    let rcx: &mut ReadContext = unsafe { cast::transmute(data) };
+    unsafe { assert_eq!(uvll::uv_read_stop(rcx.handle), 0); }
+    rcx.result = uvll::ETIMEDOUT as ssize_t;
+
+    let scheduler: ~Scheduler = Local::take();
+    scheduler.resume_blocked_task_immediately(rcx.task.take_unwrap());
+}
+
+// Invoked when a write times out. The write itself remains queued in libuv, and
+// write_cb takes care of cleaning up after it.
+fn write_timed_out(data: *c_void) {
+    let wcx: &mut WriteContext = unsafe { cast::transmute(data) };
     let sched: ~Scheduler = Local::take();
     sched.resume_blocked_task_immediately(wcx.task.take_unwrap());
 }
@@ -0,0 +1,181 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cast;
+use std::libc::{c_int, c_void};
+use std::ptr;
+use std::rt::BlockedTask;
+use std::rt::local::Local;
+use std::rt::sched::Scheduler;
+
+use super::{UvHandle, UvError, Request, ForbidSwitch, wait_until_woken_after};
+use uvll;
+
+// A helper structure for putting a time limit on blocking I/O operations.
+//
+// Each watcher which supports timeouts embeds one of these for every kind of
+// operation which can time out. The underlying uv timer is only allocated the
+// first time that it is armed, and it must be explicitly closed by the
+// enclosing watcher's destructor (which runs while homed, unlike the destructors
+// of its fields).
+//
+// While an operation is blocked, the timer's data field points at this
+// structure, so it must not move between `arm` and `disarm`. This always holds
+// because both are called from the same `&mut self` method of the watcher.
+pub struct Timeout {
+    priv timer: *uvll::uv_timer_t,
+    priv ms: Option<u64>,
+    priv fired: bool,
+    priv data: *c_void,
+    priv on_timeout: Option<fn(*c_void)>,
+}
+
+impl Timeout {
+    pub fn new() -> Timeout {
+        Timeout {
+            timer: ptr::null(),
+            ms: None,
+            fired: false,
+            data: ptr::null(),
+            on_timeout: None,
+        }
+    }
+
+    // Sets the time limit for all future operations, `None` meaning that they
+    // may block forever.
+    pub fn set(&mut self, ms: Option<u64>) { self.ms = ms; }
+
+    pub fn is_set(&self) -> bool { self.ms.is_some() }
+
+    // Starts the timer (if a limit is set) before blocking on an operation of
+    // `handle`. If the limit expires before the operation completes, then
+    // `on_timeout` is invoked with `data` from inside the event loop. It is
+    // responsible for cancelling the operation and waking up the blocked task.
+    pub fn arm<T>(&mut self, handle: *T, data: *c_void, on_timeout: fn(*c_void)) {
+        let ms = match self.ms { Some(ms) => ms, None => return };
+        if self.timer.is_null() {
+            self.timer = UvHandle::alloc(None::<Timeout>, uvll::UV_TIMER);
+            assert_eq!(unsafe {
+                let loop_ = uvll::get_loop_for_uv_handle(handle);
+                uvll::uv_timer_init(loop_ as *uvll::uv_loop_t, self.timer)
+            }, 0);
+        }
+        self.fired = false;
+        self.data = data;
+        self.on_timeout = Some(on_timeout);
+        unsafe {
+            uvll::set_data_for_uv_handle(self.timer, self as *mut Timeout);
+            assert_eq!(uvll::uv_timer_start(self.timer, timer_cb, ms, 0), 0);
+        }
+    }
+
+    // Stops the timer after the blocked task has been woken up, returning
+    // whether it was woken up because the time limit expired.
+    pub fn disarm(&mut self) -> bool {
+        if !self.timer.is_null() {
+            assert_eq!(unsafe { uvll::uv_timer_stop(self.timer) }, 0);
+        }
+        self.on_timeout = None;
+        self.data = ptr::null();
+        self.fired
+    }
+
+    // Frees the underlying timer, if one was ever allocated. This must be
+    // called from the home of the enclosing watcher.
+    pub fn close(&mut self) {
+        if !self.timer.is_null() {
+            self.close_async_();
+            self.timer = ptr::null();
+        }
+    }
+}
+
+impl UvHandle<uvll::uv_timer_t> for Timeout {
+    fn uv_handle(&self) -> *uvll::uv_timer_t { self.timer }
+}
+
+extern fn timer_cb(timer: *uvll::uv_timer_t, status: c_int) {
+    let _f = ForbidSwitch::new("timeout callback can't switch");
+    assert_eq!(status, 0);
+    let t: &mut Timeout = unsafe {
+        cast::transmute(uvll::get_data_for_uv_handle(timer))
+    };
+    t.fired = true;
+    let on_timeout = t.on_timeout.take_unwrap();
+    on_timeout(t.data);
+}
+
+// The state shared between a task connecting a stream (TCP or pipe) and the
+// callback of its connect request, with an optional time limit.
+pub struct ConnectCtx {
+    priv status: c_int,
+    priv task: Option<BlockedTask>,
+    priv timeout: Timeout,
+}
+
+impl ConnectCtx {
+    pub fn new(ms: Option<u64>) -> ConnectCtx {
+        let mut timeout = Timeout::new();
+        timeout.set(ms);
+        ConnectCtx { status: 0, task: None, timeout: timeout }
+    }
+
+    // Issues a connect request for `handle` through `f` and blocks until it
+    // completes. If the time limit expires first, the request is left pending
+    // and the caller is expected to close `handle`, which cancels it.
+    pub fn connect<T>(&mut self, handle: *T,
+                      f: |*uvll::uv_connect_t, uvll::uv_connect_cb| -> c_int)
+        -> Result<(), UvError>
+    {
+        let mut req = Request::new(uvll::UV_CONNECT);
+        match f(req.handle, connect_cb) {
+            0 => {
+                req.defuse(); // uv callback now owns this request
+                let me = self as *mut ConnectCtx;
+                self.timeout.arm(handle, me as *c_void, connect_timed_out);
+                wait_until_woken_after(&mut self.task, || {
+                    req.set_data(me);
+                });
+                let timed_out = self.timeout.disarm();
+                self.timeout.close();
+                if timed_out {
+                    // When the request is cancelled this context will be long
+                    // gone, so make sure that connect_cb doesn't touch it.
+                    unsafe {
+                        uvll::set_data_for_req(req.handle, ptr::null::<()>());
+                    }
+                    return Err(UvError(uvll::ETIMEDOUT));
+                }
+                match self.status {
+                    0 => Ok(()),
+                    n => Err(UvError(n)),
+                }
+            }
+            n => Err(UvError(n)),
+        }
+    }
+}
+
+extern fn connect_cb(req: *uvll::uv_connect_t, status: c_int) {
+    let req = Request::wrap(req);
+    let data = unsafe { uvll::get_data_for_req(req.handle) };
+    if data.is_null() { return } // the connecting task timed out
+    assert!(status != uvll::ECANCELED);
+    let cx: &mut ConnectCtx = unsafe { cast::transmute(data) };
+    cx.status = status;
+    let scheduler: ~Scheduler = Local::take();
+    scheduler.resume_blocked_task_immediately(cx.task.take_unwrap());
+}
+
+fn connect_timed_out(data: *c_void) {
+    let cx: &mut ConnectCtx = unsafe { cast::transmute(data) };
+    let scheduler: ~Scheduler = Local::take();
+    scheduler.resume_blocked_task_immediately(cx.task.take_unwrap());
+}
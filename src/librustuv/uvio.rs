@@ -14,6 +14,7 @@ use std::libc::c_int;
 use std::libc;
 use std::path::Path;
 use std::io::IoError;
+use std::io::net::BindOptions;
 use std::io::net::ip::SocketAddr;
 use std::io::process::ProcessConfig;
 use std::io;
@@ -198,27 +199,25 @@ impl IoFactory for UvIoFactory {
     // Connect to an address and return a new stream
     // NB: This blocks the task waiting on the connection.
     // It would probably be better to return a future
-    fn tcp_connect(&mut self, addr: SocketAddr)
+    fn tcp_connect(&mut self, addr: SocketAddr, timeout: Option<u64>)
         -> Result<~RtioTcpStream, IoError>
     {
-        match TcpWatcher::connect(self.uv_loop(), addr) {
+        match TcpWatcher::connect(self.uv_loop(), addr, timeout) {
             Ok(t) => Ok(~t as ~RtioTcpStream),
             Err(e) => Err(uv_error_to_io_error(e)),
         }
     }
 
-    fn tcp_bind(&mut self, addr: SocketAddr) -> Result<~RtioTcpListener, IoError> {
-        match TcpListener::bind(self.uv_loop(), addr) {
-            Ok(t) => Ok(t as ~RtioTcpListener),
-            Err(e) => Err(uv_error_to_io_error(e)),
-        }
+    fn tcp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> Result<~RtioTcpListener, IoError> {
+        TcpListener::bind_with_options(self.uv_loop(), addr, opts)
+                    .map(|t| t as ~RtioTcpListener)
     }
 
-    fn udp_bind(&mut self, addr: SocketAddr) -> Result<~RtioUdpSocket, IoError> {
-        match UdpWatcher::bind(self.uv_loop(), addr) {
-            Ok(u) => Ok(~u as ~RtioUdpSocket),
-            Err(e) => Err(uv_error_to_io_error(e)),
-        }
+    fn udp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> Result<~RtioUdpSocket, IoError> {
+        UdpWatcher::bind_with_options(self.uv_loop(), addr, opts)
+                   .map(|u| ~u as ~RtioUdpSocket)
     }
 
     fn timer_init(&mut self) -> Result<~RtioTimer, IoError> {
@@ -295,6 +294,16 @@ impl IoFactory for UvIoFactory {
         let r = FsRequest::readdir(self.uv_loop(), path, flags);
         r.map_err(uv_error_to_io_error)
     }
+    fn fs_opendir(&mut self, path: &CString) -> Result<~RtioDirectory, IoError> {
+        // This libuv has no way to read a directory entry by entry or to get
+        // the types of the entries, so the listing is read here all at once
+        // and the entries are left to be lstat'ed.
+        let r = FsRequest::readdir(self.uv_loop(), path, 0);
+        match r {
+            Ok(paths) => Ok(~DirListing::new(paths) as ~RtioDirectory),
+            Err(e) => Err(uv_error_to_io_error(e)),
+        }
+    }
     fn fs_link(&mut self, src: &CString, dst: &CString) -> Result<(), IoError> {
         let r = FsRequest::link(self.uv_loop(), src, dst);
         r.map_err(uv_error_to_io_error)
@@ -338,8 +347,10 @@ impl IoFactory for UvIoFactory {
         }
     }
 
-    fn unix_connect(&mut self, path: &CString) -> Result<~RtioPipe, IoError> {
-        match PipeWatcher::connect(self.uv_loop(), path) {
+    fn unix_connect(&mut self, path: &CString, timeout: Option<u64>)
+        -> Result<~RtioPipe, IoError>
+    {
+        match PipeWatcher::connect(self.uv_loop(), path, timeout) {
             Ok(p) => Ok(~p as ~RtioPipe),
             Err(e) => Err(uv_error_to_io_error(e)),
         }
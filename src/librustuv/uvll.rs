@@ -55,6 +55,7 @@ pub mod errors {
     pub static ECONNABORTED: c_int = -4079;
     pub static ECANCELED: c_int = -4081;
     pub static EBADF: c_int = -4083;
+    pub static ETIMEDOUT: c_int = -4039;
 }
 #[cfg(not(windows))]
 pub mod errors {
@@ -69,6 +70,7 @@ pub mod errors {
     pub static ECONNABORTED: c_int = -libc::ECONNABORTED;
     pub static ECANCELED : c_int = -libc::ECANCELED;
     pub static EBADF : c_int = -libc::EBADF;
+    pub static ETIMEDOUT : c_int = -libc::ETIMEDOUT;
 }
 
 pub static PROCESS_SETUID: c_int = 1 << 0;
@@ -77,6 +79,9 @@ pub static PROCESS_WINDOWS_VERBATIM_ARGUMENTS: c_int = 1 << 2;
 pub static PROCESS_DETACHED: c_int = 1 << 3;
 pub static PROCESS_WINDOWS_HIDE: c_int = 1 << 4;
 
+pub static UV_READABLE: c_int = 1;
+pub static UV_WRITABLE: c_int = 2;
+
 pub static STDIO_IGNORE: c_int = 0x00;
 pub static STDIO_CREATE_PIPE: c_int = 0x01;
 pub static STDIO_INHERIT_FD: c_int = 0x02;
@@ -139,6 +144,7 @@ pub type uv_udp_t = c_void;
 pub type uv_connect_t = c_void;
 pub type uv_connection_t = c_void;
 pub type uv_write_t = c_void;
+pub type uv_shutdown_t = c_void;
 pub type uv_async_t = c_void;
 pub type uv_timer_t = c_void;
 pub type uv_stream_t = c_void;
@@ -146,6 +152,16 @@ pub type uv_fs_t = c_void;
 pub type uv_udp_send_t = c_void;
 pub type uv_getaddrinfo_t = c_void;
 pub type uv_process_t = c_void;
+pub type uv_poll_t = c_void;
+
+#[cfg(unix)]
+pub type uv_os_sock_t = c_int;
+#[cfg(windows)]
+pub type uv_os_sock_t = libc::SOCKET;
+#[cfg(unix)]
+pub type uv_os_fd_t = c_int;
+#[cfg(windows)]
+pub type uv_os_fd_t = libc::HANDLE;
 pub type uv_pipe_t = c_void;
 pub type uv_tty_t = c_void;
 pub type uv_signal_t = c_void;
@@ -231,6 +247,8 @@ pub type uv_timer_cb = extern "C" fn(handle: *uv_timer_t,
                                      status: c_int);
 pub type uv_write_cb = extern "C" fn(handle: *uv_write_t,
                                      status: c_int);
+pub type uv_shutdown_cb = extern "C" fn(req: *uv_shutdown_t,
+                                        status: c_int);
 pub type uv_getaddrinfo_cb = extern "C" fn(req: *uv_getaddrinfo_t,
                                            status: c_int,
                                            res: *addrinfo);
@@ -240,6 +258,9 @@ pub type uv_exit_cb = extern "C" fn(handle: *uv_process_t,
 pub type uv_signal_cb = extern "C" fn(handle: *uv_signal_t,
                                       signum: c_int);
 pub type uv_fs_cb = extern "C" fn(req: *uv_fs_t);
+pub type uv_poll_cb = extern "C" fn(handle: *uv_poll_t,
+                                    status: c_int,
+                                    events: c_int);
 
 pub type sockaddr = c_void;
 
@@ -573,6 +594,7 @@ extern {
     pub fn uv_run(l: *uv_loop_t, mode: uv_run_mode) -> c_int;
     pub fn uv_close(h: *uv_handle_t, cb: uv_close_cb);
     pub fn uv_walk(l: *uv_loop_t, cb: uv_walk_cb, arg: *c_void);
+    pub fn uv_fileno(h: *uv_handle_t, fd: *mut uv_os_fd_t) -> c_int;
     pub fn uv_buf_init(base: *c_char, len: c_uint) -> uv_buf_t;
     pub fn uv_strerror(err: c_int) -> *c_char;
     pub fn uv_err_name(err: c_int) -> *c_char;
@@ -583,6 +605,8 @@ extern {
                          on_alloc: uv_alloc_cb,
                          on_read: uv_read_cb) -> c_int;
     pub fn uv_read_stop(stream: *uv_stream_t) -> c_int;
+    pub fn uv_shutdown(req: *uv_shutdown_t, stream: *uv_stream_t,
+                       cb: uv_shutdown_cb) -> c_int;
 
     // idle bindings
     pub fn uv_idle_init(l: *uv_loop_t, i: *uv_idle_t) -> c_int;
@@ -599,6 +623,7 @@ extern {
     pub fn uv_tcp_connect(c: *uv_connect_t, h: *uv_tcp_t,
                           addr: *sockaddr, cb: uv_connect_cb) -> c_int;
     pub fn uv_tcp_bind(t: *uv_tcp_t, addr: *sockaddr) -> c_int;
+    pub fn uv_tcp_open(h: *uv_tcp_t, sock: uv_os_sock_t) -> c_int;
     pub fn uv_ip4_name(src: *sockaddr, dst: *c_char,
                        size: size_t) -> c_int;
     pub fn uv_ip6_name(src: *sockaddr, dst: *c_char,
@@ -617,6 +642,7 @@ extern {
     // udp bindings
     pub fn uv_udp_init(l: *uv_loop_t, h: *uv_udp_t) -> c_int;
     pub fn uv_udp_bind(h: *uv_udp_t, addr: *sockaddr, flags: c_uint) -> c_int;
+    pub fn uv_udp_open(h: *uv_udp_t, sock: uv_os_sock_t) -> c_int;
     pub fn uv_udp_recv_start(server: *uv_udp_t,
                              on_alloc: uv_alloc_cb,
                              on_recv: uv_udp_recv_cb) -> c_int;
@@ -715,6 +741,13 @@ extern {
     pub fn uv_signal_start(h: *uv_signal_t, cb: uv_signal_cb,
                            signum: c_int) -> c_int;
     pub fn uv_signal_stop(handle: *uv_signal_t) -> c_int;
+
+    // polling
+    pub fn uv_poll_init_socket(loop_: *uv_loop_t, handle: *uv_poll_t,
+                               socket: uv_os_sock_t) -> c_int;
+    pub fn uv_poll_start(handle: *uv_poll_t, events: c_int,
+                         cb: uv_poll_cb) -> c_int;
+    pub fn uv_poll_stop(handle: *uv_poll_t) -> c_int;
 }
 
 // libuv requires various system libraries to successfully link on some
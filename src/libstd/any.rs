@@ -14,6 +14,7 @@
 use cast::transmute;
 use cmp::Eq;
 use option::{Option, Some, None};
+use hash::{Hash, Hasher};
 use to_bytes::{IterBytes, Cb};
 use to_str::ToStr;
 use unstable::intrinsics;
@@ -49,6 +50,13 @@ impl IterBytes for TypeId {
     }
 }
 
+impl Hash for TypeId {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.t.hash(state)
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // Any trait
 ///////////////////////////////////////////////////////////////////////////////
@@ -182,7 +190,7 @@ mod tests {
     use super::*;
     use super::AnyRefExt;
     use option::{Some, None};
-    use hash::Hash;
+    use hash;
 
     #[deriving(Eq)]
     struct Test;
@@ -209,7 +217,7 @@ mod tests {
     fn type_id_hash() {
         let (a, b) = (TypeId::of::<uint>(), TypeId::of::<uint>());
 
-        assert_eq!(a.hash(), b.hash());
+        assert_eq!(hash::hash(&a), hash::hash(&b));
     }
 
     #[test]
@@ -18,6 +18,7 @@ use container::Container;
 use cast;
 use iter::Iterator;
 use vec::{ImmutableVector, MutableVector};
+use hash::{Hash, Hasher};
 use to_bytes::IterBytes;
 use option::{Some, None};
 
@@ -308,6 +309,13 @@ impl IterBytes for Ascii {
     }
 }
 
+impl Hash for Ascii {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_byte().hash(state)
+    }
+}
+
 /// Trait to convert to a owned byte array by consuming self
 pub trait ToBytesConsume {
     /// Converts to a owned byte array by consuming self
@@ -18,7 +18,13 @@ use clone::Clone;
 use iter::Iterator;
 use kinds::Send;
 use option::Option;
+use rt::kill::BlockedTask;
+use rt::sched::Scheduler;
+use rt::shouldnt_be_public::{SelectInner, SelectPortInner};
 use rtcomm = rt::comm;
+use select::{Select, SelectPort};
+
+pub use rt::comm::{BoundedPort, BoundedChan, bounded};
 
 /// A trait for things that can send multiple messages.
 pub trait GenericChan<T> {
@@ -138,6 +144,31 @@ impl<T: Send> Peekable<T>  for PortOne<T> {
     }
 }
 
+impl<T: Send> SelectInner for PortOne<T> {
+    fn optimistic_check(&mut self) -> bool {
+        self.x.optimistic_check()
+    }
+
+    fn block_on(&mut self, sched: &mut Scheduler, task: BlockedTask) -> bool {
+        self.x.block_on(sched, task)
+    }
+
+    fn unblock_from(&mut self) -> bool {
+        self.x.unblock_from()
+    }
+}
+
+impl<T: Send> Select for PortOne<T> { }
+
+impl<T: Send> SelectPortInner<T> for PortOne<T> {
+    fn recv_ready(self) -> Option<T> {
+        let PortOne { x: p } = self;
+        p.recv_ready()
+    }
+}
+
+impl<T: Send> SelectPort<T> for PortOne<T> { }
+
 impl<T: Send> GenericChan<T> for Chan<T> {
     fn send(&self, val: T) {
         let &Chan { x: ref c } = self;
@@ -183,6 +214,22 @@ impl<T: Send> Peekable<T> for Port<T> {
     }
 }
 
+impl<T: Send> SelectInner for Port<T> {
+    fn optimistic_check(&mut self) -> bool {
+        self.x.optimistic_check()
+    }
+
+    fn block_on(&mut self, sched: &mut Scheduler, task: BlockedTask) -> bool {
+        self.x.block_on(sched, task)
+    }
+
+    fn unblock_from(&mut self) -> bool {
+        self.x.unblock_from()
+    }
+}
+
+impl<T: Send> Select for Port<T> { }
+
 
 pub struct SharedChan<T> { priv x: rtcomm::SharedChan<T> }
 
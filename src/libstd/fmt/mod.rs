@@ -154,7 +154,9 @@ What this means is that any type of argument which implements the
 provided for these traits for a number of primitive types by the standard
 library as well. If no format is specified (as in `{}` or `{:6}`), then the
 format trait used is the `Default` trait. This is one of the more commonly
-implemented traits when formatting a custom type.
+implemented traits when formatting a custom type, and it can be derived with
+`#[deriving(Show)]`, which prints a value the way it would be written in
+source, such as `Point { x: 1, y: 2 }`.
 
 When implementing a format trait for your own time, you will have to implement a
 method of the signature:
@@ -931,6 +933,13 @@ impl<'self> Formatter<'self> {
         }
     }
 
+    /// Writes `s` to the output buffer verbatim, ignoring all of the
+    /// formatting parameters. This is how `#[deriving(Show)]` emits the
+    /// punctuation around each field.
+    pub fn write_str(&mut self, s: &str) {
+        self.buf.write(s.as_bytes());
+    }
+
     fn with_padding(&mut self,
                     padding: uint,
                     default: parse::Alignment,
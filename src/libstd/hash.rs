@@ -28,190 +28,387 @@
 
 use container::Container;
 use iter::Iterator;
-use option::{Some, None};
+use option::{Option, Some, None};
 use io::Writer;
-use str::OwnedStr;
-use to_bytes::IterBytes;
-use vec::ImmutableVector;
-use num::ToStrRadix;
-
-// Alias `SipState` to `State`.
-pub use State = hash::SipState;
+use kinds::{Freeze, Send};
+use rc::Rc;
+use str::{Str, StrSlice};
+use vec::{Vector, ImmutableVector};
+use cast;
+use clone::Clone;
 
 /**
- * Types that can meaningfully be hashed should implement this.
+ * A streaming hash function that `Hash` values are fed into.
  *
- * Note that this trait is likely to change somewhat as it is
- * closely related to `to_bytes::IterBytes` and in almost all
- * cases presently the two are (and must be) used together.
+ * `SipState` is the default, keyed, implementation. Hash tables can be given
+ * a different hasher, so tables that are not exposed to untrusted keys may
+ * use a cheaper non-keyed one instead; see `HashMap::with_hasher`.
+ */
+pub trait Hasher {
+    /// Feed `bytes` into the hash state.
+    fn write(&mut self, bytes: &[u8]);
+
+    /// Compute the hash of all bytes written so far. This does not reset
+    /// the state, so more input may follow.
+    fn finish(&self) -> u64;
+}
+
+/**
+ * Types that can meaningfully be hashed should implement this, which is
+ * usually done with `#[deriving(Hash)]`.
  *
- * In general, most types only need to implement `IterBytes`,
- * and the implementation of `Hash` below will take care of
- * the rest. This is the recommended approach, since constructing
- * good keyed hash functions is quite difficult.
+ * An implementation should feed enough of the value into the state that
+ * the extent of the input can be recovered: the implementation for
+ * vectors feeds their length first, and enums feed their discriminant.
+ * Values that compare equal must feed identical input.
  */
 pub trait Hash {
-    /**
-     * Compute a "keyed" hash of the value implementing the trait,
-     * taking `k0` and `k1` as "keying" parameters that randomize or
-     * otherwise perturb the hash function in such a way that a
-     * hash table built using such "keyed hash functions" cannot
-     * be made to perform linearly by an attacker controlling the
-     * hashtable's contents.
-     *
-     * In practical terms, we implement this using the SipHash 2-4
-     * function and require most types to only implement the
-     * IterBytes trait, that feeds SipHash.
-     */
-    fn hash_keyed(&self, k0: u64, k1: u64) -> u64;
-
-    #[inline]
-    fn hash(&self) -> u64 { self.hash_keyed(0,0) }
-}
-
-/// Streaming hash-functions should implement this.
-pub trait Streaming {
-    fn input(&mut self, &[u8]);
-    // These can be refactored some when we have default methods.
-    fn result_bytes(&mut self) -> ~[u8];
-    fn result_str(&mut self) -> ~str;
-    fn result_u64(&mut self) -> u64;
-    fn reset(&mut self);
-}
-
-impl<A:IterBytes> Hash for A {
-    #[inline]
-    fn hash_keyed(&self, k0: u64, k1: u64) -> u64 {
-        let mut s = State::new(k0, k1);
-        self.iter_bytes(true, |bytes| {
-            s.input(bytes);
-            true
-        });
-        s.result_u64()
-    }
-}
-
-fn hash_keyed_2<A: IterBytes,
-                B: IterBytes>(a: &A, b: &B, k0: u64, k1: u64) -> u64 {
-    let mut s = State::new(k0, k1);
-    a.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    b.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    s.result_u64()
-}
-
-fn hash_keyed_3<A: IterBytes,
-                B: IterBytes,
-                C: IterBytes>(a: &A, b: &B, c: &C, k0: u64, k1: u64) -> u64 {
-    let mut s = State::new(k0, k1);
-    a.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    b.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    c.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    s.result_u64()
-}
-
-fn hash_keyed_4<A: IterBytes,
-                B: IterBytes,
-                C: IterBytes,
-                D: IterBytes>(
-                a: &A,
-                b: &B,
-                c: &C,
-                d: &D,
-                k0: u64,
-                k1: u64)
-                -> u64 {
-    let mut s = State::new(k0, k1);
-    a.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    b.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    c.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    d.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    s.result_u64()
-}
-
-fn hash_keyed_5<A: IterBytes,
-                B: IterBytes,
-                C: IterBytes,
-                D: IterBytes,
-                E: IterBytes>(
-                a: &A,
-                b: &B,
-                c: &C,
-                d: &D,
-                e: &E,
-                k0: u64,
-                k1: u64)
-                -> u64 {
-    let mut s = State::new(k0, k1);
-    a.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    b.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    c.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    d.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    e.iter_bytes(true, |bytes| {
-        s.input(bytes);
-        true
-    });
-    s.result_u64()
+    /// Feed this value into `state`.
+    fn hash<H: Hasher>(&self, state: &mut H);
+}
+
+/**
+ * The hasher of a hash table, kept behind a trait object so that the type of
+ * the table does not depend on it. Every `Hasher` that is `Clone`, `Send` and
+ * `Freeze` implements this; each key is hashed with a fresh copy of the
+ * table's state.
+ */
+pub trait TableHasher {
+    /// Feed `f` a copy of this state and return the hash of what it wrote.
+    fn hash_copy(&self, f: |&mut TableHasher|) -> u64;
+
+    /// Box a copy of this state, for a copy of the table.
+    fn clone_hasher(&self) -> ~TableHasher:Send+Freeze;
+
+    /// As `Hasher::write`, for the copies given to `hash_copy`.
+    fn write_bytes(&mut self, bytes: &[u8]);
+
+    /// As `Hasher::finish`, for the copies given to `hash_copy`.
+    fn finish_hash(&self) -> u64;
+}
+
+impl<H: Hasher + Clone + Send + Freeze> TableHasher for H {
+    #[inline]
+    fn hash_copy(&self, f: |&mut TableHasher|) -> u64 {
+        let mut state = self.clone();
+        f(&mut state as &mut TableHasher);
+        state.finish()
+    }
+
+    fn clone_hasher(&self) -> ~TableHasher:Send+Freeze {
+        ~self.clone() as ~TableHasher:Send+Freeze
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write(bytes)
+    }
+
+    #[inline]
+    fn finish_hash(&self) -> u64 {
+        self.finish()
+    }
+}
+
+impl<'self> Hasher for &'self mut TableHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.write_bytes(bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish_hash()
+    }
 }
 
+/// Compute the SipHash of `value`, keyed with `k0` and `k1`.
 #[inline]
-pub fn default_state() -> State {
-    State::new(0, 0)
+pub fn hash_keyed<T: Hash>(value: &T, k0: u64, k1: u64) -> u64 {
+    let mut state = SipState::new(k0, k1);
+    value.hash(&mut state);
+    state.finish()
+}
+
+/// Compute the SipHash of `value` with both keys set to zero.
+#[inline]
+pub fn hash<T: Hash>(value: &T) -> u64 {
+    hash_keyed(value, 0, 0)
+}
+
+impl Hash for bool {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as u8).hash(state)
+    }
+}
+
+impl Hash for u8 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write([*self])
+    }
+}
+
+impl Hash for u16 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write([*self as u8, (*self >> 8) as u8])
+    }
+}
+
+impl Hash for u32 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write([
+            *self as u8,
+            (*self >> 8) as u8,
+            (*self >> 16) as u8,
+            (*self >> 24) as u8,
+        ])
+    }
+}
+
+impl Hash for u64 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write([
+            *self as u8,
+            (*self >> 8) as u8,
+            (*self >> 16) as u8,
+            (*self >> 24) as u8,
+            (*self >> 32) as u8,
+            (*self >> 40) as u8,
+            (*self >> 48) as u8,
+            (*self >> 56) as u8,
+        ])
+    }
+}
+
+macro_rules! hash_as(
+    ($ty:ty, $as_ty:ty) => (
+        impl Hash for $ty {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                (*self as $as_ty).hash(state)
+            }
+        }
+    )
+)
+
+hash_as!(i8, u8)
+hash_as!(i16, u16)
+hash_as!(i32, u32)
+hash_as!(i64, u64)
+hash_as!(char, u32)
+hash_as!(int, uint)
+
+#[cfg(target_word_size = "32")]
+impl Hash for uint {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as u32).hash(state)
+    }
+}
+
+#[cfg(target_word_size = "64")]
+impl Hash for uint {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as u64).hash(state)
+    }
+}
+
+impl Hash for f32 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let i: u32 = unsafe {
+            // 0.0 == -0.0 so they should also have the same hashcode
+            cast::transmute(if *self == -0.0 { 0.0 } else { *self })
+        };
+        i.hash(state)
+    }
+}
+
+impl Hash for f64 {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let i: u64 = unsafe {
+            // 0.0 == -0.0 so they should also have the same hashcode
+            cast::transmute(if *self == -0.0 { 0.0 } else { *self })
+        };
+        i.hash(state)
+    }
+}
+
+impl Hash for () {
+    #[inline]
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl<A: Hash> Hash for (A, ) {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            (ref a, ) => a.hash(state)
+        }
+    }
+}
+
+macro_rules! hash_tuple(
+    ($($A:ident),+) => (
+        impl<$($A: Hash),+> Hash for ($($A),+) {
+            #[inline]
+            fn hash<S: Hasher>(&self, state: &mut S) {
+                match *self {
+                    ($(ref $A),+) => {
+                        $(
+                            $A.hash(state);
+                        )+
+                    }
+                }
+            }
+        }
+    )
+)
+
+hash_tuple!(A, B)
+hash_tuple!(A, B, C)
+hash_tuple!(A, B, C, D)
+hash_tuple!(A, B, C, D, E)
+hash_tuple!(A, B, C, D, E, F)
+hash_tuple!(A, B, C, D, E, F, G)
+hash_tuple!(A, B, C, D, E, F, G, H)
+
+impl<'self, A: Hash> Hash for &'self [A] {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self.iter() {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<A: Hash> Hash for ~[A] {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<A: Hash> Hash for @[A] {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<'self> Hash for &'self str {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Terminate the string with a byte that does not appear in UTF-8
+        state.write(self.as_bytes());
+        state.write([0xFF]);
+    }
+}
+
+impl Hash for ~str {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl Hash for @str {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<A: Hash> Hash for Option<A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Some(ref a) => {
+                0u8.hash(state);
+                a.hash(state);
+            }
+            None => 1u8.hash(state)
+        }
+    }
+}
+
+impl<'self, A: Hash> Hash for &'self A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<A: Hash> Hash for @A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<A: Hash> Hash for @mut A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<A: Hash> Hash for Rc<A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.borrow().hash(state)
+    }
+}
+
+impl<A: Hash> Hash for ~A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
 }
 
-struct SipState {
-    k0: u64,
-    k1: u64,
-    length: uint, // how many bytes we've processed
-    v0: u64,      // hash state
-    v1: u64,
-    v2: u64,
-    v3: u64,
-    tail: [u8, ..8], // unprocessed bytes
-    ntail: uint,  // how many bytes in tail are valid
+// NB: raw-pointer Hash does _not_ dereference
+// to the target; it just hashes the pointer value.
+impl<A> Hash for *A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as uint).hash(state)
+    }
+}
+
+impl<A> Hash for *mut A {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as uint).hash(state)
+    }
+}
+
+/// The state of a SipHash 2-4 computation. A hash table keeps a fresh state,
+/// keyed randomly, and hashes each key with a copy of it.
+pub struct SipState {
+    priv k0: u64,
+    priv k1: u64,
+    priv length: uint, // how many bytes we've processed
+    priv v0: u64,      // hash state
+    priv v1: u64,
+    priv v2: u64,
+    priv v3: u64,
+    priv tail: [u8, ..8], // unprocessed bytes
+    priv ntail: uint,  // how many bytes in tail are valid
 }
 
 impl SipState {
+    /// Creates a state keyed with `key0` and `key1`.
     #[inline]
-    fn new(key0: u64, key1: u64) -> SipState {
+    pub fn new(key0: u64, key1: u64) -> SipState {
         let mut state = SipState {
             k0: key0,
             k1: key1,
@@ -226,6 +423,17 @@ impl SipState {
         state.reset();
         state
     }
+
+    /// Discard everything written so far, keeping the keys.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.length = 0;
+        self.v0 = self.k0 ^ 0x736f6d6570736575;
+        self.v1 = self.k1 ^ 0x646f72616e646f6d;
+        self.v2 = self.k0 ^ 0x6c7967656e657261;
+        self.v3 = self.k1 ^ 0x7465646279746573;
+        self.ntail = 0;
+    }
 }
 
 // sadly, these macro definitions can't appear later,
@@ -262,10 +470,9 @@ macro_rules! compress (
 )
 
 
-impl Writer for SipState {
-    // Methods for io::writer
+impl SipState {
     #[inline]
-    fn write(&mut self, msg: &[u8]) {
+    fn process(&mut self, msg: &[u8]) {
         let length = msg.len();
         self.length += length;
 
@@ -325,19 +532,8 @@ impl Writer for SipState {
         self.ntail = left;
     }
 
-    fn flush(&mut self) {
-        // No-op
-    }
-}
-
-impl Streaming for SipState {
-    #[inline]
-    fn input(&mut self, buf: &[u8]) {
-        self.write(buf);
-    }
-
     #[inline]
-    fn result_u64(&mut self) -> u64 {
+    fn result(&self) -> u64 {
         let mut v0 = self.v0;
         let mut v1 = self.v1;
         let mut v2 = self.v2;
@@ -366,37 +562,36 @@ impl Streaming for SipState {
 
         return (v0 ^ v1 ^ v2 ^ v3);
     }
+}
 
-    fn result_bytes(&mut self) -> ~[u8] {
-        let h = self.result_u64();
-        ~[(h >> 0) as u8,
-          (h >> 8) as u8,
-          (h >> 16) as u8,
-          (h >> 24) as u8,
-          (h >> 32) as u8,
-          (h >> 40) as u8,
-          (h >> 48) as u8,
-          (h >> 56) as u8,
-        ]
-    }
-
-    fn result_str(&mut self) -> ~str {
-        let r = self.result_bytes();
-        let mut s = ~"";
-        for b in r.iter() {
-            s.push_str((*b as uint).to_str_radix(16u));
-        }
-        s
+impl Clone for SipState {
+    #[inline]
+    fn clone(&self) -> SipState {
+        *self
     }
+}
 
+impl Hasher for SipState {
     #[inline]
-    fn reset(&mut self) {
-        self.length = 0;
-        self.v0 = self.k0 ^ 0x736f6d6570736575;
-        self.v1 = self.k1 ^ 0x646f72616e646f6d;
-        self.v2 = self.k0 ^ 0x6c7967656e657261;
-        self.v3 = self.k1 ^ 0x7465646279746573;
-        self.ntail = 0;
+    fn write(&mut self, msg: &[u8]) {
+        self.process(msg)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.result()
+    }
+}
+
+impl Writer for SipState {
+    // Methods for io::writer
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        self.process(msg)
+    }
+
+    fn flush(&mut self) {
+        // No-op
     }
 }
 
@@ -407,9 +602,9 @@ mod tests {
 
     // Hash just the bytes of the slice, without length prefix
     struct Bytes<'self>(&'self [u8]);
-    impl<'self> IterBytes for Bytes<'self> {
-        fn iter_bytes(&self, _lsb0: bool, f: |&[u8]| -> bool) -> bool {
-            f(**self)
+    impl<'self> Hash for Bytes<'self> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write(**self)
         }
     }
 
@@ -489,32 +684,24 @@ mod tests {
         let mut stream_inc = SipState::new(k0, k1);
         let mut stream_full = SipState::new(k0, k1);
 
-        fn to_hex_str(r: &[u8, ..8]) -> ~str {
-            let mut s = ~"";
-            for b in r.iter() {
-                s.push_str((*b as uint).to_str_radix(16u));
-            }
-            s
-        }
-
         while t < 64 {
             debug!("siphash test {}", t);
             let vec = u8to64_le!(vecs[t], 0);
-            let out = Bytes(buf.as_slice()).hash_keyed(k0, k1);
+            let out = hash_keyed(&Bytes(buf.as_slice()), k0, k1);
             debug!("got {:?}, expected {:?}", out, vec);
             assert_eq!(vec, out);
 
             stream_full.reset();
-            stream_full.input(buf);
-            let f = stream_full.result_str();
-            let i = stream_inc.result_str();
-            let v = to_hex_str(&vecs[t]);
-            debug!("{}: ({}) => inc={} full={}", t, v, i, f);
+            Bytes(buf.as_slice()).hash(&mut stream_full);
+            let f = stream_full.finish();
+            let i = stream_inc.finish();
+            debug!("{}: ({}) => inc={} full={}", t, vec, i, f);
 
-            assert!(f == i && f == v);
+            assert!(f == i && f == vec);
 
+            let byte = [t as u8];
             buf.push(t as u8);
-            stream_inc.input([t as u8]);
+            Bytes(byte.as_slice()).hash(&mut stream_inc);
 
             t += 1;
         }
@@ -523,42 +710,42 @@ mod tests {
     #[test] #[cfg(target_arch = "arm")]
     fn test_hash_uint() {
         let val = 0xdeadbeef_deadbeef_u64;
-        assert!((val as u64).hash() != (val as uint).hash());
-        assert_eq!((val as u32).hash(), (val as uint).hash());
+        assert!(hash(&(val as u64)) != hash(&(val as uint)));
+        assert_eq!(hash(&(val as u32)), hash(&(val as uint)));
     }
     #[test] #[cfg(target_arch = "x86_64")]
     fn test_hash_uint() {
         let val = 0xdeadbeef_deadbeef_u64;
-        assert_eq!((val as u64).hash(), (val as uint).hash());
-        assert!((val as u32).hash() != (val as uint).hash());
+        assert_eq!(hash(&(val as u64)), hash(&(val as uint)));
+        assert!(hash(&(val as u32)) != hash(&(val as uint)));
     }
     #[test] #[cfg(target_arch = "x86")]
     fn test_hash_uint() {
         let val = 0xdeadbeef_deadbeef_u64;
-        assert!((val as u64).hash() != (val as uint).hash());
-        assert_eq!((val as u32).hash(), (val as uint).hash());
+        assert!(hash(&(val as u64)) != hash(&(val as uint)));
+        assert_eq!(hash(&(val as u32)), hash(&(val as uint)));
     }
 
     #[test]
     fn test_hash_idempotent() {
         let val64 = 0xdeadbeef_deadbeef_u64;
-        val64.hash() == val64.hash();
+        hash(&val64) == hash(&val64);
         let val32 = 0xdeadbeef_u32;
-        val32.hash() == val32.hash();
+        hash(&val32) == hash(&val32);
     }
 
     #[test]
     fn test_hash_no_bytes_dropped_64() {
         let val = 0xdeadbeef_deadbeef_u64;
 
-        assert!(val.hash() != zero_byte(val, 0).hash());
-        assert!(val.hash() != zero_byte(val, 1).hash());
-        assert!(val.hash() != zero_byte(val, 2).hash());
-        assert!(val.hash() != zero_byte(val, 3).hash());
-        assert!(val.hash() != zero_byte(val, 4).hash());
-        assert!(val.hash() != zero_byte(val, 5).hash());
-        assert!(val.hash() != zero_byte(val, 6).hash());
-        assert!(val.hash() != zero_byte(val, 7).hash());
+        assert!(hash(&val) != hash(&zero_byte(val, 0)));
+        assert!(hash(&val) != hash(&zero_byte(val, 1)));
+        assert!(hash(&val) != hash(&zero_byte(val, 2)));
+        assert!(hash(&val) != hash(&zero_byte(val, 3)));
+        assert!(hash(&val) != hash(&zero_byte(val, 4)));
+        assert!(hash(&val) != hash(&zero_byte(val, 5)));
+        assert!(hash(&val) != hash(&zero_byte(val, 6)));
+        assert!(hash(&val) != hash(&zero_byte(val, 7)));
 
         fn zero_byte(val: u64, byte: uint) -> u64 {
             assert!(byte < 8);
@@ -570,10 +757,10 @@ mod tests {
     fn test_hash_no_bytes_dropped_32() {
         let val = 0xdeadbeef_u32;
 
-        assert!(val.hash() != zero_byte(val, 0).hash());
-        assert!(val.hash() != zero_byte(val, 1).hash());
-        assert!(val.hash() != zero_byte(val, 2).hash());
-        assert!(val.hash() != zero_byte(val, 3).hash());
+        assert!(hash(&val) != hash(&zero_byte(val, 0)));
+        assert!(hash(&val) != hash(&zero_byte(val, 1)));
+        assert!(hash(&val) != hash(&zero_byte(val, 2)));
+        assert!(hash(&val) != hash(&zero_byte(val, 3)));
 
         fn zero_byte(val: u32, byte: uint) -> u32 {
             assert!(byte < 4);
@@ -583,13 +770,13 @@ mod tests {
 
     #[test]
     fn test_float_hashes_differ() {
-        assert!(0.0.hash() != 1.0.hash());
-        assert!(1.0.hash() != (-1.0).hash());
+        assert!(hash(&0.0) != hash(&1.0));
+        assert!(hash(&1.0) != hash(&(-1.0)));
     }
 
     #[test]
     fn test_float_hashes_of_zero() {
-        assert_eq!(0.0.hash(), (-0.0).hash());
+        assert_eq!(hash(&0.0), hash(&(-0.0)));
     }
 
     #[test]
@@ -602,7 +789,27 @@ mod tests {
         let w = (&[1u8, 0, 0, 0], &[], &[]);
 
         assert!(v != w);
-        assert!(s.hash() != t.hash() && s.hash() != u.hash());
-        assert!(v.hash() != w.hash());
+        assert!(hash(&s) != hash(&t) && hash(&s) != hash(&u));
+        assert!(hash(&v) != hash(&w));
+    }
+
+    #[test]
+    fn test_cloned_state_hashes_alike() {
+        let k0 = 0x_07_06_05_04_03_02_01_00_u64;
+        let k1 = 0x_0f_0e_0d_0c_0b_0a_09_08_u64;
+        let v = (~"abc", Some(3u), &[1u8, 2, 3]);
+        let mut state = SipState::new(k0, k1).clone();
+        v.hash(&mut state);
+        assert_eq!(state.finish(), hash_keyed(&v, k0, k1));
+    }
+
+    #[test]
+    fn test_finish_does_not_reset() {
+        let mut state = SipState::new(0, 0);
+        Bytes(&[1u8, 2, 3]).hash(&mut state);
+        let first = state.finish();
+        assert_eq!(first, state.finish());
+        Bytes(&[4u8]).hash(&mut state);
+        assert!(first != state.finish());
     }
 }
@@ -17,9 +17,10 @@ use container::{Container, Mutable, Map, MutableMap, Set, MutableSet};
 use clone::Clone;
 use cmp::{Eq, Equiv};
 use default::Default;
-use hash::Hash;
+use hash::{Hash, Hasher, SipState, TableHasher};
 use iter::{Iterator, FromIterator, Extendable};
 use iter::{FilterMap, Chain, Repeat, Zip};
+use kinds::{Freeze, Send};
 use num;
 use option::{None, Option, Some};
 use rand::Rng;
@@ -41,13 +42,16 @@ struct Bucket<K,V> {
 /// hash function for internal state. This means that the order of all hash maps
 /// is randomized by keying each hash map randomly on creation.
 ///
-/// It is required that the keys implement the `Eq` and `Hash` traits, although
-/// this can frequently be achieved by just implementing the `Eq` and
-/// `IterBytes` traits as `Hash` is automatically implemented for types that
-/// implement `IterBytes`.
-pub struct HashMap<K,V> {
-    priv k0: u64,
-    priv k1: u64,
+/// It is required that the keys implement the `Eq` and `Hash` traits, which
+/// can usually be achieved with `#[deriving(Eq, Hash)]`.
+///
+/// A different hasher can be selected with `HashMap::with_hasher`, for example
+/// a cheaper non-keyed one for maps that never hold untrusted keys. The map
+/// keeps it behind a trait object, so the type of the map does not change.
+/// Each key is hashed with a copy of the map's hasher, so it should be a fresh
+/// state.
+pub struct HashMap<K, V> {
+    priv hasher: ~TableHasher:Send+Freeze,
     priv resize_at: uint,
     priv size: uint,
     priv buckets: ~[Option<Bucket<K, V>>],
@@ -64,7 +68,7 @@ fn resize_at(capacity: uint) -> uint {
     (capacity * 3) / 4
 }
 
-impl<K:Hash + Eq,V> HashMap<K, V> {
+impl<K: Hash + Eq, V> HashMap<K, V> {
     #[inline]
     fn to_bucket(&self, h: uint) -> uint {
         // A good hash function with entropy spread over all of the
@@ -91,16 +95,24 @@ impl<K:Hash + Eq,V> HashMap<K, V> {
         }
     }
 
+    #[inline]
+    fn hash_key<Q:Hash>(&self, k: &Q) -> uint {
+        self.hasher.hash_copy(|state| {
+            let mut state = state;
+            k.hash(&mut state)
+        }) as uint
+    }
+
     #[inline]
     fn bucket_for_key(&self, k: &K) -> SearchResult {
-        let hash = k.hash_keyed(self.k0, self.k1) as uint;
+        let hash = self.hash_key(k);
         self.bucket_for_key_with_hash(hash, k)
     }
 
     #[inline]
     fn bucket_for_key_equiv<Q:Hash + Equiv<K>>(&self, k: &Q)
                                                -> SearchResult {
-        let hash = k.hash_keyed(self.k0, self.k1) as uint;
+        let hash = self.hash_key(k);
         self.bucket_for_key_with_hash_equiv(hash, k)
     }
 
@@ -253,12 +265,12 @@ impl<K:Hash + Eq,V> HashMap<K, V> {
     }
 }
 
-impl<K:Hash + Eq,V> Container for HashMap<K, V> {
+impl<K: Hash + Eq, V> Container for HashMap<K, V> {
     /// Return the number of elements in the map
     fn len(&self) -> uint { self.size }
 }
 
-impl<K:Hash + Eq,V> Mutable for HashMap<K, V> {
+impl<K: Hash + Eq, V> Mutable for HashMap<K, V> {
     /// Clear the map, removing all key-value pairs.
     fn clear(&mut self) {
         for bkt in self.buckets.mut_iter() {
@@ -268,7 +280,7 @@ impl<K:Hash + Eq,V> Mutable for HashMap<K, V> {
     }
 }
 
-impl<K:Hash + Eq,V> Map<K, V> for HashMap<K, V> {
+impl<K: Hash + Eq, V> Map<K, V> for HashMap<K, V> {
     /// Return a reference to the value corresponding to the key
     fn find<'a>(&'a self, k: &K) -> Option<&'a V> {
         match self.bucket_for_key(k) {
@@ -278,7 +290,7 @@ impl<K:Hash + Eq,V> Map<K, V> for HashMap<K, V> {
     }
 }
 
-impl<K:Hash + Eq,V> MutableMap<K, V> for HashMap<K, V> {
+impl<K: Hash + Eq, V> MutableMap<K, V> for HashMap<K, V> {
     /// Return a mutable reference to the value corresponding to the key
     fn find_mut<'a>(&'a mut self, k: &K) -> Option<&'a mut V> {
         let idx = match self.bucket_for_key(k) {
@@ -303,14 +315,14 @@ impl<K:Hash + Eq,V> MutableMap<K, V> for HashMap<K, V> {
             self.expand();
         }
 
-        let hash = k.hash_keyed(self.k0, self.k1) as uint;
+        let hash = self.hash_key(&k);
         self.insert_internal(hash, k, v)
     }
 
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     fn pop(&mut self, k: &K) -> Option<V> {
-        let hash = k.hash_keyed(self.k0, self.k1) as uint;
+        let hash = self.hash_key(k);
         self.pop_internal(hash, k)
     }
 }
@@ -335,10 +347,33 @@ impl<K: Hash + Eq, V> HashMap<K, V> {
     /// are designed to allow HashMaps to be resistant to attacks that
     /// cause many collisions and very poor performance. Setting them
     /// manually using this function can expose a DoS attack vector.
-    pub fn with_capacity_and_keys(k0: u64, k1: u64, capacity: uint) -> HashMap<K, V> {
+    pub fn with_capacity_and_keys(k0: u64, k1: u64,
+                                  capacity: uint) -> HashMap<K, V> {
+        HashMap::with_capacity_and_hasher(capacity, SipState::new(k0, k1))
+    }
+
+    /// Create an empty HashMap which hashes its keys with copies of `hasher`
+    /// instead of with SipHash.
+    pub fn with_hasher<H: Hasher + Clone + Send + Freeze>(hasher: H) -> HashMap<K, V> {
+        HashMap::with_capacity_and_hasher(INITIAL_CAPACITY, hasher)
+    }
+
+    /// Create an empty HashMap with space for at least `capacity`
+    /// elements, which hashes its keys with copies of `hasher` instead of
+    /// with SipHash.
+    pub fn with_capacity_and_hasher<H: Hasher + Clone + Send + Freeze>(
+        capacity: uint,
+        hasher: H) -> HashMap<K, V> {
+        HashMap::with_capacity_and_table_hasher(
+            capacity, ~hasher as ~TableHasher:Send+Freeze)
+    }
+
+    fn with_capacity_and_table_hasher(capacity: uint,
+                                      hasher: ~TableHasher:Send+Freeze)
+                                      -> HashMap<K, V> {
         let cap = num::max(INITIAL_CAPACITY, capacity);
         HashMap {
-            k0: k0, k1: k1,
+            hasher: hasher,
             resize_at: resize_at(cap),
             size: 0,
             buckets: vec::from_fn(cap, |_| None)
@@ -373,7 +408,7 @@ impl<K: Hash + Eq, V> HashMap<K, V> {
             self.expand();
         }
 
-        let hash = k.hash_keyed(self.k0, self.k1) as uint;
+        let hash = self.hash_key(&k);
         let idx = match self.bucket_for_key_with_hash(hash, &k) {
             TableFull => fail!("Internal logic error"),
             FoundEntry(idx) => { found(&k, self.mut_value_for_bucket(idx), a); idx }
@@ -494,7 +529,7 @@ impl<K: Hash + Eq, V: Clone> HashMap<K, V> {
     }
 }
 
-impl<K:Hash + Eq,V:Eq> Eq for HashMap<K, V> {
+impl<K: Hash + Eq, V: Eq> Eq for HashMap<K, V> {
     fn eq(&self, other: &HashMap<K, V>) -> bool {
         if self.len() != other.len() { return false; }
 
@@ -509,9 +544,10 @@ impl<K:Hash + Eq,V:Eq> Eq for HashMap<K, V> {
     fn ne(&self, other: &HashMap<K, V>) -> bool { !self.eq(other) }
 }
 
-impl<K:Hash + Eq + Clone,V:Clone> Clone for HashMap<K,V> {
-    fn clone(&self) -> HashMap<K,V> {
-        let mut new_map = HashMap::with_capacity(self.len());
+impl<K: Hash + Eq + Clone, V: Clone> Clone for HashMap<K, V> {
+    fn clone(&self) -> HashMap<K, V> {
+        let mut new_map = HashMap::with_capacity_and_table_hasher(
+            self.len(), self.hasher.clone_hasher());
         for (key, value) in self.iter() {
             new_map.insert((*key).clone(), (*value).clone());
         }
@@ -634,27 +670,28 @@ impl<K: Eq + Hash, V> Default for HashMap<K, V> {
 
 /// An implementation of a hash set using the underlying representation of a
 /// HashMap where the value is (). As with the `HashMap` type, a `HashSet`
-/// requires that the elements implement the `Eq` and `Hash` traits.
+/// requires that the elements implement the `Eq` and `Hash` traits, and it can
+/// be given a different hasher in the same way.
 pub struct HashSet<T> {
     priv map: HashMap<T, ()>
 }
 
-impl<T:Hash + Eq> Eq for HashSet<T> {
+impl<T: Hash + Eq> Eq for HashSet<T> {
     fn eq(&self, other: &HashSet<T>) -> bool { self.map == other.map }
     fn ne(&self, other: &HashSet<T>) -> bool { self.map != other.map }
 }
 
-impl<T:Hash + Eq> Container for HashSet<T> {
+impl<T: Hash + Eq> Container for HashSet<T> {
     /// Return the number of elements in the set
     fn len(&self) -> uint { self.map.len() }
 }
 
-impl<T:Hash + Eq> Mutable for HashSet<T> {
+impl<T: Hash + Eq> Mutable for HashSet<T> {
     /// Clear the set, removing all values.
     fn clear(&mut self) { self.map.clear() }
 }
 
-impl<T:Hash + Eq> Set<T> for HashSet<T> {
+impl<T: Hash + Eq> Set<T> for HashSet<T> {
     /// Return true if the set contains a value
     fn contains(&self, value: &T) -> bool { self.map.contains_key(value) }
 
@@ -675,7 +712,7 @@ impl<T:Hash + Eq> Set<T> for HashSet<T> {
     }
 }
 
-impl<T:Hash + Eq> MutableSet<T> for HashSet<T> {
+impl<T: Hash + Eq> MutableSet<T> for HashSet<T> {
     /// Add a value to the set. Return true if the value was not already
     /// present in the set.
     fn insert(&mut self, value: T) -> bool { self.map.insert(value, ()) }
@@ -685,7 +722,7 @@ impl<T:Hash + Eq> MutableSet<T> for HashSet<T> {
     fn remove(&mut self, value: &T) -> bool { self.map.remove(value) }
 }
 
-impl<T:Hash + Eq> HashSet<T> {
+impl<T: Hash + Eq> HashSet<T> {
     /// Create an empty HashSet
     pub fn new() -> HashSet<T> {
         HashSet::with_capacity(INITIAL_CAPACITY)
@@ -704,10 +741,26 @@ impl<T:Hash + Eq> HashSet<T> {
     /// are designed to allow HashSets to be resistant to attacks that
     /// cause many collisions and very poor performance. Setting them
     /// manually using this function can expose a DoS attack vector.
-    pub fn with_capacity_and_keys(k0: u64, k1: u64, capacity: uint) -> HashSet<T> {
+    pub fn with_capacity_and_keys(k0: u64, k1: u64,
+                                  capacity: uint) -> HashSet<T> {
         HashSet { map: HashMap::with_capacity_and_keys(k0, k1, capacity) }
     }
 
+    /// Create an empty HashSet which hashes its values with copies of
+    /// `hasher` instead of with SipHash.
+    pub fn with_hasher<H: Hasher + Clone + Send + Freeze>(hasher: H) -> HashSet<T> {
+        HashSet { map: HashMap::with_hasher(hasher) }
+    }
+
+    /// Create an empty HashSet with space for at least `capacity`
+    /// elements, which hashes its values with copies of `hasher` instead of
+    /// with SipHash.
+    pub fn with_capacity_and_hasher<H: Hasher + Clone + Send + Freeze>(
+        capacity: uint,
+        hasher: H) -> HashSet<T> {
+        HashSet { map: HashMap::with_capacity_and_hasher(capacity, hasher) }
+    }
+
     /// Reserve space for at least `n` elements in the hash table.
     pub fn reserve_at_least(&mut self, n: uint) {
         self.map.reserve_at_least(n)
@@ -734,7 +787,8 @@ impl<T:Hash + Eq> HashSet<T> {
     }
 
     /// Visit the values representing the difference
-    pub fn difference<'a>(&'a self, other: &'a HashSet<T>) -> SetAlgebraIter<'a, T> {
+    pub fn difference<'a>(&'a self, other: &'a HashSet<T>)
+        -> SetAlgebraIter<'a, T> {
         Repeat::new(other)
             .zip(self.iter())
             .filter_map(|(other, elt)| {
@@ -766,7 +820,7 @@ impl<T:Hash + Eq> HashSet<T> {
 
 }
 
-impl<T:Hash + Eq + Clone> Clone for HashSet<T> {
+impl<T: Hash + Eq + Clone> Clone for HashSet<T> {
     fn clone(&self) -> HashSet<T> {
         HashSet {
             map: self.map.clone()
@@ -807,6 +861,7 @@ pub type SetAlgebraIter<'self, T> =
 mod test_map {
     use prelude::*;
     use super::*;
+    use hash::Hasher;
 
     #[test]
     fn test_create_capacity_zero() {
@@ -1016,6 +1071,60 @@ mod test_map {
             assert_eq!(map.find(&k), Some(&v));
         }
     }
+
+    // A non-keyed FNV-1a hasher
+    #[deriving(Clone)]
+    struct FnvState {
+        h: u64
+    }
+
+    impl Hasher for FnvState {
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes.iter() {
+                self.h = (self.h ^ (b as u64)) * 0x100000001b3;
+            }
+        }
+
+        fn finish(&self) -> u64 { self.h }
+    }
+
+    fn fnv() -> FnvState { FnvState { h: 0xcbf29ce484222325 } }
+
+    #[test]
+    fn test_with_hasher() {
+        let mut m = HashMap::with_hasher(fnv());
+        for i in range(0, 100) {
+            assert!(m.insert(i, i * 2));
+        }
+        for i in range(0, 100) {
+            assert_eq!(m.find(&i), Some(&(i * 2)));
+        }
+
+        let mut m2 = m.clone();
+        assert!(m2.remove(&7));
+        assert_eq!(m2.find(&7), None);
+        assert_eq!(m.len(), 100);
+    }
+
+    #[test]
+    fn test_hasher_does_not_change_type() {
+        let mut maps: ~[HashMap<int, int>] = ~[HashMap::new(),
+                                                HashMap::with_hasher(fnv())];
+        for m in maps.mut_iter() {
+            m.insert(1, 2);
+        }
+        for m in maps.iter() {
+            assert_eq!(m.find(&1), Some(&2));
+        }
+    }
+
+    #[test]
+    fn test_find_equiv_with_hasher() {
+        let mut m = HashMap::with_hasher(fnv());
+        m.insert(~"foo", 1);
+        assert_eq!(m.find_equiv(&("foo")), Some(&1));
+        assert_eq!(m.find_equiv(&("bar")), None);
+    }
 }
 
 #[cfg(test)]
@@ -46,18 +46,29 @@ particular bits of it, etc.
 */
 
 use c_str::ToCStr;
+use cast;
 use clone::Clone;
+use cmp;
 use iter::Iterator;
+use libc;
+use os;
+use os::{MemoryMap, MapError, MapReadable, MapWritable, MapFd, MapShared};
 use super::{Reader, Writer, Seek};
+use super::{SeekSet, SeekCur, SeekEnd};
 use super::{SeekStyle, Read, Write, Open, IoError, Truncate,
-            FileMode, FileAccess, FileStat, io_error, FilePermission};
-use rt::rtio::{RtioFileStream, IoFactory, with_local_io};
+            FileMode, FileAccess, FileStat, io_error, FilePermission,
+            FileType};
+use rt::rtio::{RtioFileStream, RtioDirectory, IoFactory, with_local_io};
 use io;
 use option::{Some, None, Option};
 use result::{Ok, Err, Result};
 use path;
 use path::{Path, GenericPath};
+use ptr;
+use to_str::ToStr;
+use uint;
 use vec::{OwnedVector, ImmutableVector};
+use vec;
 
 /// Unconstrained file access type that exposes read and write operations
 ///
@@ -191,7 +202,7 @@ impl File {
     ///
     /// This function will raise on the `io_error` condition on failure.
     pub fn fsync(&mut self) {
-        self.fd.fsync().map_err(|e| io_error::cond.raise(e));
+        let _ = self.fd.fsync().map_err(|e| io_error::cond.raise(e));
     }
 
     /// This function is similar to `fsync`, except that it may not synchronize
@@ -203,7 +214,7 @@ impl File {
     ///
     /// This function will raise on the `io_error` condition on failure.
     pub fn datasync(&mut self) {
-        self.fd.datasync().map_err(|e| io_error::cond.raise(e));
+        let _ = self.fd.datasync().map_err(|e| io_error::cond.raise(e));
     }
 
     /// Either truncates or extends the underlying file, updating the size of
@@ -219,7 +230,251 @@ impl File {
     ///
     /// On error, this function will raise on the `io_error` condition.
     pub fn truncate(&mut self, size: i64) {
-        self.fd.truncate(size).map_err(|e| io_error::cond.raise(e));
+        let _ = self.fd.truncate(size).map_err(|e| io_error::cond.raise(e));
+    }
+
+    // Finds the length of the file, leaving its position alone.
+    fn len(&mut self) -> Result<u64, IoError> {
+        let pos = match self.fd.tell() {
+            Ok(pos) => pos,
+            Err(e) => return Err(e),
+        };
+        let len = match self.fd.seek(0, SeekEnd) {
+            Ok(len) => len,
+            Err(e) => return Err(e),
+        };
+        self.fd.seek(pos as i64, SeekSet).map(|_| len)
+    }
+
+    /// Maps the contents of this file into memory, consuming the file. The
+    /// file must have been opened for reading, and also for writing if
+    /// `writable` is true. See `MappedFile` for more information.
+    ///
+    /// # Example
+    ///
+    ///     use std::io::File;
+    ///
+    ///     let file = File::open(&Path::new("data.bin")).unwrap();
+    ///     let map = file.map(false).unwrap();
+    ///     let header = map.as_slice().slice_to(4);
+    ///
+    /// # Errors
+    ///
+    /// This function will raise on the `io_error` condition if the file can't
+    /// be mapped with the requested access, or if it is too large to fit into
+    /// the address space (which can happen to files of 4GB or more on 32-bit
+    /// platforms).
+    pub fn map(self, writable: bool) -> Option<MappedFile> {
+        let mut mapped = MappedFile {
+            file: self,
+            map: None,
+            len: 0,
+            writable: writable,
+            pos: 0,
+        };
+        match mapped.map_file() {
+            Ok(()) => Some(mapped),
+            Err(ioerr) => {
+                io_error::cond.raise(ioerr);
+                None
+            }
+        }
+    }
+}
+
+/// A file whose contents are mapped into memory, created by `File::map`.
+///
+/// The contents can be accessed directly as a slice, or through the `Reader`
+/// and `Seek` implementations, which are just like those of `MemReader`. If
+/// the file was mapped writable, changes made through `as_mut_slice` are
+/// carried through to the file itself (although not necessarily right away;
+/// see `sync`).
+///
+/// The mapping covers the file as it was when it was mapped. If the file
+/// changes size afterwards, call `remap` to map it again. Note that if the file
+/// is truncated by someone else while it is mapped, accessing the part of the
+/// mapping past the new end of the file will kill the process on most
+/// platforms.
+///
+/// The mapping is shared with the file itself, even when it's read-only, so
+/// the contents seen through it aren't a snapshot: anything which writes to
+/// the file (another mapping of it included) changes them, in this process or
+/// in any other.
+pub struct MappedFile {
+    priv file: File,
+    // None for empty files, which can't be mapped.
+    priv map: Option<MemoryMap>,
+    priv len: uint,
+    priv writable: bool,
+    priv pos: uint,
+}
+
+impl MappedFile {
+    fn map_file(&mut self) -> Result<(), IoError> {
+        // Drop the old mapping first, so that remapping a large file never
+        // needs the address space for two mappings of it at once.
+        self.map = None;
+        let len = match self.file.len() {
+            Ok(len) if len > uint::max_value as u64 => {
+                return Err(IoError {
+                    kind: io::OtherIoError,
+                    desc: "file is too large to be mapped into memory",
+                    detail: None,
+                })
+            }
+            Ok(len) => len as uint,
+            Err(e) => return Err(e),
+        };
+        self.len = len;
+        if len == 0 { return Ok(()) }
+
+        let fd = self.file.fd.fd();
+        let map = if self.writable {
+            MemoryMap::new(len, [MapReadable, MapWritable, MapFd(fd), MapShared])
+        } else {
+            MemoryMap::new(len, [MapReadable, MapFd(fd), MapShared])
+        };
+        match map {
+            Ok(map) => { self.map = Some(map); Ok(()) }
+            Err(e) => Err(map_error(e)),
+        }
+    }
+
+    /// Returns the number of bytes of the file which are mapped.
+    pub fn len(&self) -> uint { self.len }
+
+    /// Returns the contents of the file.
+    ///
+    /// This is a view of the file rather than a copy of it: if the file is
+    /// written to while the slice is alive, the slice changes along with it,
+    /// even though it's immutable. Copy out whatever must not change.
+    pub fn as_slice<'a>(&'a self) -> &'a [u8] {
+        match self.map {
+            Some(ref map) => unsafe {
+                cast::transmute((map.data as *u8, self.len))
+            },
+            None => unsafe { cast::transmute((ptr::null::<u8>(), 0u)) },
+        }
+    }
+
+    /// Returns the contents of the file for modification.
+    ///
+    /// # Failure
+    ///
+    /// Fails if the file wasn't mapped writable.
+    pub fn as_mut_slice<'a>(&'a mut self) -> &'a mut [u8] {
+        if !self.writable {
+            fail!("MappedFile::as_mut_slice: the file was mapped read-only");
+        }
+        match self.map {
+            Some(ref map) => unsafe { cast::transmute((map.data, self.len)) },
+            None => unsafe { cast::transmute((ptr::mut_null::<u8>(), 0u)) },
+        }
+    }
+
+    /// Writes the changes which have been made to the mapped memory back to
+    /// the file, blocking until this is done. This is `msync` on unix.
+    ///
+    /// # Errors
+    ///
+    /// This function will raise on the `io_error` condition on failure.
+    pub fn sync(&mut self) {
+        match self.map {
+            Some(ref map) => match flush_map(map, self.len) {
+                Ok(()) => {}
+                Err(ioerr) => io_error::cond.raise(ioerr),
+            },
+            None => {}
+        }
+    }
+
+    /// Maps the file again, picking up any change in its size. The position of
+    /// the reader is kept, but it may now be past the end of the file.
+    ///
+    /// # Errors
+    ///
+    /// This function will raise on the `io_error` condition on failure, in
+    /// which case the file is left unmapped (as though it were empty).
+    pub fn remap(&mut self) {
+        match self.map_file() {
+            Ok(()) => {}
+            Err(ioerr) => {
+                self.len = 0;
+                io_error::cond.raise(ioerr);
+            }
+        }
+    }
+
+    /// Unmaps the file, returning it.
+    pub fn unmap(self) -> File {
+        let MappedFile { file, _ } = self;
+        file
+    }
+}
+
+impl Reader for MappedFile {
+    fn read(&mut self, buf: &mut [u8]) -> Option<uint> {
+        if self.eof() { return None }
+        let len = cmp::min(buf.len(), self.len - self.pos);
+        vec::bytes::copy_memory(buf, self.as_slice().slice(self.pos, self.pos + len),
+                                len);
+        self.pos += len;
+        Some(len)
+    }
+
+    fn eof(&mut self) -> bool { self.pos >= self.len }
+}
+
+impl Seek for MappedFile {
+    fn tell(&self) -> u64 { self.pos as u64 }
+
+    fn seek(&mut self, pos: i64, style: SeekStyle) {
+        let offset = match style {
+            SeekSet => 0,
+            SeekEnd => self.len,
+            SeekCur => self.pos,
+        } as i64;
+        self.pos = cmp::max(0, offset + pos) as uint;
+    }
+}
+
+fn map_error(err: MapError) -> IoError {
+    IoError {
+        kind: match err {
+            os::ErrFdNotAvail => io::PermissionDenied,
+            os::ErrNoMem => io::ResourceUnavailable,
+            _ => io::OtherIoError,
+        },
+        desc: "couldn't map file into memory",
+        detail: Some(err.to_str()),
+    }
+}
+
+#[cfg(unix)]
+fn flush_map(map: &MemoryMap, len: uint) -> Result<(), IoError> {
+    match unsafe {
+        libc::msync(map.data as *libc::c_void, len as libc::size_t, libc::MS_SYNC)
+    } {
+        0 => Ok(()),
+        _ => Err(flush_error()),
+    }
+}
+
+#[cfg(windows)]
+fn flush_map(map: &MemoryMap, len: uint) -> Result<(), IoError> {
+    match unsafe {
+        libc::FlushViewOfFile(map.data as libc::LPCVOID, len as libc::SIZE_T)
+    } {
+        0 => Err(flush_error()),
+        _ => Ok(()),
+    }
+}
+
+fn flush_error() -> IoError {
+    IoError {
+        kind: io::OtherIoError,
+        desc: "couldn't write mapped memory back to file",
+        detail: Some(os::last_os_error()),
     }
 }
 
@@ -507,32 +762,212 @@ pub fn readdir(path: &Path) -> ~[Path] {
     io_raise(|io| io.fs_readdir(&path.to_c_str(), 0)).unwrap_or_else(|| ~[])
 }
 
+/// An entry of a directory, as yielded by `dir_entries` and `walk_dir`.
+pub struct DirEntry {
+    /// The path of the entry, which is the path of the directory joined with
+    /// the name of the entry.
+    path: Path,
+    /// The type of the entry. Symlinks are not followed, so this is
+    /// `TypeSymlink` for a symlink no matter what it points at.
+    kind: FileType,
+}
+
+/// Returns an iterator over the entries of the directory at `path` (except for
+/// `.` and `..`), which are read from the directory lazily.
+///
+/// Unlike `readdir`, this yields the type of each entry along with its path.
+/// The type is taken from the directory listing itself where the platform
+/// provides it, so that looking at it usually doesn't cost a `stat` call. If
+/// the listing doesn't say, the entry is `lstat`ed, and `TypeUnknown` is
+/// reported if that fails.
+///
+/// Only the native I/O of native tasks works this way. The libuv-based I/O of
+/// green tasks doesn't: the bundled libuv reads the whole listing when the
+/// directory is opened and doesn't report the types of the entries, so there
+/// the listing isn't lazy and every entry is `lstat`ed.
+///
+/// # Example
+///
+///     use std::io;
+///     use std::io::fs;
+///
+///     for entry in fs::dir_entries(&Path::new(".")) {
+///         if entry.kind == io::TypeDirectory {
+///             println!("{}/", entry.path.display());
+///         }
+///     }
+///
+/// # Errors
+///
+/// Will raise an `io_error` condition if the directory can't be opened (in
+/// which case the iterator is empty) or if reading the next entry fails (in
+/// which case the iteration stops early). See `readdir` for the reasons.
+pub fn dir_entries(path: &Path) -> DirEntries {
+    DirEntries { obj: io_raise(|io| io.fs_opendir(&path.to_c_str())) }
+}
+
+/// An iterator over the entries of a directory. See `dir_entries`.
+pub struct DirEntries {
+    priv obj: Option<~RtioDirectory>,
+}
+
+impl Iterator<DirEntry> for DirEntries {
+    fn next(&mut self) -> Option<DirEntry> {
+        let next = match self.obj {
+            Some(ref mut obj) => obj.next_entry(),
+            None => return None,
+        };
+        match next {
+            Ok(Some((path, kind))) => {
+                let kind = match kind {
+                    io::TypeUnknown => match io::result(|| lstat(&path)) {
+                        Ok(stat) => stat.kind,
+                        Err(*) => io::TypeUnknown,
+                    },
+                    kind => kind,
+                };
+                Some(DirEntry { path: path, kind: kind })
+            }
+            Ok(None) => { self.obj = None; None }
+            Err(ioerr) => {
+                self.obj = None;
+                io_error::cond.raise(ioerr);
+                None
+            }
+        }
+    }
+}
+
 /// Returns an iterator which will recursively walk the directory structure
 /// rooted at `path`. The path given will not be iterated over, and this will
-/// perform iteration in a top-down order.
+/// perform iteration in a top-down order: all of the entries of a directory
+/// are visited before those of its subdirectories.
+///
+/// The walk can be limited with the `max_depth` and `follow_symlinks` methods
+/// before it starts, and subtrees can be pruned with `skip_dir` as it goes.
+/// The iterator yields the paths of the entries; use `next_entry` instead of
+/// `next` to also get their types. Directories are listed with `dir_entries`,
+/// so the walk only avoids a `stat` per entry under the native I/O.
+///
+/// # Example
+///
+///     use std::io::fs;
+///
+///     // visit everything except for the contents of .git directories
+///     let mut walker = fs::walk_dir(&Path::new(".")).follow_symlinks(false);
+///     loop {
+///         let entry = match walker.next_entry() { Some(e) => e, None => break };
+///         if entry.path.filename() == Some(bytes!(".git")) {
+///             walker.skip_dir();
+///         } else {
+///             println!("{}", entry.path.display());
+///         }
+///     }
+///
+/// # Errors
+///
+/// Raises an `io_error` condition for each directory which can't be read, as
+/// `dir_entries` does, and carries on with the rest of the walk.
 pub fn walk_dir(path: &Path) -> WalkIterator {
-    WalkIterator { stack: readdir(path) }
+    WalkIterator {
+        current: None,
+        queue: ~[(path.clone(), 0)],
+        queued_last: false,
+        max_depth: None,
+        follow_symlinks: true,
+    }
 }
 
 /// An iterator which walks over a directory
 pub struct WalkIterator {
-    priv stack: ~[Path],
+    // The directory currently being listed, along with the depth of its
+    // entries (the entries of the root have depth 1).
+    priv current: Option<(DirEntries, uint)>,
+    // The directories still to be listed, along with their own depth.
+    priv queue: ~[(Path, uint)],
+    // Whether the last entry returned was added to `queue`.
+    priv queued_last: bool,
+    priv max_depth: Option<uint>,
+    priv follow_symlinks: bool,
 }
 
-impl Iterator<Path> for WalkIterator {
-    fn next(&mut self) -> Option<Path> {
-        match self.stack.shift_opt() {
-            Some(path) => {
-                if path.is_dir() {
-                    self.stack.push_all_move(readdir(&path));
+impl WalkIterator {
+    /// Limits the walk to entries at most `depth` levels below the root, so a
+    /// depth of 1 only visits the entries of the root itself, and a depth of 0
+    /// visits nothing at all.
+    pub fn max_depth(self, depth: uint) -> WalkIterator {
+        WalkIterator { max_depth: Some(depth), ..self }
+    }
+
+    /// Sets whether symlinks to directories are descended into, which they
+    /// are by default. The symlinks themselves are visited either way.
+    pub fn follow_symlinks(self, follow: bool) -> WalkIterator {
+        WalkIterator { follow_symlinks: follow, ..self }
+    }
+
+    /// Prevents the walk from descending into the directory which was
+    /// returned last. This has no effect if the last entry was not a
+    /// directory, or if it wasn't going to be descended into anyway.
+    pub fn skip_dir(&mut self) {
+        if self.queued_last {
+            self.queue.pop();
+            self.queued_last = false;
+        }
+    }
+
+    /// Returns the next entry of the walk, along with its type.
+    pub fn next_entry(&mut self) -> Option<DirEntry> {
+        self.queued_last = false;
+        loop {
+            let next = match self.current {
+                Some((ref mut entries, depth)) => {
+                    entries.next().map(|entry| (entry, depth))
+                }
+                None => None,
+            };
+            match next {
+                Some((entry, depth)) => {
+                    if self.should_descend(&entry, depth) {
+                        self.queue.push((entry.path.clone(), depth));
+                        self.queued_last = true;
+                    }
+                    return Some(entry);
+                }
+                None if self.queue.is_empty() => {
+                    self.current = None;
+                    return None;
+                }
+                None => {
+                    let (dir, depth) = self.queue.shift();
+                    match self.max_depth {
+                        // Only the root can be too deep to list, at depth 0
+                        Some(max) if depth >= max => {}
+                        _ => self.current = Some((dir_entries(&dir), depth + 1)),
+                    }
                 }
-                Some(path)
             }
-            None => None
+        }
+    }
+
+    fn should_descend(&self, entry: &DirEntry, depth: uint) -> bool {
+        match self.max_depth {
+            Some(max) if depth >= max => return false,
+            _ => {}
+        }
+        match entry.kind {
+            io::TypeDirectory => true,
+            io::TypeSymlink if self.follow_symlinks => entry.path.is_dir(),
+            _ => false,
         }
     }
 }
 
+impl Iterator<Path> for WalkIterator {
+    fn next(&mut self) -> Option<Path> {
+        self.next_entry().map(|entry| entry.path)
+    }
+}
+
 /// Recursively create a directory and all of its parent components if they
 /// are missing.
 ///
@@ -562,12 +997,14 @@ pub fn mkdir_recursive(path: &Path, mode: FilePermission) {
 /// happens. See `file::unlink` and `fs::readdir` for possible error
 /// conditions.
 pub fn rmdir_recursive(path: &Path) {
-    let children = readdir(path);
+    // Symlinks are removed rather than followed, so that this never strays
+    // outside of `path`.
+    let children = dir_entries(path).collect::<~[DirEntry]>();
     for child in children.iter() {
-        if child.is_dir() {
-            rmdir_recursive(child);
+        if child.kind == io::TypeDirectory {
+            rmdir_recursive(&child.path);
         } else {
-            unlink(child);
+            unlink(&child.path);
         }
     }
     // Directory should now be empty
@@ -708,7 +1145,8 @@ mod test {
     use str;
     use io::fs::{File, rmdir, mkdir, readdir, rmdir_recursive,
                  mkdir_recursive, copy, unlink, stat, symlink, link,
-                 readlink, chmod, lstat, change_file_times};
+                 readlink, chmod, lstat, change_file_times,
+                 dir_entries, walk_dir};
     use util;
     use path::Path;
     use io;
@@ -742,7 +1180,8 @@ mod test {
                 use str;
                 use io::fs::{File, rmdir, mkdir, readdir, rmdir_recursive,
                              mkdir_recursive, copy, unlink, stat, symlink, link,
-                             readlink, chmod, lstat, change_file_times};
+                             readlink, chmod, lstat, change_file_times,
+                             dir_entries, walk_dir};
                 use io::fs::test::tmpdir;
                 use util;
 
@@ -989,6 +1428,115 @@ mod test {
         rmdir(dir);
     })
 
+    test!(fn map_file() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("mapped.bin");
+        File::create(&path).write(bytes!("hello world"));
+
+        let mut map = File::open(&path).unwrap().map(false).unwrap();
+        assert_eq!(map.len(), 11);
+        assert_eq!(map.as_slice(), bytes!("hello world"));
+        map.seek(-5, SeekEnd);
+        assert_eq!(map.read_to_end(), bytes!("world").to_owned());
+        assert!(map.eof());
+
+        {
+            let file = File::open_mode(&path, Open, ReadWrite).unwrap();
+            let mut writable = file.map(true).unwrap();
+            writable.as_mut_slice()[0] = 'j' as u8;
+            writable.sync();
+        }
+        assert_eq!(map.as_slice(), bytes!("jello world"));
+        assert_eq!(File::open(&path).read_to_end(), bytes!("jello world").to_owned());
+
+        File::open_mode(&path, io::Append, io::Write).write(bytes!("!"));
+        map.remap();
+        assert_eq!(map.as_slice(), bytes!("jello world!"));
+        assert_eq!(map.tell(), 11);
+        assert_eq!(map.read_to_end(), bytes!("!").to_owned());
+    })
+
+    test!(fn map_empty_file() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("empty.bin");
+        File::create(&path);
+        let mut map = File::open(&path).unwrap().map(false).unwrap();
+        assert_eq!(map.len(), 0);
+        assert_eq!(map.as_slice().len(), 0);
+        let mut buf = [0u8, ..4];
+        assert!(map.read(buf).is_none());
+    })
+
+    test!(fn dir_entries_have_types() {
+        let tmpdir = tmpdir();
+        let dir = tmpdir.join("di_entries");
+        mkdir(&dir, io::UserRWX);
+        File::create(&dir.join("file.txt"));
+        mkdir(&dir.join("sub"), io::UserRWX);
+
+        let entries = dir_entries(&dir).collect::<~[io::fs::DirEntry]>();
+        assert_eq!(entries.len(), 2);
+        for entry in entries.iter() {
+            if entry.path == dir.join("file.txt") {
+                assert_eq!(entry.kind, io::TypeFile);
+            } else {
+                assert!(entry.path == dir.join("sub"));
+                assert_eq!(entry.kind, io::TypeDirectory);
+            }
+        }
+
+        match io::result(|| dir_entries(&dir.join("nope")).next()) {
+            Ok(*) => fail!("listed a directory which doesn't exist"),
+            Err(*) => {}
+        }
+    })
+
+    test!(fn walk_dir_depth_and_pruning() {
+        let tmpdir = tmpdir();
+        let dir = tmpdir.join("di_walk");
+        mkdir_recursive(&dir.join("a").join("b"), io::UserRWX);
+        mkdir_recursive(&dir.join("c").join("d"), io::UserRWX);
+        File::create(&dir.join("a").join("b").join("f"));
+
+        let all = walk_dir(&dir).collect::<~[Path]>();
+        assert_eq!(all.len(), 5);
+
+        let shallow = walk_dir(&dir).max_depth(1).collect::<~[Path]>();
+        assert_eq!(shallow.len(), 2);
+
+        assert!(walk_dir(&dir).max_depth(0).next().is_none());
+
+        let mut walker = walk_dir(&dir);
+        let mut seen = ~[];
+        loop {
+            let entry = match walker.next_entry() {
+                Some(entry) => entry,
+                None => break,
+            };
+            if entry.path == dir.join("a") { walker.skip_dir(); }
+            seen.push(entry.path);
+        }
+        assert_eq!(seen.len(), 3);
+        assert!(!seen.contains(&dir.join("a").join("b")));
+        assert!(seen.contains(&dir.join("c").join("d")));
+    })
+
+    #[cfg(not(windows))] // FIXME(#10264) operation not permitted?
+    test!(fn walk_dir_symlinks() {
+        let tmpdir = tmpdir();
+        let dir = tmpdir.join("di_walk_links");
+        mkdir_recursive(&dir.join("real"), io::UserRWX);
+        File::create(&dir.join("real").join("f"));
+        symlink(&dir.join("real"), &dir.join("link"));
+
+        assert_eq!(walk_dir(&dir).collect::<~[Path]>().len(), 4);
+        let walker = walk_dir(&dir).follow_symlinks(false);
+        assert_eq!(walker.collect::<~[Path]>().len(), 3);
+
+        let kinds = dir_entries(&dir).map(|e| e.kind).collect::<~[io::FileType]>();
+        assert!(kinds.contains(&io::TypeSymlink));
+    })
+
     test!(fn recursive_mkdir_slash() {
         mkdir_recursive(&Path::new("/"), io::UserRWX);
     })
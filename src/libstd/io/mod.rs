@@ -312,6 +312,9 @@ pub mod native;
 /// Signal handling
 pub mod signal;
 
+/// Readiness notifications for sockets and pipes
+pub mod poll;
+
 /// The default buffer size for various I/O operations
 static DEFAULT_BUF_SIZE: uint = 1024 * 64;
 
@@ -359,6 +362,7 @@ pub enum IoErrorKind {
     MismatchedFileTypeForOperation,
     ResourceUnavailable,
     IoUnavailable,
+    TimedOut,
 }
 
 // FIXME: #8242 implementing manually because deriving doesn't work for some reason
@@ -382,6 +386,7 @@ impl ToStr for IoErrorKind {
             IoUnavailable => ~"IoUnavailable",
             ResourceUnavailable => ~"ResourceUnavailable",
             ConnectionAborted => ~"ConnectionAborted",
+            TimedOut => ~"TimedOut",
         }
     }
 }
@@ -1174,6 +1179,13 @@ pub fn standard_error(kind: IoErrorKind) -> IoError {
                 detail: None
             }
         }
+        TimedOut => {
+            IoError {
+                kind: TimedOut,
+                desc: "Operation timed out",
+                detail: None
+            }
+        }
         _ => fail!()
     }
 }
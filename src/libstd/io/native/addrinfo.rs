@@ -0,0 +1,125 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Blocking name resolution through the system's getaddrinfo
+
+use ai = io::net::addrinfo;
+use c_str::ToCStr;
+use cast;
+use io::IoError;
+use io;
+use libc::c_int;
+use libc;
+use option::{Option, Some, None};
+use ptr::{null, RawPtr};
+use result::{Ok, Err};
+use super::IoResult;
+use super::net::sockaddr_to_addr;
+
+pub struct GetAddrInfoRequest;
+
+impl GetAddrInfoRequest {
+    pub fn run(host: Option<&str>, servname: Option<&str>,
+               hint: Option<ai::Hint>) -> IoResult<~[ai::Info]> {
+        assert!(host.is_some() || servname.is_some());
+        super::net::init();
+
+        let c_host = host.map(|x| x.to_c_str());
+        let c_serv = servname.map(|x| x.to_c_str());
+
+        let hint = hint.map(|hint| {
+            libc::addrinfo {
+                ai_flags: hint.flags as c_int,
+                ai_family: hint.family as c_int,
+                ai_socktype: 0,
+                ai_protocol: 0,
+                ai_addrlen: 0,
+                ai_canonname: null(),
+                ai_addr: null(),
+                ai_next: null()
+            }
+        });
+
+        let hint_ptr = hint.as_ref().map_default(null(), |x| x as *libc::addrinfo);
+        let mut res = null();
+
+        // Make the call
+        let s = unsafe {
+            let ch = c_host.as_ref().map_default(null(), |x| x.with_ref(|x| x));
+            let cs = c_serv.as_ref().map_default(null(), |x| x.with_ref(|x| x));
+            libc::getaddrinfo(ch, cs, hint_ptr, &mut res)
+        };
+
+        // Error?
+        if s != 0 {
+            return Err(get_error(s));
+        }
+
+        // Collect all the results we found
+        let mut addrs = ~[];
+        let mut rp = res;
+        while rp.is_not_null() {
+            unsafe {
+                let storage = cast::transmute((*rp).ai_addr);
+                let addr = sockaddr_to_addr(storage, (*rp).ai_addrlen as uint);
+                match addr {
+                    Ok(addr) => {
+                        addrs.push(ai::Info {
+                            address: addr,
+                            family: (*rp).ai_family as uint,
+                            socktype: None,
+                            protocol: None,
+                            flags: (*rp).ai_flags as uint
+                        });
+                    }
+                    // skip over any address families we don't understand
+                    Err(*) => {}
+                }
+                rp = (*rp).ai_next;
+            }
+        }
+
+        unsafe { libc::freeaddrinfo(res); }
+
+        Ok(addrs)
+    }
+}
+
+#[cfg(windows)]
+fn get_error(_: c_int) -> IoError {
+    use super::last_error;
+    last_error()
+}
+
+#[cfg(not(windows))]
+fn get_error(s: c_int) -> IoError {
+    use str::raw::from_c_str;
+
+    let err_str = unsafe { from_c_str(libc::gai_strerror(s)) };
+    IoError {
+        kind: io::OtherIoError,
+        desc: "unable to resolve host",
+        detail: Some(err_str),
+    }
+}
+
+#[cfg(test, not(target_os = "android"))]
+mod test {
+    use super::GetAddrInfoRequest;
+    use io::net::ip::Ipv4Addr;
+    use prelude::*;
+
+    #[test]
+    fn lookup_localhost() {
+        let infos = GetAddrInfoRequest::run(Some("localhost"), None,
+                                            None).unwrap();
+        assert!(infos.iter().any(|i| i.address.ip == Ipv4Addr(127, 0, 0, 1)));
+    }
+}
@@ -33,7 +33,7 @@ use vec;
 #[cfg(windows)] use ptr;
 #[cfg(windows)] use str;
 
-fn keep_going(data: &[u8], f: |*u8, uint| -> i64) -> i64 {
+pub fn keep_going(data: &[u8], f: |*u8, uint| -> i64) -> i64 {
     #[cfg(windows)] static eintr: int = 0; // doesn't matter
     #[cfg(not(windows))] static eintr: int = libc::EINTR as int;
 
@@ -80,7 +80,7 @@ impl FileDesc {
         FileDesc { fd: fd, close_on_drop: close_on_drop }
     }
 
-    fn inner_read(&mut self, buf: &mut [u8]) -> Result<uint, IoError> {
+    pub fn inner_read(&mut self, buf: &mut [u8]) -> Result<uint, IoError> {
         #[cfg(windows)] type rlen = libc::c_uint;
         #[cfg(not(windows))] type rlen = libc::size_t;
         let ret = keep_going(buf, |buf, len| {
@@ -96,7 +96,7 @@ impl FileDesc {
             Ok(ret as uint)
         }
     }
-    fn inner_write(&mut self, buf: &[u8]) -> Result<(), IoError> {
+    pub fn inner_write(&mut self, buf: &[u8]) -> Result<(), IoError> {
         #[cfg(windows)] type wlen = libc::c_uint;
         #[cfg(not(windows))] type wlen = libc::size_t;
         let ret = keep_going(buf, |buf, len| {
@@ -284,6 +284,8 @@ impl rtio::RtioFileStream for FileDesc {
             libc::ftruncate(self.fd, offset as libc::off_t)
         })
     }
+
+    fn fd(&self) -> c_int { self.fd }
 }
 
 impl rtio::RtioPipe for FileDesc {
@@ -293,22 +295,21 @@ impl rtio::RtioPipe for FileDesc {
     fn write(&mut self, buf: &[u8]) -> Result<(), IoError> {
         self.inner_write(buf)
     }
-}
 
-impl rtio::RtioTTY for FileDesc {
-    fn read(&mut self, buf: &mut [u8]) -> Result<uint, IoError> {
-        self.inner_read(buf)
-    }
-    fn write(&mut self, buf: &[u8]) -> Result<(), IoError> {
-        self.inner_write(buf)
+    // Each end of an OS pipe only goes in one direction, so there is no half
+    // of it which could be shut down on its own.
+    fn close_write(&mut self) -> Result<(), IoError> { Err(super::unimpl()) }
+    fn close_read(&mut self) -> Result<(), IoError> { Err(super::unimpl()) }
+    fn poller(&mut self) -> Result<~rtio::RtioPoll, IoError> {
+        let fd = self.fd as super::net::sock_t;
+        super::poll::Poller::new(fd).map(|p| ~p as ~rtio::RtioPoll)
     }
-    fn set_raw(&mut self, _raw: bool) -> Result<(), IoError> {
-        Err(super::unimpl())
-    }
-    fn get_winsize(&mut self) -> Result<(int, int), IoError> {
-        Err(super::unimpl())
-    }
-    fn isatty(&self) -> bool { false }
+
+    // Plain file descriptors always block in the underlying read/write calls,
+    // timeouts are only implemented for sockets.
+    fn set_timeout(&mut self, _timeout: Option<u64>) {}
+    fn set_read_timeout(&mut self, _timeout: Option<u64>) {}
+    fn set_write_timeout(&mut self, _timeout: Option<u64>) {}
 }
 
 impl Drop for FileDesc {
@@ -414,6 +415,8 @@ impl rtio::RtioFileStream for CFile {
         self.flush();
         self.fd.truncate(offset)
     }
+
+    fn fd(&self) -> c_int { self.fd.fd }
 }
 
 impl Drop for CFile {
@@ -478,95 +481,163 @@ pub fn mkdir(p: &CString, mode: io::FilePermission) -> IoResult<()> {
 }
 
 pub fn readdir(p: &CString) -> IoResult<~[Path]> {
-    fn prune(root: &CString, dirs: ~[Path]) -> ~[Path] {
-        let root = unsafe { CString::new(root.with_ref(|p| p), false) };
-        let root = Path::new(root);
-
-        dirs.move_iter().filter(|path| {
-            path.as_vec() != bytes!(".") && path.as_vec() != bytes!("..")
-        }).map(|path| root.join(path)).collect()
-    }
-
-    unsafe {
-        #[cfg(not(windows))]
-        unsafe fn get_list(p: &CString) -> IoResult<~[Path]> {
-            use libc::{dirent_t};
-            use libc::{opendir, readdir, closedir};
-            extern {
-                fn rust_list_dir_val(ptr: *dirent_t) -> *libc::c_char;
-            }
-            debug!("os::list_dir -- BEFORE OPENDIR");
-
-            let dir_ptr = p.with_ref(|buf| opendir(buf));
-
-            if (dir_ptr as uint != 0) {
-                let mut paths = ~[];
-                debug!("os::list_dir -- opendir() SUCCESS");
-                let mut entry_ptr = readdir(dir_ptr);
-                while (entry_ptr as uint != 0) {
-                    let cstr = CString::new(rust_list_dir_val(entry_ptr), false);
-                    paths.push(Path::new(cstr));
-                    entry_ptr = readdir(dir_ptr);
-                }
-                closedir(dir_ptr);
-                Ok(paths)
-            } else {
-                Err(super::last_error())
-            }
+    let mut dir = match Directory::open(p) {
+        Ok(dir) => dir,
+        Err(e) => return Err(e),
+    };
+    let mut paths = ~[];
+    loop {
+        match dir.next_entry() {
+            Ok(Some((path, _))) => paths.push(path),
+            Ok(None) => return Ok(paths),
+            Err(e) => return Err(e),
         }
+    }
+}
 
-        #[cfg(windows)]
-        unsafe fn get_list(p: &CString) -> IoResult<~[Path]> {
-            use libc::consts::os::extra::INVALID_HANDLE_VALUE;
-            use libc::{wcslen, free};
-            use libc::funcs::extra::kernel32::{
-                FindFirstFileW,
-                FindNextFileW,
-                FindClose,
-            };
-            use libc::types::os::arch::extra::HANDLE;
-            use os::win32::{
-                as_utf16_p
-            };
-            use rt::global_heap::malloc_raw;
+// Translates the entry types reported by the runtime's directory listing
+// helpers (rust_list_dir_type and rust_list_dir_wfd_type).
+fn entry_type(t: c_int) -> io::FileType {
+    match t {
+        1 => io::TypeFile,
+        2 => io::TypeDirectory,
+        3 => io::TypeNamedPipe,
+        4 => io::TypeBlockSpecial,
+        5 => io::TypeSymlink,
+        _ => io::TypeUnknown,
+    }
+}
+
+fn is_dot(name: &[u8]) -> bool {
+    name == bytes!(".") || name == bytes!("..")
+}
+
+/// An open directory, whose entries are read one at a time.
+#[cfg(unix)]
+pub struct Directory {
+    priv dir: *libc::DIR,
+    priv root: Path,
+}
+
+#[cfg(unix)]
+impl Directory {
+    pub fn open(p: &CString) -> IoResult<Directory> {
+        let dir = p.with_ref(|buf| unsafe { libc::opendir(buf) });
+        if dir.is_null() {
+            Err(super::last_error())
+        } else {
+            let root = Path::new(unsafe { CString::new(p.with_ref(|p| p), false) });
+            Ok(Directory { dir: dir, root: root })
+        }
+    }
+}
 
-            #[nolink]
-            extern {
-                fn rust_list_dir_wfd_size() -> libc::size_t;
-                fn rust_list_dir_wfd_fp_buf(wfd: *libc::c_void) -> *u16;
+#[cfg(unix)]
+impl rtio::RtioDirectory for Directory {
+    fn next_entry(&mut self) -> IoResult<Option<(Path, io::FileType)>> {
+        extern {
+            fn rust_list_dir_val(ptr: *libc::dirent_t) -> *libc::c_char;
+            fn rust_list_dir_type(ptr: *libc::dirent_t) -> c_int;
+        }
+        loop {
+            let entry = unsafe { libc::readdir(self.dir) };
+            if entry.is_null() { return Ok(None) }
+            let name = unsafe { CString::new(rust_list_dir_val(entry), false) };
+            let name = name.as_bytes();
+            let name = name.slice_to(name.len() - 1); // chop off the NUL
+            if is_dot(name) { continue }
+            let kind = entry_type(unsafe { rust_list_dir_type(entry) });
+            return Ok(Some((self.root.join(name), kind)));
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Directory {
+    fn drop(&mut self) {
+        unsafe { libc::closedir(self.dir); }
+    }
+}
+
+/// An open directory, whose entries are read one at a time.
+#[cfg(windows)]
+pub struct Directory {
+    priv handle: libc::HANDLE,
+    priv wfd: *libc::c_void,
+    // Whether `wfd` holds an entry which hasn't been returned yet.
+    priv pending: bool,
+    priv root: Path,
+}
+
+#[cfg(windows)]
+extern {
+    fn rust_list_dir_wfd_size() -> libc::size_t;
+    fn rust_list_dir_wfd_fp_buf(wfd: *libc::c_void) -> *u16;
+    fn rust_list_dir_wfd_type(wfd: *libc::c_void) -> c_int;
+}
+
+#[cfg(windows)]
+impl Directory {
+    pub fn open(p: &CString) -> IoResult<Directory> {
+        use libc::consts::os::extra::INVALID_HANDLE_VALUE;
+        use libc::funcs::extra::kernel32::FindFirstFileW;
+        use rt::global_heap::malloc_raw;
+
+        let root = Path::new(unsafe { CString::new(p.with_ref(|p| p), false) });
+        let star = root.join("*");
+        as_utf16_p(star.as_str().unwrap(), |path_ptr| unsafe {
+            let wfd = malloc_raw(rust_list_dir_wfd_size() as uint);
+            let handle = FindFirstFileW(path_ptr, wfd as libc::HANDLE);
+            if handle as libc::c_int != INVALID_HANDLE_VALUE {
+                Ok(Directory {
+                    handle: handle,
+                    wfd: wfd as *libc::c_void,
+                    pending: true,
+                    root: root.clone(),
+                })
+            } else {
+                let err = super::last_error();
+                libc::free(wfd);
+                Err(err)
             }
-            let p = CString::new(p.with_ref(|p| p), false);
-            let p = Path::new(p);
-            let star = p.join("*");
-            as_utf16_p(star.as_str().unwrap(), |path_ptr| {
-                let wfd_ptr = malloc_raw(rust_list_dir_wfd_size() as uint);
-                let find_handle = FindFirstFileW(path_ptr, wfd_ptr as HANDLE);
-                if find_handle as libc::c_int != INVALID_HANDLE_VALUE {
-                    let mut paths = ~[];
-                    let mut more_files = 1 as libc::c_int;
-                    while more_files != 0 {
-                        let fp_buf = rust_list_dir_wfd_fp_buf(wfd_ptr);
-                        if fp_buf as uint == 0 {
-                            fail!("os::list_dir() failure: got null ptr from wfd");
-                        }
-                        else {
-                            let fp_vec = vec::from_buf(
-                                fp_buf, wcslen(fp_buf) as uint);
-                            let fp_str = str::from_utf16(fp_vec);
-                            paths.push(Path::new(fp_str));
-                        }
-                        more_files = FindNextFileW(find_handle, wfd_ptr as HANDLE);
-                    }
-                    FindClose(find_handle);
-                    free(wfd_ptr);
-                    Ok(paths)
-                } else {
-                    Err(super::last_error())
+        })
+    }
+}
+
+#[cfg(windows)]
+impl rtio::RtioDirectory for Directory {
+    fn next_entry(&mut self) -> IoResult<Option<(Path, io::FileType)>> {
+        use libc::funcs::extra::kernel32::FindNextFileW;
+
+        loop {
+            if !self.pending {
+                if unsafe { FindNextFileW(self.handle, self.wfd as libc::HANDLE) } == 0 {
+                    return Ok(None);
                 }
-            })
+            }
+            self.pending = false;
+            let fp_buf = unsafe { rust_list_dir_wfd_fp_buf(self.wfd) };
+            if fp_buf.is_null() {
+                fail!("os::list_dir() failure: got null ptr from wfd");
+            }
+            let name = unsafe {
+                str::from_utf16(vec::from_buf(fp_buf, libc::wcslen(fp_buf) as uint))
+            };
+            if is_dot(name.as_bytes()) { continue }
+            let kind = entry_type(unsafe { rust_list_dir_wfd_type(self.wfd) });
+            return Ok(Some((self.root.join(name), kind)));
         }
+    }
+}
 
-        get_list(p).map(|paths| prune(p, paths))
+#[cfg(windows)]
+impl Drop for Directory {
+    fn drop(&mut self) {
+        use libc::funcs::extra::kernel32::FindClose;
+        unsafe {
+            FindClose(self.handle);
+            libc::free(self.wfd);
+        }
     }
 }
 
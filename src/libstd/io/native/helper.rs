@@ -0,0 +1,361 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The native helper thread
+//!
+//! Native readiness notifications need something to do the waiting on behalf
+//! of the task which asked for them. Rather than a thread per request, one
+//! helper thread is started the first time that it's needed, and it waits for
+//! all outstanding requests at once: with a single call to poll(2) over the
+//! requested descriptors along with the read end of a wakeup pipe, or on
+//! windows (where descriptors can't be waited for) on a wakeup event. New
+//! requests and cancellations wake the helper up so that it can recompute what
+//! it's waiting for.
+//!
+//! Besides one-off requests, which are done with once they complete, the helper
+//! also runs periodic requests, which is what native timers are built on.
+
+use cast;
+use cell::Cell;
+use io::poll::{Readiness, NOT_READY};
+use iter::Iterator;
+use libc::c_int;
+use ops::Drop;
+use option::{Option, Some, None};
+use rt::rtio::Callback;
+use rt::thread::Thread;
+use unstable::mutex::{Mutex, MUTEX_INIT};
+use vec::{OwnedVector, ImmutableVector};
+
+enum Action {
+    // Completes the request, which is removed afterwards
+    Once(proc(Readiness)),
+    // Runs every so many nanoseconds, for as long as the request is registered
+    Every(u64, ~Callback),
+}
+
+struct Request {
+    id: uint,
+    fd: Option<(c_int, Readiness)>,
+    // When the request gives up (or next runs, if it's periodic), in
+    // nanoseconds
+    deadline: Option<u64>,
+    action: Action,
+}
+
+struct State {
+    requests: ~[Request],
+    next_id: uint,
+    wakeup: imp::Wakeup,
+}
+
+static mut LOCK: Mutex = MUTEX_INIT;
+static mut STATE: *mut State = 0 as *mut State;
+
+/// An outstanding request. Dropping it cancels the request, unless the helper
+/// has already gotten to it.
+pub struct Registration {
+    priv id: uint,
+}
+
+/// Asks the helper thread to call `done` once `fd` meets one of the conditions
+/// of `interest`, with the conditions which it meets, or with `NOT_READY` once
+/// `timeout_ms` milliseconds have passed. `done` runs on the helper thread, so
+/// it must not block.
+pub fn register(fd: Option<(c_int, Readiness)>, timeout_ms: Option<u64>,
+                done: proc(Readiness)) -> Registration {
+    let deadline = timeout_ms.map(|ms| precise_time_ns() + ms * 1000000);
+    add(fd, deadline, Once(done))
+}
+
+/// Asks the helper thread to call `tick` every `period_ms` milliseconds, the
+/// first time `period_ms` milliseconds from now, until the registration is
+/// dropped. `tick` runs on the helper thread with the helper's lock held, so
+/// it must neither block nor register or cancel any requests itself.
+pub fn register_periodic(period_ms: u64, tick: ~Callback) -> Registration {
+    let period = period_ms * 1000000;
+    add(None, Some(precise_time_ns() + period), Every(period, tick))
+}
+
+fn add(fd: Option<(c_int, Readiness)>, deadline: Option<u64>,
+       action: Action) -> Registration {
+    let action = Cell::new(action);
+    with_state(|state| {
+        let id = state.next_id;
+        state.next_id += 1;
+        state.requests.push(Request {
+            id: id,
+            fd: fd,
+            deadline: deadline,
+            action: action.take(),
+        });
+        state.wakeup.signal();
+        Registration { id: id }
+    })
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // A cancelled request is destroyed outside of the lock
+        let _request = with_state(|state| {
+            match state.requests.iter().position(|r| r.id == self.id) {
+                Some(i) => {
+                    state.wakeup.signal();
+                    Some(state.requests.swap_remove(i))
+                }
+                None => None
+            }
+        });
+    }
+}
+
+// Runs `f` on the helper's state with the lock held, starting up the helper
+// thread if it isn't running yet.
+fn with_state<T>(f: |&mut State| -> T) -> T {
+    unsafe {
+        LOCK.lock();
+        if STATE.is_null() {
+            let (wakeup, waiter) = imp::pair();
+            let state: ~State = ~State {
+                requests: ~[],
+                next_id: 0,
+                wakeup: wakeup,
+            };
+            STATE = cast::transmute(state);
+            let waiter = Cell::new(waiter);
+            do Thread::spawn {
+                helper(waiter.take());
+            }
+        }
+        let ret = f(&mut *STATE);
+        LOCK.unlock();
+        ret
+    }
+}
+
+fn helper(waiter: imp::Waiter) {
+    loop {
+        // Gather up what to wait for, and for how long
+        let (fds, timeout) = with_state(|state| {
+            let now = precise_time_ns();
+            let mut fds = ~[];
+            let mut deadline = None;
+            for r in state.requests.iter() {
+                match r.fd {
+                    Some(fd) => fds.push(fd),
+                    None => {}
+                }
+                deadline = match (deadline, r.deadline) {
+                    (Some(a), Some(b)) if b < a => Some(b),
+                    (None, b) => b,
+                    (a, _) => a,
+                };
+            }
+            let timeout = deadline.map(|d| {
+                if d <= now {0} else {(d - now + 999999) / 1000000}
+            });
+            (fds, timeout)
+        });
+
+        let ready = waiter.wait(fds, timeout);
+
+        // Complete every request which was met or which ran out of time, and
+        // run the periodic requests which are due. The requests are only
+        // matched up with the descriptors now, as some may have been cancelled
+        // (and their descriptors closed and reused) while the helper was
+        // waiting, in which case they're gone already.
+        let done = with_state(|state| {
+            let now = precise_time_ns();
+            let mut done = ~[];
+            let mut i = 0;
+            while i < state.requests.len() {
+                let met = match state.requests[i].fd {
+                    Some((fd, _)) => {
+                        ready.iter().find(|&&(r, _)| r == fd).map(|&(_, r)| r)
+                    }
+                    None => None
+                };
+                let met = match (met, state.requests[i].deadline) {
+                    (Some(r), _) => Some(r),
+                    (None, Some(d)) if d <= now => Some(NOT_READY),
+                    (None, _) => None,
+                };
+                let ready = match met {
+                    Some(r) => r,
+                    None => { i += 1; continue }
+                };
+                let period = match state.requests[i].action {
+                    Every(period, ref mut tick) => { tick.call(); Some(period) }
+                    Once(*) => None,
+                };
+                match period {
+                    Some(period) => {
+                        // Keep to the schedule, unless the helper has fallen
+                        // a whole period behind it
+                        let request = &mut state.requests[i];
+                        let next = request.deadline.unwrap() + period;
+                        request.deadline = Some(if next <= now {now + period}
+                                                else {next});
+                        i += 1;
+                    }
+                    None => done.push((state.requests.swap_remove(i), ready)),
+                }
+            }
+            done
+        });
+        for (request, ready) in done.move_iter() {
+            match request {
+                Request { action: Once(done), _ } => done(ready),
+                Request { action: Every(*), _ } => {}
+            }
+        }
+    }
+}
+
+fn precise_time_ns() -> u64 {
+    extern {
+        fn rust_precise_time_ns(ns: &mut u64);
+    }
+    let mut ns = 0;
+    unsafe { rust_precise_time_ns(&mut ns) }
+    ns
+}
+
+#[cfg(unix)]
+mod imp {
+    use io::poll::Readiness;
+    use iter::Iterator;
+    use libc;
+    use option::{Option, Some, None};
+    use os;
+    use super::super::poll::{poll, events, readiness};
+    use vec::{OwnedVector, ImmutableVector, MutableVector};
+
+    pub struct Wakeup { priv fd: libc::c_int }
+    pub struct Waiter { priv fd: libc::c_int }
+
+    pub fn pair() -> (Wakeup, Waiter) {
+        let os::Pipe { input, out } = os::pipe();
+        // Wakeups are only signalled with the helper's lock held, so a full
+        // pipe must not block the signaller. A full pipe wakes the helper up
+        // all the same.
+        unsafe {
+            libc::fcntl(out, libc::F_SETFL, libc::O_NONBLOCK);
+        }
+        (Wakeup { fd: out }, Waiter { fd: input })
+    }
+
+    impl Wakeup {
+        pub fn signal(&self) {
+            unsafe {
+                [0u8].as_imm_buf(|p, len| {
+                    libc::write(self.fd, p as *libc::c_void, len as libc::size_t)
+                });
+            }
+        }
+    }
+
+    impl Waiter {
+        /// Waits for any of `fds` to become ready, for `timeout` milliseconds,
+        /// or for a wakeup, returning the descriptors which became ready.
+        pub fn wait(&self, fds: ~[(libc::c_int, Readiness)],
+                    timeout: Option<u64>) -> ~[(libc::c_int, Readiness)] {
+            let mut pfds = ~[libc::pollfd {
+                fd: self.fd,
+                events: libc::POLLIN,
+                revents: 0,
+            }];
+            for &(fd, interest) in fds.iter() {
+                pfds.push(libc::pollfd {
+                    fd: fd,
+                    events: events(interest),
+                    revents: 0,
+                });
+            }
+            if !poll(pfds, timeout) {
+                return ~[];
+            }
+            if pfds[0].revents != 0 {
+                let mut buf = [0u8, ..64];
+                unsafe {
+                    buf.as_mut_buf(|p, len| {
+                        libc::read(self.fd, p as *mut libc::c_void,
+                                   len as libc::size_t)
+                    });
+                }
+            }
+            let mut ready = ~[];
+            for (pfd, &(fd, interest)) in pfds.slice_from(1).iter().zip(fds.iter()) {
+                match readiness(pfd.revents, interest) {
+                    Some(r) => ready.push((fd, r)),
+                    None => {}
+                }
+            }
+            ready
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use container::Container;
+    use io::poll::Readiness;
+    use libc;
+    use ops::Drop;
+    use option::{Option, Some};
+    use ptr;
+    use unstable::sync::UnsafeArc;
+
+    // Leave room for INFINITE, which is the largest DWORD
+    static MAX_CHUNK: u64 = 1 << 31;
+
+    struct Event { handle: libc::HANDLE }
+
+    pub struct Wakeup { priv event: UnsafeArc<Event> }
+    pub struct Waiter { priv event: UnsafeArc<Event> }
+
+    pub fn pair() -> (Wakeup, Waiter) {
+        let handle = unsafe {
+            libc::CreateEventW(ptr::mut_null(), libc::FALSE, libc::FALSE,
+                               ptr::null())
+        };
+        assert!(handle != ptr::mut_null());
+        let (a, b) = UnsafeArc::new2(Event { handle: handle });
+        (Wakeup { event: a }, Waiter { event: b })
+    }
+
+    impl Wakeup {
+        pub fn signal(&self) {
+            unsafe { libc::SetEvent((*self.event.get_immut()).handle); }
+        }
+    }
+
+    impl Waiter {
+        /// Waits for `timeout` milliseconds or for a wakeup. Descriptors can't
+        /// be polled on windows, so there are never any to wait for.
+        pub fn wait(&self, fds: ~[(libc::c_int, Readiness)],
+                    timeout: Option<u64>) -> ~[(libc::c_int, Readiness)] {
+            assert!(fds.is_empty());
+            let chunk = match timeout {
+                Some(ms) if ms < MAX_CHUNK => ms,
+                _ => MAX_CHUNK,
+            };
+            unsafe {
+                libc::WaitForSingleObject((*self.event.get_immut()).handle,
+                                          chunk as libc::DWORD);
+            }
+            ~[]
+        }
+    }
+
+    impl Drop for Event {
+        fn drop(&mut self) { unsafe { libc::CloseHandle(self.handle); } }
+    }
+}
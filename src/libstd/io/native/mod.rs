@@ -32,9 +32,10 @@ use result::{Result, Ok, Err};
 use rt::rtio;
 use rt::rtio::{RtioTcpStream, RtioTcpListener, RtioUdpSocket, RtioUnixListener,
                RtioPipe, RtioFileStream, RtioProcess, RtioSignal, RtioTTY,
-               CloseBehavior, RtioTimer};
+               CloseBehavior, RtioTimer, RtioDirectory};
 use io;
 use io::IoError;
+use io::net::BindOptions;
 use io::net::ip::SocketAddr;
 use io::process::ProcessConfig;
 use io::signal::Signum;
@@ -45,8 +46,16 @@ pub use self::file::FileDesc;
 pub use self::process::Process;
 
 // Native I/O implementations
+pub mod addrinfo;
 pub mod file;
+mod helper;
+pub mod net;
+pub mod notify;
+pub mod poll;
 pub mod process;
+pub mod signal;
+pub mod timer;
+pub mod tty;
 
 type IoResult<T> = Result<T, IoError>;
 
@@ -59,10 +68,26 @@ fn unimpl() -> IoError {
 }
 
 fn last_error() -> IoError {
+    let mut err = translate_error(os::errno() as i32);
+    err.detail = Some(os::last_os_error());
+    err
+}
+
+// Translates an error code from the system (such as one reported by
+// SO_ERROR) into an IoError.
+fn translate_error(errno: i32) -> IoError {
     #[cfg(windows)]
     fn get_err(errno: i32) -> (io::IoErrorKind, &'static str) {
         match errno {
             libc::EOF => (io::EndOfFile, "end of file"),
+            libc::WSAECONNREFUSED => (io::ConnectionRefused, "connection refused"),
+            libc::WSAECONNRESET => (io::ConnectionReset, "connection reset"),
+            libc::WSAEACCES => (io::PermissionDenied, "permission denied"),
+            libc::WSAEWOULDBLOCK =>
+                (io::ResourceUnavailable, "resource temporarily unavailable"),
+            libc::WSAENOTCONN => (io::NotConnected, "not connected"),
+            libc::WSAECONNABORTED => (io::ConnectionAborted, "connection aborted"),
+            libc::WSAETIMEDOUT => (io::TimedOut, "operation timed out"),
             _ => (io::OtherIoError, "unknown error"),
         }
     }
@@ -72,6 +97,14 @@ fn last_error() -> IoError {
         // XXX: this should probably be a bit more descriptive...
         match errno {
             libc::EOF => (io::EndOfFile, "end of file"),
+            libc::ECONNREFUSED => (io::ConnectionRefused, "connection refused"),
+            libc::ECONNRESET => (io::ConnectionReset, "connection reset"),
+            libc::EPERM | libc::EACCES =>
+                (io::PermissionDenied, "permission denied"),
+            libc::EPIPE => (io::BrokenPipe, "broken pipe"),
+            libc::ENOTCONN => (io::NotConnected, "not connected"),
+            libc::ECONNABORTED => (io::ConnectionAborted, "connection aborted"),
+            libc::ETIMEDOUT => (io::TimedOut, "operation timed out"),
 
             // These two constants can have the same value on some systems, but
             // different values on others, so we can't use a match clause
@@ -82,11 +115,11 @@ fn last_error() -> IoError {
         }
     }
 
-    let (kind, desc) = get_err(os::errno() as i32);
+    let (kind, desc) = get_err(errno);
     IoError {
         kind: kind,
         desc: desc,
-        detail: Some(os::last_os_error())
+        detail: None,
     }
 }
 
@@ -114,24 +147,28 @@ pub struct IoFactory;
 
 impl rtio::IoFactory for IoFactory {
     // networking
-    fn tcp_connect(&mut self, _addr: SocketAddr) -> IoResult<~RtioTcpStream> {
-        Err(unimpl())
+    fn tcp_connect(&mut self, addr: SocketAddr,
+                   timeout: Option<u64>) -> IoResult<~RtioTcpStream> {
+        net::TcpStream::connect(addr, timeout).map(|s| ~s as ~RtioTcpStream)
     }
-    fn tcp_bind(&mut self, _addr: SocketAddr) -> IoResult<~RtioTcpListener> {
-        Err(unimpl())
+    fn tcp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> IoResult<~RtioTcpListener> {
+        net::TcpListener::bind(addr, opts).map(|s| ~s as ~RtioTcpListener)
     }
-    fn udp_bind(&mut self, _addr: SocketAddr) -> IoResult<~RtioUdpSocket> {
-        Err(unimpl())
+    fn udp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> IoResult<~RtioUdpSocket> {
+        net::UdpSocket::bind(addr, opts).map(|u| ~u as ~RtioUdpSocket)
     }
-    fn unix_bind(&mut self, _path: &CString) -> IoResult<~RtioUnixListener> {
-        Err(unimpl())
+    fn unix_bind(&mut self, path: &CString) -> IoResult<~RtioUnixListener> {
+        net::UnixListener::bind(path).map(|s| ~s as ~RtioUnixListener)
     }
-    fn unix_connect(&mut self, _path: &CString) -> IoResult<~RtioPipe> {
-        Err(unimpl())
+    fn unix_connect(&mut self, path: &CString,
+                    timeout: Option<u64>) -> IoResult<~RtioPipe> {
+        net::UnixStream::connect(path, timeout).map(|s| ~s as ~RtioPipe)
     }
-    fn get_host_addresses(&mut self, _host: Option<&str>, _servname: Option<&str>,
-                          _hint: Option<ai::Hint>) -> IoResult<~[ai::Info]> {
-        Err(unimpl())
+    fn get_host_addresses(&mut self, host: Option<&str>, servname: Option<&str>,
+                          hint: Option<ai::Hint>) -> IoResult<~[ai::Info]> {
+        addrinfo::GetAddrInfoRequest::run(host, servname, hint)
     }
 
     // filesystem operations
@@ -170,6 +207,9 @@ impl rtio::IoFactory for IoFactory {
     fn fs_readdir(&mut self, path: &CString, _flags: c_int) -> IoResult<~[Path]> {
         file::readdir(path)
     }
+    fn fs_opendir(&mut self, path: &CString) -> IoResult<~RtioDirectory> {
+        file::Directory::open(path).map(|d| ~d as ~RtioDirectory)
+    }
     fn fs_lstat(&mut self, path: &CString) -> IoResult<io::FileStat> {
         file::lstat(path)
     }
@@ -192,7 +232,7 @@ impl rtio::IoFactory for IoFactory {
 
     // misc
     fn timer_init(&mut self) -> IoResult<~RtioTimer> {
-        Err(unimpl())
+        timer::Timer::new().map(|t| ~t as ~RtioTimer)
     }
     fn spawn(&mut self, config: ProcessConfig)
             -> IoResult<(~RtioProcess, ~[Option<~RtioPipe>])> {
@@ -205,18 +245,10 @@ impl rtio::IoFactory for IoFactory {
         Ok(~file::FileDesc::new(fd, true) as ~RtioPipe)
     }
     fn tty_open(&mut self, fd: c_int, _readable: bool) -> IoResult<~RtioTTY> {
-        if unsafe { libc::isatty(fd) } != 0 {
-            Ok(~file::FileDesc::new(fd, true) as ~RtioTTY)
-        } else {
-            Err(IoError {
-                kind: io::MismatchedFileTypeForOperation,
-                desc: "file descriptor is not a TTY",
-                detail: None,
-            })
-        }
+        tty::TTY::new(fd).map(|t| ~t as ~RtioTTY)
     }
-    fn signal(&mut self, _signal: Signum, _channel: SharedChan<Signum>)
+    fn signal(&mut self, signum: Signum, channel: SharedChan<Signum>)
         -> IoResult<~RtioSignal> {
-        Err(unimpl())
+        signal::Signal::new(signum, channel).map(|s| ~s as ~RtioSignal)
     }
 }
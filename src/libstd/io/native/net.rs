@@ -0,0 +1,1401 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Blocking BSD socket implementations of TCP, UDP, and unix sockets
+
+#[allow(non_camel_case_types)];
+
+use c_str::CString;
+use cast;
+use io::net::BindOptions;
+use io::net::ip;
+use io;
+use libc;
+use mem;
+use ops::Drop;
+use option::{Option, Some, None};
+use os;
+use result::{Ok, Err};
+use rt::rtio;
+use super::IoResult;
+use super::file::keep_going;
+use super::poll;
+use unstable::intrinsics;
+use vec::ImmutableVector;
+use vec;
+
+use container::Container;
+#[cfg(unix)] use iter::Iterator;
+#[cfg(unix)] use vec::MutableVector;
+#[cfg(windows)] use ptr;
+
+#[cfg(windows)] pub type sock_t = libc::SOCKET;
+#[cfg(unix)]    pub type sock_t = super::file::fd_t;
+
+#[cfg(windows)] type wrlen = libc::c_int;
+#[cfg(unix)]    type wrlen = libc::size_t;
+
+pub fn htons(u: u16) -> u16 {
+    intrinsics::to_be16(u as i16) as u16
+}
+pub fn ntohs(u: u16) -> u16 {
+    // byte swapping is its own inverse
+    intrinsics::to_be16(u as i16) as u16
+}
+
+enum InAddr {
+    InAddr(libc::in_addr),
+    In6Addr(libc::in6_addr),
+}
+
+fn ip_to_inaddr(ip: ip::IpAddr) -> InAddr {
+    match ip {
+        ip::Ipv4Addr(a, b, c, d) => {
+            // the address is stored in network byte order, which is the order
+            // the octets are written in
+            let octets = [a, b, c, d];
+            InAddr(libc::in_addr {
+                s_addr: unsafe { cast::transmute(octets) }
+            })
+        }
+        ip::Ipv6Addr(a, b, c, d, e, f, g, h) => {
+            In6Addr(libc::in6_addr {
+                s6_addr: [
+                    htons(a),
+                    htons(b),
+                    htons(c),
+                    htons(d),
+                    htons(e),
+                    htons(f),
+                    htons(g),
+                    htons(h),
+                ]
+            })
+        }
+    }
+}
+
+fn addr_to_sockaddr(addr: ip::SocketAddr) -> (libc::sockaddr_storage, uint) {
+    unsafe {
+        let storage: libc::sockaddr_storage = intrinsics::init();
+        let len = match ip_to_inaddr(addr.ip) {
+            InAddr(inaddr) => {
+                let storage: *mut libc::sockaddr_in = cast::transmute(&storage);
+                (*storage).sin_family = libc::AF_INET as libc::sa_family_t;
+                (*storage).sin_port = htons(addr.port);
+                (*storage).sin_addr = inaddr;
+                mem::size_of::<libc::sockaddr_in>()
+            }
+            In6Addr(inaddr) => {
+                let storage: *mut libc::sockaddr_in6 = cast::transmute(&storage);
+                (*storage).sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                (*storage).sin6_port = htons(addr.port);
+                (*storage).sin6_addr = inaddr;
+                mem::size_of::<libc::sockaddr_in6>()
+            }
+        };
+        return (storage, len);
+    }
+}
+
+pub fn sockaddr_to_addr(storage: &libc::sockaddr_storage,
+                        len: uint) -> IoResult<ip::SocketAddr> {
+    match storage.ss_family as libc::c_int {
+        libc::AF_INET => {
+            assert!(len as uint >= mem::size_of::<libc::sockaddr_in>());
+            let storage: &libc::sockaddr_in = unsafe {
+                cast::transmute(storage)
+            };
+            let octets: [u8, ..4] = unsafe {
+                cast::transmute(storage.sin_addr.s_addr)
+            };
+            Ok(ip::SocketAddr {
+                ip: ip::Ipv4Addr(octets[0], octets[1], octets[2], octets[3]),
+                port: ntohs(storage.sin_port),
+            })
+        }
+        libc::AF_INET6 => {
+            assert!(len as uint >= mem::size_of::<libc::sockaddr_in6>());
+            let storage: &libc::sockaddr_in6 = unsafe {
+                cast::transmute(storage)
+            };
+            let a = ntohs(storage.sin6_addr.s6_addr[0]);
+            let b = ntohs(storage.sin6_addr.s6_addr[1]);
+            let c = ntohs(storage.sin6_addr.s6_addr[2]);
+            let d = ntohs(storage.sin6_addr.s6_addr[3]);
+            let e = ntohs(storage.sin6_addr.s6_addr[4]);
+            let f = ntohs(storage.sin6_addr.s6_addr[5]);
+            let g = ntohs(storage.sin6_addr.s6_addr[6]);
+            let h = ntohs(storage.sin6_addr.s6_addr[7]);
+            Ok(ip::SocketAddr {
+                ip: ip::Ipv6Addr(a, b, c, d, e, f, g, h),
+                port: ntohs(storage.sin6_port),
+            })
+        }
+        _ => {
+            Err(io::standard_error(io::OtherIoError))
+        }
+    }
+}
+
+fn socket(addr: ip::SocketAddr, ty: libc::c_int) -> IoResult<sock_t> {
+    init();
+    let fam = match addr.ip {
+        ip::Ipv4Addr(*) => libc::AF_INET,
+        ip::Ipv6Addr(*) => libc::AF_INET6,
+    };
+    match unsafe { libc::socket(fam, ty, 0) } {
+        fd if fd == -1 as sock_t => Err(super::last_error()),
+        fd => Ok(fd),
+    }
+}
+
+fn setsockopt<T>(fd: sock_t, opt: libc::c_int, val: libc::c_int,
+                 payload: T) -> IoResult<()> {
+    unsafe {
+        let payload = &payload as *T as *libc::c_void;
+        let ret = libc::setsockopt(fd, opt, val,
+                                   payload,
+                                   mem::size_of::<T>() as libc::socklen_t);
+        super::mkerr_libc(ret)
+    }
+}
+
+fn sockname(fd: sock_t,
+            f: extern "system" unsafe fn(sock_t, *mut libc::sockaddr,
+                                         *mut libc::socklen_t) -> libc::c_int)
+    -> IoResult<ip::SocketAddr>
+{
+    let mut storage: libc::sockaddr_storage = unsafe { intrinsics::init() };
+    let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    unsafe {
+        let storage = &mut storage as *mut libc::sockaddr_storage;
+        let ret = f(fd,
+                    storage as *mut libc::sockaddr,
+                    &mut len as *mut libc::socklen_t);
+        if ret != 0 {
+            return Err(super::last_error())
+        }
+    }
+    return sockaddr_to_addr(&storage, len as uint);
+}
+
+fn shutdown(fd: sock_t, how: libc::c_int) -> IoResult<()> {
+    super::mkerr_libc(unsafe { libc::shutdown(fd, how) })
+}
+
+// Applies the options which have to be set between creating a socket and
+// binding it to `addr`.
+fn set_bind_options(fd: sock_t, addr: ip::SocketAddr,
+                    opts: BindOptions) -> IoResult<()> {
+    let reuse = if opts.reuse_address {
+        setsockopt(fd, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1 as libc::c_int)
+    } else {
+        Ok(())
+    };
+    reuse.and_then(|()| {
+        match addr.ip {
+            // The default varies between systems (and with sysctls), so it
+            // is always set one way or the other
+            ip::Ipv6Addr(*) => {
+                setsockopt(fd, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY,
+                           opts.ipv6_only as libc::c_int)
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+fn bind(fd: sock_t, addr: ip::SocketAddr) -> IoResult<()> {
+    let (addr, len) = addr_to_sockaddr(addr);
+    let addrp = &addr as *libc::sockaddr_storage;
+    match unsafe {
+        libc::bind(fd, addrp as *libc::sockaddr, len as libc::socklen_t)
+    } {
+        -1 => Err(super::last_error()),
+        _ => Ok(()),
+    }
+}
+
+// Retry a socket call for as long as it is interrupted by a signal
+#[cfg(unix)]
+fn retry(f: || -> i64) -> i64 {
+    loop {
+        match f() {
+            -1 if os::errno() as int == libc::EINTR as int => {}
+            n => return n,
+        }
+    }
+}
+#[cfg(windows)]
+fn retry(f: || -> i64) -> i64 { f() }
+
+#[cfg(unix)]
+unsafe fn close(sock: sock_t) { let _ = libc::close(sock); }
+#[cfg(windows)]
+unsafe fn close(sock: sock_t) { let _ = libc::closesocket(sock); }
+
+/// Winsock must be started before any socket functions may be used. This is
+/// done lazily the first time a socket is created.
+#[cfg(windows)]
+pub fn init() {
+    use unstable::mutex::{Mutex, MUTEX_INIT};
+    static mut lock: Mutex = MUTEX_INIT;
+    static mut initialized: bool = false;
+
+    unsafe {
+        lock.lock();
+        if !initialized {
+            let mut data: libc::WSADATA = intrinsics::init();
+            let ret = libc::WSAStartup(0x202,      // version 2.2
+                                       &mut data);
+            assert_eq!(ret, 0);
+            initialized = true;
+        }
+        lock.unlock();
+    }
+}
+
+#[cfg(unix)]
+pub fn init() {}
+
+////////////////////////////////////////////////////////////////////////////////
+// Timeouts
+////////////////////////////////////////////////////////////////////////////////
+
+enum Readiness {
+    Readable,
+    Writable,
+}
+
+fn timed_out() -> io::IoError {
+    io::standard_error(io::TimedOut)
+}
+
+// Blocks until `fd` is ready for the given kind of I/O, failing with a TimedOut
+// error if that takes longer than `timeout` milliseconds. Without a timeout
+// there's nothing to do here as the following I/O call will simply block.
+#[cfg(unix)]
+fn await(fd: sock_t, timeout: Option<u64>, status: Readiness) -> IoResult<()> {
+    // poll(2) takes a c_int timeout
+    static MAX_TIMEOUT: u64 = 0x7fffffff;
+
+    let ms = match timeout {
+        Some(ms) if ms > MAX_TIMEOUT => MAX_TIMEOUT,
+        Some(ms) => ms,
+        None => return Ok(()),
+    };
+    poll(fd, ms as libc::c_int, status)
+}
+
+// Waits on `fd` with poll(2) for up to `ms` milliseconds, or for as long as it
+// takes if `ms` is negative.
+#[cfg(unix)]
+fn poll(fd: sock_t, ms: libc::c_int, status: Readiness) -> IoResult<()> {
+    let mut pfd = libc::pollfd {
+        fd: fd,
+        events: match status {
+            Readable => libc::POLLIN,
+            Writable => libc::POLLOUT,
+        },
+        revents: 0,
+    };
+    match retry(|| unsafe { libc::poll(&mut pfd, 1, ms) as i64 }) {
+        -1 => Err(super::last_error()),
+        0 => Err(timed_out()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn await(fd: sock_t, timeout: Option<u64>, status: Readiness) -> IoResult<()> {
+    let ms = match timeout {
+        Some(ms) => ms,
+        None => return Ok(()),
+    };
+    let mut set: libc::fd_set = unsafe { intrinsics::init() };
+    set.fd_count = 1;
+    set.fd_array[0] = fd;
+    // A failed connect is only reported through the exceptional set
+    let mut except = set;
+    let tv = libc::timeval {
+        tv_sec: (ms / 1000) as libc::c_long,
+        tv_usec: ((ms % 1000) * 1000) as libc::c_long,
+    };
+    let ret = unsafe {
+        match status {
+            Readable => libc::select(1, &mut set, ptr::mut_null(),
+                                     ptr::mut_null(), &tv),
+            Writable => libc::select(1, ptr::mut_null(), &mut set,
+                                     &mut except, &tv),
+        }
+    };
+    match ret {
+        -1 => Err(super::last_error()),
+        0 => Err(timed_out()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn set_nonblocking(fd: sock_t, nonblocking: bool) -> IoResult<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags == -1 { return Err(super::last_error()) }
+    let flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+    match unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } {
+        -1 => Err(super::last_error()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn set_nonblocking(fd: sock_t, nonblocking: bool) -> IoResult<()> {
+    let mut arg = nonblocking as libc::c_ulong;
+    match unsafe { libc::ioctlsocket(fd, libc::FIONBIO, &mut arg) } {
+        0 => Ok(()),
+        _ => Err(super::last_error()),
+    }
+}
+
+#[cfg(unix)]
+fn would_block() -> bool {
+    let err = os::errno() as libc::c_int;
+    err == libc::EAGAIN || err == libc::EWOULDBLOCK
+}
+
+#[cfg(windows)]
+fn would_block() -> bool {
+    os::errno() as libc::c_int == libc::WSAEWOULDBLOCK
+}
+
+#[cfg(unix)]
+fn connect_in_progress() -> bool {
+    let err = os::errno() as libc::c_int;
+    err == libc::EINPROGRESS || err == libc::EINTR
+}
+#[cfg(windows)]
+fn connect_in_progress() -> bool {
+    os::errno() as libc::c_int == libc::WSAEWOULDBLOCK
+}
+
+// Finds out whether a connection which was started in the background
+// actually succeeded.
+fn connect_error(fd: sock_t) -> IoResult<()> {
+    let mut err: libc::c_int = 0;
+    let mut errlen = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(fd, libc::SOL_SOCKET, libc::SO_ERROR,
+                         &mut err as *mut libc::c_int as *mut libc::c_void,
+                         &mut errlen)
+    };
+    if ret != 0 {
+        Err(super::last_error())
+    } else if err != 0 {
+        Err(super::translate_error(err as i32))
+    } else {
+        Ok(())
+    }
+}
+
+// A blocking connect which is interrupted by a signal carries on in the
+// background, and calling connect again would fail with EALREADY or EISCONN.
+// Instead, wait for the socket to become writable and then see how it went.
+#[cfg(unix)]
+fn connect_interrupted(fd: sock_t) -> Option<IoResult<()>> {
+    if os::errno() as libc::c_int != libc::EINTR { return None }
+    Some(poll(fd, -1, Writable).and_then(|()| connect_error(fd)))
+}
+#[cfg(windows)]
+fn connect_interrupted(_fd: sock_t) -> Option<IoResult<()>> { None }
+
+// Connects `fd` to the given address. With a timeout, the connection is
+// started in nonblocking mode and waited upon, after which the socket is put
+// back into blocking mode.
+fn connect(fd: sock_t, addrp: *libc::sockaddr, len: libc::socklen_t,
+           timeout: Option<u64>) -> IoResult<()> {
+    if timeout.is_none() {
+        return match unsafe { libc::connect(fd, addrp, len) } {
+            -1 => match connect_interrupted(fd) {
+                Some(ret) => ret,
+                None => Err(super::last_error()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    match set_nonblocking(fd, true) {
+        Ok(()) => {}
+        Err(e) => return Err(e),
+    }
+    let ret = match unsafe { libc::connect(fd, addrp, len) } {
+        -1 if connect_in_progress() => {
+            await(fd, timeout, Writable).and_then(|()| connect_error(fd))
+        }
+        -1 => Err(super::last_error()),
+        _ => Ok(()),
+    };
+    match ret {
+        Ok(()) => set_nonblocking(fd, false),
+        Err(e) => Err(e),
+    }
+}
+
+// The flags for I/O which follows an `await`. Without a timeout, the call is
+// allowed to block. With one, it must not: the socket may have been drained
+// or filled up by another thread in the meantime, in which case the caller
+// waits again.
+#[cfg(unix)]
+fn dontwait(timeout: Option<u64>) -> libc::c_int {
+    if timeout.is_some() {libc::MSG_DONTWAIT} else {0}
+}
+// XXX: there's no MSG_DONTWAIT on windows, so I/O there can still block for
+//      longer than the timeout once the socket has become ready.
+#[cfg(windows)]
+fn dontwait(_timeout: Option<u64>) -> libc::c_int { 0 }
+
+// Reads from a connected stream socket, giving up if no data arrives within
+// `timeout` milliseconds.
+fn read(fd: sock_t, buf: &mut [u8], timeout: Option<u64>) -> IoResult<uint> {
+    let flags = dontwait(timeout);
+    let mut ret;
+    loop {
+        match await(fd, timeout, Readable) {
+            Ok(()) => {}
+            Err(e) => return Err(e),
+        }
+        ret = retry(|| unsafe {
+            libc::recv(fd,
+                       vec::raw::to_ptr(buf) as *mut libc::c_void,
+                       buf.len() as wrlen,
+                       flags) as i64
+        });
+        // Another thread may have drained the socket in the meantime
+        if ret != -1 || !would_block() { break }
+    }
+    if ret == 0 {
+        Err(io::standard_error(io::EndOfFile))
+    } else if ret < 0 {
+        Err(super::last_error())
+    } else {
+        Ok(ret as uint)
+    }
+}
+
+// Writes all of `buf` to a connected stream socket. With a timeout, the write
+// gives up if the socket remains unwritable for longer than `timeout`
+// milliseconds, in which case part of the buffer may have been sent.
+fn write(fd: sock_t, buf: &[u8], timeout: Option<u64>) -> IoResult<()> {
+    // Without a timeout, sends are allowed to block until everything has been
+    // handed to the kernel. With one, each send only writes as much as fits
+    // into the socket's buffer right now.
+    let flags = dontwait(timeout);
+    let mut err = None;
+    let ret = keep_going(buf, |buf, len| {
+        loop {
+            match await(fd, timeout, Writable) {
+                Ok(()) => {}
+                Err(e) => { err = Some(e); return -1 }
+            }
+            let ret = unsafe {
+                libc::send(fd, buf as *libc::c_void, len as wrlen, flags) as i64
+            };
+            // Another thread may have filled up the buffer in the meantime
+            if ret != -1 || !would_block() { return ret }
+        }
+    });
+    match err {
+        Some(e) => Err(e),
+        None if ret < 0 => Err(super::last_error()),
+        None => Ok(()),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TCP streams
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct TcpStream {
+    priv fd: sock_t,
+    priv read_timeout: Option<u64>,
+    priv write_timeout: Option<u64>,
+}
+
+impl TcpStream {
+    fn new(fd: sock_t) -> TcpStream {
+        TcpStream { fd: fd, read_timeout: None, write_timeout: None }
+    }
+
+    pub fn connect(addr: ip::SocketAddr,
+                   timeout: Option<u64>) -> IoResult<TcpStream> {
+        socket(addr, libc::SOCK_STREAM).and_then(|fd| {
+            let (addr, len) = addr_to_sockaddr(addr);
+            let addrp = &addr as *libc::sockaddr_storage;
+            let ret = TcpStream::new(fd);
+            connect(fd, addrp as *libc::sockaddr, len as libc::socklen_t,
+                    timeout).map(|()| ret)
+        })
+    }
+
+    pub fn fd(&self) -> sock_t { self.fd }
+
+    fn set_nodelay(&mut self, nodelay: bool) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_TCP, libc::TCP_NODELAY,
+                   nodelay as libc::c_int)
+    }
+
+    fn set_keepalive(&mut self, seconds: Option<uint>) -> IoResult<()> {
+        let ret = setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_KEEPALIVE,
+                             seconds.is_some() as libc::c_int);
+        match seconds {
+            Some(n) => ret.and_then(|()| self.set_tcp_keepalive(n)),
+            None => ret,
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_tcp_keepalive(&mut self, seconds: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_TCP, libc::TCP_KEEPALIVE,
+                   seconds as libc::c_int)
+    }
+    #[cfg(target_os = "freebsd")]
+    #[cfg(target_os = "linux")]
+    #[cfg(target_os = "android")]
+    fn set_tcp_keepalive(&mut self, seconds: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE,
+                   seconds as libc::c_int)
+    }
+    #[cfg(windows)]
+    fn set_tcp_keepalive(&mut self, _seconds: uint) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl rtio::RtioTcpStream for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<uint> {
+        read(self.fd, buf, self.read_timeout)
+    }
+    fn write(&mut self, buf: &[u8]) -> IoResult<()> {
+        write(self.fd, buf, self.write_timeout)
+    }
+    fn peer_name(&mut self) -> IoResult<ip::SocketAddr> {
+        sockname(self.fd, libc::getpeername)
+    }
+    fn control_congestion(&mut self) -> IoResult<()> {
+        self.set_nodelay(false)
+    }
+    fn nodelay(&mut self) -> IoResult<()> {
+        self.set_nodelay(true)
+    }
+    fn keepalive(&mut self, delay_in_seconds: uint) -> IoResult<()> {
+        self.set_keepalive(Some(delay_in_seconds))
+    }
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_RCVBUF,
+                   bytes as libc::c_int)
+    }
+    fn set_send_buffer_size(&mut self, bytes: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_SNDBUF,
+                   bytes as libc::c_int)
+    }
+    fn close_write(&mut self) -> IoResult<()> {
+        shutdown(self.fd, libc::SHUT_WR)
+    }
+    fn close_read(&mut self) -> IoResult<()> {
+        shutdown(self.fd, libc::SHUT_RD)
+    }
+    fn poller(&mut self) -> IoResult<~rtio::RtioPoll> {
+        poll::Poller::new(self.fd).map(|p| ~p as ~rtio::RtioPoll)
+    }
+    fn letdie(&mut self) -> IoResult<()> {
+        self.set_keepalive(None)
+    }
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+        self.write_timeout = timeout;
+    }
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+    }
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.write_timeout = timeout;
+    }
+}
+
+impl rtio::RtioSocket for TcpStream {
+    fn socket_name(&mut self) -> IoResult<ip::SocketAddr> {
+        sockname(self.fd, libc::getsockname)
+    }
+}
+
+impl Drop for TcpStream {
+    fn drop(&mut self) { unsafe { close(self.fd); } }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TCP listeners
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct TcpListener {
+    priv fd: sock_t,
+}
+
+impl TcpListener {
+    pub fn bind(addr: ip::SocketAddr,
+                opts: BindOptions) -> IoResult<TcpListener> {
+        socket(addr, libc::SOCK_STREAM).and_then(|fd| {
+            let ret = TcpListener { fd: fd };
+
+            // On platforms with Berkeley-derived sockets, this allows a socket
+            // to be quickly rebound without waiting for the OS to clean up the
+            // previous one (libuv does the same for its listeners).
+            let opts = BindOptions {
+                reuse_address: opts.reuse_address || cfg!(unix),
+                .. opts
+            };
+            set_bind_options(fd, addr, opts).and_then(|()| {
+                bind(fd, addr).map(|()| ret)
+            })
+        })
+    }
+
+    pub fn fd(&self) -> sock_t { self.fd }
+
+    pub fn native_listen(self, backlog: int) -> IoResult<TcpAcceptor> {
+        match unsafe { libc::listen(self.fd, backlog as libc::c_int) } {
+            -1 => Err(super::last_error()),
+            _ => Ok(TcpAcceptor { listener: self, timeout: None })
+        }
+    }
+}
+
+impl rtio::RtioTcpListener for TcpListener {
+    fn listen(~self) -> IoResult<~rtio::RtioTcpAcceptor> {
+        // XXX: the 128 backlog should be configurable
+        (*self).native_listen(128).map(|a| ~a as ~rtio::RtioTcpAcceptor)
+    }
+}
+
+impl rtio::RtioSocket for TcpListener {
+    fn socket_name(&mut self) -> IoResult<ip::SocketAddr> {
+        sockname(self.fd, libc::getsockname)
+    }
+}
+
+impl Drop for TcpListener {
+    fn drop(&mut self) { unsafe { close(self.fd); } }
+}
+
+pub struct TcpAcceptor {
+    priv listener: TcpListener,
+    priv timeout: Option<u64>,
+}
+
+impl TcpAcceptor {
+    pub fn fd(&self) -> sock_t { self.listener.fd }
+
+    pub fn native_accept(&mut self) -> IoResult<TcpStream> {
+        match await(self.fd(), self.timeout, Readable) {
+            Ok(()) => {}
+            Err(e) => return Err(e),
+        }
+        unsafe {
+            let mut storage: libc::sockaddr_storage = intrinsics::init();
+            let storagep = &mut storage as *mut libc::sockaddr_storage;
+            let size = mem::size_of::<libc::sockaddr_storage>();
+            let mut size = size as libc::socklen_t;
+            let fd = retry(|| {
+                libc::accept(self.fd(),
+                             storagep as *mut libc::sockaddr,
+                             &mut size as *mut libc::socklen_t) as i64
+            }) as sock_t;
+            if fd == -1 as sock_t {
+                Err(super::last_error())
+            } else {
+                Ok(TcpStream::new(fd))
+            }
+        }
+    }
+}
+
+impl rtio::RtioSocket for TcpAcceptor {
+    fn socket_name(&mut self) -> IoResult<ip::SocketAddr> {
+        sockname(self.fd(), libc::getsockname)
+    }
+}
+
+impl rtio::RtioTcpAcceptor for TcpAcceptor {
+    fn accept(&mut self) -> IoResult<~rtio::RtioTcpStream> {
+        self.native_accept().map(|s| ~s as ~rtio::RtioTcpStream)
+    }
+
+    // Simultaneous accepts are a libuv optimization for windows; blocking
+    // accepts on a native thread have nothing to toggle.
+    fn accept_simultaneously(&mut self) -> IoResult<()> { Ok(()) }
+    fn dont_accept_simultaneously(&mut self) -> IoResult<()> { Ok(()) }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.timeout = timeout;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// UDP
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct UdpSocket {
+    priv fd: sock_t,
+    priv read_timeout: Option<u64>,
+    priv write_timeout: Option<u64>,
+}
+
+impl UdpSocket {
+    pub fn bind(addr: ip::SocketAddr,
+                opts: BindOptions) -> IoResult<UdpSocket> {
+        socket(addr, libc::SOCK_DGRAM).and_then(|fd| {
+            let ret = UdpSocket {
+                fd: fd,
+                read_timeout: None,
+                write_timeout: None,
+            };
+            set_bind_options(fd, addr, opts).and_then(|()| {
+                bind(fd, addr).map(|()| ret)
+            })
+        })
+    }
+
+    pub fn fd(&self) -> sock_t { self.fd }
+
+    pub fn set_broadcast(&mut self, on: bool) -> IoResult<()> {
+        setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_BROADCAST,
+                   on as libc::c_int)
+    }
+
+    pub fn set_multicast_loop(&mut self, on: bool) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_IP, libc::IP_MULTICAST_LOOP,
+                   on as libc::c_int)
+    }
+
+    pub fn set_membership(&mut self, addr: ip::IpAddr,
+                          interface: Option<ip::IpAddr>,
+                          opt: libc::c_int) -> IoResult<()> {
+        match (ip_to_inaddr(addr), interface) {
+            (InAddr(addr), None) => {
+                let mreq = libc::ip_mreq {
+                    imr_multiaddr: addr,
+                    // interface == INADDR_ANY
+                    imr_interface: libc::in_addr { s_addr: 0x0 },
+                };
+                setsockopt(self.fd, libc::IPPROTO_IP, opt, mreq)
+            }
+            (InAddr(addr), Some(interface)) => {
+                match ip_to_inaddr(interface) {
+                    InAddr(interface) => {
+                        let mreq = libc::ip_mreq {
+                            imr_multiaddr: addr,
+                            imr_interface: interface,
+                        };
+                        setsockopt(self.fd, libc::IPPROTO_IP, opt, mreq)
+                    }
+                    In6Addr(*) => Err(ipv6_interface_error()),
+                }
+            }
+            (In6Addr(addr), None) => {
+                let mreq = libc::ip6_mreq {
+                    ipv6mr_multiaddr: addr,
+                    ipv6mr_interface: 0,
+                };
+                setsockopt(self.fd, libc::IPPROTO_IPV6, opt, mreq)
+            }
+            (In6Addr(*), Some(*)) => Err(ipv6_interface_error()),
+        }
+    }
+}
+
+// IPv6 selects interfaces by index rather than by address, so only IPv4
+// interfaces can be named.
+fn ipv6_interface_error() -> io::IoError {
+    io::IoError {
+        kind: io::OtherIoError,
+        desc: "multicast interfaces must be IPv4 addresses",
+        detail: None,
+    }
+}
+
+impl rtio::RtioSocket for UdpSocket {
+    fn socket_name(&mut self) -> IoResult<ip::SocketAddr> {
+        sockname(self.fd(), libc::getsockname)
+    }
+}
+
+impl rtio::RtioUdpSocket for UdpSocket {
+    fn recvfrom(&mut self, buf: &mut [u8]) -> IoResult<(uint, ip::SocketAddr)> {
+        let flags = dontwait(self.read_timeout);
+        unsafe {
+            let mut storage: libc::sockaddr_storage = intrinsics::init();
+            let storagep = &mut storage as *mut libc::sockaddr_storage;
+            let mut addrlen: libc::socklen_t;
+            let mut ret;
+            loop {
+                match await(self.fd, self.read_timeout, Readable) {
+                    Ok(()) => {}
+                    Err(e) => return Err(e),
+                }
+                addrlen = mem::size_of::<libc::sockaddr_storage>() as
+                                libc::socklen_t;
+                ret = retry(|| {
+                    libc::recvfrom(self.fd,
+                                   vec::raw::to_ptr(buf) as *mut libc::c_void,
+                                   buf.len() as wrlen,
+                                   flags,
+                                   storagep as *mut libc::sockaddr,
+                                   &mut addrlen) as i64
+                });
+                // Another thread may have received the datagram in the
+                // meantime
+                if ret != -1 || !would_block() { break }
+            }
+            if ret < 0 { return Err(super::last_error()) }
+            sockaddr_to_addr(&storage, addrlen as uint).and_then(|addr| {
+                Ok((ret as uint, addr))
+            })
+        }
+    }
+    fn sendto(&mut self, buf: &[u8], dst: ip::SocketAddr) -> IoResult<()> {
+        match await(self.fd, self.write_timeout, Writable) {
+            Ok(()) => {}
+            Err(e) => return Err(e),
+        }
+        let (dst, len) = addr_to_sockaddr(dst);
+        let dstp = &dst as *libc::sockaddr_storage;
+        unsafe {
+            let ret = retry(|| {
+                libc::sendto(self.fd,
+                             vec::raw::to_ptr(buf) as *libc::c_void,
+                             buf.len() as wrlen,
+                             0,
+                             dstp as *libc::sockaddr,
+                             len as libc::socklen_t) as i64
+            });
+            match ret {
+                -1 => Err(super::last_error()),
+                n if n as uint != buf.len() => {
+                    Err(io::IoError {
+                        kind: io::OtherIoError,
+                        desc: "couldn't send entire packet at once",
+                        detail: None,
+                    })
+                }
+                _ => Ok(())
+            }
+        }
+    }
+
+    fn join_multicast(&mut self, multi: ip::IpAddr,
+                      interface: Option<ip::IpAddr>) -> IoResult<()> {
+        match multi {
+            ip::Ipv4Addr(*) => {
+                self.set_membership(multi, interface, libc::IP_ADD_MEMBERSHIP)
+            }
+            ip::Ipv6Addr(*) => {
+                self.set_membership(multi, interface, libc::IPV6_ADD_MEMBERSHIP)
+            }
+        }
+    }
+    fn leave_multicast(&mut self, multi: ip::IpAddr,
+                       interface: Option<ip::IpAddr>) -> IoResult<()> {
+        match multi {
+            ip::Ipv4Addr(*) => {
+                self.set_membership(multi, interface, libc::IP_DROP_MEMBERSHIP)
+            }
+            ip::Ipv6Addr(*) => {
+                self.set_membership(multi, interface,
+                                    libc::IPV6_DROP_MEMBERSHIP)
+            }
+        }
+    }
+    fn multicast_interface(&mut self, interface: ip::IpAddr) -> IoResult<()> {
+        match ip_to_inaddr(interface) {
+            InAddr(interface) => {
+                setsockopt(self.fd, libc::IPPROTO_IP, libc::IP_MULTICAST_IF,
+                           interface)
+            }
+            In6Addr(*) => Err(ipv6_interface_error()),
+        }
+    }
+
+    fn loop_multicast_locally(&mut self) -> IoResult<()> {
+        self.set_multicast_loop(true)
+    }
+    fn dont_loop_multicast_locally(&mut self) -> IoResult<()> {
+        self.set_multicast_loop(false)
+    }
+
+    fn multicast_time_to_live(&mut self, ttl: int) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_IP, libc::IP_MULTICAST_TTL,
+                   ttl as libc::c_int)
+    }
+    fn time_to_live(&mut self, ttl: int) -> IoResult<()> {
+        setsockopt(self.fd, libc::IPPROTO_IP, libc::IP_TTL, ttl as libc::c_int)
+    }
+
+    fn hear_broadcasts(&mut self) -> IoResult<()> {
+        self.set_broadcast(true)
+    }
+    fn ignore_broadcasts(&mut self) -> IoResult<()> {
+        self.set_broadcast(false)
+    }
+
+    fn poller(&mut self) -> IoResult<~rtio::RtioPoll> {
+        poll::Poller::new(self.fd).map(|p| ~p as ~rtio::RtioPoll)
+    }
+
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_RCVBUF,
+                   bytes as libc::c_int)
+    }
+    fn set_send_buffer_size(&mut self, bytes: uint) -> IoResult<()> {
+        setsockopt(self.fd, libc::SOL_SOCKET, libc::SO_SNDBUF,
+                   bytes as libc::c_int)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+        self.write_timeout = timeout;
+    }
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+    }
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.write_timeout = timeout;
+    }
+}
+
+impl Drop for UdpSocket {
+    fn drop(&mut self) { unsafe { close(self.fd); } }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Unix domain sockets
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(unix)]
+fn addr_to_sockaddr_un(addr: &CString) -> IoResult<(libc::sockaddr_storage,
+                                                   uint)> {
+    // the sun_path length is limited to SUN_LEN (with null)
+    let mut storage: libc::sockaddr_storage = unsafe { intrinsics::init() };
+    let s: &mut libc::sockaddr_un = unsafe { cast::transmute(&mut storage) };
+    let len = addr.len();
+    if len > s.sun_path.len() - 1 {
+        return Err(io::IoError {
+            kind: io::OtherIoError,
+            desc: "path must be smaller than SUN_LEN",
+            detail: None,
+        })
+    }
+    s.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    for (slot, value) in s.sun_path.mut_iter().zip(addr.iter()) {
+        *slot = value;
+    }
+
+    // count the null terminator
+    let len = mem::size_of::<libc::sockaddr_un>() - s.sun_path.len() + len + 1;
+    return Ok((storage, len));
+}
+
+#[cfg(unix)]
+fn unix_socket() -> IoResult<sock_t> {
+    match unsafe { libc::socket(libc::AF_UNIX, libc::SOCK_STREAM, 0) } {
+        -1 => Err(super::last_error()),
+        fd => Ok(fd)
+    }
+}
+
+pub struct UnixStream {
+    priv fd: sock_t,
+    priv read_timeout: Option<u64>,
+    priv write_timeout: Option<u64>,
+}
+
+impl UnixStream {
+    fn new(fd: sock_t) -> UnixStream {
+        UnixStream { fd: fd, read_timeout: None, write_timeout: None }
+    }
+
+    #[cfg(unix)]
+    pub fn connect(addr: &CString,
+                   timeout: Option<u64>) -> IoResult<UnixStream> {
+        addr_to_sockaddr_un(addr).and_then(|(addr, len)| {
+            unix_socket().and_then(|fd| {
+                let addrp = &addr as *libc::sockaddr_storage;
+                let ret = UnixStream::new(fd);
+                connect(fd, addrp as *libc::sockaddr, len as libc::socklen_t,
+                        timeout).map(|()| ret)
+            })
+        })
+    }
+
+    // XXX: windows has named pipes rather than unix sockets, and those are
+    //      not implemented natively yet.
+    #[cfg(windows)]
+    pub fn connect(_addr: &CString,
+                   _timeout: Option<u64>) -> IoResult<UnixStream> {
+        Err(super::unimpl())
+    }
+
+    pub fn fd(&self) -> sock_t { self.fd }
+}
+
+impl rtio::RtioPipe for UnixStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<uint> {
+        read(self.fd, buf, self.read_timeout)
+    }
+    fn write(&mut self, buf: &[u8]) -> IoResult<()> {
+        write(self.fd, buf, self.write_timeout)
+    }
+    fn close_write(&mut self) -> IoResult<()> {
+        shutdown(self.fd, libc::SHUT_WR)
+    }
+    fn close_read(&mut self) -> IoResult<()> {
+        shutdown(self.fd, libc::SHUT_RD)
+    }
+    fn poller(&mut self) -> IoResult<~rtio::RtioPoll> {
+        poll::Poller::new(self.fd).map(|p| ~p as ~rtio::RtioPoll)
+    }
+
+    fn set_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+        self.write_timeout = timeout;
+    }
+    fn set_read_timeout(&mut self, timeout: Option<u64>) {
+        self.read_timeout = timeout;
+    }
+    fn set_write_timeout(&mut self, timeout: Option<u64>) {
+        self.write_timeout = timeout;
+    }
+}
+
+impl Drop for UnixStream {
+    fn drop(&mut self) { unsafe { close(self.fd); } }
+}
+
+pub struct UnixListener {
+    priv fd: sock_t,
+}
+
+impl UnixListener {
+    #[cfg(unix)]
+    pub fn bind(addr: &CString) -> IoResult<UnixListener> {
+        addr_to_sockaddr_un(addr).and_then(|(addr, len)| {
+            unix_socket().and_then(|fd| {
+                let addrp = &addr as *libc::sockaddr_storage;
+                let ret = UnixListener { fd: fd };
+                match unsafe {
+                    libc::bind(fd, addrp as *libc::sockaddr,
+                               len as libc::socklen_t)
+                } {
+                    -1 => Err(super::last_error()),
+                    _ => Ok(ret)
+                }
+            })
+        })
+    }
+
+    #[cfg(windows)]
+    pub fn bind(_addr: &CString) -> IoResult<UnixListener> {
+        Err(super::unimpl())
+    }
+
+    pub fn fd(&self) -> sock_t { self.fd }
+
+    pub fn native_listen(self, backlog: int) -> IoResult<UnixAcceptor> {
+        match unsafe { libc::listen(self.fd, backlog as libc::c_int) } {
+            -1 => Err(super::last_error()),
+            _ => Ok(UnixAcceptor { listener: self })
+        }
+    }
+}
+
+impl rtio::RtioUnixListener for UnixListener {
+    fn listen(~self) -> IoResult<~rtio::RtioUnixAcceptor> {
+        // XXX: the 128 backlog should be configurable
+        (*self).native_listen(128).map(|a| ~a as ~rtio::RtioUnixAcceptor)
+    }
+}
+
+impl Drop for UnixListener {
+    fn drop(&mut self) { unsafe { close(self.fd); } }
+}
+
+pub struct UnixAcceptor {
+    priv listener: UnixListener,
+}
+
+impl UnixAcceptor {
+    pub fn fd(&self) -> sock_t { self.listener.fd }
+
+    pub fn native_accept(&mut self) -> IoResult<UnixStream> {
+        unsafe {
+            let mut storage: libc::sockaddr_storage = intrinsics::init();
+            let storagep = &mut storage as *mut libc::sockaddr_storage;
+            let size = mem::size_of::<libc::sockaddr_storage>();
+            let mut size = size as libc::socklen_t;
+            let fd = retry(|| {
+                libc::accept(self.fd(),
+                             storagep as *mut libc::sockaddr,
+                             &mut size as *mut libc::socklen_t) as i64
+            }) as sock_t;
+            if fd == -1 as sock_t {
+                Err(super::last_error())
+            } else {
+                Ok(UnixStream::new(fd))
+            }
+        }
+    }
+}
+
+impl rtio::RtioUnixAcceptor for UnixAcceptor {
+    fn accept(&mut self) -> IoResult<~rtio::RtioPipe> {
+        self.native_accept().map(|s| ~s as ~rtio::RtioPipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TcpStream, TcpListener, UdpSocket, UnixStream, UnixListener};
+    use c_str::ToCStr;
+    use io::fs;
+    use io::net::BindOptions;
+    use io::net::ip::{Ipv4Addr, SocketAddr};
+    use io;
+    use prelude::*;
+    use rt::rtio::{RtioSocket, RtioTcpStream, RtioTcpAcceptor, RtioUdpSocket,
+                   RtioPipe};
+    use rt::test::{next_test_ip4, next_test_ip6, next_test_unix};
+    use rt::thread::Thread;
+
+    #[test]
+    fn connect_error() {
+        let addr = SocketAddr { ip: Ipv4Addr(0, 0, 0, 0), port: 1 };
+        match TcpStream::connect(addr, None) {
+            Ok(*) => fail!(),
+            Err(e) => {
+                if cfg!(unix) {
+                    assert_eq!(e.kind, io::ConnectionRefused);
+                }
+            }
+        }
+    }
+
+    fn smoke_test(addr: SocketAddr) {
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        let t = do Thread::start {
+            let mut stream = TcpStream::connect(addr, None).unwrap();
+            stream.write([99]).unwrap();
+        };
+        let mut stream = acceptor.native_accept().unwrap();
+        let mut buf = [0];
+        match stream.read(buf) {
+            Ok(1) => assert_eq!(buf[0], 99),
+            r => fail!("invalid read: {:?}", r),
+        }
+        assert_eq!(stream.read(buf).unwrap_err().kind, io::EndOfFile);
+        t.join();
+    }
+
+    #[test]
+    fn smoke_test_ip4() { smoke_test(next_test_ip4()) }
+
+    #[test]
+    fn smoke_test_ip6() { smoke_test(next_test_ip6()) }
+
+    #[test]
+    fn close_write() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        let t = do Thread::start {
+            let mut stream = TcpStream::connect(addr, None).unwrap();
+            stream.write([1]).unwrap();
+            stream.close_write().unwrap();
+            let mut buf = [0];
+            assert_eq!(stream.read(buf), Ok(1));
+            assert_eq!(buf[0], 2);
+        };
+        let mut stream = acceptor.native_accept().unwrap();
+        let mut buf = [0];
+        assert_eq!(stream.read(buf), Ok(1));
+        assert_eq!(stream.read(buf).unwrap_err().kind, io::EndOfFile);
+        stream.write([2]).unwrap();
+        t.join();
+    }
+
+    #[test]
+    fn socket_and_peer_name() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        assert_eq!(acceptor.socket_name().unwrap(), addr);
+        let t = do Thread::start {
+            let mut stream = TcpStream::connect(addr, None).unwrap();
+            assert_eq!(stream.peer_name().unwrap(), addr);
+        };
+        acceptor.accept().unwrap();
+        t.join();
+    }
+
+    #[test]
+    fn connect_with_timeout() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        let t = do Thread::start {
+            TcpStream::connect(addr, Some(1000)).unwrap();
+        };
+        acceptor.native_accept().unwrap();
+        t.join();
+    }
+
+    #[test]
+    fn accept_timeout() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        acceptor.set_timeout(Some(10));
+        assert_eq!(acceptor.native_accept().unwrap_err().kind, io::TimedOut);
+    }
+
+    #[test]
+    fn read_timeout() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        let t = do Thread::start {
+            let mut stream = TcpStream::connect(addr, None).unwrap();
+            // don't send anything until the server has timed out once
+            let mut buf = [0];
+            stream.read(buf).unwrap();
+            stream.write([2]).unwrap();
+        };
+        let mut stream = acceptor.native_accept().unwrap();
+        let mut buf = [0];
+        stream.set_read_timeout(Some(10));
+        assert_eq!(stream.read(buf).unwrap_err().kind, io::TimedOut);
+        stream.write([1]).unwrap();
+        stream.set_read_timeout(None);
+        match stream.read(buf) {
+            Ok(1) => assert_eq!(buf[0], 2),
+            r => fail!("invalid read: {:?}", r),
+        }
+        t.join();
+    }
+
+    #[test]
+    fn udp_read_timeout() {
+        let mut socket = UdpSocket::bind(next_test_ip4(), BindOptions::new()).unwrap();
+        socket.set_read_timeout(Some(10));
+        let mut buf = [0];
+        assert_eq!(socket.recvfrom(buf).unwrap_err().kind, io::TimedOut);
+    }
+
+    #[test]
+    fn udp_smoke_test() {
+        let server_ip = next_test_ip4();
+        let client_ip = next_test_ip4();
+        let mut server = UdpSocket::bind(server_ip, BindOptions::new()).unwrap();
+        let mut client = UdpSocket::bind(client_ip, BindOptions::new()).unwrap();
+        client.sendto([99], server_ip).unwrap();
+        let mut buf = [0];
+        match server.recvfrom(buf) {
+            Ok((1, src)) => {
+                assert_eq!(buf[0], 99);
+                assert_eq!(src, client_ip);
+            }
+            r => fail!("invalid recvfrom: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn buffer_sizes() {
+        let addr = next_test_ip4();
+        let listener = TcpListener::bind(addr, BindOptions::new()).unwrap();
+        let mut acceptor = listener.native_listen(128).unwrap();
+        let t = do Thread::start {
+            let mut stream = TcpStream::connect(addr, None).unwrap();
+            stream.set_recv_buffer_size(4096).unwrap();
+            stream.set_send_buffer_size(4096).unwrap();
+        };
+        acceptor.native_accept().unwrap();
+        t.join();
+
+        let mut socket = UdpSocket::bind(next_test_ip4(),
+                                         BindOptions::new()).unwrap();
+        socket.set_recv_buffer_size(4096).unwrap();
+        socket.set_send_buffer_size(4096).unwrap();
+    }
+
+    #[test]
+    fn udp_reuse_address() {
+        let addr = next_test_ip4();
+        let opts = BindOptions { reuse_address: true, .. BindOptions::new() };
+        let _a = UdpSocket::bind(addr, opts).unwrap();
+        let _b = UdpSocket::bind(addr, opts).unwrap();
+    }
+
+    #[test]
+    fn ipv6_only() {
+        let opts = BindOptions { ipv6_only: true, .. BindOptions::new() };
+        TcpListener::bind(next_test_ip6(), opts).unwrap();
+        UdpSocket::bind(next_test_ip6(), opts).unwrap();
+    }
+
+    #[test]
+    fn udp_socket_options() {
+        let mut socket = UdpSocket::bind(next_test_ip4(),
+                                         BindOptions::new()).unwrap();
+        socket.loop_multicast_locally().unwrap();
+        socket.dont_loop_multicast_locally().unwrap();
+        socket.multicast_time_to_live(1).unwrap();
+        socket.time_to_live(64).unwrap();
+        socket.hear_broadcasts().unwrap();
+        socket.ignore_broadcasts().unwrap();
+    }
+
+    #[test] #[ignore(cfg(windows))]
+    fn unix_smoke_test() {
+        let path = next_test_unix();
+        let path2 = path.clone();
+        let mut acceptor = UnixListener::bind(&path.to_c_str()).unwrap()
+                                                               .native_listen(128)
+                                                               .unwrap();
+        let t = do Thread::start {
+            let mut stream = UnixStream::connect(&path2.to_c_str(), None).unwrap();
+            stream.write([1, 2]).unwrap();
+        };
+        let mut stream = acceptor.native_accept().unwrap();
+        let mut buf = [0, 0];
+        match stream.read(buf) {
+            Ok(2) => assert_eq!(buf, [1, 2]),
+            r => fail!("invalid read: {:?}", r),
+        }
+        t.join();
+        fs::unlink(&path);
+    }
+
+    #[test] #[ignore(cfg(windows))]
+    fn unix_path_too_long() {
+        let path = "a".repeat(200);
+        assert!(UnixListener::bind(&path.to_c_str()).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Delivery of notifications from native helper threads
+//!
+//! Native timers, readiness notifications and signal listeners do their
+//! waiting on helper OS threads, but the ports that they fill in may very well
+//! belong to green tasks. A channel may only wake up a blocked green task from
+//! inside of a scheduler, so a `Notifier` sets up a remote callback on the
+//! event loop of the scheduler it was created on (if there is one), and each
+//! notification is queued up for that callback to run.
+
+use ops::Drop;
+use option::{Option, Some, None};
+use rt::local::Local;
+use rt::message_queue::MessageQueue;
+use rt::rtio::{Callback, RemoteCallback};
+use rt::sched::Scheduler;
+
+pub struct Notifier {
+    priv remote: Option<~RemoteCallback>,
+    priv queue: MessageQueue<proc()>,
+}
+
+// Runs on the scheduler's event loop, where the scheduler is at hand for the
+// channels to wake their receivers up with.
+struct Delivery {
+    queue: MessageQueue<proc()>,
+}
+
+impl Callback for Delivery {
+    fn call(&mut self) {
+        loop {
+            match self.queue.pop() {
+                Some(f) => f(),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Notifier {
+    /// Creates a notifier which will deliver notifications to the scheduler of
+    /// the calling task. If there is no local scheduler, notifications will be
+    /// run directly on the thread which delivers them.
+    pub fn new() -> Notifier {
+        let queue = MessageQueue::new();
+        let remote = unsafe {
+            let sched: Option<*mut Scheduler> = Local::try_unsafe_borrow();
+            sched.map(|sched| {
+                let delivery = ~Delivery { queue: queue.clone() };
+                (*sched).event_loop.remote_callback(delivery as ~Callback)
+            })
+        };
+        Notifier { remote: remote, queue: queue }
+    }
+
+    /// Runs the given procedure on the scheduler that this notifier was
+    /// created on. The procedure is expected to do nothing more than send on a
+    /// channel, and it should use a deferred send so that it is also safe to run
+    /// on a thread without a scheduler.
+    pub fn notify(&mut self, f: proc()) {
+        match self.remote {
+            Some(ref mut remote) => {
+                self.queue.push(f);
+                remote.fire();
+            }
+            None => f(),
+        }
+    }
+}
+
+impl Drop for Notifier {
+    // The remote callback is what keeps the scheduler's event loop alive while
+    // a helper thread is waiting, so this just needs to let go of it. Dropping
+    // it runs the callback one last time, which delivers anything still queued.
+    fn drop(&mut self) { self.remote.take(); }
+}
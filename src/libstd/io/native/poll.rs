@@ -0,0 +1,243 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Native readiness notifications
+//!
+//! The requests of a `Poller` are handed to the native helper thread (see the
+//! `helper` module), which waits for all of the outstanding ones with a single
+//! call to poll(2). Issuing a new request (or dropping the poller) cancels the
+//! previous one. `wait_fds` instead waits for several descriptors at once on
+//! the calling thread, which is how `std::io::poll` waits on native pollers.
+//!
+//! Windows is not currently supported.
+
+use cell::Cell;
+use comm::{oneshot, PortOne, SendDeferred};
+use io::poll::Readiness;
+use libc::c_int;
+use option::{Option, Some, None};
+use rt::rtio;
+use super::IoResult;
+use super::helper;
+use super::net::sock_t;
+use super::notify::Notifier;
+
+#[cfg(unix)] pub use self::imp::{poll, events, readiness};
+
+pub struct Poller {
+    priv fd: sock_t,
+    priv pending: Option<helper::Registration>,
+}
+
+impl Poller {
+    /// Creates a poller for `fd`, which remains owned by the caller.
+    pub fn new(fd: sock_t) -> IoResult<Poller> {
+        imp::check().map(|()| Poller { fd: fd, pending: None })
+    }
+}
+
+impl rtio::RtioPoll for Poller {
+    fn oneshot(&mut self, interest: Readiness,
+               timeout: Option<u64>) -> PortOne<Readiness> {
+        let (port, chan) = oneshot();
+        self.pending = None;
+        let chan = Cell::new(chan);
+        let notifier = Cell::new(Notifier::new());
+        let fd = Some((self.fd as c_int, interest));
+        self.pending = Some(helper::register(fd, timeout, proc(ready) {
+            let chan = Cell::new(chan.take());
+            let mut notifier = notifier.take();
+            notifier.notify(proc() { chan.take().try_send_deferred(ready); });
+        }));
+        port
+    }
+
+    fn cancel(&mut self) { self.pending = None; }
+
+    fn fd(&self) -> Option<c_int> { Some(self.fd as c_int) }
+}
+
+/// Blocks the calling thread until one of `fds` meets one of the conditions
+/// of `interest`, returning its index along with the conditions which it
+/// meets, or `None` if `timeout_ms` milliseconds pass first.
+pub fn wait_fds(fds: &[c_int], interest: Readiness,
+                timeout_ms: Option<u64>) -> Option<(uint, Readiness)> {
+    imp::wait_fds(fds, interest, timeout_ms)
+}
+
+#[cfg(unix)]
+mod imp {
+    use io::poll::Readiness;
+    use iter::Iterator;
+    use libc;
+    use option::{Option, Some, None};
+    use os;
+    use result::Ok;
+    use super::super::IoResult;
+    use vec::{ImmutableVector, MutableVector};
+
+    // poll(2) takes a c_int timeout, so long waits are split up into chunks
+    // which comfortably fit inside of one.
+    static MAX_CHUNK: u64 = 1 << 30;
+
+    pub fn check() -> IoResult<()> { Ok(()) }
+
+    pub fn events(interest: Readiness) -> libc::c_short {
+        let mut events = 0;
+        if interest.readable { events |= libc::POLLIN; }
+        if interest.writable { events |= libc::POLLOUT; }
+        events
+    }
+
+    /// Translates the `revents` of a descriptor polled for `interest`, or
+    /// returns `None` if nothing happened to it.
+    pub fn readiness(revents: libc::c_short,
+                     interest: Readiness) -> Option<Readiness> {
+        if revents == 0 {
+            None
+        // Errors and hangups are reported as whatever was asked for (as are
+        // failures of poll itself), so that the next operation on the
+        // descriptor sees them.
+        } else if revents & (libc::POLLERR | libc::POLLHUP |
+                             libc::POLLNVAL) != 0 {
+            Some(interest)
+        } else {
+            Some(Readiness {
+                readable: revents & libc::POLLIN != 0,
+                writable: revents & libc::POLLOUT != 0,
+            })
+        }
+    }
+
+    /// Polls `pfds` for at most `timeout` milliseconds, returning false if
+    /// none of them became ready in time.
+    pub fn poll(pfds: &mut [libc::pollfd], timeout: Option<u64>) -> bool {
+        let mut remaining = timeout;
+        loop {
+            let chunk = match remaining {
+                Some(ms) if ms > MAX_CHUNK => MAX_CHUNK,
+                Some(ms) => ms,
+                None => MAX_CHUNK,
+            };
+            match pfds.as_mut_buf(|p, len| unsafe {
+                libc::poll(p, len as libc::nfds_t, chunk as libc::c_int)
+            }) {
+                // An interrupted poll is simply retried with the same chunk.
+                // This errs on the side of waiting a little too long, never
+                // too short.
+                -1 if os::errno() as int == libc::EINTR as int => {}
+                -1 => {
+                    for pfd in pfds.mut_iter() { pfd.revents = libc::POLLERR; }
+                    return true;
+                }
+                0 => match remaining {
+                    Some(ms) if ms == chunk => return false,
+                    Some(ms) => remaining = Some(ms - chunk),
+                    None => {}
+                },
+                _ => return true,
+            }
+        }
+    }
+
+    pub fn wait_fds(fds: &[libc::c_int], interest: Readiness,
+                    timeout: Option<u64>) -> Option<(uint, Readiness)> {
+        let mut pfds = fds.map(|&fd| {
+            libc::pollfd { fd: fd, events: events(interest), revents: 0 }
+        });
+        if !poll(pfds, timeout) {
+            return None;
+        }
+        pfds.iter().enumerate().filter_map(|(i, pfd)| {
+            readiness(pfd.revents, interest).map(|r| (i, r))
+        }).next()
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use io::poll::Readiness;
+    use libc;
+    use option::{Option, None};
+    use result::Err;
+    use super::super::{IoResult, unimpl};
+
+    pub fn check() -> IoResult<()> { Err(unimpl()) }
+
+    // No native pollers can be created, so there's never anything to wait for
+    pub fn wait_fds(_: &[libc::c_int], _: Readiness,
+                    _: Option<u64>) -> Option<(uint, Readiness)> { None }
+}
+
+#[cfg(test, unix)]
+mod test {
+    use io::poll::{READABLE, WRITABLE, NOT_READY};
+    use libc;
+    use os;
+    use prelude::*;
+    use rt::rtio::RtioPoll;
+    use super::{Poller, wait_fds};
+
+    #[test]
+    fn pipe() {
+        let os::Pipe { input, out } = os::pipe();
+        let mut reader = Poller::new(input).unwrap();
+        let mut writer = Poller::new(out).unwrap();
+
+        assert_eq!(reader.oneshot(READABLE, Some(10)).recv(), NOT_READY);
+        assert!(writer.oneshot(WRITABLE, None).recv().writable);
+        unsafe {
+            [1u8].as_imm_buf(|p, len| {
+                libc::write(out, p as *libc::c_void, len as libc::size_t)
+            });
+        }
+        assert!(reader.oneshot(READABLE, Some(10000)).recv().readable);
+        unsafe {
+            libc::close(input);
+            libc::close(out);
+        }
+    }
+
+    #[test]
+    fn cancelled_by_next_request() {
+        let os::Pipe { input, out } = os::pipe();
+        let mut reader = Poller::new(input).unwrap();
+        let port1 = reader.oneshot(READABLE, None);
+        let port2 = reader.oneshot(READABLE, Some(1));
+        assert_eq!(port2.recv(), NOT_READY);
+        assert!(port1.try_recv().is_none());
+        unsafe {
+            libc::close(input);
+            libc::close(out);
+        }
+    }
+
+    #[test]
+    fn wait_fds_finds_the_ready_one() {
+        let a = os::pipe();
+        let b = os::pipe();
+        let fds = [a.input, b.input];
+        assert!(wait_fds(fds, READABLE, Some(10)).is_none());
+        unsafe {
+            [1u8].as_imm_buf(|p, len| {
+                libc::write(b.out, p as *libc::c_void, len as libc::size_t)
+            });
+        }
+        match wait_fds(fds, READABLE, None) {
+            Some((1, ready)) => assert!(ready.readable),
+            r => fail!("{:?}", r),
+        }
+        unsafe {
+            for &fd in [a.input, a.out, b.input, b.out].iter() {
+                libc::close(fd);
+            }
+        }
+    }
+}
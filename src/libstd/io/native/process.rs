@@ -36,8 +36,8 @@ pub struct Process {
     /// pid being re-used until the handle is closed.
     priv handle: *(),
 
-    /// None until finish() is called.
-    priv exit_code: Option<int>,
+    /// None until the process has been waited on and found to have exited.
+    priv exit_code: Option<p::ProcessExit>,
 }
 
 impl Process {
@@ -63,19 +63,22 @@ impl Process {
     pub fn spawn(config: p::ProcessConfig)
         -> Result<(Process, ~[Option<file::FileDesc>]), io::IoError>
     {
-        // right now we only handle stdin/stdout/stderr.
-        if config.io.len() > 3 {
+        // windows can only hand the standard streams to a child, and it has no
+        // notion of user and group ids.
+        if cfg!(windows) && (config.io.len() > 3 || config.uid.is_some() ||
+                             config.gid.is_some()) {
             return Err(super::unimpl());
         }
 
-        fn get_io(io: &[p::StdioContainer],
-                  ret: &mut ~[Option<file::FileDesc>],
-                  idx: uint) -> (Option<os::Pipe>, c_int) {
-            if idx >= io.len() { return (None, -1); }
+        // Returns the file descriptor that the child should get for `io`, and
+        // whether it was opened just for the child (in which case the parent
+        // closes it once the child has been spawned).
+        fn get_io(io: &p::StdioContainer,
+                  ret: &mut ~[Option<file::FileDesc>]) -> (c_int, bool) {
             ret.push(None);
-            match io[idx] {
-                p::Ignored => (None, -1),
-                p::InheritFd(fd) => (None, fd),
+            match *io {
+                p::Ignored => (open_null(), true),
+                p::InheritFd(fd) => (fd, false),
                 p::CreatePipe(readable, _writable) => {
                     let pipe = os::pipe();
                     let (theirs, ours) = if readable {
@@ -83,26 +86,30 @@ impl Process {
                     } else {
                         (pipe.out, pipe.input)
                     };
-                    ret[idx] = Some(file::FileDesc::new(ours, true));
-                    (Some(pipe), theirs)
+                    ret[ret.len() - 1] = Some(file::FileDesc::new(ours, true));
+                    (theirs, true)
                 }
             }
         }
 
         let mut ret_io = ~[];
-        let (in_pipe, in_fd) = get_io(config.io, &mut ret_io, 0);
-        let (out_pipe, out_fd) = get_io(config.io, &mut ret_io, 1);
-        let (err_pipe, err_fd) = get_io(config.io, &mut ret_io, 2);
+        let mut fds = ~[];
+        let mut opened = ~[];
+        for io in config.io.iter() {
+            let (fd, fresh) = get_io(io, &mut ret_io);
+            fds.push(fd);
+            if fresh { opened.push(fd); }
+        }
+        while fds.len() < 3 {
+            let fd = open_null();
+            fds.push(fd);
+            opened.push(fd);
+        }
 
-        let env = config.env.map(|a| a.to_owned());
-        let cwd = config.cwd.map(|a| Path::new(a));
-        let res = spawn_process_os(config.program, config.args, env,
-                                   cwd.as_ref(), in_fd, out_fd, err_fd);
+        let res = spawn_process_os(&config, fds);
 
         unsafe {
-            for pipe in in_pipe.iter() { libc::close(pipe.input); }
-            for pipe in out_pipe.iter() { libc::close(pipe.out); }
-            for pipe in err_pipe.iter() { libc::close(pipe.out); }
+            for &fd in opened.iter() { libc::close(fd); }
         }
 
         Ok((Process { pid: res.pid, handle: res.handle, exit_code: None }, ret_io))
@@ -113,15 +120,24 @@ impl rtio::RtioProcess for Process {
     fn id(&self) -> pid_t { self.pid }
 
     fn wait(&mut self) -> p::ProcessExit {
-        let code = match self.exit_code {
+        match self.exit_code {
             Some(code) => code,
             None => {
-                let code = waitpid(self.pid);
+                let code = waitpid(self.pid, true).unwrap();
                 self.exit_code = Some(code);
                 code
             }
-        };
-        return p::ExitStatus(code); // XXX: this is wrong
+        }
+    }
+
+    fn try_wait(&mut self) -> Option<p::ProcessExit> {
+        match self.exit_code {
+            Some(code) => Some(code),
+            None => {
+                self.exit_code = waitpid(self.pid, false);
+                self.exit_code
+            }
+        }
     }
 
     fn kill(&mut self, signum: int) -> Result<(), io::IoError> {
@@ -173,11 +189,23 @@ struct SpawnProcessResult {
     handle: *(),
 }
 
+// Opens the null device, which is what the child gets for ignored streams.
+fn open_null() -> c_int {
+    #[cfg(windows)] static NULL_DEVICE: &'static str = "NUL";
+    #[cfg(unix)] static NULL_DEVICE: &'static str = "/dev/null";
+
+    let fd = NULL_DEVICE.with_c_str(|p| unsafe {
+        libc::open(p, libc::O_RDWR, 0)
+    });
+    if fd == -1 {
+        fail!("failure opening {}: {}", NULL_DEVICE, os::last_os_error());
+    }
+    fd
+}
+
 #[cfg(windows)]
-fn spawn_process_os(prog: &str, args: &[~str],
-                    env: Option<~[(~str, ~str)]>,
-                    dir: Option<&Path>,
-                    in_fd: c_int, out_fd: c_int, err_fd: c_int) -> SpawnProcessResult {
+fn spawn_process_os(config: &p::ProcessConfig,
+                    fds: &[c_int]) -> SpawnProcessResult {
     use libc::types::os::arch::extra::{DWORD, HANDLE, STARTUPINFO};
     use libc::consts::os::extra::{
         TRUE, FALSE,
@@ -195,6 +223,14 @@ fn spawn_process_os(prog: &str, args: &[~str],
 
     use mem;
 
+    static DETACHED_PROCESS: DWORD = 0x00000008;
+
+    let (in_fd, out_fd, err_fd) = (fds[0], fds[1], fds[2]);
+    let env = config.env.map(|a| a.to_owned());
+    let cwd = config.cwd.map(|a| Path::new(a));
+    let dir = cwd.as_ref();
+    let flags = if config.detach {DETACHED_PROCESS} else {0};
+
     unsafe {
 
         let mut si = zeroed_startupinfo();
@@ -230,7 +266,7 @@ fn spawn_process_os(prog: &str, args: &[~str],
             fail!("failure in DuplicateHandle: {}", os::last_os_error());
         }
 
-        let cmd = make_command_line(prog, args);
+        let cmd = make_command_line(config.program, config.args);
         let mut pi = zeroed_process_information();
         let mut create_err = None;
 
@@ -239,7 +275,7 @@ fn spawn_process_os(prog: &str, args: &[~str],
                 cmd.with_c_str(|cmdp| {
                     let created = CreateProcessA(ptr::null(), cast::transmute(cmdp),
                                                  ptr::mut_null(), ptr::mut_null(), TRUE,
-                                                 0, envp, dirp, &mut si, &mut pi);
+                                                 flags, envp, dirp, &mut si, &mut pi);
                     if created == FALSE {
                         create_err = Some(os::last_os_error());
                     }
@@ -357,12 +393,15 @@ pub fn make_command_line(prog: &str, args: &[~str]) -> ~str {
 }
 
 #[cfg(unix)]
-fn spawn_process_os(prog: &str, args: &[~str],
-                    env: Option<~[(~str, ~str)]>,
-                    dir: Option<&Path>,
-                    in_fd: c_int, out_fd: c_int, err_fd: c_int) -> SpawnProcessResult {
+fn spawn_process_os(config: &p::ProcessConfig,
+                    fds: &[c_int]) -> SpawnProcessResult {
+    use libc::funcs::posix88::fcntl::fcntl;
     use libc::funcs::posix88::unistd::{fork, dup2, close, chdir, execvp};
+    use libc::funcs::posix88::unistd::{setgid, setuid, setsid};
     use libc::funcs::bsd44::getdtablesize;
+    use libc::types::os::arch::posix88::{gid_t, uid_t};
+
+    static F_DUPFD: c_int = 0;
 
     mod rustrt {
         extern {
@@ -386,6 +425,10 @@ fn spawn_process_os(prog: &str, args: &[~str],
         environ = envp;
     }
 
+    let env = config.env.map(|a| a.to_owned());
+    let cwd = config.cwd.map(|a| Path::new(a));
+    let dir = cwd.as_ref();
+
     unsafe {
 
         let pid = fork();
@@ -397,17 +440,29 @@ fn spawn_process_os(prog: &str, args: &[~str],
 
         rustrt::rust_unset_sigprocmask();
 
-        if dup2(in_fd, 0) == -1 {
-            fail!("failure in dup2(in_fd, 0): {}", os::last_os_error());
-        }
-        if dup2(out_fd, 1) == -1 {
-            fail!("failure in dup2(out_fd, 1): {}", os::last_os_error());
+        if config.detach && setsid() == -1 {
+            fail!("failure in setsid: {}", os::last_os_error());
         }
-        if dup2(err_fd, 2) == -1 {
-            fail!("failure in dup3(err_fd, 2): {}", os::last_os_error());
+
+        // Move all of the child's file descriptors above the range that they
+        // are about to be installed into first, so that installing one of them
+        // can't clobber another which hasn't been installed yet.
+        let nfds = fds.len() as c_int;
+        let fds = fds.map(|&fd| {
+            let new = fcntl(fd, F_DUPFD, nfds);
+            if new == -1 {
+                fail!("failure in fcntl(F_DUPFD): {}", os::last_os_error());
+            }
+            new
+        });
+        for (i, &fd) in fds.iter().enumerate() {
+            if dup2(fd, i as c_int) == -1 {
+                fail!("failure in dup2({}, {}): {}", fd, i,
+                      os::last_os_error());
+            }
         }
         // close all other fds
-        for fd in range(3, getdtablesize()).invert() {
+        for fd in range(nfds, getdtablesize()).invert() {
             close(fd as c_int);
         }
 
@@ -417,11 +472,24 @@ fn spawn_process_os(prog: &str, args: &[~str],
             }
         });
 
+        // The group has to be changed first, as changing the user may give up
+        // the privileges needed to do so.
+        for &gid in config.gid.iter() {
+            if setgid(gid as gid_t) == -1 {
+                fail!("failure in setgid: {}", os::last_os_error());
+            }
+        }
+        for &uid in config.uid.iter() {
+            if setuid(uid as uid_t) == -1 {
+                fail!("failure in setuid: {}", os::last_os_error());
+            }
+        }
+
         with_envp(env, |envp| {
             if !envp.is_null() {
                 set_environ(envp);
             }
-            with_argv(prog, args, |argv| {
+            with_argv(config.program, config.args, |argv| {
                 execvp(*argv, argv);
                 // execvp only returns if an error occurred
                 fail!("failure in execvp: {}", os::last_os_error());
@@ -525,8 +593,9 @@ fn free_handle(_handle: *()) {
 }
 
 /**
- * Waits for a process to exit and returns the exit code, failing
- * if there is no process with the specified id.
+ * Waits for a process to exit and returns how it exited, failing
+ * if there is no process with the specified id. If `block` is false and
+ * the process is still running, `None` is returned instead.
  *
  * Note that this is private to avoid race conditions on unix where if
  * a user calls waitpid(some_process.get_id()) then some_process.finish()
@@ -534,11 +603,11 @@ fn free_handle(_handle: *()) {
  * operate on a none-existent process or, even worse, on a newer process
  * with the same id.
  */
-fn waitpid(pid: pid_t) -> int {
-    return waitpid_os(pid);
+fn waitpid(pid: pid_t, block: bool) -> Option<p::ProcessExit> {
+    return waitpid_os(pid, block);
 
     #[cfg(windows)]
-    fn waitpid_os(pid: pid_t) -> int {
+    fn waitpid_os(pid: pid_t, block: bool) -> Option<p::ProcessExit> {
         use libc::types::os::arch::extra::DWORD;
         use libc::consts::os::extra::{
             SYNCHRONIZE,
@@ -572,7 +641,11 @@ fn waitpid(pid: pid_t) -> int {
                 }
                 if status != STILL_ACTIVE {
                     CloseHandle(process);
-                    return status as int;
+                    return Some(p::ExitStatus(status as int));
+                }
+                if !block {
+                    CloseHandle(process);
+                    return None;
                 }
                 if WaitForSingleObject(process, INFINITE) == WAIT_FAILED {
                     CloseHandle(process);
@@ -583,9 +656,11 @@ fn waitpid(pid: pid_t) -> int {
     }
 
     #[cfg(unix)]
-    fn waitpid_os(pid: pid_t) -> int {
+    fn waitpid_os(pid: pid_t, block: bool) -> Option<p::ProcessExit> {
         use libc::funcs::posix01::wait::*;
 
+        static WNOHANG: c_int = 1;
+
         #[cfg(target_os = "linux")]
         #[cfg(target_os = "android")]
         fn WIFEXITED(status: i32) -> bool {
@@ -611,15 +686,18 @@ fn waitpid(pid: pid_t) -> int {
         }
 
         let mut status = 0 as c_int;
-        if unsafe { waitpid(pid, &mut status, 0) } == -1 {
-            fail!("failure in waitpid: {}", os::last_os_error());
+        let options = if block {0} else {WNOHANG};
+        match unsafe { waitpid(pid, &mut status, options) } {
+            -1 => fail!("failure in waitpid: {}", os::last_os_error()),
+            0 => return None,
+            _ => {}
         }
 
-        return if WIFEXITED(status) {
-            WEXITSTATUS(status) as int
+        return Some(if WIFEXITED(status) {
+            p::ExitStatus(WEXITSTATUS(status) as int)
         } else {
-            1
-        };
+            p::ExitSignal((status & 0x7f) as int)
+        });
     }
 }
 
@@ -0,0 +1,223 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Native signal handling
+//!
+//! One process-wide signal handler (living in the runtime's C support code)
+//! writes the number of each signal it catches onto a pipe. A helper thread
+//! reads signal numbers off of the other end of the pipe and forwards them to
+//! every listener registered for that signal. The handler for a signal is
+//! installed when the first listener for it is registered, and the default
+//! disposition is restored when the last one goes away.
+//!
+//! Windows is not currently supported.
+
+use comm::SharedChan;
+use io::signal::Signum;
+use ops::Drop;
+use rt::rtio;
+use super::IoResult;
+
+pub struct Signal {
+    priv id: uint,
+}
+
+impl Signal {
+    pub fn new(signum: Signum, chan: SharedChan<Signum>) -> IoResult<Signal> {
+        imp::register(signum, chan).map(|id| Signal { id: id })
+    }
+}
+
+impl rtio::RtioSignal for Signal {}
+
+impl Drop for Signal {
+    fn drop(&mut self) { imp::unregister(self.id); }
+}
+
+#[cfg(unix)]
+mod imp {
+    use cast;
+    use cell::Cell;
+    use comm::{SharedChan, SendDeferred};
+    use io::signal::Signum;
+    use iter::Iterator;
+    use libc::{c_int, c_void, size_t};
+    use libc;
+    use option::{Some, None};
+    use os;
+    use result::{Ok, Err};
+    use rt::thread::Thread;
+    use super::super::IoResult;
+    use super::super::notify::Notifier;
+    use unstable::finally::Finally;
+    use unstable::mutex::{Mutex, MUTEX_INIT};
+    use vec::{ImmutableVector, MutableVector, OwnedVector};
+
+    extern {
+        fn rust_signal_init(fd: c_int) -> c_int;
+        fn rust_signal_install(signum: c_int) -> c_int;
+        fn rust_signal_reset(signum: c_int) -> c_int;
+    }
+
+    struct Registration {
+        id: uint,
+        signum: Signum,
+        chan: SharedChan<Signum>,
+        notifier: Notifier,
+    }
+
+    struct State {
+        registrations: ~[Registration],
+        next_id: uint,
+        started: bool,
+    }
+
+    static mut lock: Mutex = MUTEX_INIT;
+    static mut global_state: uint = 0;
+
+    fn with_state<T>(f: |&mut State| -> T) -> T {
+        (|| {
+            unsafe {
+                lock.lock();
+                if global_state == 0 {
+                    let state = ~State {
+                        registrations: ~[],
+                        next_id: 0,
+                        started: false,
+                    };
+                    global_state = cast::transmute(state);
+                }
+                f(cast::transmute(global_state))
+            }
+        }).finally(|| {
+            unsafe { lock.unlock(); }
+        })
+    }
+
+    pub fn register(signum: Signum, chan: SharedChan<Signum>) -> IoResult<uint> {
+        with_state(|state| {
+            if !state.started {
+                let os::Pipe { input, out } = os::pipe();
+                if unsafe { rust_signal_init(out) } != 0 {
+                    let err = super::super::last_error();
+                    unsafe {
+                        libc::close(input);
+                        libc::close(out);
+                    }
+                    return Err(err);
+                }
+                do Thread::spawn { helper(input) }
+                state.started = true;
+            }
+
+            if !state.registrations.iter().any(|r| r.signum == signum) {
+                if unsafe { rust_signal_install(signum as c_int) } != 0 {
+                    return Err(super::super::last_error());
+                }
+            }
+
+            let id = state.next_id;
+            state.next_id += 1;
+            state.registrations.push(Registration {
+                id: id,
+                signum: signum,
+                chan: chan,
+                notifier: Notifier::new(),
+            });
+            Ok(id)
+        })
+    }
+
+    pub fn unregister(id: uint) {
+        with_state(|state| {
+            let signum = match state.registrations.iter().find(|r| r.id == id) {
+                Some(r) => r.signum,
+                None => return,
+            };
+            state.registrations.retain(|r| r.id != id);
+            if !state.registrations.iter().any(|r| r.signum == signum) {
+                unsafe { rust_signal_reset(signum as c_int); }
+            }
+        })
+    }
+
+    // Runs forever, forwarding each signal read off of the pipe to the
+    // listeners which are registered for it at the time.
+    fn helper(fd: c_int) {
+        let mut buf = [0u8, ..64];
+        loop {
+            let n = buf.as_mut_buf(|p, len| unsafe {
+                libc::read(fd, p as *mut c_void, len as size_t)
+            });
+            if n == -1 && os::errno() as int == libc::EINTR as int {
+                continue
+            } else if n <= 0 {
+                break
+            }
+
+            for &signum in buf.slice_to(n as uint).iter() {
+                with_state(|state| {
+                    for r in state.registrations.mut_iter() {
+                        if r.signum as int != signum as int { continue }
+                        let chan = Cell::new(r.chan.clone());
+                        let signum = r.signum;
+                        r.notifier.notify(proc() {
+                            chan.take().try_send_deferred(signum);
+                        });
+                    }
+                })
+            }
+        }
+        unsafe { libc::close(fd); }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use comm::SharedChan;
+    use io::signal::Signum;
+    use result::Err;
+    use super::super::{IoResult, unimpl};
+
+    pub fn register(_: Signum, _: SharedChan<Signum>) -> IoResult<uint> {
+        Err(unimpl())
+    }
+
+    pub fn unregister(_: uint) {}
+}
+
+#[cfg(test, unix)]
+mod test {
+    use comm::{stream, SharedChan};
+    use io::signal::{User1, User2};
+    use libc;
+    use prelude::*;
+    use super::Signal;
+
+    // All of these listeners are registered in one test so that no other test
+    // can restore the default disposition of SIGUSR1 while a signal is in
+    // flight.
+    #[test]
+    fn smoke() {
+        let (port1, chan1) = stream();
+        let (port2, chan2) = stream();
+        let (port3, chan3) = stream();
+        let _s1 = Signal::new(User1, SharedChan::new(chan1)).unwrap();
+        let _s2 = Signal::new(User1, SharedChan::new(chan2)).unwrap();
+        let _s3 = Signal::new(User2, SharedChan::new(chan3)).unwrap();
+        unsafe {
+            libc::funcs::posix88::signal::kill(libc::getpid(),
+                                               User1 as libc::c_int);
+        }
+        assert_eq!(port1.recv(), User1);
+        assert_eq!(port2.recv(), User1);
+        assert!(port3.try_recv().is_none());
+    }
+}
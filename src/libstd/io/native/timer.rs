@@ -0,0 +1,154 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Native timers
+//!
+//! `oneshot` and `period` requests are registered with the native helper
+//! thread (see `helper`), which keeps track of every outstanding timer in the
+//! process at once. Issuing a new request (or dropping the timer) cancels the
+//! previous one. `sleep` simply blocks the calling thread.
+
+use cell::Cell;
+use comm::{oneshot, stream, Port, PortOne, SharedChan, SendDeferred};
+use option::{Option, Some, None};
+use result::Ok;
+use rt::rtio;
+use rt::rtio::Callback;
+use super::IoResult;
+use super::helper;
+use super::helper::Registration;
+use super::notify::Notifier;
+
+pub struct Timer {
+    priv registration: Option<Registration>,
+}
+
+impl Timer {
+    pub fn new() -> IoResult<Timer> {
+        Ok(Timer { registration: None })
+    }
+}
+
+// Fires a `period` request's port every time the helper thread runs it
+struct Tick {
+    chan: SharedChan<()>,
+    notifier: Notifier,
+}
+
+impl Callback for Tick {
+    fn call(&mut self) {
+        let chan = Cell::new(self.chan.clone());
+        self.notifier.notify(proc() { chan.take().try_send_deferred(()); });
+    }
+}
+
+impl rtio::RtioTimer for Timer {
+    fn sleep(&mut self, msecs: u64) {
+        self.registration = None;
+        imp::sleep(msecs);
+    }
+
+    fn oneshot(&mut self, msecs: u64) -> PortOne<()> {
+        let (port, chan) = oneshot();
+        let chan = Cell::new(chan);
+        let notifier = Cell::new(Notifier::new());
+        self.registration = Some(helper::register(None, Some(msecs), proc(_ready) {
+            let chan = Cell::new(chan.take());
+            let mut notifier = notifier.take();
+            notifier.notify(proc() { chan.take().try_send_deferred(()); });
+        }));
+        port
+    }
+
+    fn period(&mut self, msecs: u64) -> Port<()> {
+        let (port, chan) = stream();
+        let tick = ~Tick { chan: SharedChan::new(chan), notifier: Notifier::new() };
+        self.registration = Some(helper::register_periodic(msecs,
+                                                           tick as ~Callback));
+        port
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use libc;
+    use ptr;
+
+    // poll(2) takes a c_int timeout, so long waits are split up into chunks
+    // which comfortably fit inside of one.
+    static MAX_CHUNK: u64 = 1 << 30;
+
+    pub fn sleep(msecs: u64) {
+        let mut remaining = msecs;
+        while remaining > 0 {
+            let chunk = if remaining > MAX_CHUNK {MAX_CHUNK} else {remaining};
+            let ret = unsafe {
+                libc::poll(ptr::mut_null(), 0, chunk as libc::c_int)
+            };
+            if ret == 0 { remaining -= chunk; }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use libc;
+
+    // Leave room for INFINITE, which is the largest DWORD
+    static MAX_CHUNK: u64 = 1 << 31;
+
+    pub fn sleep(msecs: u64) {
+        let mut remaining = msecs;
+        while remaining > 0 {
+            let chunk = if remaining > MAX_CHUNK {MAX_CHUNK} else {remaining};
+            unsafe { libc::Sleep(chunk as libc::DWORD); }
+            remaining -= chunk;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use prelude::*;
+    use rt::rtio::RtioTimer;
+    use super::Timer;
+
+    #[test]
+    fn sleep() {
+        let mut timer = Timer::new().unwrap();
+        timer.sleep(1);
+        timer.sleep(0);
+    }
+
+    #[test]
+    fn oneshot() {
+        let mut timer = Timer::new().unwrap();
+        let port = timer.oneshot(1);
+        port.recv();
+    }
+
+    #[test]
+    fn oneshot_cancelled_by_next_request() {
+        let mut timer = Timer::new().unwrap();
+        let port1 = timer.oneshot(10000);
+        let port2 = timer.oneshot(1);
+        port2.recv();
+        assert!(port1.try_recv().is_none());
+    }
+
+    #[test]
+    fn period() {
+        let mut timer = Timer::new().unwrap();
+        let port = timer.period(1);
+        port.recv();
+        port.recv();
+        port.recv();
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Native TTYs
+//!
+//! Raw mode and window sizes are managed through termios and ioctl on unix.
+//! The layout of `struct termios` differs from platform to platform, so it is
+//! only ever manipulated as an opaque buffer by the runtime's C support code.
+
+use clone::Clone;
+use io::IoError;
+use io;
+use libc::c_int;
+use libc;
+use ops::Drop;
+use option::{Option, Some, None};
+use result::{Result, Ok, Err};
+use rt::rtio;
+use super::IoResult;
+use super::file::FileDesc;
+
+pub struct TTY {
+    priv fd: c_int,
+    priv inner: FileDesc,
+    // The terminal's settings before it was first put into raw mode, restored
+    // when raw mode is turned off (or the TTY is dropped).
+    priv original: Option<~[u8]>,
+}
+
+impl TTY {
+    pub fn new(fd: c_int) -> IoResult<TTY> {
+        if unsafe { libc::isatty(fd) } != 0 {
+            Ok(TTY { fd: fd, inner: FileDesc::new(fd, true), original: None })
+        } else {
+            Err(IoError {
+                kind: io::MismatchedFileTypeForOperation,
+                desc: "file descriptor is not a TTY",
+                detail: None,
+            })
+        }
+    }
+}
+
+impl rtio::RtioTTY for TTY {
+    fn read(&mut self, buf: &mut [u8]) -> Result<uint, IoError> {
+        self.inner.inner_read(buf)
+    }
+    fn write(&mut self, buf: &[u8]) -> Result<(), IoError> {
+        self.inner.inner_write(buf)
+    }
+    fn set_raw(&mut self, raw: bool) -> Result<(), IoError> {
+        if raw {
+            let original = match self.original.take() {
+                Some(t) => t,
+                None => match imp::get(self.fd) {
+                    Ok(t) => t,
+                    Err(e) => return Err(e),
+                },
+            };
+            let mut t = original.clone();
+            imp::make_raw(t);
+            let ret = imp::set(self.fd, t);
+            self.original = Some(original);
+            ret
+        } else {
+            match self.original.take() {
+                Some(t) => imp::set(self.fd, t),
+                None => Ok(()),
+            }
+        }
+    }
+    fn get_winsize(&mut self) -> Result<(int, int), IoError> {
+        imp::get_winsize(self.fd)
+    }
+    fn isatty(&self) -> bool { true }
+}
+
+impl Drop for TTY {
+    fn drop(&mut self) {
+        match self.original.take() {
+            Some(t) => { let _ = imp::set(self.fd, t); }
+            None => {}
+        }
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use libc::{c_int, c_void, size_t};
+    use result::{Ok, Err};
+    use super::super::{IoResult, last_error};
+    use vec::{ImmutableVector, MutableVector};
+    use vec;
+
+    extern {
+        fn rust_termios_size() -> size_t;
+        fn rust_termios_get(fd: c_int, t: *mut c_void) -> c_int;
+        fn rust_termios_set(fd: c_int, t: *c_void) -> c_int;
+        fn rust_termios_make_raw(t: *mut c_void);
+        fn rust_tty_get_winsize(fd: c_int, width: *mut c_int,
+                                height: *mut c_int) -> c_int;
+    }
+
+    pub fn get(fd: c_int) -> IoResult<~[u8]> {
+        let mut t = vec::from_elem(unsafe { rust_termios_size() } as uint, 0u8);
+        let ret = t.as_mut_buf(|p, _| unsafe {
+            rust_termios_get(fd, p as *mut c_void)
+        });
+        match ret {
+            0 => Ok(t),
+            _ => Err(last_error()),
+        }
+    }
+
+    pub fn set(fd: c_int, t: &[u8]) -> IoResult<()> {
+        let ret = t.as_imm_buf(|p, _| unsafe {
+            rust_termios_set(fd, p as *c_void)
+        });
+        match ret {
+            0 => Ok(()),
+            _ => Err(last_error()),
+        }
+    }
+
+    pub fn make_raw(t: &mut [u8]) {
+        t.as_mut_buf(|p, _| unsafe { rust_termios_make_raw(p as *mut c_void) })
+    }
+
+    pub fn get_winsize(fd: c_int) -> IoResult<(int, int)> {
+        let mut width = 0;
+        let mut height = 0;
+        match unsafe { rust_tty_get_winsize(fd, &mut width, &mut height) } {
+            0 => Ok((width as int, height as int)),
+            _ => Err(last_error()),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use libc::c_int;
+    use result::Err;
+    use super::super::{IoResult, unimpl};
+
+    pub fn get(_: c_int) -> IoResult<~[u8]> { Err(unimpl()) }
+    pub fn set(_: c_int, _: &[u8]) -> IoResult<()> { Err(unimpl()) }
+    pub fn make_raw(_: &mut [u8]) {}
+    pub fn get_winsize(_: c_int) -> IoResult<(int, int)> { Err(unimpl()) }
+}
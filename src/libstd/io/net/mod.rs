@@ -16,3 +16,28 @@ pub mod udp;
 pub mod ip;
 #[cfg(unix)]
 pub mod unix;
+
+/// Options for a socket which have to be set before it is bound to an address.
+///
+/// These are handed to `TcpListener::bind_with_options` and
+/// `UdpSocket::bind_with_options`. The default is to set none of them, which is
+/// what the plain `bind` constructors use.
+#[deriving(Eq, Clone)]
+pub struct BindOptions {
+    /// Allow other sockets to bind to the same address (`SO_REUSEADDR`). This
+    /// is what lets several processes listen to the same multicast group. On
+    /// unix, TCP listeners always allow their address to be reused once they
+    /// have been closed, regardless of this option.
+    reuse_address: bool,
+    /// Only accept IPv6 traffic on an IPv6 socket, rather than also accepting
+    /// IPv4 traffic through IPv4-mapped addresses (`IPV6_V6ONLY`). The option
+    /// is set on every IPv6 socket either way, so the system's default doesn't
+    /// matter. This has no effect on IPv4 sockets.
+    ipv6_only: bool,
+}
+
+impl BindOptions {
+    pub fn new() -> BindOptions {
+        BindOptions { reuse_address: false, ipv6_only: false }
+    }
+}
@@ -10,9 +10,11 @@
 
 use option::{Option, Some, None};
 use result::{Ok, Err};
+use io::net::BindOptions;
 use io::net::ip::SocketAddr;
 use io::{Reader, Writer, Listener, Acceptor};
 use io::{io_error, EndOfFile};
+use io::poll::{Pollable, Poller, new_poller};
 use rt::rtio::{IoFactory, with_local_io,
                RtioSocket, RtioTcpListener, RtioTcpAcceptor, RtioTcpStream};
 
@@ -26,8 +28,26 @@ impl TcpStream {
     }
 
     pub fn connect(addr: SocketAddr) -> Option<TcpStream> {
+        TcpStream::connect_inner(addr, None)
+    }
+
+    /// Creates a TCP connection to `addr`, giving up if the connection has
+    /// not been established within `timeout_ms` milliseconds.
+    ///
+    /// # Failure
+    ///
+    /// If the connection could not be made, an error is raised on the
+    /// `io_error` condition and `None` is returned. If the timeout elapsed,
+    /// the error will be of kind `TimedOut`.
+    pub fn connect_timeout(addr: SocketAddr,
+                           timeout_ms: u64) -> Option<TcpStream> {
+        TcpStream::connect_inner(addr, Some(timeout_ms))
+    }
+
+    fn connect_inner(addr: SocketAddr,
+                     timeout: Option<u64>) -> Option<TcpStream> {
         with_local_io(|io| {
-            match io.tcp_connect(addr) {
+            match io.tcp_connect(addr, timeout) {
                 Ok(s) => Some(TcpStream::new(s)),
                 Err(ioerr) => {
                     io_error::cond.raise(ioerr);
@@ -58,6 +78,111 @@ impl TcpStream {
             }
         }
     }
+
+    /// Sets a timeout, in milliseconds, for every subsequent read and write on
+    /// this stream.
+    ///
+    /// Any single operation which remains blocked for longer than the timeout
+    /// gives up and raises an error of kind `TimedOut` on the `io_error`
+    /// condition. A write which times out may have already sent part of its
+    /// buffer. Passing `None` clears the timeout, and operations will block
+    /// indefinitely again.
+    ///
+    /// The timeout is not a deadline: it starts afresh with every call, and a
+    /// write waits for up to the timeout for each part of its buffer which it
+    /// manages to send, so it can take longer than the timeout altogether.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for reads on this stream, leaving the write timeout
+    /// untouched. See `set_timeout` for more information.
+    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_read_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for writes on this stream, leaving the read timeout
+    /// untouched. See `set_timeout` for more information.
+    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_write_timeout(timeout_ms)
+    }
+
+    /// Enables or disables Nagle's algorithm (`TCP_NODELAY`). With `nodelay`
+    /// set, small writes are sent right away instead of being coalesced into
+    /// larger segments.
+    pub fn set_nodelay(&mut self, nodelay: bool) {
+        let ret = if nodelay {
+            self.obj.nodelay()
+        } else {
+            self.obj.control_congestion()
+        };
+        match ret {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Enables keepalive probes on this connection after it has been idle for
+    /// `delay_in_seconds`, or disables them if `None` is given.
+    pub fn set_keepalive(&mut self, delay_in_seconds: Option<uint>) {
+        let ret = match delay_in_seconds {
+            Some(delay) => self.obj.keepalive(delay),
+            None => self.obj.letdie(),
+        };
+        match ret {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the size, in bytes, of the kernel's receive buffer for this
+    /// connection (`SO_RCVBUF`). The kernel may round the size or clamp it to
+    /// its own limits.
+    pub fn set_recv_buffer_size(&mut self, bytes: uint) {
+        match self.obj.set_recv_buffer_size(bytes) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the size, in bytes, of the kernel's send buffer for this
+    /// connection (`SO_SNDBUF`). See `set_recv_buffer_size`.
+    pub fn set_send_buffer_size(&mut self, bytes: uint) {
+        match self.obj.set_send_buffer_size(bytes) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Shuts down the writing half of this connection. Once all pending
+    /// writes have been flushed, the remote end will see end-of-file, but
+    /// this stream can continue to read whatever the remote end sends back.
+    ///
+    /// # Failure
+    ///
+    /// Raises on the `io_error` condition if the connection could not be shut
+    /// down (for example if it is no longer connected).
+    pub fn close_write(&mut self) {
+        match self.obj.close_write() {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Shuts down the reading half of this connection. Subsequent reads will
+    /// return end-of-file, while writes are unaffected. See `close_write`.
+    pub fn close_read(&mut self) {
+        match self.obj.close_read() {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+}
+
+impl Pollable for TcpStream {
+    fn poller(&mut self) -> Option<Poller> {
+        new_poller(self.obj.poller())
+    }
 }
 
 impl Reader for TcpStream {
@@ -92,8 +217,15 @@ pub struct TcpListener {
 
 impl TcpListener {
     pub fn bind(addr: SocketAddr) -> Option<TcpListener> {
+        TcpListener::bind_with_options(addr, BindOptions::new())
+    }
+
+    /// Binds a listener to `addr`, first applying the socket options in
+    /// `opts`.
+    pub fn bind_with_options(addr: SocketAddr,
+                             opts: BindOptions) -> Option<TcpListener> {
         with_local_io(|io| {
-            match io.tcp_bind(addr) {
+            match io.tcp_bind(addr, opts) {
                 Ok(l) => Some(TcpListener { obj: l }),
                 Err(ioerr) => {
                     io_error::cond.raise(ioerr);
@@ -131,6 +263,18 @@ pub struct TcpAcceptor {
     priv obj: ~RtioTcpAcceptor
 }
 
+impl TcpAcceptor {
+    /// Sets a timeout, in milliseconds, for every subsequent call to `accept`.
+    ///
+    /// If no connection arrives within the timeout, `accept` raises an error of
+    /// kind `TimedOut` on the `io_error` condition and returns `None`. Passing
+    /// `None` clears the timeout. The timeout starts afresh with every call to
+    /// `accept`, it's not a deadline.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_timeout(timeout_ms)
+    }
+}
+
 impl Acceptor<TcpStream> for TcpAcceptor {
     fn accept(&mut self) -> Option<TcpStream> {
         match self.obj.accept() {
@@ -148,6 +292,7 @@ mod test {
     use super::*;
     use cell::Cell;
     use rt::test::*;
+    use io::net::BindOptions;
     use io::net::ip::{Ipv4Addr, SocketAddr};
     use io::*;
     use prelude::*;
@@ -722,4 +867,40 @@ mod test {
         socket_name(next_test_ip6());
     }
 
+    #[test]
+    fn close_write() {
+        do run_in_mt_newsched_task {
+            let addr = next_test_ip4();
+            let (port, chan) = oneshot();
+            let port = Cell::new(port);
+            let chan = Cell::new(chan);
+
+            do spawntask {
+                let mut acceptor = TcpListener::bind(addr).listen();
+                chan.take().send(());
+                let mut stream = acceptor.accept().unwrap();
+                assert_eq!(stream.read_to_end(), ~[1, 2, 3]);
+                stream.write([4]);
+            }
+
+            do spawntask {
+                port.take().recv();
+                let mut stream = TcpStream::connect(addr).unwrap();
+                stream.write([1, 2, 3]);
+                stream.close_write();
+                let mut buf = [0];
+                assert_eq!(stream.read(buf), Some(1));
+                assert_eq!(buf[0], 4);
+            }
+        }
+    }
+
+    #[test]
+    fn bind_ipv6_only() {
+        do run_in_mt_newsched_task {
+            let opts = BindOptions { ipv6_only: true, .. BindOptions::new() };
+            let listener = TcpListener::bind_with_options(next_test_ip6(), opts);
+            assert!(listener.is_some());
+        }
+    }
 }
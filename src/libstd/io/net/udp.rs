@@ -10,9 +10,11 @@
 
 use option::{Option, Some, None};
 use result::{Ok, Err};
-use io::net::ip::SocketAddr;
+use io::net::BindOptions;
+use io::net::ip::{SocketAddr, IpAddr};
 use io::{Reader, Writer};
 use io::{io_error, EndOfFile};
+use io::poll::{Pollable, Poller, new_poller};
 use rt::rtio::{RtioSocket, RtioUdpSocket, IoFactory, with_local_io};
 
 pub struct UdpSocket {
@@ -21,8 +23,16 @@ pub struct UdpSocket {
 
 impl UdpSocket {
     pub fn bind(addr: SocketAddr) -> Option<UdpSocket> {
+        UdpSocket::bind_with_options(addr, BindOptions::new())
+    }
+
+    /// Binds a socket to `addr`, first applying the socket options in `opts`.
+    /// Several sockets which all want to receive the datagrams sent to one
+    /// multicast group should be bound with `reuse_address` set.
+    pub fn bind_with_options(addr: SocketAddr,
+                             opts: BindOptions) -> Option<UdpSocket> {
         with_local_io(|io| {
-            match io.udp_bind(addr) {
+            match io.udp_bind(addr, opts) {
                 Ok(s) => Some(UdpSocket { obj: s }),
                 Err(ioerr) => {
                     io_error::cond.raise(ioerr);
@@ -52,6 +62,138 @@ impl UdpSocket {
         }
     }
 
+    /// Sets a timeout, in milliseconds, for every subsequent `recvfrom` and
+    /// `sendto` on this socket.
+    ///
+    /// An operation which remains blocked for longer than the timeout raises
+    /// an error of kind `TimedOut` on the `io_error` condition. Passing `None`
+    /// clears the timeout. The timeout starts afresh with every call, it's not
+    /// a deadline.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for `recvfrom` only. See `set_timeout` for more
+    /// information.
+    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_read_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for `sendto` only. See `set_timeout` for more
+    /// information.
+    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_write_timeout(timeout_ms)
+    }
+
+    /// Joins the multicast group `multi` on the default interface, after which
+    /// datagrams sent to the group will be received by this socket.
+    pub fn join_multicast(&mut self, multi: IpAddr) {
+        match self.obj.join_multicast(multi, None) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Joins the multicast group `multi` on the interface with the IPv4
+    /// address `interface`, such as `127.0.0.1` for the loopback interface.
+    pub fn join_multicast_on(&mut self, multi: IpAddr, interface: IpAddr) {
+        match self.obj.join_multicast(multi, Some(interface)) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Leaves a multicast group previously joined with `join_multicast`.
+    pub fn leave_multicast(&mut self, multi: IpAddr) {
+        match self.obj.leave_multicast(multi, None) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Leaves a multicast group previously joined with `join_multicast_on`.
+    pub fn leave_multicast_on(&mut self, multi: IpAddr, interface: IpAddr) {
+        match self.obj.leave_multicast(multi, Some(interface)) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sends the multicast datagrams from this socket out of the interface
+    /// with the IPv4 address `interface`, rather than the one chosen by the
+    /// routing table.
+    pub fn set_multicast_interface(&mut self, interface: IpAddr) {
+        match self.obj.multicast_interface(interface) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets whether multicast datagrams sent from this socket are looped back
+    /// to sockets on the local host (including this one).
+    pub fn set_multicast_loop(&mut self, on: bool) {
+        let ret = if on {
+            self.obj.loop_multicast_locally()
+        } else {
+            self.obj.dont_loop_multicast_locally()
+        };
+        match ret {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the time-to-live of outgoing multicast datagrams, which limits how
+    /// many hops they may travel. The default of 1 keeps them on the local
+    /// network.
+    pub fn set_multicast_ttl(&mut self, ttl: int) {
+        match self.obj.multicast_time_to_live(ttl) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the time-to-live of outgoing unicast datagrams.
+    pub fn set_ttl(&mut self, ttl: int) {
+        match self.obj.time_to_live(ttl) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets whether this socket may send datagrams to broadcast addresses
+    /// (`SO_BROADCAST`).
+    pub fn set_broadcast(&mut self, on: bool) {
+        let ret = if on {
+            self.obj.hear_broadcasts()
+        } else {
+            self.obj.ignore_broadcasts()
+        };
+        match ret {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the size, in bytes, of the kernel's receive buffer for this socket
+    /// (`SO_RCVBUF`). The kernel may round the size or clamp it to its own
+    /// limits.
+    pub fn set_recv_buffer_size(&mut self, bytes: uint) {
+        match self.obj.set_recv_buffer_size(bytes) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Sets the size, in bytes, of the kernel's send buffer for this socket
+    /// (`SO_SNDBUF`). See `set_recv_buffer_size`.
+    pub fn set_send_buffer_size(&mut self, bytes: uint) {
+        match self.obj.set_send_buffer_size(bytes) {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
     pub fn connect(self, other: SocketAddr) -> UdpStream {
         UdpStream { socket: self, connectedTo: other }
     }
@@ -68,6 +210,12 @@ impl UdpSocket {
     }
 }
 
+impl Pollable for UdpSocket {
+    fn poller(&mut self) -> Option<Poller> {
+        new_poller(self.obj.poller())
+    }
+}
+
 pub struct UdpStream {
     priv socket: UdpSocket,
     priv connectedTo: SocketAddr
@@ -81,6 +229,10 @@ impl UdpStream {
     pub fn disconnect(self) -> UdpSocket { self.socket }
 }
 
+impl Pollable for UdpStream {
+    fn poller(&mut self) -> Option<Poller> { self.socket.poller() }
+}
+
 impl Reader for UdpStream {
     fn read(&mut self, buf: &mut [u8]) -> Option<uint> {
         let peer = self.connectedTo;
@@ -106,7 +258,8 @@ impl Writer for UdpStream {
 mod test {
     use super::*;
     use rt::test::*;
-    use io::net::ip::{Ipv4Addr, SocketAddr};
+    use io::net::BindOptions;
+    use io::net::ip::{Ipv4Addr, Ipv6Addr, SocketAddr};
     use io::*;
     use option::{Some, None};
     use rt::comm::oneshot;
@@ -318,4 +471,70 @@ mod test {
     fn socket_name_ip6() {
         socket_name(next_test_ip6());
     }
+
+    #[test]
+    fn socket_options() {
+        do run_in_mt_newsched_task {
+            let mut socket = UdpSocket::bind(next_test_ip4()).unwrap();
+            socket.set_multicast_loop(true);
+            socket.set_multicast_ttl(1);
+            socket.set_ttl(64);
+            socket.set_broadcast(true);
+            socket.set_broadcast(false);
+            socket.set_recv_buffer_size(4096);
+            socket.set_send_buffer_size(4096);
+        }
+    }
+
+    #[test]
+    fn bind_reuse_address() {
+        do run_in_mt_newsched_task {
+            let addr = next_test_ip4();
+            let opts = BindOptions { reuse_address: true, .. BindOptions::new() };
+            let a = UdpSocket::bind_with_options(addr, opts);
+            let b = UdpSocket::bind_with_options(addr, opts);
+            assert!(a.is_some() && b.is_some());
+        }
+    }
+
+    // The group is joined and sent to over the loopback interface, so that
+    // the test doesn't depend on the machine having a multicast route.
+    #[test]
+    fn multicast_smoke_test() {
+        do run_in_mt_newsched_task {
+            let group = Ipv4Addr(239, 255, 42, 99);
+            let lo = Ipv4Addr(127, 0, 0, 1);
+            let port = next_test_ip4().port;
+            let any = SocketAddr { ip: Ipv4Addr(0, 0, 0, 0), port: port };
+            let opts = BindOptions { reuse_address: true, .. BindOptions::new() };
+            let mut server = UdpSocket::bind_with_options(any, opts).unwrap();
+            server.join_multicast_on(group, lo);
+
+            let mut client = UdpSocket::bind(next_test_ip4()).unwrap();
+            client.set_multicast_interface(lo);
+            client.set_multicast_loop(true);
+            client.sendto([99], SocketAddr { ip: group, port: port });
+
+            let mut buf = [0];
+            match server.recvfrom(buf) {
+                Some((1, _)) => assert_eq!(buf[0], 99),
+                r => fail!("unexpected recvfrom: {:?}", r),
+            }
+            server.leave_multicast_on(group, lo);
+        }
+    }
+
+    #[test]
+    fn multicast_ipv6_interface() {
+        do run_in_mt_newsched_task {
+            let mut socket = UdpSocket::bind(next_test_ip4()).unwrap();
+            let mut called = false;
+            io_error::cond.trap(|_| {
+                called = true;
+            }).inside(|| {
+                socket.set_multicast_interface(Ipv6Addr(0, 0, 0, 0, 0, 0, 0, 1));
+            });
+            assert!(called);
+        }
+    }
 }
@@ -29,6 +29,7 @@ use rt::rtio::{IoFactory, RtioUnixListener, with_local_io};
 use rt::rtio::{RtioUnixAcceptor, RtioPipe};
 use io::pipe::PipeStream;
 use io::{io_error, Listener, Acceptor, Reader, Writer};
+use io::poll::{Pollable, Poller};
 
 /// A stream which communicates over a named pipe.
 pub struct UnixStream {
@@ -59,8 +60,26 @@ impl UnixStream {
     ///     stream.write([1, 2, 3]);
     ///
     pub fn connect<P: ToCStr>(path: &P) -> Option<UnixStream> {
+        UnixStream::connect_inner(path, None)
+    }
+
+    /// Connect to a pipe named by `path`, giving up if the connection has not
+    /// been established within `timeout_ms` milliseconds.
+    ///
+    /// # Failure
+    ///
+    /// This function will raise on the `io_error` condition if the connection
+    /// could not be made. If the timeout elapsed, the error will be of kind
+    /// `TimedOut`.
+    pub fn connect_timeout<P: ToCStr>(path: &P,
+                                      timeout_ms: u64) -> Option<UnixStream> {
+        UnixStream::connect_inner(path, Some(timeout_ms))
+    }
+
+    fn connect_inner<P: ToCStr>(path: &P,
+                                timeout: Option<u64>) -> Option<UnixStream> {
         with_local_io(|io| {
-            match io.unix_connect(&path.to_c_str()) {
+            match io.unix_connect(&path.to_c_str(), timeout) {
                 Ok(s) => Some(UnixStream::new(s)),
                 Err(ioerr) => {
                     io_error::cond.raise(ioerr);
@@ -69,6 +88,34 @@ impl UnixStream {
             }
         })
     }
+
+    /// Sets a timeout, in milliseconds, for every subsequent read and write on
+    /// this stream. See `TcpStream::set_timeout` for more information.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for reads on this stream only.
+    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_read_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for writes on this stream only.
+    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_write_timeout(timeout_ms)
+    }
+
+    /// Shuts down the writing half of this stream, so that the remote end
+    /// sees end-of-file while this end can still read its reply. See
+    /// `TcpStream::close_write` for more information.
+    pub fn close_write(&mut self) { self.obj.close_write() }
+
+    /// Shuts down the reading half of this stream.
+    pub fn close_read(&mut self) { self.obj.close_read() }
+}
+
+impl Pollable for UnixStream {
+    fn poller(&mut self) -> Option<Poller> { self.obj.poller() }
 }
 
 impl Reader for UnixStream {
@@ -234,6 +281,22 @@ mod tests {
         })
     }
 
+    #[test]
+    fn close_write() {
+        smalltest(proc(mut server) {
+            let mut buf = [0];
+            assert_eq!(server.read(buf), Some(1));
+            assert!(server.read(buf).is_none());
+            server.write([2]);
+        }, proc(mut client) {
+            client.write([1]);
+            client.close_write();
+            let mut buf = [0];
+            assert_eq!(client.read(buf), Some(1));
+            assert_eq!(buf[0], 2);
+        })
+    }
+
     #[test]
     fn write_begone() {
         smalltest(proc(mut server) {
@@ -16,6 +16,7 @@
 use prelude::*;
 use super::{Reader, Writer};
 use io::{io_error, EndOfFile};
+use io::poll::{Pollable, Poller, new_poller};
 use io::native::file;
 use rt::rtio::{RtioPipe, with_local_io};
 
@@ -58,6 +59,56 @@ impl PipeStream {
     pub fn new(inner: ~RtioPipe) -> PipeStream {
         PipeStream { obj: inner }
     }
+
+    /// Sets a timeout, in milliseconds, for every subsequent read and write on
+    /// this pipe. An operation which remains blocked for longer than the
+    /// timeout raises an error of kind `TimedOut` on the `io_error` condition.
+    /// Passing `None` clears the timeout.
+    ///
+    /// Timeouts are not supported by the native implementation of pipes opened
+    /// from raw file descriptors, and are ignored there.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for reads on this pipe only.
+    pub fn set_read_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_read_timeout(timeout_ms)
+    }
+
+    /// Sets the timeout for writes on this pipe only.
+    pub fn set_write_timeout(&mut self, timeout_ms: Option<u64>) {
+        self.obj.set_write_timeout(timeout_ms)
+    }
+
+    /// Shuts down the writing half of a bidirectional pipe (such as a unix
+    /// socket), leaving the reading half open.
+    ///
+    /// # Failure
+    ///
+    /// Raises on the `io_error` condition if the pipe could not be shut down.
+    /// Pipes opened from raw file descriptors only go in one direction and
+    /// cannot be half-closed natively.
+    pub fn close_write(&mut self) {
+        match self.obj.close_write() {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+
+    /// Shuts down the reading half of a bidirectional pipe. See `close_write`.
+    pub fn close_read(&mut self) {
+        match self.obj.close_read() {
+            Ok(()) => {}
+            Err(ioerr) => io_error::cond.raise(ioerr),
+        }
+    }
+}
+
+impl Pollable for PipeStream {
+    fn poller(&mut self) -> Option<Poller> {
+        new_poller(self.obj.poller())
+    }
 }
 
 impl Reader for PipeStream {
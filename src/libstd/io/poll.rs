@@ -0,0 +1,269 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/*!
+
+Readiness notifications for I/O objects
+
+This module allows one task to wait on several sockets and pipes at once,
+instead of having to dedicate a task to each of them. A `Poller` is created
+for an I/O object, and it hands out ports which receive a message as soon as
+that object becomes readable or writable. These are ordinary ports, so they
+can be waited on together with `std::select::select`, and a time limit can be
+put on each wait in place of a separate timer.
+
+Readiness is only a hint: a read on an object which was reported as readable
+may still block if another task got to the data first, and an object which has
+hit an error (or end of file) is always reported as ready so that the next
+operation on it can surface the condition.
+
+# Example
+
+```rust
+use std::io::poll::{Pollable, READABLE, wait_any};
+
+let mut pollers = ~[a.poller().unwrap(), b.poller().unwrap()];
+match wait_any(pollers, READABLE, Some(1000)) {
+    Some((0, _)) => { /* `a` has data */ }
+    Some((_, _)) => { /* `b` has data */ }
+    None => { /* neither one had any data within a second */ }
+}
+```
+
+*/
+
+use comm::PortOne;
+use container::Container;
+use io::{io_error, IoError};
+use io::native;
+use iter::Iterator;
+use libc::c_int;
+use option::{Option, Some, None};
+use result::{Result, Ok, Err};
+use rt::rtio::RtioPoll;
+use select::select;
+use vec::{OwnedVector, ImmutableVector, MutableVector};
+
+/// A set of readiness conditions, used both to ask for a notification and to
+/// report which of the conditions were met.
+#[deriving(Eq, Clone)]
+pub struct Readiness {
+    /// The object can be read from (or accepted on) without blocking.
+    readable: bool,
+    /// The object can be written to without blocking.
+    writable: bool,
+}
+
+/// Readable, but not necessarily writable.
+pub static READABLE: Readiness = Readiness { readable: true, writable: false };
+/// Writable, but not necessarily readable.
+pub static WRITABLE: Readiness = Readiness { readable: false, writable: true };
+/// Either readable or writable.
+pub static READ_WRITE: Readiness = Readiness { readable: true, writable: true };
+/// Neither readable nor writable. This is what is reported when a wait times
+/// out.
+pub static NOT_READY: Readiness = Readiness { readable: false, writable: false };
+
+impl Readiness {
+    /// Returns whether none of the conditions in this set are met.
+    pub fn is_empty(&self) -> bool { !self.readable && !self.writable }
+}
+
+/// An I/O object which can report its readiness.
+pub trait Pollable {
+    /// Creates a poller for this object.
+    ///
+    /// The poller must not outlive the object, and the object should not be
+    /// read from or written to while one of the poller's ports is pending.
+    ///
+    /// # Failure
+    ///
+    /// Raises on the `io_error` condition if the object can't be polled (for
+    /// example, objects can't be polled natively on windows).
+    fn poller(&mut self) -> Option<Poller>;
+}
+
+/// Waits for one I/O object to become ready. See the module documentation.
+pub struct Poller {
+    priv obj: ~RtioPoll,
+}
+
+impl Poller {
+    pub fn new(obj: ~RtioPoll) -> Poller {
+        Poller { obj: obj }
+    }
+
+    /// Creates a oneshot port which will receive the conditions of `interest`
+    /// which are met as soon as at least one of them is. This does not block
+    /// the current task.
+    ///
+    /// Note that this invalidates any previous port which has been created by
+    /// this poller, and that the returned port will be invalidated once the
+    /// poller is destroyed.
+    pub fn oneshot(&mut self, interest: Readiness) -> PortOne<Readiness> {
+        self.obj.oneshot(interest, None)
+    }
+
+    /// As `oneshot`, except that `NOT_READY` is sent on the port if none of
+    /// the conditions are met within `timeout_ms` milliseconds.
+    pub fn oneshot_timeout(&mut self, interest: Readiness,
+                           timeout_ms: u64) -> PortOne<Readiness> {
+        self.obj.oneshot(interest, Some(timeout_ms))
+    }
+
+    /// Blocks the current task until one of the conditions of `interest` is
+    /// met, returning the ones which are.
+    pub fn wait(&mut self, interest: Readiness) -> Readiness {
+        match self.obj.fd() {
+            Some(fd) => {
+                self.obj.cancel();
+                match native::poll::wait_fds([fd], interest, None) {
+                    Some((_, ready)) => ready,
+                    None => NOT_READY,
+                }
+            }
+            None => self.obj.oneshot(interest, None).recv()
+        }
+    }
+
+    /// Invalidates any pending port created by this poller, no longer waiting
+    /// for the object to become ready.
+    pub fn cancel(&mut self) {
+        self.obj.cancel()
+    }
+}
+
+/// Blocks the current task until any of the objects being watched by
+/// `pollers` meets one of the conditions of `interest`, returning its index
+/// along with the conditions that it met. Given a timeout, `None` is returned
+/// if no object becomes ready within `timeout_ms` milliseconds.
+///
+/// All pollers are cancelled before this returns.
+///
+/// # Failure
+///
+/// Fails if `pollers` is empty.
+pub fn wait_any(pollers: &mut [Poller], interest: Readiness,
+                timeout_ms: Option<u64>) -> Option<(uint, Readiness)> {
+    assert!(!pollers.is_empty());
+
+    // Native pollers are all waited for at once by the calling thread, with
+    // no need for ports.
+    let fds = pollers.iter().map(|p| p.obj.fd()).collect::<~[Option<c_int>]>();
+    if fds.iter().all(|fd| fd.is_some()) {
+        for p in pollers.mut_iter() { p.cancel(); }
+        let fds = fds.move_iter().map(|fd| fd.unwrap()).collect::<~[c_int]>();
+        return native::poll::wait_fds(fds, interest, timeout_ms);
+    }
+
+    let mut ports = pollers.mut_iter().map(|p| {
+        p.obj.oneshot(interest, timeout_ms)
+    }).collect::<~[PortOne<Readiness>]>();
+    let i = select(ports);
+    let ready = ports.swap_remove(i).recv();
+    for p in pollers.mut_iter() { p.cancel(); }
+    if ready.is_empty() {None} else {Some((i, ready))}
+}
+
+/// Helper for the implementations of `Pollable`, raising on the `io_error`
+/// condition if creating the poller failed.
+pub fn new_poller(obj: Result<~RtioPoll, IoError>) -> Option<Poller> {
+    match obj {
+        Ok(obj) => Some(Poller::new(obj)),
+        Err(ioerr) => {
+            io_error::cond.raise(ioerr);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cell::Cell;
+    use io::net::tcp::{TcpListener, TcpStream};
+    use io::*;
+    use prelude::*;
+    use rt::comm::oneshot;
+    use rt::test::*;
+    use super::*;
+
+    #[test]
+    fn readable_and_writable() {
+        do run_in_mt_newsched_task {
+            let addr = next_test_ip4();
+            let (port, chan) = oneshot();
+            let port = Cell::new(port);
+            let chan = Cell::new(chan);
+            let (done_port, done_chan) = oneshot();
+            let done_port = Cell::new(done_port);
+            let done_chan = Cell::new(done_chan);
+
+            do spawntask {
+                let mut acceptor = TcpListener::bind(addr).listen();
+                chan.take().send(());
+                let mut stream = acceptor.accept().unwrap();
+                let mut poller = stream.poller().unwrap();
+
+                // Nothing has been sent yet, but there's plenty of room to
+                // send some data of our own.
+                let ready = poller.oneshot_timeout(READABLE, 10).recv();
+                assert_eq!(ready, NOT_READY);
+                assert!(poller.wait(WRITABLE).writable);
+                done_chan.take().send(());
+
+                assert!(poller.wait(READABLE).readable);
+                let mut buf = [0];
+                assert_eq!(stream.read(buf), Some(1));
+                assert_eq!(buf[0], 99);
+            }
+
+            do spawntask {
+                port.take().recv();
+                let mut stream = TcpStream::connect(addr).unwrap();
+                done_port.take().recv();
+                stream.write([99]);
+            }
+        }
+    }
+
+    #[test]
+    fn wait_any_finds_the_ready_one() {
+        do run_in_mt_newsched_task {
+            let addr = next_test_ip4();
+            let (port, chan) = oneshot();
+            let port = Cell::new(port);
+            let chan = Cell::new(chan);
+
+            do spawntask {
+                let mut acceptor = TcpListener::bind(addr).listen();
+                chan.take().send(());
+                let mut a = acceptor.accept().unwrap();
+                let mut b = acceptor.accept().unwrap();
+                let mut pollers = ~[a.poller().unwrap(), b.poller().unwrap()];
+                assert!(wait_any(pollers, READABLE, Some(10)).is_none());
+                b.write([1]);
+                match wait_any(pollers, READABLE, None) {
+                    Some((0, ready)) => assert!(ready.readable),
+                    r => fail!("{:?}", r),
+                }
+            }
+
+            do spawntask {
+                port.take().recv();
+                let mut a = TcpStream::connect(addr).unwrap();
+                let mut b = TcpStream::connect(addr).unwrap();
+                let mut buf = [0];
+                // Wait for the go-ahead on b, then ping on a.
+                assert_eq!(b.read(buf), Some(1));
+                a.write([2]);
+            }
+        }
+    }
+}
@@ -13,9 +13,11 @@
 use prelude::*;
 use cell::Cell;
 
+use comm::SharedChan;
 use libc;
 use io;
 use io::io_error;
+use io::timer::Timer;
 use rt::rtio::{RtioProcess, IoFactory, with_local_io};
 
 use fmt;
@@ -42,7 +44,8 @@ pub struct ProcessConfig<'self> {
     args: &'self [~str],
 
     /// Optional environment to specify for the program. If this is None, then
-    /// it will inherit the current process's environment.
+    /// it will inherit the current process's environment. An empty slice gives
+    /// the program an empty environment.
     env: Option<&'self [(~str, ~str)]>,
 
     /// Optional working directory for the new process. If this is None, then
@@ -59,7 +62,27 @@ pub struct ProcessConfig<'self> {
     ///     0 - stdin
     ///     1 - stdout
     ///     2 - stderr
-    io: &'self [StdioContainer]
+    ///
+    /// Any containers past the first three give the child extra file
+    /// descriptors, which is how arbitrary file descriptors of the parent can
+    /// be inherited. Standard streams which are not listed here are ignored.
+    io: &'self [StdioContainer],
+
+    /// Sets the child process's user id. This translates to a `setuid` call in
+    /// the child process. Setting this value on windows will cause the spawn to
+    /// fail.
+    uid: Option<uint>,
+
+    /// Similar to `uid`, but sets the group id of the child process. This has
+    /// the same semantics as the `uid` field.
+    gid: Option<uint>,
+
+    /// If true, the child process is spawned in a detached state. On unix,
+    /// this means that the child is the leader of a new session (and thus of a
+    /// new process group), so it won't receive the signals that are sent to
+    /// the parent's process group or terminal. On windows the child gets no
+    /// console of its own.
+    detach: bool,
 }
 
 /// Describes what to do with a standard io stream for a child process.
@@ -84,6 +107,7 @@ pub enum StdioContainer {
 /// Describes the result of a process after it has terminated.
 /// Note that Windows have no signals, so the result is usually ExitStatus.
 #[deriving(Eq)]
+#[must_use]
 pub enum ProcessExit {
     /// Normal termination with an exit status.
     ExitStatus(int),
@@ -92,6 +116,32 @@ pub enum ProcessExit {
     ExitSignal(int),
 }
 
+/// The output of a finished process.
+pub struct ProcessOutput {
+    /// The status (exit code) of the process.
+    status: ProcessExit,
+
+    /// The data that the process wrote to stdout.
+    output: ~[u8],
+
+    /// The data that the process wrote to stderr.
+    error: ~[u8],
+
+    /// Whether the process was killed because it was still running when its
+    /// deadline passed.
+    timed_out: bool,
+}
+
+// The messages received while collecting the output of a process.
+enum OutputEvent {
+    Data(uint, ~[u8]),
+    // The deadline has passed.
+    Deadline,
+}
+
+// How often a child with a deadline is checked on once its output is done.
+static DEADLINE_POLL_MS: u64 = 10;
+
 impl fmt::Default for ProcessExit {
     /// Format a ProcessExit enum, to nicely present the information.
     fn fmt(obj: &ProcessExit, f: &mut fmt::Formatter) {
@@ -160,6 +210,98 @@ impl Process {
     /// exited with. This function will continue to have the same return value
     /// after it has been called at least once.
     pub fn wait(&mut self) -> ProcessExit { self.handle.wait() }
+
+    /// Checks whether the child has exited without blocking, returning the
+    /// status that it exited with if it has. Once this has returned a status,
+    /// `wait` will return the same one.
+    pub fn try_wait(&mut self) -> Option<ProcessExit> { self.handle.try_wait() }
+
+    /// Closes the child's stdin, reads all of its stdout and stderr, and waits
+    /// for it to exit, returning everything that it wrote along with its exit
+    /// status. The two streams are read in parallel, so a child which fills up
+    /// one of them while the other is being read from can't deadlock.
+    ///
+    /// Given a timeout, the child is killed with `MustDieSignal` if it hasn't
+    /// exited within `timeout_ms` milliseconds, in which case `timed_out` is
+    /// set and the output is whatever was written before it was killed.
+    ///
+    /// Streams which were not created as pipes (or which have already been
+    /// taken out of `io`) are reported as empty.
+    pub fn wait_with_output(&mut self, timeout_ms: Option<u64>) -> ProcessOutput {
+        if self.io.len() > 0 { self.io[0] = None; }
+
+        let (port, chan) = stream();
+        let chan = SharedChan::new(chan);
+        for fd in range(1u, 3) {
+            let stream = if fd < self.io.len() { self.io[fd].take() } else { None };
+            let stream = Cell::new(stream);
+            let chan = chan.clone();
+            do spawn {
+                let bytes = match stream.take() {
+                    Some(ref mut s) => io::ignore_io_error(|| s.read_to_end()),
+                    None => ~[],
+                };
+                chan.send(Data(fd, bytes));
+            }
+        }
+
+        // The child is only ever waited on and killed from this task, so it
+        // can't be killed after it has been reaped, at which point its pid may
+        // belong to some other process. The task of the deadline only says
+        // when it has passed.
+        let timer = timeout_ms.map(|ms| {
+            let mut timer = Timer::new().expect("wait_with_output: could not create a Timer");
+            let deadline = Cell::new(timer.oneshot(ms));
+            let chan = chan.clone();
+            do spawn {
+                // Destroying the timer closes the port without a message
+                if deadline.take().try_recv().is_some() {
+                    chan.send(Deadline);
+                }
+            }
+            timer
+        });
+
+        let mut output = None;
+        let mut error = None;
+        let mut timed_out = false;
+        while output.is_none() || error.is_none() {
+            match port.recv() {
+                Data(1, bytes) => output = Some(bytes),
+                Data(_, bytes) => error = Some(bytes),
+                Deadline => timed_out = self.kill_for_deadline(),
+            }
+        }
+
+        // A child can outlive its output, so with a deadline which hasn't
+        // passed yet it's polled rather than waited upon, which would block
+        // past the deadline.
+        if timer.is_some() && !timed_out {
+            let mut poll = Timer::new().expect("wait_with_output: could not create a Timer");
+            while self.try_wait().is_none() {
+                match port.try_recv() {
+                    Some(Deadline) => timed_out = self.kill_for_deadline(),
+                    Some(Data(*)) => unreachable!(),
+                    None => poll.sleep(DEADLINE_POLL_MS),
+                }
+            }
+        }
+        let status = self.wait();
+
+        ProcessOutput {
+            status: status,
+            output: output.unwrap(),
+            error: error.unwrap(),
+            timed_out: timed_out,
+        }
+    }
+
+    // Kills the child once the deadline of `wait_with_output` has passed,
+    // returning whether it was still running. The handle refuses to kill a
+    // child which it has already reaped.
+    fn kill_for_deadline(&mut self) -> bool {
+        self.try_wait().is_none() && self.handle.kill(MustDieSignal).is_ok()
+    }
 }
 
 impl Drop for Process {
@@ -170,7 +312,7 @@ impl Drop for Process {
             self.io.pop();
         }
 
-        self.wait();
+        let _ = self.wait();
     }
 }
 
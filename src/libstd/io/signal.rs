@@ -29,7 +29,7 @@ use result::{Err, Ok};
 use rt::rtio::{IoFactory, RtioSignal, with_local_io};
 
 #[repr(int)]
-#[deriving(Eq, IterBytes)]
+#[deriving(Eq, IterBytes, Hash)]
 pub enum Signum {
     /// Equivalent to SIGBREAK, delivered when the user presses Ctrl-Break.
     Break = 21i,
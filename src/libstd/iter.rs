@@ -1686,15 +1686,6 @@ impl<A, T: RandomAccessIterator<A>> RandomAccessIterator<A> for Fuse<T> {
     }
 }
 
-impl<T> Fuse<T> {
-    /// Resets the fuse such that the next call to .next() or .next_back() will
-    /// call the underlying iterator again even if it prevously returned None.
-    #[inline]
-    fn reset_fuse(&mut self) {
-        self.done = false
-    }
-}
-
 /// An iterator that calls a function with a reference to each
 /// element before yielding it.
 pub struct Inspect<'self, A, T> {
@@ -220,6 +220,7 @@ mod std {
     pub use cmp;
     pub use condition;
     pub use fmt;
+    pub use hash;
     pub use kinds;
     pub use local_data;
     pub use logging;
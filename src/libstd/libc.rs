@@ -76,6 +76,7 @@ pub use libc::types::common::posix01::*;
 pub use libc::types::common::posix08::*;
 pub use libc::types::common::bsd44::*;
 pub use libc::types::os::common::posix01::*;
+pub use libc::types::os::common::bsd44::*;
 pub use libc::types::os::arch::c95::*;
 pub use libc::types::os::arch::c99::*;
 pub use libc::types::os::arch::posix88::*;
@@ -105,12 +106,15 @@ pub use libc::funcs::posix88::dirent::*;
 pub use libc::funcs::posix88::unistd::*;
 pub use libc::funcs::posix88::mman::*;
 
+pub use libc::funcs::posix01::poll::*;
 pub use libc::funcs::posix01::stat_::*;
 pub use libc::funcs::posix01::unistd::*;
 pub use libc::funcs::posix01::glob::*;
 pub use libc::funcs::posix01::mman::*;
 pub use libc::funcs::posix08::unistd::*;
 
+pub use libc::funcs::bsd43::*;
+
 pub use libc::funcs::bsd44::*;
 pub use libc::funcs::extra::*;
 
@@ -211,7 +215,15 @@ pub mod types {
             pub enum DIR {}
             pub enum dirent_t {}
         }
-        pub mod posix01 {}
+        pub mod posix01 {
+            use libc::types::os::arch::c95::{c_int, c_short};
+
+            pub struct pollfd {
+                fd: c_int,
+                events: c_short,
+                revents: c_short,
+            }
+        }
         pub mod posix08 {}
         pub mod bsd44 {}
     }
@@ -227,6 +239,7 @@ pub mod types {
                 use libc::types::os::arch::c95::{c_char, c_ulong, size_t};
 
                 pub type pthread_t = c_ulong;
+                pub type nfds_t = c_ulong;
 
                 pub struct glob_t {
                     gl_pathc: size_t,
@@ -240,6 +253,64 @@ pub mod types {
                     __unused5: *c_void,
                 }
             }
+            pub mod bsd44 {
+                use libc::types::os::arch::c95::{c_char, c_int, c_uint};
+
+                pub type socklen_t = u32;
+                pub type sa_family_t = u16;
+                pub type in_port_t = u16;
+                pub type in_addr_t = u32;
+                pub struct sockaddr {
+                    sa_family: sa_family_t,
+                    sa_data: [u8, ..14],
+                }
+                pub struct sockaddr_storage {
+                    ss_family: sa_family_t,
+                    __ss_align: i64,
+                    __ss_pad2: [u8, ..112],
+                }
+                pub struct sockaddr_in {
+                    sin_family: sa_family_t,
+                    sin_port: in_port_t,
+                    sin_addr: in_addr,
+                    sin_zero: [u8, ..8],
+                }
+                pub struct in_addr {
+                    s_addr: in_addr_t,
+                }
+                pub struct sockaddr_in6 {
+                    sin6_family: sa_family_t,
+                    sin6_port: in_port_t,
+                    sin6_flowinfo: u32,
+                    sin6_addr: in6_addr,
+                    sin6_scope_id: u32,
+                }
+                pub struct in6_addr {
+                    s6_addr: [u16, ..8]
+                }
+                pub struct ip_mreq {
+                    imr_multiaddr: in_addr,
+                    imr_interface: in_addr,
+                }
+                pub struct ip6_mreq {
+                    ipv6mr_multiaddr: in6_addr,
+                    ipv6mr_interface: c_uint,
+                }
+                pub struct addrinfo {
+                    ai_flags: c_int,
+                    ai_family: c_int,
+                    ai_socktype: c_int,
+                    ai_protocol: c_int,
+                    ai_addrlen: socklen_t,
+                    ai_addr: *sockaddr,
+                    ai_canonname: *c_char,
+                    ai_next: *addrinfo,
+                }
+                pub struct sockaddr_un {
+                    sun_family: sa_family_t,
+                    sun_path: [c_char, ..108]
+                }
+            }
         }
 
         #[cfg(target_arch = "x86")]
@@ -517,10 +588,11 @@ pub mod types {
         pub mod common {
             pub mod posix01 {
                 use libc::types::common::c95::{c_void};
-                use libc::types::os::arch::c95::{c_char, c_int, size_t};
+                use libc::types::os::arch::c95::{c_char, c_int, c_uint, size_t};
                 use libc::types::os::arch::c99::{uintptr_t};
 
                 pub type pthread_t = uintptr_t;
+                pub type nfds_t = c_uint;
 
                 pub struct glob_t {
                     gl_pathc:  size_t,
@@ -538,6 +610,70 @@ pub mod types {
                     __unused8: *c_void,
                 }
             }
+            pub mod bsd44 {
+                use libc::types::os::arch::c95::{c_char, c_int, c_uint};
+
+                pub type socklen_t = c_int;
+                pub type sa_family_t = u8;
+                pub type in_port_t = u16;
+                pub type in_addr_t = u32;
+                pub struct sockaddr {
+                    sa_len: u8,
+                    sa_family: sa_family_t,
+                    sa_data: [u8, ..14],
+                }
+                pub struct sockaddr_storage {
+                    ss_len: u8,
+                    ss_family: sa_family_t,
+                    __ss_pad1: [u8, ..6],
+                    __ss_align: i64,
+                    __ss_pad2: [u8, ..112],
+                }
+                pub struct sockaddr_in {
+                    sin_len: u8,
+                    sin_family: sa_family_t,
+                    sin_port: in_port_t,
+                    sin_addr: in_addr,
+                    sin_zero: [u8, ..8],
+                }
+                pub struct in_addr {
+                    s_addr: in_addr_t,
+                }
+                pub struct sockaddr_in6 {
+                    sin6_len: u8,
+                    sin6_family: sa_family_t,
+                    sin6_port: in_port_t,
+                    sin6_flowinfo: u32,
+                    sin6_addr: in6_addr,
+                    sin6_scope_id: u32,
+                }
+                pub struct in6_addr {
+                    s6_addr: [u16, ..8]
+                }
+                pub struct ip_mreq {
+                    imr_multiaddr: in_addr,
+                    imr_interface: in_addr,
+                }
+                pub struct ip6_mreq {
+                    ipv6mr_multiaddr: in6_addr,
+                    ipv6mr_interface: c_uint,
+                }
+                pub struct addrinfo {
+                    ai_flags: c_int,
+                    ai_family: c_int,
+                    ai_socktype: c_int,
+                    ai_protocol: c_int,
+                    ai_addrlen: socklen_t,
+                    ai_canonname: *c_char,
+                    ai_addr: *sockaddr,
+                    ai_next: *addrinfo,
+                }
+                pub struct sockaddr_un {
+                    sun_len: u8,
+                    sun_family: sa_family_t,
+                    sun_path: [c_char, ..104]
+                }
+            }
         }
 
         #[cfg(target_arch = "x86_64")]
@@ -661,6 +797,98 @@ pub mod types {
                     modtime: time64_t,
                 }
             }
+
+            pub mod bsd44 {
+                use libc::types::os::arch::c95::{c_char, c_int, c_long, c_uint,
+                                                 size_t};
+
+                pub type SOCKET = uint;
+                pub type socklen_t = c_int;
+                pub type sa_family_t = u16;
+                pub type in_port_t = u16;
+                pub type in_addr_t = u32;
+                pub struct sockaddr {
+                    sa_family: sa_family_t,
+                    sa_data: [u8, ..14],
+                }
+                pub struct sockaddr_storage {
+                    ss_family: sa_family_t,
+                    __ss_pad1: [u8, ..6],
+                    __ss_align: i64,
+                    __ss_pad2: [u8, ..112],
+                }
+                pub struct sockaddr_in {
+                    sin_family: sa_family_t,
+                    sin_port: in_port_t,
+                    sin_addr: in_addr,
+                    sin_zero: [u8, ..8],
+                }
+                pub struct in_addr {
+                    s_addr: in_addr_t,
+                }
+                pub struct sockaddr_in6 {
+                    sin6_family: sa_family_t,
+                    sin6_port: in_port_t,
+                    sin6_flowinfo: u32,
+                    sin6_addr: in6_addr,
+                    sin6_scope_id: u32,
+                }
+                pub struct in6_addr {
+                    s6_addr: [u16, ..8]
+                }
+                pub struct ip_mreq {
+                    imr_multiaddr: in_addr,
+                    imr_interface: in_addr,
+                }
+                pub struct ip6_mreq {
+                    ipv6mr_multiaddr: in6_addr,
+                    ipv6mr_interface: c_uint,
+                }
+                pub struct addrinfo {
+                    ai_flags: c_int,
+                    ai_family: c_int,
+                    ai_socktype: c_int,
+                    ai_protocol: c_int,
+                    ai_addrlen: size_t,
+                    ai_canonname: *c_char,
+                    ai_addr: *sockaddr,
+                    ai_next: *addrinfo,
+                }
+                pub struct sockaddr_un {
+                    sun_family: sa_family_t,
+                    sun_path: [c_char, ..108]
+                }
+
+                #[cfg(target_arch = "x86")]
+                pub struct WSADATA {
+                    wVersion: u16,
+                    wHighVersion: u16,
+                    szDescription: [u8, ..257],
+                    szSystemStatus: [u8, ..129],
+                    iMaxSockets: u16,
+                    iMaxUdpDg: u16,
+                    lpVendorInfo: *u8,
+                }
+                #[cfg(target_arch = "x86_64")]
+                pub struct WSADATA {
+                    wVersion: u16,
+                    wHighVersion: u16,
+                    iMaxSockets: u16,
+                    iMaxUdpDg: u16,
+                    lpVendorInfo: *u8,
+                    szDescription: [u8, ..257],
+                    szSystemStatus: [u8, ..129],
+                }
+
+                pub struct fd_set {
+                    fd_count: c_uint,
+                    fd_array: [SOCKET, ..64],
+                }
+                pub struct timeval {
+                    tv_sec: c_long,
+                    tv_usec: c_long,
+                }
+            }
         }
 
         pub mod arch {
@@ -879,10 +1107,11 @@ pub mod types {
         pub mod common {
             pub mod posix01 {
                 use libc::types::common::c95::{c_void};
-                use libc::types::os::arch::c95::{c_char, c_int, size_t};
+                use libc::types::os::arch::c95::{c_char, c_int, c_uint, size_t};
                 use libc::types::os::arch::c99::{uintptr_t};
 
                 pub type pthread_t = uintptr_t;
+                pub type nfds_t = c_uint;
 
                 pub struct glob_t {
                     gl_pathc:  size_t,
@@ -900,6 +1129,70 @@ pub mod types {
                     __unused8: *c_void,
                 }
             }
+            pub mod bsd44 {
+                use libc::types::os::arch::c95::{c_char, c_int, c_uint};
+
+                pub type socklen_t = c_int;
+                pub type sa_family_t = u8;
+                pub type in_port_t = u16;
+                pub type in_addr_t = u32;
+                pub struct sockaddr {
+                    sa_len: u8,
+                    sa_family: sa_family_t,
+                    sa_data: [u8, ..14],
+                }
+                pub struct sockaddr_storage {
+                    ss_len: u8,
+                    ss_family: sa_family_t,
+                    __ss_pad1: [u8, ..6],
+                    __ss_align: i64,
+                    __ss_pad2: [u8, ..112],
+                }
+                pub struct sockaddr_in {
+                    sin_len: u8,
+                    sin_family: sa_family_t,
+                    sin_port: in_port_t,
+                    sin_addr: in_addr,
+                    sin_zero: [u8, ..8],
+                }
+                pub struct in_addr {
+                    s_addr: in_addr_t,
+                }
+                pub struct sockaddr_in6 {
+                    sin6_len: u8,
+                    sin6_family: sa_family_t,
+                    sin6_port: in_port_t,
+                    sin6_flowinfo: u32,
+                    sin6_addr: in6_addr,
+                    sin6_scope_id: u32,
+                }
+                pub struct in6_addr {
+                    s6_addr: [u16, ..8]
+                }
+                pub struct ip_mreq {
+                    imr_multiaddr: in_addr,
+                    imr_interface: in_addr,
+                }
+                pub struct ip6_mreq {
+                    ipv6mr_multiaddr: in6_addr,
+                    ipv6mr_interface: c_uint,
+                }
+                pub struct addrinfo {
+                    ai_flags: c_int,
+                    ai_family: c_int,
+                    ai_socktype: c_int,
+                    ai_protocol: c_int,
+                    ai_addrlen: socklen_t,
+                    ai_canonname: *c_char,
+                    ai_addr: *sockaddr,
+                    ai_next: *addrinfo,
+                }
+                pub struct sockaddr_un {
+                    sun_len: u8,
+                    sun_family: sa_family_t,
+                    sun_path: [c_char, ..104]
+                }
+            }
         }
 
         #[cfg(target_arch = "x86")]
@@ -1149,6 +1442,48 @@ pub mod consts {
         pub mod posix08 {
         }
         pub mod bsd44 {
+            use libc::types::os::arch::c95::{c_int, c_long};
+
+            pub static AF_INET: c_int = 2;
+            pub static AF_INET6: c_int = 23;
+            pub static SOCK_STREAM: c_int = 1;
+            pub static SOCK_DGRAM: c_int = 2;
+
+            pub static IPPROTO_TCP: c_int = 6;
+            pub static IPPROTO_IP: c_int = 0;
+            pub static IPPROTO_IPV6: c_int = 41;
+            pub static IP_MULTICAST_IF: c_int = 9;
+            pub static IP_MULTICAST_TTL: c_int = 10;
+            pub static IP_MULTICAST_LOOP: c_int = 11;
+            pub static IP_TTL: c_int = 4;
+            pub static IP_ADD_MEMBERSHIP: c_int = 12;
+            pub static IP_DROP_MEMBERSHIP: c_int = 13;
+            pub static IPV6_ADD_MEMBERSHIP: c_int = 5;
+            pub static IPV6_DROP_MEMBERSHIP: c_int = 6;
+            pub static IPV6_V6ONLY: c_int = 27;
+
+            pub static TCP_NODELAY: c_int = 1;
+            pub static SOL_SOCKET: c_int = 0xffff;
+            pub static SO_KEEPALIVE: c_int = 8;
+            pub static SO_BROADCAST: c_int = 32;
+            pub static SO_REUSEADDR: c_int = 4;
+            pub static SO_SNDBUF: c_int = 0x1001;
+            pub static SO_RCVBUF: c_int = 0x1002;
+            pub static SO_ERROR: c_int = 0x1007;
+
+            pub static SHUT_RD: c_int = 0;
+            pub static SHUT_WR: c_int = 1;
+            pub static SHUT_RDWR: c_int = 2;
+
+            pub static WSAEWOULDBLOCK: c_int = 10035;
+            pub static WSAEACCES: c_int = 10013;
+            pub static WSAECONNABORTED: c_int = 10053;
+            pub static WSAECONNRESET: c_int = 10054;
+            pub static WSAENOTCONN: c_int = 10057;
+            pub static WSAETIMEDOUT: c_int = 10060;
+            pub static WSAECONNREFUSED: c_int = 10061;
+
+            pub static FIONBIO: c_long = 0x8004667eu32 as c_long;
         }
         pub mod extra {
             use libc::types::os::arch::c95::c_int;
@@ -1366,6 +1701,9 @@ pub mod consts {
             pub static O_CREAT : c_int = 64;
             pub static O_EXCL : c_int = 128;
             pub static O_TRUNC : c_int = 512;
+            pub static O_NONBLOCK : c_int = 2048;
+            pub static F_GETFL : c_int = 3;
+            pub static F_SETFL : c_int = 4;
             pub static S_IFIFO : c_int = 4096;
             pub static S_IFCHR : c_int = 8192;
             pub static S_IFBLK : c_int = 24576;
@@ -1577,6 +1915,9 @@ pub mod consts {
             pub static O_CREAT : c_int = 256;
             pub static O_EXCL : c_int = 1024;
             pub static O_TRUNC : c_int = 512;
+            pub static O_NONBLOCK : c_int = 128;
+            pub static F_GETFL : c_int = 3;
+            pub static F_SETFL : c_int = 4;
             pub static S_IFIFO : c_int = 4096;
             pub static S_IFCHR : c_int = 8192;
             pub static S_IFBLK : c_int = 24576;
@@ -1775,10 +2116,17 @@ pub mod consts {
             pub static EDQUOT: c_int = 1133;
         }
         pub mod posix01 {
-            use libc::types::os::arch::c95::c_int;
+            use libc::types::os::arch::c95::{c_int, c_short};
 
             pub static SIGTRAP : c_int = 5;
 
+            pub static POLLIN   : c_short = 0x0001;
+            pub static POLLPRI  : c_short = 0x0002;
+            pub static POLLOUT  : c_short = 0x0004;
+            pub static POLLERR  : c_short = 0x0008;
+            pub static POLLHUP  : c_short = 0x0010;
+            pub static POLLNVAL : c_short = 0x0020;
+
             pub static GLOB_ERR      : c_int = 1 << 0;
             pub static GLOB_MARK     : c_int = 1 << 1;
             pub static GLOB_NOSORT   : c_int = 1 << 2;
@@ -1845,6 +2193,41 @@ pub mod consts {
             pub static MADV_MERGEABLE : c_int = 12;
             pub static MADV_UNMERGEABLE : c_int = 13;
             pub static MADV_HWPOISON : c_int = 100;
+
+            pub static AF_UNIX: c_int = 1;
+            pub static AF_INET: c_int = 2;
+            pub static AF_INET6: c_int = 10;
+            pub static SOCK_STREAM: c_int = 1;
+            pub static SOCK_DGRAM: c_int = 2;
+
+            pub static IPPROTO_TCP: c_int = 6;
+            pub static IPPROTO_IP: c_int = 0;
+            pub static IPPROTO_IPV6: c_int = 41;
+            pub static IP_MULTICAST_IF: c_int = 32;
+            pub static IP_MULTICAST_TTL: c_int = 33;
+            pub static IP_MULTICAST_LOOP: c_int = 34;
+            pub static IP_TTL: c_int = 2;
+            pub static IP_ADD_MEMBERSHIP: c_int = 35;
+            pub static IP_DROP_MEMBERSHIP: c_int = 36;
+            pub static IPV6_ADD_MEMBERSHIP: c_int = 20;
+            pub static IPV6_DROP_MEMBERSHIP: c_int = 21;
+            pub static IPV6_V6ONLY: c_int = 26;
+
+            pub static TCP_NODELAY: c_int = 1;
+            pub static TCP_KEEPIDLE: c_int = 4;
+            pub static SOL_SOCKET: c_int = 1;
+            pub static SO_KEEPALIVE: c_int = 9;
+            pub static SO_BROADCAST: c_int = 6;
+            pub static SO_REUSEADDR: c_int = 2;
+            pub static SO_SNDBUF: c_int = 7;
+            pub static SO_RCVBUF: c_int = 8;
+            pub static SO_ERROR: c_int = 4;
+
+            pub static SHUT_RD: c_int = 0;
+            pub static SHUT_WR: c_int = 1;
+            pub static SHUT_RDWR: c_int = 2;
+
+            pub static MSG_DONTWAIT: c_int = 0x40;
         }
         #[cfg(target_arch = "x86")]
         #[cfg(target_arch = "x86_64")]
@@ -2025,6 +2408,9 @@ pub mod consts {
             pub static O_CREAT : c_int = 512;
             pub static O_EXCL : c_int = 2048;
             pub static O_TRUNC : c_int = 1024;
+            pub static O_NONBLOCK : c_int = 4;
+            pub static F_GETFL : c_int = 3;
+            pub static F_SETFL : c_int = 4;
             pub static S_IFIFO : c_int = 4096;
             pub static S_IFCHR : c_int = 8192;
             pub static S_IFBLK : c_int = 24576;
@@ -2185,10 +2571,17 @@ pub mod consts {
             pub static ELAST : c_int = 99;
         }
         pub mod posix01 {
-            use libc::types::os::arch::c95::c_int;
+            use libc::types::os::arch::c95::{c_int, c_short};
 
             pub static SIGTRAP : c_int = 5;
 
+            pub static POLLIN   : c_short = 0x0001;
+            pub static POLLPRI  : c_short = 0x0002;
+            pub static POLLOUT  : c_short = 0x0004;
+            pub static POLLERR  : c_short = 0x0008;
+            pub static POLLHUP  : c_short = 0x0010;
+            pub static POLLNVAL : c_short = 0x0020;
+
             pub static GLOB_APPEND   : c_int = 0x0001;
             pub static GLOB_DOOFFS   : c_int = 0x0002;
             pub static GLOB_ERR      : c_int = 0x0004;
@@ -2262,6 +2655,41 @@ pub mod consts {
             pub static MINCORE_REFERENCED_OTHER : c_int = 0x8;
             pub static MINCORE_MODIFIED_OTHER : c_int = 0x10;
             pub static MINCORE_SUPER : c_int = 0x20;
+
+            pub static AF_UNIX: c_int = 1;
+            pub static AF_INET: c_int = 2;
+            pub static AF_INET6: c_int = 28;
+            pub static SOCK_STREAM: c_int = 1;
+            pub static SOCK_DGRAM: c_int = 2;
+
+            pub static IPPROTO_TCP: c_int = 6;
+            pub static IPPROTO_IP: c_int = 0;
+            pub static IPPROTO_IPV6: c_int = 41;
+            pub static IP_MULTICAST_IF: c_int = 9;
+            pub static IP_MULTICAST_TTL: c_int = 10;
+            pub static IP_MULTICAST_LOOP: c_int = 11;
+            pub static IP_TTL: c_int = 4;
+            pub static IP_ADD_MEMBERSHIP: c_int = 12;
+            pub static IP_DROP_MEMBERSHIP: c_int = 13;
+            pub static IPV6_ADD_MEMBERSHIP: c_int = 12;
+            pub static IPV6_DROP_MEMBERSHIP: c_int = 13;
+            pub static IPV6_V6ONLY: c_int = 27;
+
+            pub static TCP_NODELAY: c_int = 1;
+            pub static TCP_KEEPIDLE: c_int = 256;
+            pub static SOL_SOCKET: c_int = 0xffff;
+            pub static SO_KEEPALIVE: c_int = 0x0008;
+            pub static SO_BROADCAST: c_int = 0x0020;
+            pub static SO_REUSEADDR: c_int = 0x0004;
+            pub static SO_SNDBUF: c_int = 0x1001;
+            pub static SO_RCVBUF: c_int = 0x1002;
+            pub static SO_ERROR: c_int = 0x1007;
+
+            pub static SHUT_RD: c_int = 0;
+            pub static SHUT_WR: c_int = 1;
+            pub static SHUT_RDWR: c_int = 2;
+
+            pub static MSG_DONTWAIT: c_int = 0x80;
         }
         pub mod extra {
             use libc::types::os::arch::c95::c_int;
@@ -2371,6 +2799,9 @@ pub mod consts {
             pub static O_CREAT : c_int = 512;
             pub static O_EXCL : c_int = 2048;
             pub static O_TRUNC : c_int = 1024;
+            pub static O_NONBLOCK : c_int = 4;
+            pub static F_GETFL : c_int = 3;
+            pub static F_SETFL : c_int = 4;
             pub static S_IFIFO : c_int = 4096;
             pub static S_IFCHR : c_int = 8192;
             pub static S_IFBLK : c_int = 24576;
@@ -2541,10 +2972,17 @@ pub mod consts {
             pub static ELAST : c_int = 106;
         }
         pub mod posix01 {
-            use libc::types::os::arch::c95::c_int;
+            use libc::types::os::arch::c95::{c_int, c_short};
 
             pub static SIGTRAP : c_int = 5;
 
+            pub static POLLIN   : c_short = 0x0001;
+            pub static POLLPRI  : c_short = 0x0002;
+            pub static POLLOUT  : c_short = 0x0004;
+            pub static POLLERR  : c_short = 0x0008;
+            pub static POLLHUP  : c_short = 0x0010;
+            pub static POLLNVAL : c_short = 0x0020;
+
             pub static GLOB_APPEND   : c_int = 0x0001;
             pub static GLOB_DOOFFS   : c_int = 0x0002;
             pub static GLOB_ERR      : c_int = 0x0004;
@@ -2616,6 +3054,41 @@ pub mod consts {
             pub static MINCORE_MODIFIED : c_int = 0x4;
             pub static MINCORE_REFERENCED_OTHER : c_int = 0x8;
             pub static MINCORE_MODIFIED_OTHER : c_int = 0x10;
+
+            pub static AF_UNIX: c_int = 1;
+            pub static AF_INET: c_int = 2;
+            pub static AF_INET6: c_int = 30;
+            pub static SOCK_STREAM: c_int = 1;
+            pub static SOCK_DGRAM: c_int = 2;
+
+            pub static IPPROTO_TCP: c_int = 6;
+            pub static IPPROTO_IP: c_int = 0;
+            pub static IPPROTO_IPV6: c_int = 41;
+            pub static IP_MULTICAST_IF: c_int = 9;
+            pub static IP_MULTICAST_TTL: c_int = 10;
+            pub static IP_MULTICAST_LOOP: c_int = 11;
+            pub static IP_TTL: c_int = 4;
+            pub static IP_ADD_MEMBERSHIP: c_int = 12;
+            pub static IP_DROP_MEMBERSHIP: c_int = 13;
+            pub static IPV6_ADD_MEMBERSHIP: c_int = 12;
+            pub static IPV6_DROP_MEMBERSHIP: c_int = 13;
+            pub static IPV6_V6ONLY: c_int = 27;
+
+            pub static TCP_NODELAY: c_int = 1;
+            pub static TCP_KEEPALIVE: c_int = 0x10;
+            pub static SOL_SOCKET: c_int = 0xffff;
+            pub static SO_KEEPALIVE: c_int = 0x0008;
+            pub static SO_BROADCAST: c_int = 0x0020;
+            pub static SO_REUSEADDR: c_int = 0x0004;
+            pub static SO_SNDBUF: c_int = 0x1001;
+            pub static SO_RCVBUF: c_int = 0x1002;
+            pub static SO_ERROR: c_int = 0x1007;
+
+            pub static SHUT_RD: c_int = 0;
+            pub static SHUT_WR: c_int = 1;
+            pub static SHUT_RDWR: c_int = 2;
+
+            pub static MSG_DONTWAIT: c_int = 0x80;
         }
         pub mod extra {
             use libc::types::os::arch::c95::c_int;
@@ -3035,7 +3508,7 @@ pub mod funcs {
                 pub fn open(path: *c_char, oflag: c_int, mode: c_int)
                             -> c_int;
                 pub fn creat(path: *c_char, mode: mode_t) -> c_int;
-                pub fn fcntl(fd: c_int, cmd: c_int) -> c_int;
+                pub fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
             }
         }
 
@@ -3185,6 +3658,18 @@ pub mod funcs {
     #[cfg(target_os = "macos")]
     #[cfg(target_os = "freebsd")]
     pub mod posix01 {
+        #[nolink]
+        pub mod poll {
+            use libc::types::common::posix01::pollfd;
+            use libc::types::os::arch::c95::c_int;
+            use libc::types::os::common::posix01::nfds_t;
+
+            extern {
+                pub fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int)
+                            -> c_int;
+            }
+        }
+
         #[nolink]
         pub mod stat_ {
             use libc::types::os::arch::c95::{c_char, c_int};
@@ -3272,6 +3757,9 @@ pub mod funcs {
 
     #[cfg(target_os = "win32")]
     pub mod posix01 {
+        pub mod poll {
+        }
+
         pub mod stat_ {
         }
 
@@ -3297,6 +3785,104 @@ pub mod funcs {
     }
 
 
+    #[cfg(not(windows))]
+    pub mod bsd43 {
+        use libc::types::common::c95::{c_void};
+        use libc::types::os::common::bsd44::{socklen_t, sockaddr, addrinfo};
+        use libc::types::os::arch::c95::{c_int, c_char, size_t};
+        use libc::types::os::arch::posix88::ssize_t;
+
+        extern "system" {
+            pub fn socket(domain: c_int, ty: c_int, protocol: c_int) -> c_int;
+            pub fn connect(socket: c_int, address: *sockaddr,
+                           len: socklen_t) -> c_int;
+            pub fn bind(socket: c_int, address: *sockaddr,
+                        address_len: socklen_t) -> c_int;
+            pub fn listen(socket: c_int, backlog: c_int) -> c_int;
+            pub fn accept(socket: c_int, address: *mut sockaddr,
+                          address_len: *mut socklen_t) -> c_int;
+            pub fn getpeername(socket: c_int, address: *mut sockaddr,
+                               address_len: *mut socklen_t) -> c_int;
+            pub fn getsockname(socket: c_int, address: *mut sockaddr,
+                               address_len: *mut socklen_t) -> c_int;
+            pub fn setsockopt(socket: c_int, level: c_int, name: c_int,
+                              value: *c_void,
+                              option_len: socklen_t) -> c_int;
+            pub fn getsockopt(socket: c_int, level: c_int, name: c_int,
+                              value: *mut c_void,
+                              option_len: *mut socklen_t) -> c_int;
+            pub fn recv(socket: c_int, buf: *mut c_void, len: size_t,
+                        flags: c_int) -> ssize_t;
+            pub fn send(socket: c_int, buf: *c_void, len: size_t,
+                        flags: c_int) -> ssize_t;
+            pub fn recvfrom(socket: c_int, buf: *mut c_void, len: size_t,
+                            flags: c_int, addr: *mut sockaddr,
+                            addrlen: *mut socklen_t) -> ssize_t;
+            pub fn sendto(socket: c_int, buf: *c_void, len: size_t,
+                          flags: c_int, addr: *sockaddr,
+                          addrlen: socklen_t) -> ssize_t;
+            pub fn shutdown(socket: c_int, how: c_int) -> c_int;
+            pub fn getaddrinfo(node: *c_char, service: *c_char,
+                               hints: *addrinfo,
+                               res: *mut *addrinfo) -> c_int;
+            pub fn freeaddrinfo(res: *addrinfo);
+            pub fn gai_strerror(errcode: c_int) -> *c_char;
+        }
+    }
+
+    #[cfg(windows)]
+    pub mod bsd43 {
+        use libc::types::common::c95::{c_void};
+        use libc::types::os::common::bsd44::{socklen_t, sockaddr, addrinfo,
+                                             SOCKET, WSADATA, fd_set, timeval};
+        use libc::types::os::arch::c95::{c_int, c_char, c_long, c_ulong};
+
+        #[link_args = "-lws2_32"]
+        extern "system" {
+            pub fn WSAStartup(wVersionRequested: u16,
+                              lpWSAData: *mut WSADATA) -> c_int;
+            pub fn WSAGetLastError() -> c_int;
+            pub fn ioctlsocket(s: SOCKET, cmd: c_long, argp: *mut c_ulong) -> c_int;
+            pub fn select(nfds: c_int, readfds: *mut fd_set, writefds: *mut fd_set,
+                          exceptfds: *mut fd_set, timeout: *timeval) -> c_int;
+
+            pub fn socket(domain: c_int, ty: c_int, protocol: c_int) -> SOCKET;
+            pub fn connect(socket: SOCKET, address: *sockaddr,
+                           len: socklen_t) -> c_int;
+            pub fn bind(socket: SOCKET, address: *sockaddr,
+                        address_len: socklen_t) -> c_int;
+            pub fn listen(socket: SOCKET, backlog: c_int) -> c_int;
+            pub fn accept(socket: SOCKET, address: *mut sockaddr,
+                          address_len: *mut socklen_t) -> SOCKET;
+            pub fn getpeername(socket: SOCKET, address: *mut sockaddr,
+                               address_len: *mut socklen_t) -> c_int;
+            pub fn getsockname(socket: SOCKET, address: *mut sockaddr,
+                               address_len: *mut socklen_t) -> c_int;
+            pub fn setsockopt(socket: SOCKET, level: c_int, name: c_int,
+                              value: *c_void,
+                              option_len: socklen_t) -> c_int;
+            pub fn getsockopt(socket: SOCKET, level: c_int, name: c_int,
+                              value: *mut c_void,
+                              option_len: *mut socklen_t) -> c_int;
+            pub fn closesocket(socket: SOCKET) -> c_int;
+            pub fn recv(socket: SOCKET, buf: *mut c_void, len: c_int,
+                        flags: c_int) -> c_int;
+            pub fn send(socket: SOCKET, buf: *c_void, len: c_int,
+                        flags: c_int) -> c_int;
+            pub fn recvfrom(socket: SOCKET, buf: *mut c_void, len: c_int,
+                            flags: c_int, addr: *mut sockaddr,
+                            addrlen: *mut c_int) -> c_int;
+            pub fn sendto(socket: SOCKET, buf: *c_void, len: c_int,
+                          flags: c_int, addr: *sockaddr,
+                          addrlen: c_int) -> c_int;
+            pub fn shutdown(socket: SOCKET, how: c_int) -> c_int;
+            pub fn getaddrinfo(node: *c_char, service: *c_char,
+                               hints: *addrinfo,
+                               res: *mut *addrinfo) -> c_int;
+            pub fn freeaddrinfo(res: *addrinfo);
+        }
+    }
+
     #[cfg(target_os = "macos")]
     #[cfg(target_os = "freebsd")]
     pub mod bsd44 {
@@ -3452,6 +4038,13 @@ pub mod funcs {
                 pub fn WaitForSingleObject(hHandle: HANDLE,
                                            dwMilliseconds: DWORD)
                                            -> DWORD;
+                pub fn CreateEventW(lpEventAttributes: LPSECURITY_ATTRIBUTES,
+                                    bManualReset: BOOL,
+                                    bInitialState: BOOL,
+                                    lpName: LPCWSTR)
+                                    -> HANDLE;
+                pub fn SetEvent(hEvent: HANDLE) -> BOOL;
+                pub fn Sleep(dwMilliseconds: DWORD);
                 pub fn TerminateProcess(hProcess: HANDLE, uExitCode: c_uint)
                                         -> BOOL;
                 pub fn GetExitCodeProcess(hProcess: HANDLE,
@@ -3493,6 +4086,8 @@ pub mod funcs {
                                      dwNumberOfBytesToMap: SIZE_T)
                                      -> LPVOID;
                 pub fn UnmapViewOfFile(lpBaseAddress: LPCVOID) -> BOOL;
+                pub fn FlushViewOfFile(lpBaseAddress: LPCVOID,
+                                       dwNumberOfBytesToFlush: SIZE_T) -> BOOL;
                 pub fn MoveFileExW(lpExistingFileName: LPCWSTR,
                                    lpNewFileName: LPCWSTR,
                                    dwFlags: DWORD) -> BOOL;
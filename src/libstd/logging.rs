@@ -74,7 +74,8 @@ Each of these macros will expand to code similar to:
 
 ```rust
 if log_level <= my_module_log_level() {
-    ::std::logging::log(log_level, format!(...));
+    ::std::logging::log_at(log_level, module_path!(), file!(), line!(),
+                           format_args!(...));
 }
 ```
 
@@ -82,6 +83,40 @@ What this means is that each of these macros are very cheap at runtime if
 they're turned off (just a load and an integer comparison). This also means that
 if logging is disabled, none of the components of the log will be executed.
 
+## Changing the log levels at runtime
+
+`set_log_spec` takes a string of the same form as `RUST_LOG` and applies it to
+all modules, replacing the levels set before.
+
+## Custom loggers
+
+Every task writes its log messages to a `Logger`, which is handed a
+`LogRecord` holding the message along with its level, module path, file and
+line. The module path is relative to the crate root, so it is empty for
+messages logged at the root. By default this logger writes just the message to stderr. A task can
+install a logger of its own with `set_logger`; the logger's `inherit` method
+decides what tasks spawned from that task use, which makes it a good place to
+pass down context such as a request id. Tasks without a logger of their own
+create one with the function passed to `set_default_logger`.
+
+```rust
+struct RequestLogger { request: uint, out: SharedChan<~str> }
+
+impl Logger for RequestLogger {
+    fn log(&mut self, record: &LogRecord) {
+        self.out.send(format!("[request {}] {}:{}: {}", self.request,
+                              record.module_path, record.line,
+                              fmt::format(record.args)));
+    }
+    fn inherit(&self) -> Option<~Logger> {
+        Some(~RequestLogger { request: self.request, out: self.out.clone() }
+             as ~Logger)
+    }
+}
+
+set_logger(~RequestLogger { request: 42, out: chan } as ~Logger);
+```
+
 ## Useful Values
 
 For convenience, if a value of `::help` is set for `RUST_LOG`, a program will
@@ -89,11 +124,20 @@ start, print out all modules registered for logging, and then exit.
 
 */
 
+use cast;
 use fmt;
 use option::*;
 use rt::local::Local;
-use rt::logging::{Logger, StdErrLogger};
+use rt::logging::StdErrLogger;
+use rt::logging;
 use rt::task::Task;
+use unstable::atomics::{AtomicUint, INIT_ATOMIC_UINT, SeqCst};
+use util;
+
+pub use rt::logging::{LogRecord, Logger};
+
+// The function set with `set_default_logger`, or 0 for StdErrLogger
+static mut DEFAULT_LOGGER: AtomicUint = INIT_ATOMIC_UINT;
 
 /// This function is called directly by the compiler when using the logging
 /// macros. This function does not take into account whether the log level
@@ -102,25 +146,75 @@ use rt::task::Task;
 ///
 /// It is not recommended to call this function directly, rather it should be
 /// invoked through the logging family of macros.
-pub fn log(_level: u32, args: &fmt::Arguments) {
+pub fn log_at(level: u32, module_path: &str, file: &str, line: uint,
+              args: &fmt::Arguments) {
+    let record = LogRecord {
+        level: level,
+        module_path: module_path,
+        file: file,
+        line: line,
+        args: args,
+    };
     unsafe {
         let optional_task: Option<*mut Task> = Local::try_unsafe_borrow();
         match optional_task {
             Some(local) => {
-                match (*local).logger {
-                    // Use the available logger if we have one
-                    Some(ref mut logger) => return logger.log(args),
-                    None => {
-                        let mut logger = StdErrLogger::new();
-                        logger.log(args);
-                        (*local).logger = Some(logger);
-                    }
+                if (*local).logger.is_none() {
+                    (*local).logger = Some(new_default_logger());
                 }
+                (*local).logger.get_mut_ref().log(&record);
+            }
+            // There is no task to keep a logger in
+            None => new_default_logger().log(&record)
+        }
+    }
+}
+
+/// The entry point of the logging macros of older compilers, which don't
+/// pass the location of the message along.
+// FIXME: remove after a snapshot
+pub fn log(level: u32, args: &fmt::Arguments) {
+    log_at(level, "<unknown>", "<unknown>", 0, args)
+}
+
+/// Installs `logger` as the current task's logger, returning the one it
+/// replaces. Tasks spawned from now on start with the logger returned by
+/// `logger.inherit()`.
+pub fn set_logger(logger: ~Logger) -> Option<~Logger> {
+    unsafe {
+        let optional_task: Option<*mut Task> = Local::try_unsafe_borrow();
+        match optional_task {
+            Some(local) => util::replace(&mut (*local).logger, Some(logger)),
+            None => fail!("can't set a logger outside of a task")
+        }
+    }
+}
+
+/// Sets the function that creates the logger of every task that has none
+/// of its own, i.e. that neither installed one with `set_logger` nor
+/// inherited one. Without a call to this function, tasks log to stderr.
+///
+/// Tasks that have logged already keep the logger they have.
+pub fn set_default_logger(new_logger: fn() -> ~Logger) {
+    unsafe { DEFAULT_LOGGER.store(cast::transmute(new_logger), SeqCst) }
+}
+
+fn new_default_logger() -> ~Logger {
+    unsafe {
+        match DEFAULT_LOGGER.load(SeqCst) {
+            0 => ~StdErrLogger::new() as ~Logger,
+            f => {
+                let new_logger: fn() -> ~Logger = cast::transmute(f);
+                new_logger()
             }
-            None => {}
         }
-        // There is no logger anywhere, just write to stderr
-        let mut logger = StdErrLogger::new();
-        logger.log(args);
     }
 }
+
+/// Changes which messages get logged while the program is running. `spec`
+/// takes the same form as the `RUST_LOG` environment variable, and
+/// replaces whatever that variable set. Unlike `RUST_LOG`, `::help` doesn't
+/// list the modules and exit: it is ignored.
+pub fn set_log_spec(spec: &str) {
+    logging::set_log_spec(spec)
+}
@@ -787,10 +787,6 @@ extern "system" {
     fn CommandLineToArgvW(lpCmdLine: LPCWSTR, pNumArgs: *mut c_int) -> **u16;
 }
 
-struct OverriddenArgs {
-    val: ~[~str]
-}
-
 /// Returns the arguments which this program was started with (normally passed
 /// via the command line).
 pub fn args() -> ~[~str] {
@@ -874,7 +870,11 @@ pub enum MapOption {
     /// Create a memory mapping for a file with a given fd.
     MapFd(c_int),
     /// When using `MapFd`, the start of the map is `uint` bytes from the start of the file.
-    MapOffset(uint)
+    MapOffset(uint),
+    /// When using `MapFd`, writes to the memory are carried through to the file, and changes made
+    /// to the file by others are visible in the memory. Corresponds to `MAP_SHARED` on POSIX (the
+    /// default being `MAP_PRIVATE`). File mappings on Windows are always shared.
+    MapShared
 }
 
 /// Possible errors when creating a map.
@@ -960,6 +960,9 @@ impl MemoryMap {
                     fd = fd_;
                 },
                 MapOffset(offset_) => { offset = offset_ as off_t; }
+                MapShared => {
+                    flags = (flags & !libc::MAP_PRIVATE) | libc::MAP_SHARED;
+                }
             }
         }
         if fd == -1 { flags |= libc::MAP_ANON; }
@@ -1034,6 +1037,7 @@ impl MemoryMap {
                 MapAddr(addr_) => { lpAddress = addr_ as LPVOID; },
                 MapFd(fd_) => { fd = fd_; },
                 MapOffset(offset_) => { offset = offset_; }
+                MapShared => {}
             }
         }
 
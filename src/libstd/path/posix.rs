@@ -19,6 +19,7 @@ use iter::{AdditiveIterator, Extendable, Iterator, Map};
 use option::{Option, None, Some};
 use str;
 use str::Str;
+use hash::{Hash, Hasher};
 use to_bytes::IterBytes;
 use vec;
 use vec::{CopyableVector, RSplitIterator, SplitIterator, Vector, VectorVector};
@@ -92,6 +93,13 @@ impl IterBytes for Path {
     }
 }
 
+impl Hash for Path {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.repr.hash(state)
+    }
+}
+
 impl BytesContainer for Path {
     #[inline]
     fn container_as_bytes<'a>(&'a self) -> &'a [u8] {
@@ -19,6 +19,7 @@ use iter::{AdditiveIterator, DoubleEndedIterator, Extendable, Invert, Iterator,
 use option::{Option, Some, None};
 use str;
 use str::{CharSplitIterator, OwnedStr, Str, StrVector};
+use hash::{Hash, Hasher};
 use to_bytes::IterBytes;
 use vec::Vector;
 use super::{contains_nul, BytesContainer, GenericPath, GenericPathUnsafe};
@@ -117,6 +118,13 @@ impl IterBytes for Path {
     }
 }
 
+impl Hash for Path {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.repr.hash(state)
+    }
+}
+
 impl BytesContainer for Path {
     #[inline]
     fn container_as_bytes<'a>(&'a self) -> &'a [u8] {
@@ -1049,11 +1057,6 @@ fn prefix_len(p: Option<PathPrefix>) -> uint {
     }
 }
 
-fn prefix_is_sep(p: Option<PathPrefix>, c: u8) -> bool {
-    c.is_ascii() && if !prefix_is_verbatim(p) { is_sep(c as char) }
-                    else { is_sep_verbatim(c as char) }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
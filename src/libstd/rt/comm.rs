@@ -16,18 +16,26 @@ use ops::Drop;
 use rt::kill::BlockedTask;
 use kinds::Send;
 use rt;
-use rt::sched::Scheduler;
+use rt::in_green_task_context;
+use rt::sched::{Scheduler, SchedHandle, TaskFromFriend};
 use rt::local::Local;
 use rt::select::{SelectInner, SelectPortInner};
 use select::{Select, SelectPort};
 use unstable::atomics::{AtomicUint, AtomicOption, Acquire, Relaxed, SeqCst};
-use unstable::sync::UnsafeArc;
+use unstable::mutex::Mutex;
+use unstable::sync::{UnsafeArc, Exclusive};
 use util;
 use util::Void;
 use comm::{GenericChan, GenericSmartChan, GenericPort, Peekable, SendDeferred};
 use cell::{Cell, RefCell};
 use clone::Clone;
+use container::Container;
+use io::timer::Timer;
+use iter::Iterator;
+use result::{Result, Ok, Err};
 use tuple::ImmutableTuple;
+use vec;
+use vec::OwnedVector;
 
 /// A combined refcount / BlockedTask-as-uint pointer.
 ///
@@ -714,12 +722,490 @@ impl<T: Send> SendDeferred<T> for MegaPipe<T> {
     }
 }
 
+/// Something parked on a bounded channel, waiting for room to send or for
+/// a message to receive.
+enum Waiter {
+    // A green task. The handle to its scheduler lets a native thread, which
+    // has no scheduler to put the task on, send it back home. It's the
+    // scheduler's shared handle, so parking doesn't allocate a new one.
+    GreenWaiter(BlockedTask, UnsafeArc<SchedHandle>),
+    // A native thread, asleep on a condition variable of its own.
+    NativeWaiter(UnsafeArc<NativeWait>)
+}
+
+struct NativeWait {
+    lock: Mutex,
+    woken: bool
+}
+
+impl Drop for NativeWait {
+    fn drop(&mut self) {
+        unsafe { self.lock.destroy() }
+    }
+}
+
+impl Waiter {
+    fn wake(self) {
+        match self {
+            GreenWaiter(task, handle) => {
+                // A selecting or timed-out receiver may be awake already.
+                task.wake().map(|task| {
+                    if in_green_task_context() {
+                        Scheduler::run_task_later(task);
+                    } else {
+                        unsafe { (*handle.get()).send(TaskFromFriend(task)); }
+                    }
+                });
+            }
+            NativeWaiter(wait) => unsafe {
+                let wait = wait.get();
+                (*wait).lock.lock();
+                (*wait).woken = true;
+                (*wait).lock.signal();
+                (*wait).lock.unlock();
+            }
+        }
+    }
+
+    fn assert_already_awake(self) {
+        match self {
+            GreenWaiter(task, _) => task.assert_already_awake(),
+            NativeWaiter(_) => {}
+        }
+    }
+}
+
+/// What a sender or the receiver of a bounded channel is waiting for.
+enum WaitFor {
+    WaitForRoom,
+    WaitForData
+}
+
+/// The state shared by all endpoints of a bounded channel.
+struct BoundedState<T> {
+    // A ring buffer of queued messages, as long as the channel's capacity
+    buf: ~[Option<T>],
+    start: uint,
+    size: uint,
+    // The number of live BoundedChans
+    senders: uint,
+    port_alive: bool,
+    blocked_senders: ~[Waiter],
+    blocked_port: Option<Waiter>
+}
+
+impl<T: Send> BoundedState<T> {
+    fn is_full(&self) -> bool {
+        self.size == self.buf.len()
+    }
+
+    fn push(&mut self, val: T) {
+        let end = (self.start + self.size) % self.buf.len();
+        self.buf[end] = Some(val);
+        self.size += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        let val = self.buf[self.start].take();
+        self.start = (self.start + 1) % self.buf.len();
+        self.size -= 1;
+        val
+    }
+
+    /// Whether whoever waits for `what` can stop waiting: there is room or
+    /// data, or there's nobody left at the other end.
+    fn ready(&self, what: WaitFor) -> bool {
+        match what {
+            WaitForRoom => !self.port_alive || !self.is_full(),
+            WaitForData => self.senders == 0 || self.size > 0
+        }
+    }
+
+    /// Registers `waiter` to be woken up once `what` happens, or hands it
+    /// back if it already has.
+    fn park(&mut self, what: WaitFor, waiter: Waiter) -> Option<Waiter> {
+        if self.ready(what) {
+            return Some(waiter);
+        }
+        match what {
+            WaitForRoom => self.blocked_senders.push(waiter),
+            WaitForData => {
+                rtassert!(self.blocked_port.is_none());
+                self.blocked_port = Some(waiter);
+            }
+        }
+        None
+    }
+
+    /// Takes back the waiter of a wait that gave up before being woken.
+    /// Only the receiver gives up, when its timeout runs out.
+    fn unpark(&mut self, what: WaitFor) -> Option<Waiter> {
+        match what {
+            WaitForRoom => None,
+            WaitForData => self.blocked_port.take()
+        }
+    }
+}
+
+/// Parks a descheduled green task on the channel, returning true if it
+/// didn't need to wait after all and has been rescheduled.
+fn park_green<T: Send>(state: &Exclusive<BoundedState<T>>, what: WaitFor,
+                       sched: &mut Scheduler, task: BlockedTask) -> bool {
+    let waiter = Cell::new(GreenWaiter(task, sched.shared_handle()));
+    match unsafe { state.with(|s| s.park(what, waiter.take())) } {
+        Some(GreenWaiter(task, _)) => {
+            sched.enqueue_blocked_task(task);
+            true
+        }
+        Some(NativeWaiter(_)) => rtabort!("green task parked as a native thread"),
+        None => false
+    }
+}
+
+/// Blocks the current task or thread until `what` happens on the channel,
+/// or until `timeout` milliseconds have passed.
+fn wait<T: Send>(state: &Exclusive<BoundedState<T>>, what: WaitFor,
+                 timeout: Option<u64>) {
+    if in_green_task_context() {
+        wait_green(state, what, timeout)
+    } else {
+        wait_native(state, what, timeout)
+    }
+}
+
+fn wait_green<T: Send>(state: &Exclusive<BoundedState<T>>, what: WaitFor,
+                       timeout: Option<u64>) {
+    // A timeout selects on a timer's port alongside the channel, so that
+    // whichever comes first wakes the task up.
+    let mut timer = timeout.map(|msecs| {
+        let mut timer = Timer::new().expect("couldn't create a timer for a channel timeout");
+        let port = timer.oneshot(msecs);
+        (timer, port)
+    });
+
+    let sched: ~Scheduler = Local::take();
+    sched.deschedule_running_task_and_then(|sched, task| {
+        let task = match timer {
+            Some((_, ref mut port)) => {
+                let mut handles = task.make_selectable(2);
+                // Block on the timer first: once the task is parked on the
+                // channel another thread may wake it up, and from then on
+                // nothing on its stack can be touched.
+                if port.block_on(sched, handles.pop()) {
+                    None
+                } else {
+                    Some(handles.pop())
+                }
+            }
+            None => Some(task)
+        };
+        match task {
+            Some(task) => { park_green(state, what, sched, task); }
+            None => {}
+        }
+    });
+
+    // Take back the handles that didn't wake the task.
+    match timer {
+        Some((_, ref mut port)) => { port.unblock_from(); }
+        None => {}
+    }
+    let waiter = unsafe { state.with(|s| s.unpark(what)) };
+    waiter.map(|waiter| waiter.assert_already_awake());
+}
+
+fn wait_native<T: Send>(state: &Exclusive<BoundedState<T>>, what: WaitFor,
+                        timeout: Option<u64>) {
+    let wait = UnsafeArc::new(NativeWait {
+        lock: unsafe { Mutex::new() },
+        woken: false
+    });
+    let waiter = Cell::new(NativeWaiter(wait.clone()));
+    match unsafe { state.with(|s| s.park(what, waiter.take())) } {
+        Some(_) => return,
+        None => {}
+    }
+
+    let deadline = timeout.map(|msecs| precise_time_ns() + msecs * 1000000);
+    unsafe {
+        let wait = wait.get();
+        (*wait).lock.lock();
+        while !(*wait).woken {
+            match deadline {
+                None => (*wait).lock.wait(),
+                Some(deadline) => {
+                    let now = precise_time_ns();
+                    if now >= deadline {
+                        break;
+                    }
+                    (*wait).lock.wait_timeout((deadline - now + 999999) / 1000000);
+                }
+            }
+        }
+        (*wait).lock.unlock();
+    }
+
+    // If the wait timed out the waiter is still parked; it must be dropped
+    // outside the channel's lock.
+    let _waiter = unsafe { state.with(|s| s.unpark(what)) };
+}
+
+fn precise_time_ns() -> u64 {
+    extern {
+        fn rust_precise_time_ns(ns: &mut u64);
+    }
+    let mut ns = 0;
+    unsafe { rust_precise_time_ns(&mut ns) }
+    ns
+}
+
+/// The sending half of a bounded channel. Clone it to send from several
+/// tasks or threads.
+pub struct BoundedChan<T> {
+    priv state: Exclusive<BoundedState<T>>
+}
+
+/// The receiving half of a bounded channel.
+pub struct BoundedPort<T> {
+    priv state: Exclusive<BoundedState<T>>
+}
+
+/// Creates a channel that holds at most `capacity` messages. Once it is
+/// full, `send` blocks until the receiver catches up.
+///
+/// Both ends may be used from green tasks as well as from native threads.
+pub fn bounded<T: Send>(capacity: uint) -> (BoundedPort<T>, BoundedChan<T>) {
+    assert!(capacity > 0, "a bounded channel needs room for at least one message");
+    let state = Exclusive::new(BoundedState {
+        buf: vec::from_fn(capacity, |_| None),
+        start: 0,
+        size: 0,
+        senders: 1,
+        port_alive: true,
+        blocked_senders: ~[],
+        blocked_port: None
+    });
+    (BoundedPort { state: state.clone() }, BoundedChan { state: state })
+}
+
+enum Push<T> {
+    // The receiver to wake up, if it was waiting
+    Pushed(Option<Waiter>),
+    Full(T),
+    Disconnected(T)
+}
+
+impl<T: Send> BoundedChan<T> {
+    fn try_push(&self, val: T) -> Push<T> {
+        let val = Cell::new(val);
+        unsafe {
+            self.state.with(|s| {
+                if !s.port_alive {
+                    Disconnected(val.take())
+                } else if s.is_full() {
+                    Full(val.take())
+                } else {
+                    s.push(val.take());
+                    Pushed(s.blocked_port.take())
+                }
+            })
+        }
+    }
+
+    /// Sends a message if there is room for it, without blocking. Hands the
+    /// message back if the channel is full or the port has been dropped.
+    pub fn try_send(&self, val: T) -> Result<(), T> {
+        match self.try_push(val) {
+            Pushed(waiter) => {
+                waiter.map(|waiter| waiter.wake());
+                Ok(())
+            }
+            Full(val) | Disconnected(val) => Err(val)
+        }
+    }
+}
+
+impl<T: Send> GenericChan<T> for BoundedChan<T> {
+    /// Sends a message, blocking while the channel is full. The message is
+    /// dropped if the port is gone.
+    fn send(&self, val: T) {
+        let mut val = val;
+        loop {
+            match self.try_push(val) {
+                Pushed(waiter) => {
+                    waiter.map(|waiter| waiter.wake());
+                    return;
+                }
+                Disconnected(_) => return,
+                Full(v) => {
+                    val = v;
+                    wait(&self.state, WaitForRoom, None);
+                }
+            }
+        }
+    }
+}
+
+impl<T: Send> Clone for BoundedChan<T> {
+    fn clone(&self) -> BoundedChan<T> {
+        unsafe { self.state.with(|s| s.senders += 1) }
+        BoundedChan { state: self.state.clone() }
+    }
+}
+
+#[unsafe_destructor]
+impl<T: Send> Drop for BoundedChan<T> {
+    fn drop(&mut self) {
+        let waiter = unsafe {
+            self.state.with(|s| {
+                s.senders -= 1;
+                if s.senders == 0 { s.blocked_port.take() } else { None }
+            })
+        };
+        waiter.map(|waiter| waiter.wake());
+    }
+}
+
+impl<T: Send> BoundedPort<T> {
+    // Takes a message off the queue, waking a sender waiting for the room
+    // it leaves.
+    fn try_pop(&self) -> Option<T> {
+        let (val, waiter) = unsafe {
+            self.state.with(|s| {
+                let val = s.pop();
+                if val.is_some() && !s.blocked_senders.is_empty() {
+                    (val, Some(s.blocked_senders.shift()))
+                } else {
+                    (val, None)
+                }
+            })
+        };
+        waiter.map(|waiter| waiter.wake());
+        val
+    }
+
+    fn is_disconnected(&self) -> bool {
+        unsafe { self.state.with_imm(|s| s.senders == 0 && s.size == 0) }
+    }
+
+    /// Receives a message, waiting at most `msecs` milliseconds for one.
+    /// Returns `None` if the time runs out or all the chans are gone.
+    pub fn recv_timeout(&self, msecs: u64) -> Option<T> {
+        match self.try_pop() {
+            Some(val) => return Some(val),
+            None if self.is_disconnected() => return None,
+            None => {}
+        }
+        wait(&self.state, WaitForData, Some(msecs));
+        self.try_pop()
+    }
+}
+
+impl<T: Send> GenericPort<T> for BoundedPort<T> {
+    fn recv(&self) -> T {
+        match self.try_recv() {
+            Some(val) => val,
+            None => {
+                fail!("receiving on closed channel");
+            }
+        }
+    }
+
+    fn try_recv(&self) -> Option<T> {
+        loop {
+            match self.try_pop() {
+                Some(val) => return Some(val),
+                None if self.is_disconnected() => return None,
+                None => wait(&self.state, WaitForData, None)
+            }
+        }
+    }
+}
+
+impl<T: Send> Peekable<T> for BoundedPort<T> {
+    fn peek(&self) -> bool {
+        unsafe { self.state.with_imm(|s| s.size > 0) }
+    }
+}
+
+impl<'self, T: Send> SelectInner for &'self BoundedPort<T> {
+    #[inline]
+    fn optimistic_check(&mut self) -> bool {
+        unsafe { self.state.with_imm(|s| s.ready(WaitForData)) }
+    }
+
+    fn block_on(&mut self, sched: &mut Scheduler, task: BlockedTask) -> bool {
+        park_green(&self.state, WaitForData, sched, task)
+    }
+
+    fn unblock_from(&mut self) -> bool {
+        let (waiter, ready) = unsafe {
+            self.state.with(|s| (s.unpark(WaitForData), s.ready(WaitForData)))
+        };
+        waiter.map(|waiter| waiter.assert_already_awake());
+        ready
+    }
+}
+
+impl<'self, T: Send> Select for &'self BoundedPort<T> { }
+
+impl<T: Send> SelectInner for BoundedPort<T> {
+    #[inline]
+    fn optimistic_check(&mut self) -> bool {
+        (&*self).optimistic_check()
+    }
+
+    #[inline]
+    fn block_on(&mut self, sched: &mut Scheduler, task: BlockedTask) -> bool {
+        (&*self).block_on(sched, task)
+    }
+
+    #[inline]
+    fn unblock_from(&mut self) -> bool {
+        (&*self).unblock_from()
+    }
+}
+
+impl<T: Send> Select for BoundedPort<T> { }
+
+impl<'self, T: Send> SelectPortInner<T> for &'self BoundedPort<T> {
+    fn recv_ready(self) -> Option<T> {
+        self.try_pop()
+    }
+}
+
+impl<'self, T: Send> SelectPort<T> for &'self BoundedPort<T> { }
+
+#[unsafe_destructor]
+impl<T: Send> Drop for BoundedPort<T> {
+    fn drop(&mut self) {
+        let (_queued, senders) = unsafe {
+            self.state.with(|s| {
+                s.port_alive = false;
+                s.size = 0;
+                (util::replace(&mut s.buf, ~[]),
+                 util::replace(&mut s.blocked_senders, ~[]))
+            })
+        };
+        // The queued messages are dropped outside the lock, since dropping
+        // them may well send on this very channel.
+        for waiter in senders.move_iter() {
+            waiter.wake();
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use option::*;
+    use result::Err;
     use rt::test::*;
     use cell::Cell;
+    use iter::{Iterator, range};
     use num::Times;
     use rt::util;
 
@@ -1163,4 +1649,180 @@ mod test {
         }
     }
 
+
+    #[test]
+    fn bounded_try_send_when_full() {
+        use comm::GenericPort;
+
+        do run_in_newsched_task {
+            let (port, chan) = bounded::<int>(2);
+            assert!(chan.try_send(1).is_ok());
+            assert!(chan.try_send(2).is_ok());
+            assert_eq!(chan.try_send(3), Err(3));
+            assert_eq!(port.recv(), 1);
+            assert!(chan.try_send(3).is_ok());
+            assert_eq!(port.recv(), 2);
+            assert_eq!(port.recv(), 3);
+        }
+    }
+
+    #[test]
+    fn bounded_send_blocks_until_recv() {
+        use comm::{GenericChan, GenericPort, Peekable};
+
+        do run_in_newsched_task {
+            let (port, chan) = bounded::<int>(1);
+            let (done_port, done_chan) = oneshot();
+            let chan = Cell::new(chan);
+            let done_chan = Cell::new(done_chan);
+            do spawntask_later {
+                let chan = chan.take();
+                for i in range(0, 10) {
+                    chan.send(i);
+                }
+                done_chan.take().send(());
+            }
+            for i in range(0, 10) {
+                // The sender can never get more than one message ahead.
+                assert!(!done_port.peek() || i == 9);
+                assert_eq!(port.recv(), i);
+            }
+            done_port.recv();
+        }
+    }
+
+    #[test]
+    fn bounded_multiple_senders() {
+        use comm::{GenericChan, GenericPort};
+
+        do run_in_mt_newsched_task {
+            let (port, chan) = bounded::<uint>(4);
+            let total = stress_factor() + 10;
+            total.times(|| {
+                let chan = Cell::new(chan.clone());
+                do spawntask_random {
+                    let chan = chan.take();
+                    10.times(|| chan.send(1));
+                }
+            });
+            { let _c = chan; }
+
+            let mut recvd = 0;
+            for x in port.recv_iter() {
+                recvd += x;
+            }
+            assert_eq!(recvd, total * 10);
+        }
+    }
+
+    #[test]
+    fn bounded_port_close_wakes_senders() {
+        use comm::GenericChan;
+
+        do run_in_newsched_task {
+            let (port, chan) = bounded::<~int>(1);
+            chan.send(~1);
+            let port = Cell::new(port);
+            do spawntask_later {
+                let _p = port.take();
+            }
+            // Blocks until the port is dropped, then drops the message.
+            chan.send(~2);
+            assert_eq!(chan.try_send(~3), Err(~3));
+        }
+    }
+
+    #[test]
+    fn bounded_chan_close_wakes_port() {
+        use comm::GenericPort;
+
+        do run_in_newsched_task {
+            let (port, chan) = bounded::<int>(1);
+            let chan = Cell::new(chan);
+            do spawntask_later {
+                chan.take().try_send(10).unwrap();
+            }
+            assert_eq!(port.try_recv(), Some(10));
+            assert_eq!(port.try_recv(), None);
+        }
+    }
+
+    #[test]
+    fn bounded_recv_timeout() {
+        do run_in_uv_task {
+            let (port, chan) = bounded::<int>(1);
+            assert_eq!(port.recv_timeout(10), None);
+            chan.try_send(1).unwrap();
+            assert_eq!(port.recv_timeout(10), Some(1));
+
+            let chan = Cell::new(chan);
+            do spawntask_later {
+                chan.take().try_send(2).unwrap();
+            }
+            assert_eq!(port.recv_timeout(10000), Some(2));
+            assert_eq!(port.recv_timeout(10000), None);
+        }
+    }
+
+    #[test]
+    fn bounded_native_threads() {
+        use comm::{GenericChan, GenericPort};
+        use rt::thread::Thread;
+
+        let (port, chan) = bounded::<int>(2);
+        let sender = do Thread::start {
+            for i in range(0, 100) {
+                chan.send(i);
+            }
+        };
+        let receiver = do Thread::start {
+            assert_eq!(port.recv_timeout(10000), Some(0));
+            for i in range(1, 100) {
+                assert_eq!(port.recv(), i);
+            }
+            assert_eq!(port.recv_timeout(10), None);
+        };
+        sender.join();
+        receiver.join();
+    }
+
+    #[test]
+    fn bounded_native_to_green() {
+        use comm::{GenericChan, GenericPort};
+        use rt::thread::Thread;
+
+        do run_in_newsched_task {
+            let (port, chan) = bounded::<int>(1);
+            let chan = Cell::new(chan);
+            let sender = do Thread::start {
+                let chan = chan.take();
+                for i in range(0, 100) {
+                    chan.send(i);
+                }
+            };
+            for i in range(0, 100) {
+                assert_eq!(port.recv(), i);
+            }
+            assert_eq!(port.try_recv(), None);
+            sender.join();
+        }
+    }
+
+    #[test]
+    fn bounded_select() {
+        use rt::select::SelectPortInner;
+        use select::select;
+
+        do run_in_uv_task {
+            let (port_a, _chan_a) = bounded::<int>(1);
+            let (port_b, chan_b) = bounded::<int>(1);
+            let chan_b = Cell::new(chan_b);
+            do spawntask_later {
+                chan_b.take().try_send(7).unwrap();
+            }
+            let mut ports = [port_a, port_b];
+            assert_eq!(select(ports), 1);
+            assert_eq!((&ports[1]).recv_ready(), Some(7));
+        }
+    }
 }
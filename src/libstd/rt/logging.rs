@@ -16,7 +16,7 @@ use io;
 use io::stdio::StdWriter;
 use io::buffered::LineBufferedWriter;
 use rt::crate_map::{ModEntry, CrateMap, iter_crate_map, get_crate_map};
-use str::StrSlice;
+use str::{Str, StrSlice};
 use u32;
 use vec::ImmutableVector;
 #[cfg(test)] use cast::transmute;
@@ -140,11 +140,6 @@ fn update_entry(dirs: &[LogDirective], entry: &ModEntry) -> u32 {
 fn update_log_settings(crate_map: &CrateMap, settings: ~str) {
     let mut dirs = ~[];
     if settings.len() > 0 {
-        if settings == ~"::help" || settings == ~"?" {
-            rterrln!("\nCrate log map:\n");
-            iter_crate_map(crate_map, |entry| rterrln!(" {}", entry.name));
-            unsafe { exit(1); }
-        }
         dirs = parse_logging_spec(settings);
     }
 
@@ -162,8 +157,31 @@ fn update_log_settings(crate_map: &CrateMap, settings: ~str) {
     }
 }
 
+/// A message from one of the logging macros, along with where it was logged.
+pub struct LogRecord<'a> {
+    /// The level it was logged at: 1 for `error!` up to 4 for `debug!`
+    level: u32,
+    /// The path of the module it was logged from, relative to the crate
+    /// root: `a::b` for `mod b` inside `mod a`, and empty at the root
+    module_path: &'a str,
+    /// The source file it was logged from
+    file: &'a str,
+    /// The line it was logged from
+    line: uint,
+    /// The message, to be written out with the `fmt` functions
+    args: &'a fmt::Arguments<'a>,
+}
+
+/// Something that log records can be written to. Each task has a logger of
+/// its own, which is created lazily on the task's first log message unless
+/// one has been installed with `std::logging::set_logger`.
 pub trait Logger {
-    fn log(&mut self, args: &fmt::Arguments);
+    fn log(&mut self, record: &LogRecord);
+
+    /// Returns the logger for a task spawned by the task that owns this
+    /// logger. Loggers that return `None`, as the default does, leave the
+    /// child to the default logger.
+    fn inherit(&self) -> Option<~Logger> { None }
 }
 
 /// This logger emits output to the stderr of the process, and contains a lazily
@@ -179,8 +197,28 @@ impl StdErrLogger {
 }
 
 impl Logger for StdErrLogger {
-    fn log(&mut self, args: &fmt::Arguments) {
-        fmt::writeln(&mut self.handle as &mut io::Writer, args);
+    fn log(&mut self, record: &LogRecord) {
+        fmt::writeln(&mut self.handle as &mut io::Writer, record.args);
+    }
+}
+
+// Whether `spec` asks for the list of modules rather than setting levels
+fn is_help_spec(spec: &str) -> bool {
+    spec == "::help" || spec == "?"
+}
+
+/// Changes the log levels of all modules as if the program had been started
+/// with `RUST_LOG` set to `spec`. Asking for the list of modules with
+/// `::help` or `?` only works through `RUST_LOG`, and is ignored here.
+pub fn set_log_spec(spec: &str) {
+    if is_help_spec(spec) {
+        rterrln!("warning: log spec `{}` is only understood in RUST_LOG, \
+                  ignored", spec);
+        return
+    }
+    match get_crate_map() {
+        Some(crate_map) => update_log_settings(crate_map, spec.to_owned()),
+        None => rterrln!("warning: no crate map found, log spec ignored")
     }
 }
 
@@ -193,6 +231,11 @@ pub fn init() {
     match get_crate_map() {
         Some(crate_map) => {
             match log_spec {
+                Some(ref spec) if is_help_spec(spec.as_slice()) => {
+                    rterrln!("\nCrate log map:\n");
+                    iter_crate_map(crate_map, |entry| rterrln!(" {}", entry.name));
+                    unsafe { exit(1); }
+                }
                 Some(spec) => {
                     update_log_settings(crate_map, spec);
                 }
@@ -18,10 +18,12 @@ use result::*;
 
 use ai = io::net::addrinfo;
 use io::IoError;
+use io::net::BindOptions;
 use io::net::ip::{IpAddr, SocketAddr};
+use io::poll::Readiness;
 use io::process::{ProcessConfig, ProcessExit};
 use io::signal::Signum;
-use io::{FileMode, FileAccess, FileStat, FilePermission};
+use io::{FileMode, FileAccess, FileStat, FilePermission, FileType};
 use io::{SeekStyle};
 
 pub trait Callback {
@@ -104,12 +106,16 @@ pub fn with_local_io<T>(f: |&mut IoFactory| -> Option<T>) -> Option<T> {
 
 pub trait IoFactory {
     // networking
-    fn tcp_connect(&mut self, addr: SocketAddr) -> Result<~RtioTcpStream, IoError>;
-    fn tcp_bind(&mut self, addr: SocketAddr) -> Result<~RtioTcpListener, IoError>;
-    fn udp_bind(&mut self, addr: SocketAddr) -> Result<~RtioUdpSocket, IoError>;
+    fn tcp_connect(&mut self, addr: SocketAddr,
+                   timeout: Option<u64>) -> Result<~RtioTcpStream, IoError>;
+    fn tcp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> Result<~RtioTcpListener, IoError>;
+    fn udp_bind(&mut self, addr: SocketAddr,
+                opts: BindOptions) -> Result<~RtioUdpSocket, IoError>;
     fn unix_bind(&mut self, path: &CString) ->
         Result<~RtioUnixListener, IoError>;
-    fn unix_connect(&mut self, path: &CString) -> Result<~RtioPipe, IoError>;
+    fn unix_connect(&mut self, path: &CString,
+                    timeout: Option<u64>) -> Result<~RtioPipe, IoError>;
     fn get_host_addresses(&mut self, host: Option<&str>, servname: Option<&str>,
                           hint: Option<ai::Hint>) -> Result<~[ai::Info], IoError>;
 
@@ -127,6 +133,7 @@ pub trait IoFactory {
     fn fs_rename(&mut self, path: &CString, to: &CString) -> Result<(), IoError>;
     fn fs_readdir(&mut self, path: &CString, flags: c_int) ->
         Result<~[Path], IoError>;
+    fn fs_opendir(&mut self, path: &CString) -> Result<~RtioDirectory, IoError>;
     fn fs_lstat(&mut self, path: &CString) -> Result<FileStat, IoError>;
     fn fs_chown(&mut self, path: &CString, uid: int, gid: int) ->
         Result<(), IoError>;
@@ -155,6 +162,7 @@ pub trait RtioTcpAcceptor : RtioSocket {
     fn accept(&mut self) -> Result<~RtioTcpStream, IoError>;
     fn accept_simultaneously(&mut self) -> Result<(), IoError>;
     fn dont_accept_simultaneously(&mut self) -> Result<(), IoError>;
+    fn set_timeout(&mut self, timeout: Option<u64>);
 }
 
 pub trait RtioTcpStream : RtioSocket {
@@ -165,6 +173,14 @@ pub trait RtioTcpStream : RtioSocket {
     fn nodelay(&mut self) -> Result<(), IoError>;
     fn keepalive(&mut self, delay_in_seconds: uint) -> Result<(), IoError>;
     fn letdie(&mut self) -> Result<(), IoError>;
+    fn close_write(&mut self) -> Result<(), IoError>;
+    fn close_read(&mut self) -> Result<(), IoError>;
+    fn poller(&mut self) -> Result<~RtioPoll, IoError>;
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> Result<(), IoError>;
+    fn set_send_buffer_size(&mut self, bytes: uint) -> Result<(), IoError>;
+    fn set_timeout(&mut self, timeout: Option<u64>);
+    fn set_read_timeout(&mut self, timeout: Option<u64>);
+    fn set_write_timeout(&mut self, timeout: Option<u64>);
 }
 
 pub trait RtioSocket {
@@ -175,8 +191,11 @@ pub trait RtioUdpSocket : RtioSocket {
     fn recvfrom(&mut self, buf: &mut [u8]) -> Result<(uint, SocketAddr), IoError>;
     fn sendto(&mut self, buf: &[u8], dst: SocketAddr) -> Result<(), IoError>;
 
-    fn join_multicast(&mut self, multi: IpAddr) -> Result<(), IoError>;
-    fn leave_multicast(&mut self, multi: IpAddr) -> Result<(), IoError>;
+    fn join_multicast(&mut self, multi: IpAddr,
+                      interface: Option<IpAddr>) -> Result<(), IoError>;
+    fn leave_multicast(&mut self, multi: IpAddr,
+                       interface: Option<IpAddr>) -> Result<(), IoError>;
+    fn multicast_interface(&mut self, interface: IpAddr) -> Result<(), IoError>;
 
     fn loop_multicast_locally(&mut self) -> Result<(), IoError>;
     fn dont_loop_multicast_locally(&mut self) -> Result<(), IoError>;
@@ -186,6 +205,15 @@ pub trait RtioUdpSocket : RtioSocket {
 
     fn hear_broadcasts(&mut self) -> Result<(), IoError>;
     fn ignore_broadcasts(&mut self) -> Result<(), IoError>;
+
+    fn poller(&mut self) -> Result<~RtioPoll, IoError>;
+
+    fn set_recv_buffer_size(&mut self, bytes: uint) -> Result<(), IoError>;
+    fn set_send_buffer_size(&mut self, bytes: uint) -> Result<(), IoError>;
+
+    fn set_timeout(&mut self, timeout: Option<u64>);
+    fn set_read_timeout(&mut self, timeout: Option<u64>);
+    fn set_write_timeout(&mut self, timeout: Option<u64>);
 }
 
 pub trait RtioTimer {
@@ -194,6 +222,15 @@ pub trait RtioTimer {
     fn period(&mut self, msecs: u64) -> Port<()>;
 }
 
+pub trait RtioPoll {
+    fn oneshot(&mut self, interest: Readiness,
+               timeout: Option<u64>) -> PortOne<Readiness>;
+    fn cancel(&mut self);
+    /// The descriptor being polled, if the calling thread can wait for it
+    /// directly. Pollers which belong to an event loop return `None`.
+    fn fd(&self) -> Option<c_int>;
+}
+
 pub trait RtioFileStream {
     fn read(&mut self, buf: &mut [u8]) -> Result<int, IoError>;
     fn write(&mut self, buf: &[u8]) -> Result<(), IoError>;
@@ -204,17 +241,34 @@ pub trait RtioFileStream {
     fn fsync(&mut self) -> Result<(), IoError>;
     fn datasync(&mut self) -> Result<(), IoError>;
     fn truncate(&mut self, offset: i64) -> Result<(), IoError>;
+    /// The underlying file descriptor, which remains owned by this stream.
+    fn fd(&self) -> c_int;
+}
+
+pub trait RtioDirectory {
+    /// Returns the path of the next entry of the directory (skipping `.` and
+    /// `..`) along with its type, which is `TypeUnknown` if it can't be
+    /// determined without a stat. Symlinks are not followed.
+    fn next_entry(&mut self) -> Result<Option<(Path, FileType)>, IoError>;
 }
 
 pub trait RtioProcess {
     fn id(&self) -> libc::pid_t;
     fn kill(&mut self, signal: int) -> Result<(), IoError>;
     fn wait(&mut self) -> ProcessExit;
+    fn try_wait(&mut self) -> Option<ProcessExit>;
 }
 
 pub trait RtioPipe {
     fn read(&mut self, buf: &mut [u8]) -> Result<uint, IoError>;
     fn write(&mut self, buf: &[u8]) -> Result<(), IoError>;
+    fn close_write(&mut self) -> Result<(), IoError>;
+    fn close_read(&mut self) -> Result<(), IoError>;
+    fn poller(&mut self) -> Result<~RtioPoll, IoError>;
+
+    fn set_timeout(&mut self, timeout: Option<u64>);
+    fn set_read_timeout(&mut self, timeout: Option<u64>);
+    fn set_write_timeout(&mut self, timeout: Option<u64>);
 }
 
 pub trait RtioUnixListener {
@@ -11,13 +11,14 @@
 use option::{Option, Some, None};
 use cast::{transmute, transmute_mut_region, transmute_mut_unsafe};
 use clone::Clone;
+use ops::Drop;
 use unstable::raw;
 use super::sleeper_list::SleeperList;
 use super::work_queue::WorkQueue;
 use super::stack::{StackPool};
 use super::rtio::EventLoop;
 use super::context::Context;
-use super::task::{Task, AnySched, Sched};
+use super::task::{Task, AnySched, Sched, Pool};
 use super::message_queue::MessageQueue;
 use rt::kill::BlockedTask;
 use rt::local_ptr;
@@ -26,8 +27,13 @@ use rt::rtio::{RemoteCallback, PausibleIdleCallback, Callback};
 use borrow::{to_uint};
 use cell::Cell;
 use rand::{XorShiftRng, Rng, Rand};
-use iter::range;
-use vec::{OwnedVector};
+use iter::{Iterator, range};
+use vec::{OwnedVector, ImmutableVector, MutableVector};
+use send_str::SendStr;
+use str::Str;
+use container::Container;
+use unstable::atomics::{AtomicUint, INIT_ATOMIC_UINT, Relaxed, SeqCst};
+use unstable::sync::UnsafeArc;
 
 /// A scheduler is responsible for coordinating the execution of Tasks
 /// on a single thread. The scheduler runs inside a slightly modified
@@ -43,6 +49,8 @@ pub struct Scheduler {
     /// Work queues for the other schedulers. These are created by
     /// cloning the core work queues.
     work_queues: ~[WorkQueue<~Task>],
+    /// The index of our own queue in `work_queues`, if it is there
+    priv own_queue: Option<uint>,
     /// The queue of incoming messages from other schedulers.
     /// These are enqueued by SchedHandles after which a remote callback
     /// is triggered to handle the message.
@@ -69,9 +77,19 @@ pub struct Scheduler {
     priv cleanup_job: Option<CleanupJob>,
     /// Should this scheduler run any task, or only pinned tasks?
     run_anything: bool,
+    /// The pool this scheduler belongs to. Tasks homed to a pool run on
+    /// any scheduler with the same pool id. The schedulers started with
+    /// the runtime are pool 0.
+    pool_id: uint,
+    /// Statistics about this scheduler's activity
+    priv counters: UnsafeArc<SchedCounters>,
     /// If the scheduler shouldn't run some tasks, a friend to send
     /// them to.
     priv friend_handle: Option<SchedHandle>,
+    /// A handle to this scheduler shared by everything that may need to
+    /// send it a task from a thread with no scheduler of its own, created
+    /// the first time one is asked for.
+    priv shared_handle: Option<UnsafeArc<SchedHandle>>,
     /// A fast XorShift rng for scheduler use
     rng: XorShiftRng,
     /// A toggleable idle callback
@@ -134,6 +152,7 @@ impl Scheduler {
                        friend: Option<SchedHandle>)
         -> Scheduler {
 
+        let own_queue = work_queues.iter().position(|q| q.same_queue(&work_queue));
         let mut sched = Scheduler {
             sleeper_list: sleeper_list,
             message_queue: MessageQueue::new(),
@@ -142,11 +161,15 @@ impl Scheduler {
             event_loop: event_loop,
             work_queue: work_queue,
             work_queues: work_queues,
+            own_queue: own_queue,
             stack_pool: StackPool::new(),
             sched_task: None,
             cleanup_job: None,
             run_anything: run_anything,
+            pool_id: 0,
+            counters: UnsafeArc::new(SchedCounters::new()),
             friend_handle: friend,
+            shared_handle: None,
             rng: new_sched_rng(),
             idle_callback: None,
             yield_check_count: 0,
@@ -203,6 +226,8 @@ impl Scheduler {
         // Close the idle callback.
         let mut sched: ~Scheduler = Local::take();
         sched.idle_callback.take();
+        // The shared handle keeps the loop alive too.
+        sched.shared_handle.take();
         // Make one go through the loop to run the close callback.
         sched.run();
 
@@ -293,6 +318,7 @@ impl Scheduler {
             sched.sleepy = true;
             let handle = sched.make_handle();
             sched.sleeper_list.push(handle);
+            unsafe { (*sched.counters.get()).parks.fetch_add(1, Relaxed); }
             // Since we are sleeping, deactivate the idle callback.
             sched.idle_callback.get_mut_ref().pause();
         } else {
@@ -431,9 +457,11 @@ impl Scheduler {
         }
     }
 
-    // Try stealing from all queues the scheduler knows about. This
-    // naive implementation can steal from our own queue or from other
-    // special schedulers.
+    // Try stealing from all queues the scheduler knows about, starting
+    // from a random one. This naive implementation can steal from our
+    // own queue, which is what lets a yield pick another task, or from
+    // other special schedulers. Taking a task back from our own queue
+    // isn't counted as a steal.
     fn try_steals(&mut self) -> Option<~Task> {
         let work_queues = &mut self.work_queues;
         let len = work_queues.len();
@@ -442,12 +470,16 @@ impl Scheduler {
             match work_queues[index].steal() {
                 Some(task) => {
                     rtdebug!("found task by stealing");
+                    if Some(index) != self.own_queue {
+                        unsafe { (*self.counters.get()).steals.fetch_add(1, Relaxed); }
+                    }
                     return Some(task)
                 }
                 None => ()
             }
         };
         rtdebug!("giving up on stealing");
+        unsafe { (*self.counters.get()).failed_steals.fetch_add(1, Relaxed); }
         return None;
     }
 
@@ -482,6 +514,19 @@ impl Scheduler {
                 self.send_to_friend(task);
                 Local::put(self);
             }
+            Pool(pool) => {
+                if pool.id() != self.pool_id {
+                    rtdebug!("sending task to its pool");
+                    let mut pool = pool;
+                    task.give_home(Pool(pool.clone()));
+                    pool.send(task);
+                    Local::put(self);
+                } else {
+                    rtdebug!("running pool task here");
+                    task.give_home(Pool(pool));
+                    schedule_fn(self, task);
+                }
+            }
         }
     }
 
@@ -495,6 +540,9 @@ impl Scheduler {
             AnySched => {
                         rtabort!("error: cannot send anysched task home");
             }
+            Pool(*) => {
+                        rtabort!("error: cannot send pool task home");
+            }
         }
     }
 
@@ -750,6 +798,17 @@ impl Scheduler {
 
     pub fn sched_id(&self) -> uint { to_uint(self) }
 
+    /// A snapshot of this scheduler's activity so far.
+    pub fn stats(&self) -> SchedStats {
+        unsafe { (*self.counters.get()).snapshot(&self.work_queue) }
+    }
+
+    /// The statistics of this scheduler, shared so that they can be read
+    /// from other threads.
+    pub fn counters(&self) -> UnsafeArc<SchedCounters> {
+        self.counters.clone()
+    }
+
     pub fn run_cleanup_job(&mut self) {
         let cleanup_job = self.cleanup_job.take_unwrap();
         cleanup_job.run(self);
@@ -764,6 +823,18 @@ impl Scheduler {
             sched_id: self.sched_id()
         };
     }
+
+    /// A handle to this scheduler that can be shared between threads.
+    /// Unlike `make_handle` this only creates a remote callback the first
+    /// time it's called. Sending through it from several threads at once
+    /// is fine: the message queue takes multiple producers and firing the
+    /// remote callback is thread-safe.
+    pub fn shared_handle(&mut self) -> UnsafeArc<SchedHandle> {
+        if self.shared_handle.is_none() {
+            self.shared_handle = Some(UnsafeArc::new(self.make_handle()));
+        }
+        self.shared_handle.get_ref().clone()
+    }
 }
 
 // Supporting types
@@ -791,6 +862,156 @@ impl SchedHandle {
     }
 }
 
+/// A snapshot of the activity of a scheduler, for tuning the runtime.
+#[deriving(Clone, Eq)]
+pub struct SchedStats {
+    /// Number of tasks waiting in the scheduler's work queue
+    queue_len: uint,
+    /// Number of tasks the scheduler has stolen from the work queues of
+    /// other schedulers
+    steals: uint,
+    /// Number of times the scheduler tried to steal and found no work
+    failed_steals: uint,
+    /// Number of times the scheduler ran out of work and went to sleep
+    parks: uint,
+}
+
+/// The counters behind `SchedStats`. They are only written by their
+/// scheduler, but may be read from any thread.
+pub struct SchedCounters {
+    priv steals: AtomicUint,
+    priv failed_steals: AtomicUint,
+    priv parks: AtomicUint,
+}
+
+impl SchedCounters {
+    fn new() -> SchedCounters {
+        SchedCounters {
+            steals: AtomicUint::new(0),
+            failed_steals: AtomicUint::new(0),
+            parks: AtomicUint::new(0),
+        }
+    }
+
+    pub fn snapshot(&self, work_queue: &WorkQueue<~Task>) -> SchedStats {
+        SchedStats {
+            queue_len: work_queue.len(),
+            steals: self.steals.load(Relaxed),
+            failed_steals: self.failed_steals.load(Relaxed),
+            parks: self.parks.load(Relaxed),
+        }
+    }
+}
+
+static mut NEXT_POOL_ID: AtomicUint = INIT_ATOMIC_UINT;
+
+/// Allocate the id of a new pool. Ids start at 1; the schedulers of the
+/// runtime itself are pool 0.
+pub fn next_pool_id() -> uint {
+    unsafe { NEXT_POOL_ID.fetch_add(1, SeqCst) + 1 }
+}
+
+/// A handle to a pool of schedulers that share their work queues and
+/// steal from each other, but not from the schedulers of other pools.
+///
+/// Tasks homed to a pool are sent back to it whenever they are woken up
+/// elsewhere. The schedulers of the pool are shut down once the last
+/// handle to it, including those held by the homes of its tasks, is gone.
+pub struct PoolHandle {
+    priv pool: UnsafeArc<PoolState>
+}
+
+struct PoolState {
+    id: uint,
+    name: SendStr,
+    work_queues: ~[WorkQueue<~Task>],
+    counters: ~[UnsafeArc<SchedCounters>],
+    sleeper_list: SleeperList,
+    handles: ~[SchedHandle],
+    /// The queue the next task sent to the pool is pushed onto
+    next_queue: AtomicUint,
+}
+
+impl PoolHandle {
+    /// Wrap up the schedulers of a new pool. The schedulers must all have
+    /// been created with `work_queues` and `sleeper_list`, in that order,
+    /// and have `pool_id` set to `id`.
+    pub fn new(id: uint,
+               name: SendStr,
+               work_queues: ~[WorkQueue<~Task>],
+               sleeper_list: SleeperList,
+               scheds: &mut ~[~Scheduler]) -> PoolHandle {
+        rtassert!(scheds.len() == work_queues.len());
+        let counters: ~[UnsafeArc<SchedCounters>] =
+            scheds.iter().map(|s| s.counters()).collect();
+        let handles: ~[SchedHandle] =
+            scheds.mut_iter().map(|s| s.make_handle()).collect();
+        PoolHandle {
+            pool: UnsafeArc::new(PoolState {
+                id: id,
+                name: name,
+                work_queues: work_queues,
+                counters: counters,
+                sleeper_list: sleeper_list,
+                handles: handles,
+                next_queue: AtomicUint::new(0),
+            })
+        }
+    }
+
+    pub fn id(&self) -> uint {
+        unsafe { (*self.pool.get_immut()).id }
+    }
+
+    pub fn name<'a>(&'a self) -> &'a str {
+        unsafe { (*self.pool.get_immut()).name.as_slice() }
+    }
+
+    /// A snapshot of the activity of each scheduler in the pool.
+    pub fn stats(&self) -> ~[SchedStats] {
+        unsafe {
+            let pool = self.pool.get_immut();
+            (*pool).counters.iter().zip((*pool).work_queues.iter()).map(|(c, q)| {
+                (*c.get_immut()).snapshot(q)
+            }).collect()
+        }
+    }
+
+    /// Hand a task to the pool. The task is pushed onto the pool's work
+    /// queues in turn, and a sleeping scheduler of the pool is woken up
+    /// to run it.
+    pub fn send(&mut self, task: ~Task) {
+        unsafe {
+            let pool = self.pool.get();
+            let n = (*pool).next_queue.fetch_add(1, Relaxed);
+            let len = (*pool).work_queues.len();
+            (*pool).work_queues[n % len].push(task);
+            match (*pool).sleeper_list.casual_pop() {
+                Some(handle) => {
+                    let mut handle = handle;
+                    handle.send(Wake)
+                }
+                None => { (/* pass */) }
+            }
+        }
+    }
+}
+
+impl Clone for PoolHandle {
+    fn clone(&self) -> PoolHandle {
+        PoolHandle { pool: self.pool.clone() }
+    }
+}
+
+impl Drop for PoolState {
+    fn drop(&mut self) {
+        rtdebug!("shutting down pool {}", self.id);
+        for handle in self.handles.mut_iter() {
+            handle.send(Shutdown);
+        }
+    }
+}
+
 struct SchedRunner;
 
 impl Callback for SchedRunner {
@@ -1368,4 +1589,60 @@ mod test {
         do spawn { }
         do spawn { }
     }
+
+    #[test]
+    fn stats_dont_count_own_queue_as_steals() {
+        use rt::local::Local;
+
+        do run_in_newsched_task {
+            let before = Local::borrow(|sched: &mut Scheduler| sched.stats());
+            // Yielding takes the yielding task back off our own queue
+            let sched: ~Scheduler = Local::take();
+            sched.yield_now();
+            let after = Local::borrow(|sched: &mut Scheduler| sched.stats());
+            assert_eq!(after.steals, before.steals);
+            assert_eq!(after.queue_len, 0);
+        }
+    }
+
+    #[test]
+    fn stats_count_steals() {
+        use rt::local::Local;
+        use rt::new_event_loop;
+        use rt::sleeper_list::SleeperList;
+        use rt::work_queue::WorkQueue;
+
+        do run_in_bare_thread {
+            let mut task_ran = false;
+            let task_ran_ptr: *mut bool = &mut task_ran;
+
+            let mut theirs = WorkQueue::new();
+            let ours = WorkQueue::new();
+            let queues = ~[ours.clone(), theirs.clone()];
+            let mut sched = ~Scheduler::new(new_event_loop(), ours, queues,
+                                            SleeperList::new());
+            sched.no_sleep = true;
+
+            // Left on another scheduler's queue, so it has to be stolen
+            let mut stolen = ~do Task::new_root(&mut sched.stack_pool, None) {
+                Local::borrow(|sched: &mut Scheduler| {
+                    assert_eq!(sched.stats().steals, 1);
+                });
+                unsafe { *task_ran_ptr = true };
+            };
+            let on_exit: proc(UnwindResult) = proc(exit_status) {
+                rtassert!(exit_status.is_success())
+            };
+            stolen.death.on_exit = Some(on_exit);
+            theirs.push(stolen);
+
+            let task = ~do Task::new_root(&mut sched.stack_pool, None) {
+                Local::borrow(|sched: &mut Scheduler| {
+                    assert_eq!(sched.stats().steals, 0);
+                });
+            };
+            sched.bootstrap(task);
+            assert!(task_ran);
+        }
+    }
 }
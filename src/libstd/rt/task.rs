@@ -32,11 +32,12 @@ use rt::env;
 use io::Writer;
 use rt::kill::Death;
 use rt::local::Local;
-use rt::logging::StdErrLogger;
-use rt::sched::{Scheduler, SchedHandle};
+use rt::logging::Logger;
+use rt::sched::{Scheduler, SchedHandle, PoolHandle};
 use rt::stack::{StackSegment, StackPool};
 use send_str::SendStr;
 use unstable::finally::Finally;
+use unstable::mutex::{Mutex, MUTEX_INIT};
 
 // The Task struct represents all state associated with a rust
 // task. There are at this point two primary "subtypes" of task,
@@ -48,7 +49,7 @@ pub struct Task {
     heap: LocalHeap,
     priv gc: GarbageCollector,
     storage: LocalStorage,
-    logger: Option<StdErrLogger>,
+    logger: Option<~Logger>,
     unwinder: Unwinder,
     death: Death,
     destroyed: bool,
@@ -82,7 +83,9 @@ pub struct Coroutine {
 /// Some tasks have a dedicated home scheduler that they must run on.
 pub enum SchedHome {
     AnySched,
-    Sched(SchedHandle)
+    Sched(SchedHandle),
+    /// Run on any scheduler of the pool
+    Pool(PoolHandle)
 }
 
 pub struct GarbageCollector;
@@ -119,7 +122,95 @@ impl UnwindResult {
 
 pub struct Unwinder {
     unwinding: bool,
-    cause: Option<~Any>
+    cause: Option<~Any>,
+    /// Run when the task fails, before the global failure hook
+    on_failure: Option<proc(&FailureInfo)>
+}
+
+/// What a task fails with when `fail!` is called: the value given to
+/// `fail!` and where it was called. This is the `~Any` handed to the task's
+/// parent, e.g. in the `Err` returned by `task::try`.
+///
+/// Note that the value given to `fail!` is wrapped up in here, so code which
+/// downcasts a failed task's cause straight to a string (or to whatever else
+/// the task failed with) won't find it: downcast to `FailurePayload` first.
+pub struct FailurePayload {
+    /// The value given to `fail!`, usually a `&'static str` or a `~str`
+    msg: ~Any,
+    /// The file `fail!` was called from
+    file: &'static str,
+    /// The line `fail!` was called from
+    line: uint,
+}
+
+impl FailurePayload {
+    /// The message, if it is a string.
+    pub fn msg_str<'a>(&'a self) -> Option<&'a str> {
+        match self.msg.as_ref::<&'static str>() {
+            Some(s) => Some(*s),
+            None => self.msg.as_ref::<~str>().map(|s| s.as_slice())
+        }
+    }
+
+    /// Take the value given to `fail!` back out of the payload.
+    pub fn unwrap(~self) -> ~Any {
+        let FailurePayload { msg: msg, _ } = *self;
+        msg
+    }
+}
+
+/// What a failure hook is told about a failing task.
+pub struct FailureInfo<'a> {
+    /// The value the task failed with, e.g. the message given to `fail!`
+    cause: &'a Any,
+    /// The name of the task, if it has one
+    task_name: Option<&'a str>,
+    /// The file `fail!` was called from
+    file: &'static str,
+    /// The line `fail!` was called from
+    line: uint,
+}
+
+// The hook set with `set_failure_hook`, guarded by FAILURE_HOOK_LOCK
+static mut FAILURE_HOOK: Option<fn(&FailureInfo)> = None;
+static mut FAILURE_HOOK_LOCK: Mutex = MUTEX_INIT;
+
+/// Sets a function to be run in every task that fails, e.g. to log the
+/// failure or count it. `None` removes the hook.
+///
+/// Hooks run on the failing task before it starts unwinding, after the
+/// task's own hook if it was spawned with one. A hook that fails itself
+/// aborts the process.
+pub fn set_failure_hook(hook: Option<fn(&FailureInfo)>) {
+    unsafe {
+        FAILURE_HOOK_LOCK.lock();
+        FAILURE_HOOK = hook;
+        FAILURE_HOOK_LOCK.unlock();
+    }
+}
+
+fn run_failure_hooks(task: &mut Task, cause: &Any, file: &'static str, line: uint) {
+    let info = FailureInfo {
+        cause: cause,
+        task_name: task.name.as_ref().map(|n| n.as_slice()),
+        file: file,
+        line: line,
+    };
+    match task.unwinder.on_failure.take() {
+        Some(hook) => hook(&info),
+        None => {}
+    }
+    // The hook is called outside of the lock, as it may deschedule
+    let hook = unsafe {
+        FAILURE_HOOK_LOCK.lock();
+        let hook = FAILURE_HOOK;
+        FAILURE_HOOK_LOCK.unlock();
+        hook
+    };
+    match hook {
+        Some(hook) => hook(&info),
+        None => {}
+    }
 }
 
 impl Unwinder {
@@ -165,10 +256,11 @@ impl Task {
         let home = Cell::new(home);
         Local::borrow(|running_task: &mut Task| {
             let mut sched = running_task.sched.take_unwrap();
-            let new_task = ~Task::new_root_homed(&mut sched.stack_pool,
-                                                 stack_size,
-                                                 home.take(),
-                                                 f.take());
+            let mut new_task = ~Task::new_root_homed(&mut sched.stack_pool,
+                                                     stack_size,
+                                                     home.take(),
+                                                     f.take());
+            new_task.logger = running_task.inherit_logger();
             running_task.sched = Some(sched);
             new_task
         })
@@ -184,7 +276,7 @@ impl Task {
             gc: GarbageCollector,
             storage: LocalStorage(None),
             logger: None,
-            unwinder: Unwinder { unwinding: false, cause: None },
+            unwinder: Unwinder { unwinding: false, cause: None, on_failure: None },
             death: Death::new(),
             destroyed: false,
             coroutine: Some(Coroutine::empty()),
@@ -218,7 +310,7 @@ impl Task {
             gc: GarbageCollector,
             storage: LocalStorage(None),
             logger: None,
-            unwinder: Unwinder { unwinding: false, cause: None },
+            unwinder: Unwinder { unwinding: false, cause: None, on_failure: None },
             death: Death::new(),
             destroyed: false,
             name: None,
@@ -239,8 +331,8 @@ impl Task {
             heap: LocalHeap::new(),
             gc: GarbageCollector,
             storage: LocalStorage(None),
-            logger: None,
-            unwinder: Unwinder { unwinding: false, cause: None },
+            logger: self.inherit_logger(),
+            unwinder: Unwinder { unwinding: false, cause: None, on_failure: None },
             death: Death::new(),
             destroyed: false,
             name: None,
@@ -252,6 +344,11 @@ impl Task {
         }
     }
 
+    /// The logger a task spawned by this one starts out with.
+    fn inherit_logger(&self) -> Option<~Logger> {
+        self.logger.as_ref().and_then(|logger| logger.inherit())
+    }
+
     pub fn give_home(&mut self, new_home: SchedHome) {
         match self.task_type {
             GreenTask(ref mut home) => {
@@ -338,6 +435,9 @@ impl Task {
             GreenTask(Some(Sched(SchedHandle { sched_id: ref id, _}))) => {
                 *id == sched.sched_id()
             }
+            GreenTask(Some(Pool(ref pool))) => {
+                pool.id() == sched.pool_id
+            }
             GreenTask(None) => {
                 rtabort!("task without home");
             }
@@ -352,6 +452,7 @@ impl Task {
         match self.task_type {
             GreenTask(Some(AnySched)) => { false }
             GreenTask(Some(Sched(SchedHandle { _ }))) => { true }
+            GreenTask(Some(Pool(*))) => { true }
             GreenTask(None) => {
                 rtabort!("task without home");
             }
@@ -367,6 +468,7 @@ impl Task {
         Local::borrow(|task: &mut Task| {
             let sched_id = task.sched.get_ref().sched_id();
             let sched_run_anything = task.sched.get_ref().run_anything;
+            let sched_pool_id = task.sched.get_ref().pool_id;
             match task.task_type {
                 GreenTask(Some(AnySched)) => {
                     rtdebug!("anysched task in sched check ****");
@@ -376,6 +478,10 @@ impl Task {
                     rtdebug!("homed task in sched check ****");
                     *id == sched_id
                 }
+                GreenTask(Some(Pool(ref pool))) => {
+                    rtdebug!("pool task in sched check ****");
+                    pool.id() == sched_pool_id
+                }
                 GreenTask(None) => {
                     rtabort!("task without home");
                 }
@@ -626,32 +732,27 @@ pub fn begin_unwind_raw(msg: *c_char, file: *c_char, line: size_t) -> ! {
 
 /// This is the entry point of unwinding for fail!() and assert!().
 pub fn begin_unwind<M: Any + Send>(msg: M, file: &'static str, line: uint) -> ! {
-    use any::AnyRefExt;
     use rt::in_green_task_context;
     use rt::local::Local;
     use rt::task::Task;
-    use str::Str;
     use unstable::intrinsics;
 
     unsafe {
         let task: *mut Task;
-        // Note that this should be the only allocation performed in this block.
-        // Currently this means that fail!() on OOM will invoke this code path,
-        // but then again we're not really ready for failing on OOM anyway. If
-        // we do start doing this, then we should propagate this allocation to
-        // be performed in the parent of this task instead of the task that's
-        // failing.
-        let msg = ~msg as ~Any;
+        // Note that these should be the only allocations performed in this
+        // block. Currently this means that fail!() on OOM will invoke this code
+        // path, but then again we're not really ready for failing on OOM
+        // anyway. If we do start doing this, then we should propagate these
+        // allocations to be performed in the parent of this task instead of
+        // the task that's failing.
+        let payload = ~FailurePayload {
+            msg: ~msg as ~Any,
+            file: file,
+            line: line,
+        };
 
         {
-            //let msg: &Any = msg;
-            let msg_s = match msg.as_ref::<&'static str>() {
-                Some(s) => *s,
-                None => match msg.as_ref::<~str>() {
-                    Some(s) => s.as_slice(),
-                    None => "~Any",
-                }
-            };
+            let msg_s = payload.msg_str().unwrap_or("~Any");
 
             if !in_green_task_context() {
                 rterrln!("failed in non-task context at '{}', {}:{}",
@@ -675,9 +776,12 @@ pub fn begin_unwind<M: Any + Send>(msg: M, file: &'static str, line: uint) -> !
             if (*task).unwinder.unwinding {
                 rtabort!("unwinding again");
             }
+            // A hook that fails will now abort instead of recursing
+            (*task).unwinder.unwinding = true;
+            run_failure_hooks(&mut *task, payload.msg, file, line);
         }
 
-        (*task).unwinder.begin_unwind(msg);
+        (*task).unwinder.begin_unwind(payload as ~Any);
     }
 }
 
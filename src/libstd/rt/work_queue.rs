@@ -59,6 +59,21 @@ impl<T: Send> WorkQueue<T> {
         }
     }
 
+    pub fn len(&self) -> uint {
+        unsafe {
+            self.queue.with_imm(|q| q.len() )
+        }
+    }
+
+    /// Whether `other` is a handle to the same queue as this one.
+    pub fn same_queue(&self, other: &WorkQueue<T>) -> bool {
+        unsafe {
+            let this = self.queue.with_imm(|q| q as *~[T]);
+            let that = other.queue.with_imm(|q| q as *~[T]);
+            this == that
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         unsafe {
             self.queue.with_imm(|q| q.is_empty() )
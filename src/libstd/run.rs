@@ -12,8 +12,6 @@
 
 #[allow(missing_doc)];
 
-use cell::Cell;
-use comm::{stream, SharedChan};
 use io::Reader;
 use io::process::ProcessExit;
 use io::process;
@@ -138,6 +136,9 @@ impl Process {
             env: env,
             cwd: cwd,
             io: rtio,
+            uid: None,
+            gid: None,
+            detach: false,
         };
         let inner = process::Process::new(rtconfig).unwrap();
         Process { inner: inner }
@@ -183,11 +184,6 @@ impl Process {
         self.inner.io[0].take();
     }
 
-    fn close_outputs(&mut self) {
-        self.inner.io[1].take();
-        self.inner.io[2].take();
-    }
-
     /**
      * Closes the handle to stdin, waits for the child process to terminate,
      * and returns the exit code.
@@ -204,52 +200,13 @@ impl Process {
      * If the child has already been finished then the exit code and any
      * remaining unread output of stdout and stderr will be returned.
      *
-     * This method will fail if the child process's stdout or stderr streams
-     * were redirected to existing file descriptors.
+     * Output is reported as empty for the child process's stdout or stderr
+     * streams if they were redirected to existing file descriptors.
      */
     pub fn finish_with_output(&mut self) -> ProcessOutput {
-        self.close_input();
-        let output = Cell::new(self.inner.io[1].take());
-        let error = Cell::new(self.inner.io[2].take());
-
-        // Spawn two entire schedulers to read both stdout and sterr
-        // in parallel so we don't deadlock while blocking on one
-        // or the other. FIXME (#2625): Surely there's a much more
-        // clever way to do this.
-        let (p, ch) = stream();
-        let ch = SharedChan::new(ch);
-        let ch_clone = ch.clone();
-
-        do spawn {
-            io::ignore_io_error(|| {
-                match error.take() {
-                    Some(ref mut e) => ch.send((2, e.read_to_end())),
-                    None => ch.send((2, ~[]))
-                }
-            })
-        }
-        do spawn {
-            io::ignore_io_error(|| {
-                match output.take() {
-                    Some(ref mut e) => ch_clone.send((1, e.read_to_end())),
-                    None => ch_clone.send((1, ~[]))
-                }
-            })
-        }
-
-        let status = self.finish();
-
-        let (errs, outs) = match (p.recv(), p.recv()) {
-            ((1, o), (2, e)) => (e, o),
-            ((2, e), (1, o)) => (e, o),
-            ((x, _), (y, _)) => {
-                fail!("unexpected file numbers: {}, {}", x, y);
-            }
-        };
-
-        return ProcessOutput {status: status,
-                              output: outs,
-                              error: errs};
+        let process::ProcessOutput { status, output, error, _ } =
+            self.inner.wait_with_output(None);
+        ProcessOutput { status: status, output: output, error: error }
     }
 
     /**
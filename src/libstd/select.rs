@@ -13,10 +13,11 @@
 use cell::Cell;
 use comm;
 use container::Container;
+use io::timer::Timer;
 use iter::{Iterator, DoubleEndedIterator};
 use option::*;
 // use either::{Either, Left, Right};
-// use rt::kill::BlockedTask;
+use rt::kill::BlockedTask;
 use rt::local::Local;
 use rt::rtio::EventLoop;
 use rt::sched::Scheduler;
@@ -38,7 +39,10 @@ pub fn select<A: Select>(ports: &mut [A]) -> uint {
     if ports.is_empty() {
         fail!("can't select on an empty list");
     }
+    select_inner(ports)
+}
 
+fn select_inner<A: SelectInner>(ports: &mut [A]) -> uint {
     for (index, port) in ports.mut_iter().enumerate() {
         if port.optimistic_check() {
             return index;
@@ -97,6 +101,117 @@ pub fn select<A: Select>(ports: &mut [A]) -> uint {
     return ready_index;
 }
 
+/// Waits on several ports of different types at once, and optionally gives
+/// up after a timeout.
+///
+/// # Example
+///
+/// ~~~rust
+/// let mut timer = Timer::new().unwrap();
+/// let mut ticks = timer.periodic(100);
+/// // The selector borrows the ports, so it has to be gone before they can be
+/// // received from.
+/// let (data, tick, ready) = {
+///     let mut sel = Selector::new();
+///     let data = sel.add(&mut data_port);
+///     let tick = sel.add(&mut ticks);
+///     sel.timeout(1000);
+///     (data, tick, sel.wait())
+/// };
+/// match ready {
+///     Some(i) if i == data => println!("got {}", data_port.recv()),
+///     Some(i) if i == tick => println!("tick"),
+///     _ => println!("nothing happened for a second")
+/// }
+/// ~~~
+pub struct Selector<'a> {
+    priv ports: ~[&'a mut SelectInner],
+    priv timeout: Option<u64>,
+    // Created the first time that a wait has to time out, and reused by
+    // every later wait
+    priv timer: Option<Timer>,
+}
+
+// A registered port. select() wants a slice of one type, and trait objects
+// can't implement SelectInner themselves (#5121).
+struct Registered<'a> {
+    port: &'a mut SelectInner
+}
+
+impl<'a> SelectInner for Registered<'a> {
+    fn optimistic_check(&mut self) -> bool { self.port.optimistic_check() }
+    fn block_on(&mut self, sched: &mut Scheduler, task: BlockedTask) -> bool {
+        self.port.block_on(sched, task)
+    }
+    fn unblock_from(&mut self) -> bool { self.port.unblock_from() }
+}
+
+impl<'a> Selector<'a> {
+    pub fn new() -> Selector<'a> {
+        Selector { ports: ~[], timeout: None, timer: None }
+    }
+
+    /// Registers a port, returning the index `wait` will return once the
+    /// port is ready.
+    pub fn add<P: Select>(&mut self, port: &'a mut P) -> uint {
+        self.ports.push(port as &'a mut SelectInner);
+        self.ports.len() - 1
+    }
+
+    /// Gives up waiting after `msecs` milliseconds. A timeout of 0 only
+    /// checks whether any of the ports is ready, without blocking.
+    pub fn timeout(&mut self, msecs: u64) {
+        self.timeout = Some(msecs);
+    }
+
+    /// Blocks until one of the ports is ready and returns its index, or
+    /// `None` if the timeout ran out first. As with `select`, if several
+    /// ports are ready the lowest index is returned, and the port can then
+    /// be received from without blocking.
+    pub fn wait(&mut self) -> Option<uint> {
+        let msecs = match self.timeout {
+            Some(msecs) => msecs,
+            None => {
+                if self.ports.is_empty() {
+                    fail!("can't select on an empty list");
+                }
+                let mut ports = self.ports.mut_iter()
+                    .map(|port| Registered { port: &mut **port })
+                    .collect::<~[Registered]>();
+                return Some(select_inner(ports));
+            }
+        };
+
+        // The port which ends the wait goes last, so that it loses any ties.
+        let mut timeout = if msecs == 0 {
+            // A port which is ready already keeps select from blocking, but
+            // unlike the optimistic check (which is only a hint) select still
+            // finds every other port which is ready.
+            let (port, chan) = comm::oneshot();
+            chan.send(());
+            port
+        } else {
+            // Don't bother with a timer if something is ready already.
+            for (index, port) in self.ports.mut_iter().enumerate() {
+                if port.optimistic_check() {
+                    return Some(index);
+                }
+            }
+            if self.timer.is_none() {
+                self.timer = Some(Timer::new().expect("couldn't create a \
+                                                       timer for select"));
+            }
+            self.timer.get_mut_ref().oneshot(msecs)
+        };
+        let mut ports = self.ports.mut_iter()
+            .map(|port| Registered { port: &mut **port })
+            .collect::<~[Registered]>();
+        ports.push(Registered { port: &mut timeout as &mut SelectInner });
+        let index = select_inner(ports);
+        if index < self.ports.len() { Some(index) } else { None }
+    }
+}
+
 /* FIXME(#5121, #7914) This all should be legal, but rust is not clever enough yet.
 
 impl <'self> Select for &'self mut Select {
@@ -298,4 +413,110 @@ mod test {
             }
         }
     }
+
+    /* Selector tests */
+
+    #[test]
+    fn selector_mixed_ports() {
+        do run_in_uv_task {
+            let (mut p1, _c1) = stream::<int>();
+            let (mut p2, c2) = oneshot::<~str>();
+            let (mut p3, _c3) = bounded::<()>(1);
+            let c2 = Cell::new(c2);
+            do task::spawn {
+                c2.take().send(~"hello");
+            }
+
+            let index = {
+                let mut sel = Selector::new();
+                assert_eq!(sel.add(&mut p1), 0);
+                assert_eq!(sel.add(&mut p2), 1);
+                assert_eq!(sel.add(&mut p3), 2);
+                sel.wait()
+            };
+            assert_eq!(index, Some(1));
+            assert_eq!(p2.recv(), ~"hello");
+        }
+    }
+
+    #[test]
+    fn selector_timeout() {
+        do run_in_uv_task {
+            let (mut p1, _c1) = stream::<int>();
+            let (mut p2, _c2) = oneshot::<int>();
+            let mut sel = Selector::new();
+            sel.add(&mut p1);
+            sel.add(&mut p2);
+            sel.timeout(10);
+            assert_eq!(sel.wait(), None);
+        }
+    }
+
+    #[test]
+    fn selector_timeout_zero_polls() {
+        do run_in_uv_task {
+            let (mut p1, c1) = stream::<int>();
+            let (mut p2, c2) = stream::<int>();
+            {
+                let mut sel = Selector::new();
+                sel.add(&mut p1);
+                sel.add(&mut p2);
+                sel.timeout(0);
+                assert_eq!(sel.wait(), None);
+            }
+            // The optimistic check fails at random in test builds, so poll a
+            // few times to be sure that the ports are found without it.
+            c2.send(3);
+            do 10.times {
+                let mut sel = Selector::new();
+                sel.add(&mut p1);
+                sel.add(&mut p2);
+                sel.timeout(0);
+                assert_eq!(sel.wait(), Some(1));
+            }
+            c1.send(4);
+            do 10.times {
+                let mut sel = Selector::new();
+                sel.add(&mut p1);
+                sel.add(&mut p2);
+                sel.timeout(0);
+                assert_eq!(sel.wait(), Some(0));
+            }
+            assert_eq!(p1.recv(), 4);
+            assert_eq!(p2.recv(), 3);
+        }
+    }
+
+    #[test]
+    fn selector_reuses_its_timer() {
+        do run_in_uv_task {
+            let (mut p1, _c1) = stream::<int>();
+            let mut sel = Selector::new();
+            sel.add(&mut p1);
+            sel.timeout(1);
+            assert_eq!(sel.wait(), None);
+            assert!(sel.timer.is_some());
+            assert_eq!(sel.wait(), None);
+        }
+    }
+
+    #[test]
+    fn selector_timer_port() {
+        use io::timer::Timer;
+
+        do run_in_uv_task {
+            let (mut p1, _c1) = oneshot::<int>();
+            let mut timer = Timer::new().unwrap();
+            let mut p2 = timer.oneshot(10);
+            let mut sel = Selector::new();
+            sel.add(&mut p1);
+            sel.add(&mut p2);
+            assert_eq!(sel.wait(), Some(1));
+        }
+    }
+
+    #[test] #[should_fail]
+    fn selector_empty() {
+        Selector::new().wait();
+    }
 }
@@ -17,6 +17,7 @@ use container::Container;
 use default::Default;
 use str::{Str, StrSlice};
 use to_str::ToStr;
+use hash::{Hash, Hasher};
 use to_bytes::{IterBytes, Cb};
 
 /// A SendStr is a string that can hold either a ~str or a &'static str.
@@ -174,6 +175,13 @@ impl IterBytes for SendStr {
     }
 }
 
+impl Hash for SendStr {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use clone::{Clone, DeepClone};
@@ -42,6 +42,19 @@
  * spawned into a new scheduler with the specific properties required. See TaskBuilder's
  * documentation bellow for more information.
  *
+ * Tasks can also be spawned into a pool of schedulers created with `new_sched_pool`,
+ * for example to keep tasks that make blocking calls from holding up the rest of the
+ * program:
+ *
+ * ```
+ * let pool = new_sched_pool("blocking-io", 2);
+ * let mut builder = task();
+ * builder.sched_pool(&pool);
+ * do builder.spawn {
+ *     // runs on one of the pool's two threads
+ * }
+ * ```
+ *
  * # Example
  *
  * ```
@@ -60,10 +73,14 @@ use comm::{stream, Chan, GenericChan, GenericPort, Port, Peekable};
 use result::{Result, Ok, Err};
 use rt::in_green_task_context;
 use rt::local::Local;
+use rt::sched::PoolHandle;
 use rt::task::{UnwindResult, Success, Failure};
 use send_str::{SendStr, IntoSendStr};
 use util;
 
+pub use rt::sched::SchedStats;
+pub use rt::task::{FailureInfo, FailurePayload, set_failure_hook};
+
 #[cfg(test)] use any::Any;
 #[cfg(test)] use comm::SharedChan;
 #[cfg(test)] use ptr;
@@ -125,9 +142,38 @@ pub enum SchedMode {
  *
  * * sched_mode - The operating mode of the scheduler
  *
+ * * pool - A pool of schedulers to run the task on, instead of the
+ *          scheduler selected by the mode
+ *
  */
 pub struct SchedOpts {
     priv mode: SchedMode,
+    priv pool: Option<PoolHandle>,
+}
+
+/**
+ * A pool of scheduler threads that tasks can be spawned into.
+ *
+ * The schedulers of a pool steal work from each other but not from the
+ * rest of the runtime, and a task spawned into the pool stays on it for
+ * its whole life. The pool shuts down once every handle to it has been
+ * dropped and all of its tasks have exited.
+ */
+#[deriving(Clone)]
+pub struct SchedPool {
+    priv handle: PoolHandle
+}
+
+impl SchedPool {
+    /// The name the pool was created with.
+    pub fn name<'a>(&'a self) -> &'a str {
+        self.handle.name()
+    }
+
+    /// A snapshot of the activity of each of the pool's schedulers.
+    pub fn stats(&self) -> ~[SchedStats] {
+        self.handle.stats()
+    }
 }
 
 /**
@@ -144,6 +190,9 @@ pub struct SchedOpts {
  *
  * * name - A name for the task-to-be, for identification in failure messages.
  *
+ * * on_failure - A function to run in the task if it fails, before the
+ *                global failure hook.
+ *
  * * sched - Specify the configuration of a new scheduler to create the task
  *           in. This is of particular importance for libraries which want to call
  *           into foreign code that blocks. Without doing so in a different
@@ -153,6 +202,7 @@ pub struct TaskOpts {
     priv watched: bool,
     priv notify_chan: Option<Chan<UnwindResult>>,
     name: Option<SendStr>,
+    priv on_failure: Option<proc(&FailureInfo)>,
     sched: SchedOpts,
     stack_size: Option<uint>
 }
@@ -193,11 +243,13 @@ impl TaskBuilder {
         let gen_body = self.gen_body.take();
         let notify_chan = self.opts.notify_chan.take();
         let name = self.opts.name.take();
+        let on_failure = self.opts.on_failure.take();
         TaskBuilder {
             opts: TaskOpts {
                 watched: self.opts.watched,
                 notify_chan: notify_chan,
                 name: name,
+                on_failure: on_failure,
                 sched: self.opts.sched,
                 stack_size: self.opts.stack_size
             },
@@ -258,11 +310,23 @@ impl TaskBuilder {
         self.opts.name = Some(name.into_send_str());
     }
 
+    /// Run `hook` in the task if it fails, before the global failure hook.
+    /// The hook is told what the task failed with and where.
+    pub fn on_failure(&mut self, hook: proc(&FailureInfo)) {
+        self.opts.on_failure = Some(hook);
+    }
+
     /// Configure a custom scheduler mode for the task.
     pub fn sched_mode(&mut self, mode: SchedMode) {
         self.opts.sched.mode = mode;
     }
 
+    /// Run the task on the schedulers of `pool`. This takes precedence over
+    /// the scheduler mode.
+    pub fn sched_pool(&mut self, pool: &SchedPool) {
+        self.opts.sched.pool = Some(pool.handle.clone());
+    }
+
     /**
      * Add a wrapper to the body of the spawned task.
      *
@@ -311,11 +375,13 @@ impl TaskBuilder {
         let gen_body = self.gen_body.take();
         let notify_chan = self.opts.notify_chan.take();
         let name = self.opts.name.take();
+        let on_failure = self.opts.on_failure.take();
         let x = self.consume();
         let opts = TaskOpts {
             watched: x.opts.watched,
             notify_chan: notify_chan,
             name: name,
+            on_failure: on_failure,
             sched: x.opts.sched,
             stack_size: x.opts.stack_size
         };
@@ -338,7 +404,10 @@ impl TaskBuilder {
      *
      * If the function executed successfully then try returns result::ok
      * containing the value returned by the function. If the function fails
-     * then try returns result::err containing nil.
+     * then try returns result::err containing the failure's cause. A task
+     * which failed with `fail!` leaves a `FailurePayload` there, not the
+     * value given to `fail!` itself: downcast the cause to `FailurePayload`
+     * to get at the message (e.g. with `msg_str`) and the location.
      *
      * # Failure
      * Fails if a future_result was already set for this task.
@@ -374,8 +443,10 @@ pub fn default_task_opts() -> TaskOpts {
         watched: true,
         notify_chan: None,
         name: None,
+        on_failure: None,
         sched: SchedOpts {
             mode: DefaultScheduler,
+            pool: None,
         },
         stack_size: None
     }
@@ -412,12 +483,42 @@ pub fn spawn_sched(mode: SchedMode, f: proc()) {
     task.spawn(f)
 }
 
+pub fn new_sched_pool<S: IntoSendStr>(name: S, nthreads: uint) -> SchedPool {
+    /*!
+     * Starts a pool of `nthreads` schedulers, each on its own thread.
+     *
+     * Tasks are spawned into the pool with `TaskBuilder::sched_pool`.
+     *
+     * # Failure
+     *
+     * Fails if `nthreads` is zero.
+     */
+
+    SchedPool { handle: spawn::spawn_pool(name.into_send_str(), nthreads) }
+}
+
+pub fn sched_stats() -> Option<SchedStats> {
+    /*!
+     * A snapshot of the activity of the scheduler running the current task,
+     * or `None` when not running on a scheduler.
+     */
+
+    use rt::sched::Scheduler;
+
+    if in_green_task_context() {
+        Some(Local::borrow(|sched: &mut Scheduler| sched.stats()))
+    } else {
+        None
+    }
+}
+
 pub fn try<T:Send>(f: proc() -> T) -> Result<T, ~Any> {
     /*!
      * Execute a function in another task and return either the return value
      * of the function or result::err.
      *
-     * This is equivalent to task().supervised().try.
+     * This is equivalent to task().supervised().try. See `TaskBuilder::try`
+     * for what the `Err` holds when the task fails.
      */
 
     let task = task();
@@ -658,6 +759,59 @@ fn test_spawn_sched_childs_on_default_sched() {
     po.recv();
 }
 
+#[test]
+fn test_sched_pool() {
+    let pool = new_sched_pool("test-pool", 2);
+    assert_eq!(pool.name(), "test-pool");
+    assert_eq!(pool.stats().len(), 2);
+
+    let default_id = get_sched_id();
+    let (po, ch) = stream::<()>();
+    let (done_po, done_ch) = stream::<()>();
+    let mut builder = task();
+    builder.sched_pool(&pool);
+    do builder.spawn {
+        use rt::task::Task;
+
+        assert!(get_sched_id() != default_id);
+        // Being woken up from outside the pool sends the task back to it
+        po.recv();
+        assert!(Task::on_appropriate_sched());
+        assert!(get_sched_id() != default_id);
+        done_ch.send(());
+    }
+    ch.send(());
+    done_po.recv();
+}
+
+#[test]
+fn test_sched_pool_childs_on_default_sched() {
+    let pool = new_sched_pool("test-pool", 1);
+    let (po, ch) = stream();
+
+    let mut builder = task();
+    builder.sched_pool(&pool);
+    do builder.spawn {
+        let parent_sched_id = get_sched_id();
+        do spawn {
+            assert!(get_sched_id() != parent_sched_id);
+            ch.send(());
+        }
+    }
+
+    po.recv();
+}
+
+#[test]
+fn test_sched_stats() {
+    use unstable::run_in_bare_thread;
+
+    assert!(sched_stats().is_some());
+    do run_in_bare_thread {
+        assert!(sched_stats().is_none());
+    }
+}
+
 #[test]
 fn test_spawn_sched_blocking() {
     use unstable::mutex::Mutex;
@@ -794,8 +948,9 @@ fn test_try_fail_message_static_str() {
     } {
         Err(e) => {
             type T = &'static str;
-            assert!(e.is::<T>());
-            assert_eq!(*e.move::<T>().unwrap(), "static string");
+            let msg = e.move::<FailurePayload>().unwrap().unwrap();
+            assert!(msg.is::<T>());
+            assert_eq!(*msg.move::<T>().unwrap(), "static string");
         }
         Ok(()) => fail!()
     }
@@ -808,8 +963,9 @@ fn test_try_fail_message_owned_str() {
     } {
         Err(e) => {
             type T = ~str;
-            assert!(e.is::<T>());
-            assert_eq!(*e.move::<T>().unwrap(), ~"owned string");
+            let msg = e.move::<FailurePayload>().unwrap().unwrap();
+            assert!(msg.is::<T>());
+            assert_eq!(*msg.move::<T>().unwrap(), ~"owned string");
         }
         Ok(()) => fail!()
     }
@@ -822,8 +978,9 @@ fn test_try_fail_message_any() {
     } {
         Err(e) => {
             type T = ~Any;
-            assert!(e.is::<T>());
-            let any = e.move::<T>().unwrap();
+            let msg = e.move::<FailurePayload>().unwrap().unwrap();
+            assert!(msg.is::<T>());
+            let any = msg.move::<T>().unwrap();
             assert!(any.is::<u16>());
             assert_eq!(*any.move::<u16>().unwrap(), 413u16);
         }
@@ -838,7 +995,45 @@ fn test_try_fail_message_unit_struct() {
     match do try {
         fail!(Juju)
     } {
-        Err(ref e) if e.is::<Juju>() => {}
-        Err(_) | Ok(()) => fail!()
+        Err(e) => {
+            let payload = e.move::<FailurePayload>().unwrap();
+            assert!(payload.msg.is::<Juju>());
+        }
+        Ok(()) => fail!()
     }
 }
+
+#[test]
+fn test_try_fail_location() {
+    let line = line!();
+    match do try { fail!("located") } {
+        Err(e) => {
+            let payload = e.move::<FailurePayload>().unwrap();
+            assert_eq!(payload.msg_str(), Some("located"));
+            assert!(payload.file.ends_with("mod.rs"));
+            assert_eq!(payload.line, line + 1);
+        }
+        Ok(()) => fail!()
+    }
+}
+
+#[test]
+fn test_on_failure_hook() {
+    let (po, ch) = stream();
+    let mut builder = task();
+    builder.name("hooked");
+    builder.on_failure(proc(info: &FailureInfo) {
+        let msg = info.cause.as_ref::<&'static str>().map(|s| s.to_owned());
+        ch.send((msg, info.task_name.map(|n| n.to_owned()), info.file, info.line));
+    });
+
+    let line = line!();
+    let result: Result<(), ~Any> = do builder.try { fail!("hooked failure") };
+    assert!(result.is_err());
+
+    let (msg, name, file, hook_line) = po.recv();
+    assert_eq!(msg, Some(~"hooked failure"));
+    assert_eq!(name, Some(~"hooked"));
+    assert!(file.ends_with("mod.rs"));
+    assert_eq!(hook_line, line + 1);
+}
@@ -80,12 +80,15 @@ use prelude::*;
 use cell::Cell;
 use comm::{GenericChan, oneshot};
 use rt::local::Local;
-use rt::sched::{Scheduler, Shutdown, TaskFromFriend};
-use rt::task::{Task, Sched};
+use rt::sched::{Scheduler, Shutdown, TaskFromFriend, PoolHandle, next_pool_id};
+use rt::sleeper_list::SleeperList;
+use rt::task::{Task, Sched, Pool};
 use rt::task::UnwindResult;
 use rt::thread::Thread;
 use rt::work_queue::WorkQueue;
 use rt::{in_green_task_context, new_event_loop};
+use send_str::SendStr;
+use vec;
 use task::SingleThreaded;
 use task::TaskOpts;
 
@@ -96,7 +99,15 @@ use task::TaskOpts;
 pub fn spawn_raw(mut opts: TaskOpts, f: proc()) {
     assert!(in_green_task_context());
 
-    let mut task = if opts.sched.mode != SingleThreaded {
+    let mut task = if opts.sched.pool.is_some() {
+        // The task is sent on to the pool when it is first scheduled
+        let pool = opts.sched.pool.take_unwrap();
+        if opts.watched {
+            Task::build_homed_child(opts.stack_size, f, Pool(pool))
+        } else {
+            Task::build_homed_root(opts.stack_size, f, Pool(pool))
+        }
+    } else if opts.sched.mode != SingleThreaded {
         if opts.watched {
             Task::build_child(opts.stack_size, f)
         } else {
@@ -132,44 +143,7 @@ pub fn spawn_raw(mut opts: TaskOpts, f: proc()) {
                 Task::build_homed_root(opts.stack_size, f, Sched(new_sched_handle))
             };
 
-            // Create a task that will later be used to join with the new scheduler
-            // thread when it is ready to terminate
-            let (thread_port, thread_chan) = oneshot();
-            let thread_port_cell = Cell::new(thread_port);
-            let join_task = do Task::build_child(None) {
-                debug!("running join task");
-                let thread_port = thread_port_cell.take();
-                let thread: Thread<()> = thread_port.recv();
-                thread.join();
-            };
-
-            // Put the scheduler into another thread
-            let new_sched_cell = Cell::new(new_sched);
-            let orig_sched_handle_cell = Cell::new((*sched).make_handle());
-            let join_task_cell = Cell::new(join_task);
-
-            let thread = do Thread::start {
-                let mut new_sched = new_sched_cell.take();
-                let mut orig_sched_handle = orig_sched_handle_cell.take();
-                let join_task = join_task_cell.take();
-
-                let bootstrap_task = ~do Task::new_root(&mut new_sched.stack_pool, None) || {
-                    debug!("boostrapping a 1:1 scheduler");
-                };
-                new_sched.bootstrap(bootstrap_task);
-
-                // Now tell the original scheduler to join with this thread
-                // by scheduling a thread-joining task on the original scheduler
-                orig_sched_handle.send(TaskFromFriend(join_task));
-
-                // NB: We can't simply send a message from here to another task
-                // because this code isn't running in a task and message passing doesn't
-                // work outside of tasks. Hence we're sending a scheduler message
-                // to execute a new task directly to a scheduler.
-            };
-
-            // Give the thread handle to the join task
-            thread_chan.send(thread);
+            start_sched_thread(new_sched);
 
             // When this task is enqueued on the current scheduler it will then get
             // forwarded to the scheduler to which it is pinned
@@ -187,11 +161,92 @@ pub fn spawn_raw(mut opts: TaskOpts, f: proc()) {
     }
 
     task.name = opts.name.take();
+    task.unwinder.on_failure = opts.on_failure.take();
     debug!("spawn calling run_task");
     Scheduler::run_task(task);
 
 }
 
+/// Start a pool of `nthreads` new schedulers, each on its own thread. The
+/// schedulers only steal work from each other, and only run tasks homed to
+/// the pool. Other tasks are sent back to the current scheduler.
+pub fn spawn_pool(name: SendStr, nthreads: uint) -> PoolHandle {
+    assert!(in_green_task_context());
+    assert!(nthreads > 0);
+
+    let id = next_pool_id();
+    let sleepers = SleeperList::new();
+    let work_queues: ~[WorkQueue<~Task>] = vec::from_fn(nthreads, |_| WorkQueue::new());
+
+    let mut scheds: ~[~Scheduler] = work_queues.iter().map(|work_queue| {
+        let friend_handle = unsafe {
+            let sched: *mut Scheduler = Local::unsafe_borrow();
+            (*sched).make_handle()
+        };
+        let mut sched = ~Scheduler::new_special(new_event_loop(),
+                                                work_queue.clone(),
+                                                work_queues.clone(),
+                                                sleepers.clone(),
+                                                false,
+                                                Some(friend_handle));
+        sched.pool_id = id;
+        sched
+    }).collect();
+    let pool = PoolHandle::new(id, name, work_queues, sleepers, &mut scheds);
+
+    for sched in scheds.move_iter() {
+        start_sched_thread(sched);
+    }
+    pool
+}
+
+/// Run a scheduler on a new thread. The thread is joined by a task sent
+/// back to the current scheduler once the new scheduler has shut down.
+fn start_sched_thread(new_sched: ~Scheduler) {
+    // Create a task that will later be used to join with the new scheduler
+    // thread when it is ready to terminate
+    let (thread_port, thread_chan) = oneshot();
+    let thread_port_cell = Cell::new(thread_port);
+    let join_task = do Task::build_child(None) {
+        debug!("running join task");
+        let thread_port = thread_port_cell.take();
+        let thread: Thread<()> = thread_port.recv();
+        thread.join();
+    };
+
+    // Put the scheduler into another thread
+    let orig_sched_handle = unsafe {
+        let sched: *mut Scheduler = Local::unsafe_borrow();
+        (*sched).make_handle()
+    };
+    let new_sched_cell = Cell::new(new_sched);
+    let orig_sched_handle_cell = Cell::new(orig_sched_handle);
+    let join_task_cell = Cell::new(join_task);
+
+    let thread = do Thread::start {
+        let mut new_sched = new_sched_cell.take();
+        let mut orig_sched_handle = orig_sched_handle_cell.take();
+        let join_task = join_task_cell.take();
+
+        let bootstrap_task = ~do Task::new_root(&mut new_sched.stack_pool, None) || {
+            debug!("boostrapping a new scheduler");
+        };
+        new_sched.bootstrap(bootstrap_task);
+
+        // Now tell the original scheduler to join with this thread
+        // by scheduling a thread-joining task on the original scheduler
+        orig_sched_handle.send(TaskFromFriend(join_task));
+
+        // NB: We can't simply send a message from here to another task
+        // because this code isn't running in a task and message passing doesn't
+        // work outside of tasks. Hence we're sending a scheduler message
+        // to execute a new task directly to a scheduler.
+    };
+
+    // Give the thread handle to the join task
+    thread_chan.send(thread);
+}
+
 #[test]
 fn test_spawn_raw_simple() {
     let (po, ch) = stream();
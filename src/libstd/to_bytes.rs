@@ -25,11 +25,9 @@ use vec::{Vector, ImmutableVector};
 pub type Cb<'self> = 'self |buf: &[u8]| -> bool;
 
 ///
-/// A trait to implement in order to make a type hashable;
-/// This works in combination with the trait `std::hash::Hash`, and
-/// may in the future be merged with that trait or otherwise
-/// modified when default methods and trait inheritance are
-/// completed.
+/// A trait to implement in order to flatten a value into a sequence
+/// of bytes, as used by `ToBytes`. Hashing is done with the separate
+/// `std::hash::Hash` trait.
 ///
 /// IterBytes should be implemented so that the extent of the
 /// produced byte stream can be discovered, given the original
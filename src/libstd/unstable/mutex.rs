@@ -119,6 +119,15 @@ impl Mutex {
     /// This function assumes that the lock is already held
     pub unsafe fn wait(&mut self) { imp::wait(self.getcond(), self.getlock()) }
 
+    /// Block on the internal condition variable for at most `msecs`
+    /// milliseconds. Returns false if the time ran out before the condition
+    /// was signaled; like `wait`, a return of true may be a spurious wakeup.
+    ///
+    /// This function assumes that the lock is already held
+    pub unsafe fn wait_timeout(&mut self, msecs: u64) -> bool {
+        imp::wait_timeout(self.getcond(), self.getlock(), msecs)
+    }
+
     /// Signals a thread in `wait` to wake up
     pub unsafe fn signal(&mut self) { imp::signal(self.getcond()) }
 
@@ -216,6 +225,15 @@ mod imp {
         assert_eq!(pthread_cond_wait(cond, m), 0);
     }
 
+    pub unsafe fn wait_timeout(cond: *pthread_cond_t, m: *pthread_mutex_t,
+                               msecs: u64) -> bool {
+        match rust_pthread_cond_timedwait_ms(cond, m, msecs) {
+            0 => true,
+            libc::ETIMEDOUT => false,
+            n => fail!("pthread_cond_timedwait failed: {}", n)
+        }
+    }
+
     pub unsafe fn signal(cond: *pthread_cond_t) {
         assert_eq!(pthread_cond_signal(cond), 0);
     }
@@ -223,6 +241,9 @@ mod imp {
     extern {
         fn rust_pthread_mutex_t_size() -> libc::c_int;
         fn rust_pthread_cond_t_size() -> libc::c_int;
+        fn rust_pthread_cond_timedwait_ms(cond: *pthread_cond_t,
+                                          lock: *pthread_mutex_t,
+                                          msecs: u64) -> libc::c_int;
     }
 
     extern {
@@ -290,12 +311,19 @@ mod imp {
         lock(m);
     }
 
+    pub unsafe fn wait_timeout(cond: *c_void, m: *c_void, msecs: u64) -> bool {
+        let ret = rust_pthread_cond_timedwait_ms(cond, m, msecs);
+        ret != libc::WAIT_TIMEOUT as libc::c_int
+    }
+
     pub unsafe fn signal(cond: *c_void) {
         assert!(SetEvent(cond as HANDLE) != 0);
     }
 
     extern {
         fn rust_crit_section_size() -> libc::c_int;
+        fn rust_pthread_cond_timedwait_ms(cond: *c_void, lock: *c_void,
+                                          msecs: u64) -> libc::c_int;
     }
 
     extern "system" {
@@ -345,6 +373,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn smoke_cond_timeout() {
+        static mut lock: Mutex = MUTEX_INIT;
+        unsafe {
+            lock.lock();
+            // Nobody signals, so this has to time out eventually; a spurious
+            // wakeup just means another round.
+            while lock.wait_timeout(10) {}
+            lock.unlock();
+        }
+    }
+
     #[test]
     fn destroy_immediately() {
         unsafe {
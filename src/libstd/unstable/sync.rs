@@ -35,6 +35,8 @@ pub enum UnsafeArcUnwrap<T> {
     UnsafeArcT(T)
 }
 
+// Only the tests use these
+#[allow(dead_code)]
 impl<T> UnsafeArcUnwrap<T> {
     fn expect_t(self, msg: &'static str) -> T {
         match self {
@@ -520,9 +522,10 @@ mod tests {
             // accesses will also fail.
             let x = Exclusive::new(1);
             let x2 = x.clone();
-            do task::try || {
+            let result = do task::try || {
                 x2.with(|one| assert_eq!(*one, 2))
             };
+            assert!(result.is_err());
             x.with(|one| assert_eq!(*one, 1));
         }
     }
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::to_bytes;
+use std::hash::{Hash, Hasher};
 
 #[deriving(Eq)]
 pub enum Os { OsWin32, OsMacos, OsLinux, OsAndroid, OsFreebsd, }
@@ -264,15 +264,15 @@ impl AbiSet {
     }
 }
 
-impl to_bytes::IterBytes for Abi {
-    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
-        self.index().iter_bytes(lsb0, f)
+impl Hash for Abi {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index().hash(state)
     }
 }
 
-impl to_bytes::IterBytes for AbiSet {
-    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
-        self.bits.iter_bytes(lsb0, f)
+impl Hash for AbiSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state)
     }
 }
 
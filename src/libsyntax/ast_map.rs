@@ -23,6 +23,7 @@ use print::pprust;
 use visit::{Visitor, fn_kind};
 use visit;
 
+use std::hash;
 use std::hashmap::HashMap;
 use std::vec;
 
@@ -89,7 +90,7 @@ pub fn impl_pretty_name(trait_ref: &Option<trait_ref>,
         ty_path(ref path, _, _) => path.segments.last().identifier,
         _ => default
     };
-    let hash = (trait_ref, ty).hash();
+    let hash = hash::hash(&(trait_ref, ty));
     match *trait_ref {
         None => path_pretty_name(ty_ident, hash),
         Some(ref trait_ref) => {
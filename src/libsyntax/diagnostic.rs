@@ -14,7 +14,10 @@ use codemap;
 use std::io;
 use std::io::stdio::StdWriter;
 use std::local_data;
+use extra::json;
+use extra::json::ToJson;
 use extra::term;
+use extra::treemap::TreeMap;
 
 static BUG_REPORT_URL: &'static str =
     "https://github.com/mozilla/rust/wiki/HOWTO-submit-a-Rust-bug-report";
@@ -24,6 +27,19 @@ pub trait Emitter {
             cmsp: Option<(@codemap::CodeMap, Span)>,
             msg: &str,
             lvl: level);
+
+    /// Emits a diagnostic reported by the lint named `lint`. Emitters which
+    /// don't tell lints apart from other diagnostics can leave this alone.
+    fn emit_lint(&self,
+                 cmsp: Option<(@codemap::CodeMap, Span)>,
+                 msg: &str,
+                 lvl: level,
+                 _lint: &str) {
+        self.emit(cmsp, msg, lvl)
+    }
+
+    /// Writes out any diagnostics the emitter is still holding on to.
+    fn flush(&self) {}
 }
 
 // a handler deals with errors; certain errors
@@ -45,6 +61,11 @@ pub trait handler {
             cmsp: Option<(@codemap::CodeMap, Span)>,
             msg: &str,
             lvl: level);
+    fn emit_lint(@mut self,
+                 cmsp: Option<(@codemap::CodeMap, Span)>,
+                 msg: &str,
+                 lvl: level,
+                 lint: &str);
 }
 
 // a span-handler is like a handler but also
@@ -57,6 +78,8 @@ pub trait span_handler {
     fn span_note(@mut self, sp: Span, msg: &str);
     fn span_bug(@mut self, sp: Span, msg: &str) -> !;
     fn span_unimpl(@mut self, sp: Span, msg: &str) -> !;
+    // a diagnostic reported by the named lint, at the level the lint is set to
+    fn span_lint(@mut self, sp: Span, msg: &str, lvl: level, lint: &str);
     fn handler(@mut self) -> @mut handler;
 }
 
@@ -91,6 +114,12 @@ impl span_handler for CodemapT {
     fn span_unimpl(@mut self, sp: Span, msg: &str) -> ! {
         self.span_bug(sp, ~"unimplemented " + msg);
     }
+    fn span_lint(@mut self, sp: Span, msg: &str, lvl: level, lint: &str) {
+        self.handler.emit_lint(Some((self.cm, sp)), msg, lvl, lint);
+        if lvl == error {
+            self.handler.bump_err_count();
+        }
+    }
     fn handler(@mut self) -> @mut handler {
         self.handler
     }
@@ -144,6 +173,13 @@ impl handler for HandlerT {
             lvl: level) {
         self.emit.emit(cmsp, msg, lvl);
     }
+    fn emit_lint(@mut self,
+                 cmsp: Option<(@codemap::CodeMap, Span)>,
+                 msg: &str,
+                 lvl: level,
+                 lint: &str) {
+        self.emit.emit_lint(cmsp, msg, lvl, lint);
+    }
 }
 
 pub fn ice_msg(msg: &str) -> ~str {
@@ -350,6 +386,135 @@ fn print_macro_backtrace(cm: @codemap::CodeMap, sp: Span) {
     }
 }
 
+/// An emitter which writes each diagnostic to stderr as a single line of JSON,
+/// for consumption by tools. An object looks like
+///
+/// ~~~
+/// {"level": "error", "message": "...", "lint": null,
+///  "span": {"file": "foo.rs", "line_start": 1, "col_start": 5,
+///           "line_end": 1, "col_end": 8},
+///  "notes": [{"message": "...", "span": null}],
+///  "expansions": [{"macro": "fail", "span": null, "call_site": {...}}]}
+/// ~~~
+///
+/// Lines and columns are 1-based and the end column is exclusive. The notes
+/// of a diagnostic are the `note`s emitted right after it, so an object is
+/// only written once the next diagnostic arrives or the emitter is flushed.
+pub struct JsonEmitter {
+    // the last diagnostic and the notes gathered for it so far
+    priv pending: @mut Option<(~json::Object, json::List)>,
+}
+
+impl JsonEmitter {
+    pub fn new() -> JsonEmitter {
+        JsonEmitter { pending: @mut None }
+    }
+
+    fn add(&self,
+           cmsp: Option<(@codemap::CodeMap, Span)>,
+           msg: &str,
+           lvl: level,
+           lint: Option<&str>) {
+        let span = match cmsp {
+            Some((cm, sp)) => Some((cm, cm.adjust_span(sp))),
+            None => None
+        };
+
+        // A note belongs to whatever was reported just before it
+        if lvl == note {
+            match *self.pending {
+                Some((_, ref mut notes)) => {
+                    let mut obj = ~TreeMap::new();
+                    obj.insert(~"message", msg.to_str().to_json());
+                    obj.insert(~"span", json_span(span));
+                    notes.push(json::Object(obj));
+                    return;
+                }
+                None => {}
+            }
+        }
+        self.flush();
+
+        let mut obj = ~TreeMap::new();
+        obj.insert(~"level", diagnosticstr(lvl).to_json());
+        obj.insert(~"message", msg.to_str().to_json());
+        obj.insert(~"lint", match lint {
+            Some(lint) => lint.to_str().to_json(),
+            None => json::Null
+        });
+        obj.insert(~"span", json_span(span));
+        obj.insert(~"expansions", json::List(match span {
+            Some((cm, sp)) => json_macro_backtrace(cm, sp),
+            None => ~[]
+        }));
+        *self.pending = Some((obj, ~[]));
+
+        // Nothing else is going to be reported after a fatal error
+        if lvl == fatal {
+            self.flush();
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&self,
+            cmsp: Option<(@codemap::CodeMap, Span)>,
+            msg: &str,
+            lvl: level) {
+        self.add(cmsp, msg, lvl, None);
+    }
+
+    fn emit_lint(&self,
+                 cmsp: Option<(@codemap::CodeMap, Span)>,
+                 msg: &str,
+                 lvl: level,
+                 lint: &str) {
+        self.add(cmsp, msg, lvl, Some(lint));
+    }
+
+    fn flush(&self) {
+        match self.pending.take() {
+            Some((mut obj, notes)) => {
+                obj.insert(~"notes", json::List(notes));
+                let line = json::Object(obj).to_str() + "\n";
+                io::stderr().write(line.as_bytes());
+            }
+            None => {}
+        }
+    }
+}
+
+fn json_span(span: Option<(@codemap::CodeMap, Span)>) -> json::Json {
+    match span {
+        Some((cm, sp)) => {
+            let lo = cm.lookup_char_pos(sp.lo);
+            let hi = cm.lookup_char_pos(sp.hi);
+            let mut obj = ~TreeMap::new();
+            obj.insert(~"file", lo.file.name.to_str().to_json());
+            obj.insert(~"line_start", lo.line.to_json());
+            obj.insert(~"col_start", (lo.col.to_uint() + 1).to_json());
+            obj.insert(~"line_end", hi.line.to_json());
+            obj.insert(~"col_end", (hi.col.to_uint() + 1).to_json());
+            json::Object(obj)
+        }
+        None => json::Null
+    }
+}
+
+// The JSON counterpart of print_macro_backtrace, innermost expansion first
+fn json_macro_backtrace(cm: @codemap::CodeMap, sp: Span) -> ~[json::Json] {
+    let mut expansions = ~[];
+    for ei in sp.expn_info.iter() {
+        let mut obj = ~TreeMap::new();
+        obj.insert(~"macro", ei.callee.name.to_str().to_json());
+        obj.insert(~"span", json_span(ei.callee.span.as_ref().map(|sp| (cm, *sp))));
+        obj.insert(~"call_site", json_span(Some((cm, ei.call_site))));
+        expansions.push(json::Object(obj));
+        expansions.push_all_move(json_macro_backtrace(cm, ei.call_site));
+    }
+    expansions
+}
+
 pub fn expect<T:Clone>(
               diag: @mut span_handler,
               opt: Option<T>,
@@ -61,7 +61,7 @@ pub type SyntaxExpanderTTFunNoCtxt =
                      token_tree: &[ast::token_tree])
                      -> MacResult;
 
-enum SyntaxExpanderTTExpander {
+pub enum SyntaxExpanderTTExpander {
     SyntaxExpanderTTExpanderWithoutContext(SyntaxExpanderTTFunNoCtxt),
 }
 
@@ -80,7 +80,7 @@ impl SyntaxExpanderTTTrait for SyntaxExpanderTT {
     }
 }
 
-enum SyntaxExpanderTTItemExpander {
+pub enum SyntaxExpanderTTItemExpander {
     SyntaxExpanderTTItemExpanderWithContext(SyntaxExpanderTTItemFun),
     SyntaxExpanderTTItemExpanderWithoutContext(SyntaxExpanderTTItemFunNoCtxt),
 }
@@ -162,6 +162,37 @@ pub enum SyntaxExtension {
 }
 
 
+/// What the expander needs to know about a crate named in a
+/// `#[phase(syntax)] extern mod` in order to load its syntax extensions.
+pub struct MacroCrate {
+    /// The dynamic library of the crate
+    lib: Path,
+    /// The symbol of the crate's `#[macro_registrar]` function, if any
+    registrar: Option<~str>,
+}
+
+/// Finds the crates whose syntax extensions are loaded during expansion. This
+/// is implemented by the compiler, which knows how to search for libraries.
+pub trait CrateLoader {
+    fn load_crate(&mut self, crate: &ast::view_item) -> MacroCrate;
+}
+
+/// The type of the `#[macro_registrar]` function of a crate providing syntax
+/// extensions. It is given a callback through which to register each of its
+/// extensions under a name:
+///
+/// ~~~
+/// #[macro_registrar]
+/// pub fn registrar(register: |ast::Name, SyntaxExtension|) {
+///     register(token::intern("sql"), NormalTT(..., None));
+///     register(token::intern("deriving_Protocol"), ItemDecorator(expand));
+/// }
+/// ~~~
+///
+/// An `ItemDecorator` registered as `deriving_Foo` is not a decorator of its
+/// own but handles `Foo` in `#[deriving(...)]`.
+pub type MacroRegistrarFun = extern "Rust" fn(|ast::Name, SyntaxExtension|);
+
 // The SyntaxEnv is the environment that's threaded through the expansion
 // of macros. It contains bindings for macros, and also a special binding
 // for " block" (not a legal identifier) that maps to a BlockInfo
@@ -201,15 +232,6 @@ pub fn syntax_expander_table() -> SyntaxEnv {
         } as @SyntaxExpanderTTTrait,
         None))
     }
-    // utility function to simplify creating IdentTT syntax extensions
-    // that ignore their contexts
-    fn builtin_item_tt_no_ctxt(f: SyntaxExpanderTTItemFunNoCtxt) -> @Transformer {
-        @SE(IdentTT(@SyntaxExpanderTTItem {
-            expander: SyntaxExpanderTTItemExpanderWithoutContext(f),
-            span: None,
-        } as @SyntaxExpanderTTItemTrait,
-        None))
-    }
     let mut syntax_expanders = HashMap::new();
     // NB identifier starts with space, and can't conflict with legal idents
     syntax_expanders.insert(intern(&" block"),
@@ -322,7 +344,10 @@ pub struct ExtCtxt {
     // types that make this hard to get right at the
     // moment. - nmatsakis
     mod_path: @mut ~[ast::Ident],
-    trace_mac: @mut bool
+    trace_mac: @mut bool,
+
+    // #[deriving] expanders loaded from other crates, by trait name
+    deriving_exts: @mut HashMap<@str, ItemDecorator>
 }
 
 impl ExtCtxt {
@@ -333,7 +358,8 @@ impl ExtCtxt {
             cfg: cfg,
             backtrace: @mut None,
             mod_path: @mut ~[],
-            trace_mac: @mut false
+            trace_mac: @mut false,
+            deriving_exts: @mut HashMap::new()
         }
     }
 
@@ -516,7 +542,7 @@ pub enum MapChain<K,V> {
 
 
 // get the map from an env frame
-impl <K: Eq + Hash + IterBytes + 'static, V: 'static> MapChain<K,V>{
+impl <K: Eq + Hash + 'static, V: 'static> MapChain<K,V>{
     // Constructor. I don't think we need a zero-arg one.
     pub fn new(init: ~HashMap<K,@V>) -> @mut MapChain<K,V> {
         @mut BaseMapChain(init)
@@ -621,7 +647,7 @@ impl <K: Eq + Hash + IterBytes + 'static, V: 'static> MapChain<K,V>{
 }
 
 // returns true if the binding for 'n' satisfies 'pred' in 'map'
-fn satisfies_pred<K:Eq + Hash + IterBytes,
+fn satisfies_pred<K:Eq + Hash,
                   V>(
                   map: &mut HashMap<K,V>,
                   n: &K,
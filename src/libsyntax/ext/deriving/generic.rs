@@ -955,12 +955,12 @@ enum StructType {
     Unknown, Record, Tuple
 }
 
-fn create_struct_pattern(cx: @ExtCtxt,
-                         trait_span: Span,
-                         struct_ident: Ident,
-                         struct_def: &struct_def,
-                         prefix: &str,
-                         mutbl: ast::Mutability)
+pub fn create_struct_pattern(cx: @ExtCtxt,
+                             trait_span: Span,
+                             struct_ident: Ident,
+                             struct_def: &struct_def,
+                             prefix: &str,
+                             mutbl: ast::Mutability)
     -> (@ast::Pat, ~[(Span, Option<Ident>, @Expr)]) {
     if struct_def.fields.is_empty() {
         return (
@@ -1015,10 +1015,10 @@ fn create_struct_pattern(cx: @ExtCtxt,
     (pattern, ident_expr)
 }
 
-fn create_enum_variant_pattern(cx: @ExtCtxt,
-                               variant: &ast::variant,
-                               prefix: &str,
-                               mutbl: ast::Mutability)
+pub fn create_enum_variant_pattern(cx: @ExtCtxt,
+                                   variant: &ast::variant,
+                                   prefix: &str,
+                                   mutbl: ast::Mutability)
     -> (@ast::Pat, ~[(Span, Option<Ident>, @Expr)]) {
 
     let variant_ident = variant.node.name;
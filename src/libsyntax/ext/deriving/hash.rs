@@ -0,0 +1,84 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use ast;
+use ast::{MetaItem, item, Expr};
+use codemap::Span;
+use ext::base::ExtCtxt;
+use ext::build::AstBuilder;
+use ext::deriving::generic::*;
+
+pub fn expand_deriving_hash(cx: @ExtCtxt,
+                            span: Span,
+                            mitem: @MetaItem,
+                            in_items: ~[@item]) -> ~[@item] {
+    let trait_def = TraitDef {
+        path: Path::new(~["std", "hash", "Hash"]),
+        additional_bounds: ~[],
+        generics: LifetimeBounds::empty(),
+        methods: ~[
+            MethodDef {
+                name: "hash",
+                generics: LifetimeBounds {
+                    lifetimes: ~[],
+                    bounds: ~[("__H",
+                               ~[Path::new(~["std", "hash", "Hasher"])])]
+                },
+                explicit_self: borrowed_explicit_self(),
+                args: ~[
+                    Ptr(~Literal(Path::new_local("__H")),
+                        Borrowed(None, ast::MutMutable))
+                ],
+                ret_ty: nil_ty(),
+                inline: true,
+                const_nonmatching: false,
+                combine_substructure: hash_substructure
+            }
+        ]
+    };
+
+    trait_def.expand(cx, span, mitem, in_items)
+}
+
+fn hash_substructure(cx: @ExtCtxt, span: Span, substr: &Substructure) -> @Expr {
+    let state = match substr.nonself_args {
+        [state] => state,
+        _ => cx.span_bug(span, "Incorrect number of arguments in `deriving(Hash)`")
+    };
+    let hash_ident = substr.method_ident;
+    let call_hash = |thing_expr| {
+        let expr = cx.expr_method_call(span, thing_expr, hash_ident, ~[state]);
+        cx.stmt_expr(expr)
+    };
+    let mut stmts = ~[];
+
+    let fields = match *substr.fields {
+        Struct(ref fs) => fs,
+        EnumMatching(index, ref variant, ref fs) => {
+            // Feed the discriminant in first, so that variants with
+            // identical fields do not hash alike.
+            let discriminant = match variant.node.disr_expr {
+                Some(d) => d,
+                None => cx.expr_uint(span, index)
+            };
+
+            stmts.push(call_hash(discriminant));
+
+            fs
+        }
+        _ => cx.span_bug(span, "Impossible substructure in `deriving(Hash)`")
+    };
+
+    for &FieldInfo { self_, _ } in fields.iter() {
+        stmts.push(call_hash(self_));
+    }
+
+    cx.expr_block(cx.block(span, stmts, None))
+}
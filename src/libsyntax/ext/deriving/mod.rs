@@ -26,10 +26,12 @@ use codemap::Span;
 
 pub mod clone;
 pub mod iter_bytes;
+pub mod hash;
 pub mod encodable;
 pub mod decodable;
 pub mod rand;
 pub mod to_str;
+pub mod show;
 pub mod zero;
 pub mod default;
 pub mod primitive;
@@ -86,6 +88,7 @@ pub fn expand_meta_deriving(cx: @ExtCtxt,
                             "DeepClone" => expand!(clone::expand_deriving_deep_clone),
 
                             "IterBytes" => expand!(iter_bytes::expand_deriving_iter_bytes),
+                            "Hash" => expand!(hash::expand_deriving_hash),
 
                             "Encodable" => expand!(encodable::expand_deriving_encodable),
                             "Decodable" => expand!(decodable::expand_deriving_decodable),
@@ -98,6 +101,7 @@ pub fn expand_meta_deriving(cx: @ExtCtxt,
                             "Rand" => expand!(rand::expand_deriving_rand),
 
                             "ToStr" => expand!(to_str::expand_deriving_to_str),
+                            "Show" => expand!(show::expand_deriving_show),
 
                             "Zero" => expand!(zero::expand_deriving_zero),
                             "Default" => expand!(default::expand_deriving_default),
@@ -105,9 +109,14 @@ pub fn expand_meta_deriving(cx: @ExtCtxt,
                             "FromPrimitive" => expand!(primitive::expand_deriving_from_primitive),
 
                             ref tname => {
-                                cx.span_err(titem.span, format!("unknown \
-                                    `deriving` trait: `{}`", *tname));
-                                in_items
+                                match cx.deriving_exts.find(&tname.to_managed()) {
+                                    Some(&ext) => ext(cx, titem.span, titem, in_items),
+                                    None => {
+                                        cx.span_err(titem.span, format!("unknown \
+                                            `deriving` trait: `{}`", *tname));
+                                        in_items
+                                    }
+                                }
                             }
                         }
                     }
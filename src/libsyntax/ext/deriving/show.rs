@@ -0,0 +1,131 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use ast;
+use ast::{MetaItem, item, Expr, Ident};
+use codemap::Span;
+use ext::base::ExtCtxt;
+use ext::build::AstBuilder;
+use ext::deriving::generic::*;
+
+/// `#[deriving(Show)]` implements `std::fmt::Default`, so the type can be
+/// formatted with `{}`. The output looks like the expression that built the
+/// value, e.g. `Point { x: 1, y: 2 }`, `Some(3)` or `Nothing`. Each field is
+/// formatted with the formatter that was passed in, so any width, precision
+/// or fill given in the format string applies to every field in turn.
+///
+/// Fields are formatted with their own `Default` implementation, so string
+/// and character fields come out bare rather than quoted and escaped as they
+/// would be in the expression: `Name(~"a, b")` is shown as `Name(a, b)`. The
+/// output is therefore not necessarily unambiguous; implement the trait by
+/// hand where that matters.
+pub fn expand_deriving_show(cx: @ExtCtxt,
+                            span: Span,
+                            mitem: @MetaItem,
+                            in_items: ~[@item]) -> ~[@item] {
+    let trait_def = TraitDef {
+        path: Path::new(~["std", "fmt", "Default"]),
+        additional_bounds: ~[],
+        generics: LifetimeBounds::empty(),
+        methods: ~[
+            MethodDef {
+                name: "fmt",
+                generics: LifetimeBounds::empty(),
+                explicit_self: None,
+                args: ~[
+                    borrowed_self(),
+                    Ptr(~Literal(Path::new(~["std", "fmt", "Formatter"])),
+                        Borrowed(None, ast::MutMutable))
+                ],
+                ret_ty: nil_ty(),
+                inline: false,
+                const_nonmatching: false,
+                combine_substructure: show_substructure
+            }
+        ]
+    };
+
+    trait_def.expand(cx, span, mitem, in_items)
+}
+
+// The formatting traits take `&Self` as an ordinary argument rather than as
+// `self`, so the generic code hands us the static summary of the fields and
+// we build the match on the argument ourselves.
+fn show_substructure(cx: @ExtCtxt, span: Span, substr: &Substructure) -> @Expr {
+    let (value, fmt) = match substr.nonself_args {
+        [value, fmt] => (value, fmt),
+        _ => cx.span_bug(span, "Incorrect number of arguments in `deriving(Show)`")
+    };
+
+    let arms = match *substr.fields {
+        StaticStruct(struct_def, _) => {
+            let (pat, fields) = create_struct_pattern(cx, span, substr.type_ident,
+                                                      struct_def, "__self",
+                                                      ast::MutImmutable);
+            ~[cx.arm(span, ~[pat], show_fields(cx, span, fmt, substr.type_ident, fields))]
+        }
+        StaticEnum(enum_def, _) => {
+            enum_def.variants.map(|variant| {
+                let (pat, fields) = create_enum_variant_pattern(cx, variant, "__self",
+                                                                ast::MutImmutable);
+                cx.arm(span, ~[pat], show_fields(cx, span, fmt, variant.node.name, fields))
+            })
+        }
+        _ => cx.span_bug(span, "Impossible substructure in `deriving(Show)`")
+    };
+
+    cx.expr_match(span, cx.expr_deref(span, value), arms)
+}
+
+/// Emits `name`, `name(a, b)` or `name { x: a, y: b }` depending on the
+/// shape of the fields. The fields themselves are formatted as `{}` would,
+/// without any quoting.
+fn show_fields(cx: @ExtCtxt, span: Span, fmt: @Expr, name: Ident,
+               fields: ~[(Span, Option<Ident>, @Expr)]) -> @Expr {
+    let write_str = cx.ident_of("write_str");
+    let push_str = |stmts: &mut ~[@ast::Stmt], s: ~str| {
+        let call = cx.expr_method_call(span, fmt, write_str,
+                                       ~[cx.expr_str(span, s.to_managed())]);
+        stmts.push(cx.stmt_expr(call));
+    };
+    let fmt_path = ~[cx.ident_of("std"), cx.ident_of("fmt"),
+                     cx.ident_of("Default"), cx.ident_of("fmt")];
+
+    let mut stmts = ~[];
+    let mut buf = cx.str_of(name).to_owned();
+    let named = fields.iter().any(|&(_, opt_name, _)| opt_name.is_some());
+
+    for (i, &(field_span, opt_name, field)) in fields.iter().enumerate() {
+        buf.push_str(match (i, named) {
+            (0, true) => " { ",
+            (0, false) => "(",
+            _ => ", "
+        });
+        match opt_name {
+            Some(ident) => {
+                buf.push_str(cx.str_of(ident).as_slice());
+                buf.push_str(": ");
+            }
+            None => {}
+        }
+        push_str(&mut stmts, buf);
+        buf = ~"";
+
+        let call = cx.expr_call_global(field_span, fmt_path.clone(), ~[field, fmt]);
+        stmts.push(cx.stmt_expr(call));
+    }
+
+    if !fields.is_empty() {
+        buf.push_str(if named { " }" } else { ")" });
+    }
+    push_str(&mut stmts, buf);
+
+    cx.expr_block(cx.block(span, stmts, None))
+}
@@ -21,7 +21,6 @@ use codemap;
 use codemap::{Span, Spanned, ExpnInfo, NameAndSpan};
 use ext::base::*;
 use fold::*;
-use opt_vec;
 use parse;
 use parse::{parse_item_from_source_str};
 use parse::token;
@@ -30,6 +29,8 @@ use visit;
 use visit::Visitor;
 use util::small_vector::SmallVector;
 
+use std::cast;
+use std::unstable::dynamic_lib::DynamicLibrary;
 use std::vec;
 
 pub fn expand_expr(extsbox: @mut SyntaxEnv,
@@ -148,35 +149,6 @@ pub fn expand_expr(extsbox: @mut SyntaxEnv,
                 }
             }
 
-            fn mk_block(_: @ExtCtxt,
-                        stmts: &[@ast::Stmt],
-                        expr: Option<@ast::Expr>,
-                        span: Span)
-                        -> ast::Block {
-                ast::Block {
-                    view_items: ~[],
-                    stmts: stmts.to_owned(),
-                    expr: expr,
-                    id: ast::DUMMY_NODE_ID,
-                    rules: ast::DefaultBlock,
-                    span: span,
-                }
-            }
-
-            fn mk_simple_path(ident: ast::Ident, span: Span) -> ast::Path {
-                ast::Path {
-                    span: span,
-                    global: false,
-                    segments: ~[
-                        ast::PathSegment {
-                            identifier: ident,
-                            lifetimes: opt_vec::Empty,
-                            types: opt_vec::Empty,
-                        }
-                    ],
-                }
-            }
-
             // to:
             //
             // {
@@ -726,12 +698,6 @@ pub fn renames_to_fold(renames: @mut ~[(ast::Ident,ast::Name)]) -> @ast_fold {
     } as @ast_fold
 }
 
-// perform a bunch of renames
-fn apply_pending_renames(folder : @ast_fold, stmt : ast::Stmt) -> @ast::Stmt {
-    folder.fold_stmt(&stmt)
-            .expect_one("renaming of stmt did not produce one stmt")
-}
-
 
 
 pub fn new_span(cx: @ExtCtxt, sp: Span) -> Span {
@@ -759,7 +725,7 @@ pub fn std_macros() -> @str {
             let lvl = $lvl;
             if lvl <= __log_level() {
                 format_args!(|args| {
-                    ::std::logging::log(lvl, args)
+                    ::std::logging::log_at(lvl, module_path!(), file!(), line!(), args)
                 }, $($arg)+)
             }
         })
@@ -1036,7 +1002,68 @@ impl ast_fold for MacroExpander {
     }
 }
 
+// Whether the view item is an `extern mod` with a `#[phase(...)]` attribute
+// naming the given phase
+fn has_phase(vi: &ast::view_item, phase: &str) -> bool {
+    vi.attrs.iter().any(|attr| {
+        "phase" == attr.name() && match attr.meta_item_list() {
+            Some(phases) => attr::contains_name(phases, phase),
+            None => false
+        }
+    })
+}
+
+// Loads the syntax extensions of the crate named by a `#[phase(syntax)]
+// extern mod` and adds them to the syntax environment
+fn load_extern_macros(crate: &ast::view_item,
+                      extsbox: SyntaxEnv,
+                      cx: @ExtCtxt,
+                      loader: &mut CrateLoader) {
+    let MacroCrate { lib, registrar } = loader.load_crate(crate);
+
+    let registrar = match registrar {
+        Some(registrar) => registrar,
+        None => cx.span_fatal(crate.span, format!("{} has no \
+                                                   `#[macro_registrar]` function",
+                                                  lib.display()))
+    };
+    let lib = match DynamicLibrary::open(Some(&lib)) {
+        Ok(lib) => lib,
+        Err(msg) => cx.span_fatal(crate.span, format!("couldn't load {}: {}",
+                                                      lib.display(), msg))
+    };
+    let registrar: MacroRegistrarFun = match unsafe { lib.symbol(registrar) } {
+        Ok(registrar) => registrar,
+        Err(msg) => cx.span_fatal(crate.span, msg)
+    };
+
+    registrar(|name, extension| {
+        let name_str = token::interner_get(name);
+        match extension {
+            ItemDecorator(dec) if name_str.starts_with("deriving_") => {
+                let tname = name_str.slice_from("deriving_".len());
+                cx.deriving_exts.insert(tname.to_managed(), dec);
+            }
+            // Point the backtraces of the extension at the crate it came from
+            NormalTT(ext, _) => {
+                extsbox.insert(name, @SE(NormalTT(ext, Some(crate.span))));
+            }
+            IdentTT(ext, _) => {
+                extsbox.insert(name, @SE(IdentTT(ext, Some(crate.span))));
+            }
+            extension => {
+                extsbox.insert(name, @SE(extension));
+            }
+        }
+    });
+
+    // The extensions are code inside of the library, so it has to stay
+    // loaded for as long as the compiler runs
+    unsafe { cast::forget(lib); }
+}
+
 pub fn expand_crate(parse_sess: @mut parse::ParseSess,
+                    loader: &mut CrateLoader,
                     cfg: ast::CrateConfig,
                     c: Crate) -> Crate {
     // adding *another* layer of indirection here so that the block
@@ -1046,6 +1073,23 @@ pub fn expand_crate(parse_sess: @mut parse::ParseSess,
     // every method/element of AstFoldFns in fold.rs.
     let extsbox = syntax_expander_table();
     let cx = ExtCtxt::new(parse_sess, cfg.clone());
+
+    // Syntax extensions from other crates are loaded up front, so only
+    // `#[phase(syntax)] extern mod`s at the crate root are considered. Unless
+    // the crate is also wanted at link time, the view item goes away.
+    for vi in c.module.view_items.iter() {
+        if has_phase(vi, "syntax") {
+            load_extern_macros(vi, extsbox, cx, loader);
+        }
+    }
+    let view_items = c.module.view_items.iter().filter(|vi| {
+        !has_phase(*vi, "syntax") || has_phase(*vi, "link")
+    }).map(|vi| vi.clone()).collect();
+    let c = Crate {
+        module: ast::_mod { view_items: view_items, ..c.module.clone() },
+        ..c
+    };
+
     let expander = @MacroExpander {
         extsbox: @mut extsbox,
         cx: cx,
@@ -1224,17 +1268,43 @@ mod test {
     use ast_util;
     use codemap;
     use codemap::Spanned;
+    use ext::base::{CrateLoader, MacroCrate};
     use fold;
     use parse;
     use parse::token::{fresh_mark, gensym, intern, get_ident_interner, ident_to_str};
     use parse::token;
     use print::pprust;
     use std;
+    use std::task::FailurePayload;
     use util::parser_testing::{string_to_crate, string_to_crate_and_sess};
     use util::parser_testing::{string_to_pat, string_to_tts, strs_to_idents};
     use visit;
     use visit::Visitor;
 
+    // a loader for tests which never expect to load anything
+    struct ErrLoader;
+
+    impl CrateLoader for ErrLoader {
+        fn load_crate(&mut self, _: &ast::view_item) -> MacroCrate {
+            fail!("lolwut")
+        }
+    }
+
+    // a loader for tests which fails with the name of the crate that it's
+    // asked to load, so that they can tell which one it was
+    struct NameLoader;
+
+    impl CrateLoader for NameLoader {
+        fn load_crate(&mut self, crate: &ast::view_item) -> MacroCrate {
+            match crate.node {
+                ast::view_item_extern_mod(id, _, _, _) => {
+                    fail!(ident_to_str(&id).to_owned())
+                }
+                _ => fail!("not an extern mod")
+            }
+        }
+    }
+
     // a visitor that extracts the paths
     // from a given thingy and puts them in a mutable
     // array (passed in to the traversal)
@@ -1280,7 +1350,7 @@ mod test {
             ~[],sess);
         let crate_ast = inject_std_macros(sess, ~[], crate_ast);
         // don't bother with striping, doesn't affect fail!.
-        expand_crate(sess,~[],crate_ast);
+        expand_crate(sess,&mut ErrLoader,~[],crate_ast);
     }
 
     // these following tests are quite fragile, in that they don't test what
@@ -1297,7 +1367,7 @@ mod test {
             src,
             ~[],sess);
         // should fail:
-        expand_crate(sess,~[],crate_ast);
+        expand_crate(sess,&mut ErrLoader,~[],crate_ast);
     }
 
     // make sure that macros can leave scope for modules
@@ -1311,7 +1381,29 @@ mod test {
             src,
             ~[],sess);
         // should fail:
-        expand_crate(sess,~[],crate_ast);
+        expand_crate(sess,&mut ErrLoader,~[],crate_ast);
+    }
+
+    // syntax extensions are loaded for #[phase(syntax)] extern mods only
+    #[test] fn phase_syntax_extern_mods_are_loaded_test () {
+        let result = do std::task::try {
+            let (crate_ast, ps) = string_to_crate_and_sess(
+                @"#[phase(syntax)] extern mod foo; fn main() {}");
+            expand_crate(ps, &mut NameLoader, ~[], crate_ast);
+        };
+        match result {
+            Err(cause) => {
+                let msg = cause.as_ref::<FailurePayload>().and_then(|p| p.msg_str());
+                assert_eq!(msg, Some("foo"));
+            }
+            Ok(()) => fail!("foo wasn't loaded"),
+        }
+    }
+
+    #[test] fn phase_link_extern_mods_are_kept_test () {
+        let crate = expand_crate_str(@"#[phase(link)] extern mod foo;\
+                                       fn main() {}");
+        assert_eq!(crate.module.view_items.len(), 1);
     }
 
     // macro_escape modules shouldn't cause macros to leave scope
@@ -1324,7 +1416,7 @@ mod test {
             src,
             ~[], sess);
         // should fail:
-        expand_crate(sess,~[],crate_ast);
+        expand_crate(sess,&mut ErrLoader,~[],crate_ast);
     }
 
     #[test] fn std_macros_must_parse () {
@@ -1430,7 +1522,7 @@ mod test {
     fn expand_crate_str(crate_str: @str) -> ast::Crate {
         let (crate_ast,ps) = string_to_crate_and_sess(crate_str);
         // the cfg argument actually does matter, here...
-        expand_crate(ps,~[],crate_ast)
+        expand_crate(ps,&mut ErrLoader,~[],crate_ast)
     }
 
     //fn expand_and_resolve(crate_str: @str) -> ast::crate {
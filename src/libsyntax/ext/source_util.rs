@@ -99,7 +99,14 @@ pub fn expand_include_str(cx: @ExtCtxt, sp: Span, tts: &[ast::token_tree])
         Ok(bytes) => bytes,
     };
     match str::from_utf8_owned_opt(bytes) {
-        Some(s) => base::MRExpr(cx.expr_str(sp, s.to_managed())),
+        Some(s) => {
+            let s = s.to_managed();
+            // Add this input file to the code map so that tools which care
+            // about the crate's inputs, like --dep-info, see it.
+            // FIXME (#9639): This needs to handle non-utf8 paths
+            cx.codemap().new_filemap(file.as_str().unwrap().to_managed(), s);
+            base::MRExpr(cx.expr_str(sp, s))
+        }
         None => {
             cx.span_fatal(sp, format!("{} wasn't a utf-8 file", file.display()));
         }
@@ -119,7 +126,12 @@ pub fn expand_include_bin(cx: @ExtCtxt, sp: Span, tts: &[ast::token_tree])
                                       file.display(), e.desc));
         }
         Ok(bytes) => {
+            // Record the file in the code map like include_str! does. Its
+            // contents needn't be text, so they're kept on the side.
             let bytes = at_vec::to_managed_move(bytes);
+            // FIXME (#9639): This needs to handle non-utf8 paths
+            cx.codemap().record_binary_file(file.as_str().unwrap().to_managed(),
+                                            bytes);
             base::MRExpr(cx.expr_lit(sp, ast::lit_binary(bytes)))
         }
     }
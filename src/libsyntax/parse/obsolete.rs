@@ -24,7 +24,7 @@ use parse::token::Token;
 use parse::token;
 
 use std::str;
-use std::to_bytes;
+use std::hash::{Hash, Hasher};
 
 /// The specific types of unsupported syntax
 #[deriving(Eq)]
@@ -46,10 +46,10 @@ pub enum ObsoleteSyntax {
     ObsoleteClosureType,
 }
 
-impl to_bytes::IterBytes for ObsoleteSyntax {
+impl Hash for ObsoleteSyntax {
     #[inline]
-    fn iter_bytes(&self, lsb0: bool, f: to_bytes::Cb) -> bool {
-        (*self as uint).iter_bytes(lsb0, f)
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self as uint).hash(state)
     }
 }
 
@@ -110,6 +110,47 @@ rust_list_dir_wfd_fp_buf(void* wfd) {
 }
 #endif
 
+// The types of directory entries, as understood by std::io::native::file:
+// 0 = unknown, 1 = regular file, 2 = directory, 3 = named pipe,
+// 4 = block device, 5 = symlink
+int
+#if defined(__WIN32__)
+rust_list_dir_type(void* entry_ptr) {
+    return 0;
+}
+#else
+rust_list_dir_type(struct dirent* entry_ptr) {
+#if defined(DT_UNKNOWN)
+    switch (entry_ptr->d_type) {
+        case DT_REG: return 1;
+        case DT_DIR: return 2;
+        case DT_FIFO: return 3;
+        case DT_BLK: return 4;
+        case DT_LNK: return 5;
+    }
+#endif
+    return 0;
+}
+#endif
+
+int
+#if defined(__WIN32__)
+rust_list_dir_wfd_type(WIN32_FIND_DATAW* wfd) {
+    if ((wfd->dwFileAttributes & FILE_ATTRIBUTE_REPARSE_POINT) &&
+        wfd->dwReserved0 == IO_REPARSE_TAG_SYMLINK) {
+        return 5;
+    } else if (wfd->dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) {
+        return 2;
+    } else {
+        return 1;
+    }
+}
+#else
+rust_list_dir_wfd_type(void* wfd) {
+    return 0;
+}
+#endif
+
 #if defined(__WIN32__)
 void
 rust_get_time(int64_t *sec, int32_t *nsec) {
@@ -433,6 +474,116 @@ rust_unset_sigprocmask() {
 
 #endif
 
+#if defined(__WIN32__)
+
+// Native signals and ttys are only supported on unix, these stubs just keep
+// the linker happy.
+int
+rust_signal_init(int fd) { return -1; }
+int
+rust_signal_install(int signum) { return -1; }
+int
+rust_signal_reset(int signum) { return -1; }
+size_t
+rust_termios_size() { return 0; }
+int
+rust_termios_get(int fd, void *t) { return -1; }
+int
+rust_termios_set(int fd, const void *t) { return -1; }
+void
+rust_termios_make_raw(void *t) {}
+int
+rust_tty_get_winsize(int fd, int *width, int *height) { return -1; }
+
+#else
+
+#include <termios.h>
+#include <sys/ioctl.h>
+
+// The write end of the pipe which the signal handler reports signals on. This
+// is set once by std::io::native::signal before any handler is installed.
+static int rust_signal_fd = -1;
+
+static void
+rust_signal_handler(int signum) {
+    int saved_errno = errno;
+    unsigned char byte = (unsigned char) signum;
+    // The pipe is nonblocking, so if it's full this signal is simply lost
+    // rather than deadlocking the signal handler.
+    ssize_t ret = write(rust_signal_fd, &byte, 1);
+    (void) ret;
+    errno = saved_errno;
+}
+
+int
+rust_signal_init(int fd) {
+    int flags = fcntl(fd, F_GETFL);
+    if (flags == -1 || fcntl(fd, F_SETFL, flags | O_NONBLOCK) == -1)
+        return -1;
+    rust_signal_fd = fd;
+    return 0;
+}
+
+static int
+rust_signal_set_handler(int signum, void (*handler)(int)) {
+    // this can't be done from rust code because the layout of struct
+    // sigaction is platform-dependent
+    struct sigaction sa;
+    memset(&sa, 0, sizeof(sa));
+    sigemptyset(&sa.sa_mask);
+    sa.sa_handler = handler;
+    sa.sa_flags = SA_RESTART;
+    return sigaction(signum, &sa, NULL);
+}
+
+int
+rust_signal_install(int signum) {
+    return rust_signal_set_handler(signum, rust_signal_handler);
+}
+
+int
+rust_signal_reset(int signum) {
+    return rust_signal_set_handler(signum, SIG_DFL);
+}
+
+size_t
+rust_termios_size() {
+    return sizeof(struct termios);
+}
+
+int
+rust_termios_get(int fd, struct termios *t) {
+    return tcgetattr(fd, t);
+}
+
+int
+rust_termios_set(int fd, const struct termios *t) {
+    return tcsetattr(fd, TCSADRAIN, t);
+}
+
+void
+rust_termios_make_raw(struct termios *t) {
+    // This is the same notion of raw mode as libuv's uv_tty_set_mode
+    t->c_iflag &= ~(BRKINT | ICRNL | INPCK | ISTRIP | IXON);
+    t->c_oflag |= ONLCR;
+    t->c_cflag |= CS8;
+    t->c_lflag &= ~(ECHO | ICANON | IEXTEN | ISIG);
+    t->c_cc[VMIN] = 1;
+    t->c_cc[VTIME] = 0;
+}
+
+int
+rust_tty_get_winsize(int fd, int *width, int *height) {
+    struct winsize ws;
+    if (ioctl(fd, TIOCGWINSZ, &ws) == -1)
+        return -1;
+    *width = ws.ws_col;
+    *height = ws.ws_row;
+    return 0;
+}
+
+#endif
+
 #if defined(__WIN32__)
 void
 win32_require(LPCTSTR fn, BOOL ok) {
@@ -500,6 +651,20 @@ int
 rust_pthread_mutex_t_size() { return 0; }
 int
 rust_pthread_cond_t_size() { return 0; }
+// Condition variables are auto-reset event objects on Windows (see
+// std::unstable::mutex). Waits on `cond` for at most `ms` milliseconds with
+// `lock` released. Returns 0 if signaled and WAIT_TIMEOUT if the time ran
+// out.
+int
+rust_pthread_cond_timedwait_ms(HANDLE cond, CRITICAL_SECTION *lock,
+                               uint64_t ms) {
+    // INFINITE is itself a DWORD, so clamp just below it
+    DWORD timeout = ms >= INFINITE ? INFINITE - 1 : (DWORD)ms;
+    LeaveCriticalSection(lock);
+    DWORD ret = WaitForSingleObject(cond, timeout);
+    EnterCriticalSection(lock);
+    return ret == WAIT_TIMEOUT ? WAIT_TIMEOUT : 0;
+}
 
 #else
 
@@ -510,6 +675,20 @@ rust_pthread_mutex_t_size() { return sizeof(pthread_mutex_t); }
 int
 rust_pthread_cond_t_size() { return sizeof(pthread_cond_t); }
 
+// Waits on `cond` for at most `ms` milliseconds. Returns 0 if signaled (or
+// woken spuriously) and ETIMEDOUT if the time ran out.
+int
+rust_pthread_cond_timedwait_ms(pthread_cond_t *cond, pthread_mutex_t *lock,
+                               uint64_t ms) {
+    struct timeval now;
+    gettimeofday(&now, NULL);
+    uint64_t nsec = (uint64_t)now.tv_usec * 1000 + (ms % 1000) * 1000000;
+    struct timespec deadline;
+    deadline.tv_sec = now.tv_sec + ms / 1000 + nsec / ns_per_s;
+    deadline.tv_nsec = nsec % ns_per_s;
+    return pthread_cond_timedwait(cond, lock, &deadline);
+}
+
 #endif
 
 //
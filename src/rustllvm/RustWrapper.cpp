@@ -444,6 +444,230 @@ extern "C" bool LLVMRustStartMultithreading() {
     return ret;
 }
 
+/*
+ * Splits a crate's module into `Count` codegen units, writing the bitcode of
+ * unit `U` into `OutBuffers[U]`. The module itself is left in an unspecified
+ * state and should be disposed of afterwards.
+ *
+ * Translation names the unit of the functions and statics which it assigned
+ * to one (`Names[i]` belongs to unit `Units[i]`). Every definition either
+ * belongs to exactly one unit or is copied into each unit which uses it:
+ *
+ * - assigned definitions belong to their unit, and any other external
+ *   definition (as well as any other non-constant static) to unit 0;
+ * - internal functions which weren't assigned (monomorphizations, glue,
+ *   closures, ...) or which are marked inline, and internal constants, are
+ *   copied into each unit which uses them;
+ * - external functions marked inline belong to their unit, and every other
+ *   unit which uses them gets an available_externally copy to inline.
+ *
+ * Only the internal definitions which are used from another unit than their
+ * own are promoted to hidden external symbols, with `Suffix` appended to
+ * their names so that they can't clash with the symbols of another crate
+ * linked into the same output.
+ */
+namespace {
+
+enum Placement { Owned, Copied, Inlinable };
+
+struct Place {
+    Placement Kind;
+    unsigned Unit;
+};
+
+}
+
+// Collects the globals which `C` refers to, looking through constant
+// expressions and aggregates.
+static void collectGlobals(const Constant *C,
+                           SmallPtrSet<const Constant*, 16> &Seen,
+                           SmallVectorImpl<GlobalValue*> &Out) {
+    if (!Seen.insert(C))
+        return;
+    if (const GlobalValue *GV = dyn_cast<GlobalValue>(C)) {
+        Out.push_back(const_cast<GlobalValue*>(GV));
+        return;
+    }
+    for (User::const_op_iterator I = C->op_begin(), E = C->op_end();
+         I != E; ++I) {
+        if (const Constant *Op = dyn_cast<Constant>(*I))
+            collectGlobals(Op, Seen, Out);
+    }
+}
+
+// Collects the globals which the definition of `GV` refers to.
+static void collectReferences(GlobalValue *GV,
+                              SmallVectorImpl<GlobalValue*> &Out) {
+    SmallPtrSet<const Constant*, 16> Seen;
+    if (GlobalVariable *Var = dyn_cast<GlobalVariable>(GV)) {
+        if (Var->hasInitializer())
+            collectGlobals(Var->getInitializer(), Seen, Out);
+        return;
+    }
+    Function *F = cast<Function>(GV);
+    for (Function::iterator BB = F->begin(), BE = F->end(); BB != BE; ++BB) {
+        for (BasicBlock::iterator I = BB->begin(), IE = BB->end();
+             I != IE; ++I) {
+            for (User::op_iterator O = I->op_begin(), OE = I->op_end();
+                 O != OE; ++O) {
+                if (Constant *C = dyn_cast<Constant>(*O))
+                    collectGlobals(C, Seen, Out);
+            }
+        }
+    }
+}
+
+static bool isInline(Function *F) {
+    return F->hasFnAttribute(Attribute::InlineHint) ||
+           F->hasFnAttribute(Attribute::AlwaysInline);
+}
+
+extern "C" void
+LLVMRustSplitModule(LLVMModuleRef M, unsigned Count, const char **Names,
+                    const unsigned *Units, unsigned NumNames,
+                    const char *Suffix, LLVMMemoryBufferRef *OutBuffers) {
+    Module *Mod = unwrap(M);
+
+    StringMap<unsigned> Assigned;
+    for (unsigned i = 0; i < NumNames; i++)
+        Assigned[Names[i]] = Units[i];
+
+    // Place every definition, apart from appending globals (llvm.used and
+    // friends), which the linker concatenates so only unit 0 keeps them.
+    DenseMap<GlobalValue*, Place> Places;
+    std::vector<GlobalValue*> Defs;
+    std::vector<GlobalVariable*> Appending;
+    for (Module::global_iterator I = Mod->global_begin(),
+         E = Mod->global_end(); I != E; ++I) {
+        GlobalVariable *GV = I;
+        if (GV->isDeclaration() || GV->hasAvailableExternallyLinkage())
+            continue;
+        if (GV->hasAppendingLinkage()) {
+            Appending.push_back(GV);
+            continue;
+        }
+        StringMap<unsigned>::iterator A = Assigned.find(GV->getName());
+        Place P = { Owned, A == Assigned.end() ? 0 : A->getValue() };
+        if (GV->hasLocalLinkage() && GV->isConstant() && !GV->hasSection())
+            P.Kind = Copied;
+        Places[GV] = P;
+        Defs.push_back(GV);
+    }
+    for (Module::iterator I = Mod->begin(), E = Mod->end(); I != E; ++I) {
+        Function *F = I;
+        if (F->isDeclaration() || F->hasAvailableExternallyLinkage())
+            continue;
+        StringMap<unsigned>::iterator A = Assigned.find(F->getName());
+        bool IsAssigned = A != Assigned.end();
+        Place P = { Owned, IsAssigned ? A->getValue() : 0 };
+        if (F->hasLocalLinkage() && (!IsAssigned || isInline(F)))
+            P.Kind = Copied;
+        else if (!F->hasLocalLinkage() && isInline(F))
+            P.Kind = Inlinable;
+        Places[F] = P;
+        Defs.push_back(F);
+    }
+
+    // Find out which copies each unit needs, starting from what it owns, and
+    // which internal definitions are used from other units than their own.
+    std::vector<DenseSet<GlobalValue*> > Needed(Count);
+    SmallPtrSet<GlobalValue*, 16> Promoted;
+    for (unsigned U = 0; U < Count; U++) {
+        std::vector<GlobalValue*> Work;
+        for (unsigned i = 0; i < Defs.size(); i++) {
+            Place P = Places[Defs[i]];
+            if (P.Kind != Copied && P.Unit == U)
+                Work.push_back(Defs[i]);
+        }
+        if (U == 0)
+            Work.insert(Work.end(), Appending.begin(), Appending.end());
+
+        while (!Work.empty()) {
+            GlobalValue *GV = Work.back();
+            Work.pop_back();
+            SmallVector<GlobalValue*, 16> Refs;
+            collectReferences(GV, Refs);
+            for (unsigned i = 0; i < Refs.size(); i++) {
+                DenseMap<GlobalValue*, Place>::iterator R = Places.find(Refs[i]);
+                if (R == Places.end())
+                    continue;
+                Place P = R->second;
+                if (P.Kind == Copied ||
+                    (P.Kind == Inlinable && P.Unit != U)) {
+                    if (Needed[U].insert(Refs[i]).second)
+                        Work.push_back(Refs[i]);
+                } else if (P.Kind == Owned && P.Unit != U &&
+                           Refs[i]->hasLocalLinkage()) {
+                    Promoted.insert(Refs[i]);
+                }
+            }
+        }
+    }
+
+    for (SmallPtrSet<GlobalValue*, 16>::iterator I = Promoted.begin(),
+         E = Promoted.end(); I != E; ++I) {
+        GlobalValue *GV = *I;
+        GV->setName(GV->getName() + Suffix);
+        GV->setLinkage(GlobalValue::ExternalLinkage);
+        GV->setVisibility(GlobalValue::HiddenVisibility);
+    }
+
+    for (unsigned U = 0; U < Count; U++) {
+        ValueToValueMapTy VMap;
+        Module *Unit = CloneModule(Mod, VMap);
+
+        for (unsigned i = 0; i < Defs.size(); i++) {
+            Place P = Places[Defs[i]];
+            GlobalValue *GV = cast<GlobalValue>(VMap[Defs[i]]);
+            bool Keep = P.Unit == U || Needed[U].count(Defs[i]);
+            if (P.Kind == Copied)
+                Keep = Needed[U].count(Defs[i]);
+
+            if (Keep) {
+                if (P.Kind == Inlinable && P.Unit != U)
+                    GV->setLinkage(GlobalValue::AvailableExternallyLinkage);
+            } else if (Function *F = dyn_cast<Function>(GV)) {
+                F->deleteBody();
+            } else {
+                GlobalVariable *Var = cast<GlobalVariable>(GV);
+                Var->setInitializer(NULL);
+                Var->setLinkage(GlobalValue::ExternalLinkage);
+            }
+        }
+        if (U != 0) {
+            for (unsigned i = 0; i < Appending.size(); i++)
+                cast<GlobalVariable>(VMap[Appending[i]])->eraseFromParent();
+            while (!Unit->alias_empty())
+                Unit->alias_begin()->eraseFromParent();
+        }
+
+        // Drop the declarations which nothing refers to any more
+        for (Module::iterator I = Unit->begin(), E = Unit->end(); I != E; ) {
+            Function *F = I++;
+            if (!F->isDeclaration())
+                continue;
+            F->removeDeadConstantUsers();
+            if (F->use_empty())
+                F->eraseFromParent();
+        }
+        for (Module::global_iterator I = Unit->global_begin(),
+             E = Unit->global_end(); I != E; ) {
+            GlobalVariable *GV = I++;
+            if (!GV->isDeclaration())
+                continue;
+            GV->removeDeadConstantUsers();
+            if (GV->use_empty())
+                GV->eraseFromParent();
+        }
+
+        std::string Data;
+        raw_string_ostream OS(Data);
+        WriteBitcodeToFile(Unit, OS);
+        OS.flush();
+        OutBuffers[U] = wrap(MemoryBuffer::getMemBufferCopy(Data));
+        delete Unit;
+    }
+}
 
 typedef DIBuilder* DIBuilderRef;
 
@@ -0,0 +1,52 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[feature(macro_registrar, managed_boxes)];
+
+extern mod syntax;
+
+use syntax::ast::{Name, token_tree, item, MetaItem};
+use syntax::codemap::Span;
+use syntax::ext::base::*;
+use syntax::ext::build::AstBuilder;
+use syntax::ext::quote::rt::ExtParseUtils;
+use syntax::parse::token;
+
+#[macro_registrar]
+pub fn macro_registrar(register: |Name, SyntaxExtension|) {
+    register(token::intern("make_a_1"),
+        NormalTT(@SyntaxExpanderTT {
+            expander: SyntaxExpanderTTExpanderWithoutContext(expand_make_a_1),
+            span: None,
+        } as @SyntaxExpanderTTTrait,
+        None));
+    register(token::intern("deriving_Foo"),
+             ItemDecorator(expand_deriving_foo));
+}
+
+fn expand_make_a_1(cx: @ExtCtxt, sp: Span, tts: &[token_tree]) -> MacResult {
+    if !tts.is_empty() {
+        cx.span_fatal(sp, "make_a_1 takes no arguments");
+    }
+    MRExpr(cx.expr_int(sp, 1))
+}
+
+// Implements the test's `Foo` trait for each item, keeping the items
+fn expand_deriving_foo(cx: @ExtCtxt, _sp: Span, _mitem: @MetaItem,
+                       in_items: ~[@item]) -> ~[@item] {
+    let mut items = in_items.clone();
+    for it in in_items.iter() {
+        let name = token::ident_to_str(&it.ident);
+        let src = ~"impl Foo for " + name.as_slice() +
+                  " { fn foo(&self) -> int { 42 } }";
+        items.push(cx.parse_item(src.to_managed()));
+    }
+    items
+}
@@ -0,0 +1,28 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// crate-type: rlib
+
+#[link(name = "rlib_crate_test", vers = "0.1")];
+
+pub static ANSWER: int = 42;
+
+pub struct Counter { count: uint }
+
+impl Counter {
+    pub fn new() -> Counter { Counter { count: 0 } }
+    pub fn bump(&mut self) -> uint { self.count += 1; self.count }
+}
+
+// Comes from the archive's object code
+pub fn triple(x: int) -> int { x * 3 }
+
+// Instantiated from the metadata by the crate which uses it
+pub fn pair<T: Clone>(x: T) -> (T, T) { (x.clone(), x) }
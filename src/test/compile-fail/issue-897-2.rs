@@ -12,6 +12,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[allow(dead_code)];
 #[deny(unreachable_code)];
 
 fn g() -> ! { fail!(); }
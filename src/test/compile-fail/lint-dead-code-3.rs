@@ -0,0 +1,59 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[allow(unused_variable)];
+#[deny(dead_code)];
+
+#[crate_type="lib"];
+
+struct Foo; //~ ERROR: code is never used
+impl Foo {
+    fn foo(&self) { //~ ERROR: code is never used
+        bar()
+    }
+}
+
+fn bar() { //~ ERROR: code is never used
+    fn baz() {} //~ ERROR: code is never used
+
+    Foo.foo();
+    baz();
+}
+
+// no warning
+struct Bar;
+impl Bar {
+    fn baz(&self) {}
+}
+
+pub fn pub_fn() {
+    let b = Bar;
+    b.baz();
+}
+
+// Destructors are called implicitly, so they keep what they use alive
+struct Guard;
+impl Drop for Guard {
+    fn drop(&mut self) {
+        used_by_drop();
+    }
+}
+fn used_by_drop() {}
+
+pub fn make_guard() {
+    let _g = Guard;
+}
+
+// Functions only called from tests are still live
+#[test]
+fn a_test() {
+    used_by_test();
+}
+fn used_by_test() {}
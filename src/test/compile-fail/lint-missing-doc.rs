@@ -10,6 +10,7 @@
 
 // When denying at the crate level, be sure to not get random warnings from the
 // injected intrinsics by the compiler.
+#[allow(dead_code)];
 #[feature(struct_variant)];
 #[feature(globs)];
 #[deny(missing_doc)];
@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[allow(dead_code)];
 #[forbid(non_uppercase_statics)];
 
 static foo: int = 1; //~ ERROR static constant should have an uppercase identifier
@@ -11,6 +11,7 @@
 // xfail-fast aux-build
 // aux-build:lint_stability.rs
 
+#[allow(dead_code)];
 #[feature(globs)];
 #[deny(unstable)];
 #[deny(deprecated)];
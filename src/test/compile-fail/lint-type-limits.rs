@@ -9,6 +9,9 @@
 // except according to those terms.
 
 // compile-flags: -D type-limits
+
+#[allow(dead_code)];
+
 fn main() { }
 
 fn foo() {
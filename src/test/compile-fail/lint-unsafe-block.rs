@@ -8,6 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[allow(dead_code)];
 #[allow(unused_unsafe)];
 #[deny(unsafe_block)];
 #[feature(macro_rules)];
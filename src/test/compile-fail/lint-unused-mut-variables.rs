@@ -10,6 +10,7 @@
 
 // Exercise the unused_mut attribute in some positive and negative cases
 
+#[allow(dead_code)];
 #[allow(dead_assignment)];
 #[allow(unused_variable)];
 #[deny(unused_mut)];
@@ -11,6 +11,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[allow(dead_code)];
 #[deny(unused_variable)];
 #[deny(dead_assignment)];
 
@@ -0,0 +1,18 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// check-rustdoc
+// compile-flags: --error-format json
+// error-pattern:{"expansions":[],"level":"error","lint":null,"message":"mismatched types
+// error-pattern:{"expansions":[],"level":"error","lint":null,"message":"aborting due to previous error","notes":[],"span":null}
+
+fn main() {
+    let _x: int = "foo";
+}
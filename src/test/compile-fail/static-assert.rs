@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 #[static_assert]
 static A: bool = false; //~ ERROR static assertion failed
 
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 #[static_assert]
 static E: bool = 1 == 2; //~ ERROR static assertion failed
 
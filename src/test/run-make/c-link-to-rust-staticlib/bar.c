@@ -0,0 +1,26 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#include <assert.h>
+#include <stdint.h>
+#include <stddef.h>
+
+extern int32_t foo_triple(int32_t x);
+extern int32_t foo_is_utf8(const uint8_t *p, size_t len);
+
+int main() {
+    const uint8_t valid[] = { 'r', 'u', 's', 't' };
+    const uint8_t invalid[] = { 0xff, 0xfe };
+
+    assert(foo_triple(14) == 42);
+    assert(foo_is_utf8(valid, sizeof(valid)) == 1);
+    assert(foo_is_utf8(invalid, sizeof(invalid)) == 0);
+    return 0;
+}
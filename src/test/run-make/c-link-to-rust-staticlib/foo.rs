@@ -0,0 +1,34 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[crate_type = "staticlib"];
+#[link(name = "foo", vers = "0.1")];
+
+extern mod fizz;
+
+use std::libc::size_t;
+use std::str;
+use std::vec;
+
+// Bundled into the archive along with this crate
+#[no_mangle]
+pub extern "C" fn foo_triple(x: i32) -> i32 {
+    fizz::triple(x)
+}
+
+// Needs libstd, which isn't bundled
+#[no_mangle]
+pub extern "C" fn foo_is_utf8(p: *u8, len: size_t) -> i32 {
+    unsafe {
+        vec::raw::buf_as_slice(p, len as uint, |v| {
+            if str::is_utf8(v) {1} else {0}
+        })
+    }
+}
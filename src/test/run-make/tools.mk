@@ -0,0 +1,21 @@
+# Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# Shared definitions for the run-make tests. Everything a test builds goes in
+# $(TMPDIR), which is created fresh for each test.
+
+export LD_LIBRARY_PATH:=$(TMPDIR):$(LD_LIBRARY_PATH)
+export DYLD_LIBRARY_PATH:=$(TMPDIR):$(DYLD_LIBRARY_PATH)
+
+RUSTC := $(RUSTC) --out-dir $(TMPDIR) -L $(TMPDIR)
+CC := $(CC) -L $(TMPDIR)
+
+# The path of a program built into $(TMPDIR)
+RUN = $(TMPDIR)/$(1)
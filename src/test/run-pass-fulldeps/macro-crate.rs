@@ -0,0 +1,33 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:macro_crate_test.rs
+// xfail-stage1
+// xfail-fast
+
+// Uses a function-like macro and a #[deriving] extension which are loaded
+// from another crate's #[macro_registrar].
+
+#[feature(phase)];
+
+#[phase(syntax)]
+extern mod macro_crate_test;
+
+trait Foo {
+    fn foo(&self) -> int;
+}
+
+#[deriving(Foo)]
+struct Bar;
+
+pub fn main() {
+    assert_eq!(make_a_1!(), 1);
+    assert_eq!(Bar.foo(), 42);
+}
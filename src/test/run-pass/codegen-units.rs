@@ -0,0 +1,61 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --codegen-units 3
+// xfail-fast
+
+// Private items, monomorphizations and statics that end up in different
+// codegen units must still be able to refer to each other.
+
+static mut COUNTER: uint = 0;
+static GREETING: &'static str = "hello";
+
+trait Shape {
+    fn area(&self) -> uint;
+}
+
+struct Square(uint);
+
+impl Shape for Square {
+    fn area(&self) -> uint { let Square(side) = *self; side * side }
+}
+
+fn bump() -> uint {
+    unsafe {
+        COUNTER += 1;
+        COUNTER
+    }
+}
+
+fn twice<T: Clone>(x: T) -> (T, T) {
+    (x.clone(), x)
+}
+
+#[inline]
+fn add(a: uint, b: uint) -> uint { a + b }
+
+// Defined by one unit, and available for inlining in the others
+#[inline]
+pub fn double(a: uint) -> uint { add(a, a) }
+
+fn total_area(shapes: &[~Shape]) -> uint {
+    shapes.iter().fold(0, |acc, s| add(acc, s.area()))
+}
+
+fn main() {
+    assert_eq!(bump(), 1);
+    assert_eq!(bump(), 2);
+    assert_eq!(twice(3u), (3u, 3u));
+    assert_eq!(twice(~"a"), (~"a", ~"a"));
+    assert_eq!(GREETING.len(), 5);
+    let shapes = ~[~Square(2) as ~Shape, ~Square(3) as ~Shape];
+    assert_eq!(total_area(shapes), 13);
+    assert_eq!(double(total_area(shapes)), 26);
+}
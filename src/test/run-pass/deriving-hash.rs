@@ -0,0 +1,52 @@
+// xfail-fast
+
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+
+use std::hash::hash;
+use std::hashmap::HashMap;
+
+#[deriving(Eq, Clone, Hash)]
+struct Person {
+    id: uint,
+    name: ~str,
+    phone: uint,
+}
+
+#[deriving(Eq, Hash)]
+enum Shape<T> {
+    Circle(T),
+    Square(T),
+    Rect(T, T),
+    Empty,
+}
+
+#[deriving(Eq, Hash)]
+struct Unit;
+
+pub fn main() {
+    let person1 = Person { id: 5, name: ~"Janet", phone: 5556667 };
+    let person2 = Person { id: 5, name: ~"Bob", phone: 5556667 };
+    assert_eq!(hash(&person1), hash(&person1.clone()));
+    assert!(hash(&person1) != hash(&person2));
+
+    // Variants with identical fields must not collide.
+    assert!(hash(&Circle(1)) != hash(&Square(1)));
+    assert!(hash(&Rect(1, 2)) != hash(&Rect(2, 1)));
+    let empty: Shape<int> = Empty;
+    assert_eq!(hash(&empty), hash(&empty));
+    assert_eq!(hash(&Unit), hash(&Unit));
+
+    let mut m = HashMap::new();
+    m.insert(Circle(1u), "circle");
+    m.insert(Square(1u), "square");
+    assert_eq!(m.find(&Circle(1u)), Some(&"circle"));
+    assert_eq!(m.find(&Square(1u)), Some(&"square"));
+    assert_eq!(m.find(&Empty), None);
+}
+
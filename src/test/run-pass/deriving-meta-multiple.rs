@@ -10,9 +10,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use std::hash::hash;
+
 #[deriving(Eq)]
 #[deriving(Clone)]
-#[deriving(IterBytes)]
+#[deriving(Hash)]
 struct Foo {
     bar: uint,
     baz: int
@@ -23,5 +25,5 @@ pub fn main() {
 
     a == a;    // check for Eq impl w/o testing its correctness
     a.clone(); // check for Clone impl w/o testing its correctness
-    a.hash();  // check for IterBytes impl w/o testing its correctness
+    hash(&a);  // check for Hash impl w/o testing its correctness
 }
@@ -0,0 +1,63 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[feature(struct_variant, macro_rules)];
+
+use std::fmt;
+
+#[deriving(Show)]
+enum A {}
+#[deriving(Show)]
+enum B { B1, B2, B3 }
+#[deriving(Show)]
+enum C { C1(int), C2(B), C3(~str) }
+#[deriving(Show)]
+enum D { D1{ a: int } }
+#[deriving(Show)]
+struct E;
+#[deriving(Show)]
+struct F(int);
+#[deriving(Show)]
+struct G(int, int);
+#[deriving(Show)]
+struct H { a: int }
+#[deriving(Show)]
+struct I { a: int, b: int }
+#[deriving(Show)]
+struct J(Custom);
+#[deriving(Show)]
+struct K<T> { t: T }
+
+struct Custom;
+impl fmt::Default for Custom {
+    fn fmt(_: &Custom, f: &mut fmt::Formatter) { f.pad("yay") }
+}
+
+macro_rules! t(($x:expr, $expected:expr) => (assert_eq!(format!("{}", $x), $expected.to_owned())))
+
+pub fn main() {
+    t!(B1, "B1");
+    t!(B2, "B2");
+    t!(C1(3), "C1(3)");
+    t!(C2(B2), "C2(B2)");
+    t!(C3(~"foo"), "C3(foo)");
+    t!(D1{ a: 2 }, "D1 { a: 2 }");
+    t!(E, "E");
+    t!(F(3), "F(3)");
+    t!(G(3, 4), "G(3, 4)");
+    t!(I{ a: 2, b: 4 }, "I { a: 2, b: 4 }");
+    t!(J(Custom), "J(yay)");
+    t!(K { t: H { a: 1 } }, "K { t: H { a: 1 } }");
+
+    // Formatting parameters apply to each field.
+    assert_eq!(format!("{:3}", G(1, 2)), ~"G(  1,   2)");
+    assert_eq!(format!("{:.2}", K { t: 1.5f64 }), ~"K { t: 1.50 }");
+    assert_eq!(format!("{:>5}", J(Custom)), ~"J(  yay)");
+}
@@ -0,0 +1,59 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-fast
+
+use std::task;
+use std::task::FailureInfo;
+
+static mut FAILURES: uint = 0;
+static mut TASK_HOOK_RAN: bool = false;
+static mut LINE: uint = 0;
+
+fn hook(info: &FailureInfo) {
+    unsafe {
+        // The task's own hook runs first
+        assert!(TASK_HOOK_RAN);
+        FAILURES += 1;
+        LINE = info.line;
+    }
+    assert_eq!(info.cause.as_ref::<&'static str>().map(|s| *s), Some("boom"));
+    assert_eq!(info.task_name, Some("child"));
+    assert!(info.file.ends_with("failure-hook.rs"));
+}
+
+fn fail_in_child() -> Result<(), ~Any> {
+    let mut builder = task::task();
+    builder.name("child");
+    builder.on_failure(proc(_info: &FailureInfo) {
+        unsafe { TASK_HOOK_RAN = true; }
+    });
+    do builder.try { fail!("boom") }
+}
+
+fn main() {
+    task::set_failure_hook(Some(hook));
+
+    assert!(fail_in_child().is_err());
+    unsafe {
+        assert_eq!(FAILURES, 1);
+        assert!(LINE > 0);
+    }
+
+    // Succeeding tasks don't run the hooks
+    let result: Result<(), ~Any> = do task::try { };
+    assert!(result.is_ok());
+    unsafe { assert_eq!(FAILURES, 1); }
+
+    task::set_failure_hook(None);
+    unsafe { TASK_HOOK_RAN = false; }
+    assert!(fail_in_child().is_err());
+    unsafe { assert_eq!(FAILURES, 1); }
+}
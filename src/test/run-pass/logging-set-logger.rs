@@ -0,0 +1,84 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-fast
+
+use std::comm::SharedChan;
+use std::fmt;
+use std::logging;
+use std::logging::{Logger, LogRecord};
+
+struct Record {
+    tag: ~str,
+    level: u32,
+    module_path: ~str,
+    line: uint,
+    msg: ~str,
+}
+
+struct ChanLogger {
+    tag: ~str,
+    chan: SharedChan<Record>,
+}
+
+impl Logger for ChanLogger {
+    fn log(&mut self, record: &LogRecord) {
+        self.chan.send(Record {
+            tag: self.tag.clone(),
+            level: record.level,
+            module_path: record.module_path.to_owned(),
+            line: record.line,
+            msg: fmt::format(record.args),
+        });
+    }
+
+    fn inherit(&self) -> Option<~Logger> {
+        Some(~ChanLogger {
+            tag: self.tag + "-child",
+            chan: self.chan.clone(),
+        } as ~Logger)
+    }
+}
+
+mod inner {
+    pub fn log() -> uint {
+        error!("from inner"); line!()
+    }
+}
+
+fn main() {
+    let (port, chan) = stream();
+    let chan = SharedChan::new(chan);
+    let logger = ~ChanLogger { tag: ~"main", chan: chan.clone() };
+    assert!(logging::set_logger(logger as ~Logger).is_none());
+
+    let line = inner::log();
+    let record = port.recv();
+    assert_eq!(record.tag, ~"main");
+    assert_eq!(record.level, 1);
+    assert_eq!(record.module_path, ~"inner");
+    assert_eq!(record.line, line);
+    assert_eq!(record.msg, ~"from inner");
+
+    do spawn {
+        error!("from child {}", 1);
+    }
+    let record = port.recv();
+    assert_eq!(record.tag, ~"main-child");
+    assert_eq!(record.msg, ~"from child 1");
+
+    // Only error! is on by default
+    info!("not logged");
+    logging::set_log_spec("info");
+    info!("logged");
+    let record = port.recv();
+    assert_eq!(record.level, 3);
+    assert_eq!(record.msg, ~"logged");
+}
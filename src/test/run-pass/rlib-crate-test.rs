@@ -0,0 +1,29 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// xfail-fast
+// aux-build:rlib_crate_test.rs
+
+// Links against a crate which is only available as an rlib, which means that
+// its metadata is read out of the archive.
+
+extern mod rlib_crate_test;
+
+use rlib_crate_test::{ANSWER, Counter, triple, pair};
+
+pub fn main() {
+    assert_eq!(ANSWER, 42);
+    assert_eq!(triple(ANSWER), 126);
+    assert_eq!(pair(~"rlib"), (~"rlib", ~"rlib"));
+
+    let mut c = Counter::new();
+    c.bump();
+    assert_eq!(c.bump(), 2);
+}
@@ -26,6 +26,7 @@
 use std::io;
 use std::io::process;
 use std::io::process::{Process, ProcessConfig, CreatePipe, Ignored};
+use std::libc;
 use std::str;
 
 #[test]
@@ -39,6 +40,9 @@ fn smoke() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     let p = Process::new(args);
     assert!(p.is_some());
@@ -57,6 +61,9 @@ fn smoke_failure() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     match io::result(|| Process::new(args)) {
         Ok(*) => fail!(),
@@ -75,6 +82,9 @@ fn exit_reported_right() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     let p = Process::new(args);
     assert!(p.is_some());
@@ -92,6 +102,9 @@ fn signal_reported_right() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     let p = Process::new(args);
     assert!(p.is_some());
@@ -136,6 +149,9 @@ fn stdout_works() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     assert_eq!(run_output(args), ~"foobar\n");
 }
@@ -152,6 +168,9 @@ fn set_cwd_works() {
         env: None,
         cwd: cwd,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     assert_eq!(run_output(args), ~"/\n");
 }
@@ -168,6 +187,9 @@ fn stdin_works() {
         env: None,
         cwd: None,
         io: io,
+        uid: None,
+        gid: None,
+        detach: false,
     };
     let mut p = Process::new(args).expect("didn't create a proces?!");
     p.io[0].get_mut_ref().write("foobar".as_bytes());
@@ -176,3 +198,113 @@ fn stdin_works() {
     assert!(p.wait().success());
     assert_eq!(out, ~"foobar\n");
 }
+
+#[test]
+// FIXME(#10380)
+#[cfg(unix, not(target_os="android"))]
+fn try_wait_works() {
+    let io = ~[];
+    let args = ProcessConfig {
+        program: "/bin/sh",
+        args: [~"-c", ~"sleep 1"],
+        env: None,
+        cwd: None,
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    };
+    let mut p = Process::new(args).expect("didn't create a proces?!");
+    assert!(p.try_wait().is_none());
+    assert!(p.wait().success());
+    assert!(p.try_wait().expect("process has exited").success());
+}
+
+#[test]
+// FIXME(#10380)
+#[cfg(unix, not(target_os="android"))]
+fn extra_fds_work() {
+    let io = ~[Ignored, Ignored, Ignored, CreatePipe(false, true)];
+    let args = ProcessConfig {
+        program: "/bin/sh",
+        args: [~"-c", ~"echo foobar >&3"],
+        env: None,
+        cwd: None,
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    };
+    let mut p = Process::new(args).expect("didn't create a proces?!");
+    assert!(p.io[1].is_none());
+    let out = read_all(p.io[3].get_mut_ref() as &mut Reader);
+    assert!(p.wait().success());
+    assert_eq!(out, ~"foobar\n");
+}
+
+#[test]
+// FIXME(#10380)
+#[cfg(unix, not(target_os="android"))]
+fn uid_gid_and_detach_work() {
+    let io = ~[Ignored, CreatePipe(false, true)];
+    let args = ProcessConfig {
+        program: "/bin/sh",
+        args: [~"-c", ~"echo foobar"],
+        env: None,
+        cwd: None,
+        io: io,
+        uid: Some(unsafe { libc::getuid() } as uint),
+        gid: Some(unsafe { libc::getgid() } as uint),
+        detach: true,
+    };
+    assert_eq!(run_output(args), ~"foobar\n");
+}
+
+#[test]
+// FIXME(#10380)
+#[cfg(unix, not(target_os="android"))]
+fn wait_with_output_works() {
+    let io = ~[CreatePipe(true, false),
+               CreatePipe(false, true),
+               CreatePipe(false, true)];
+    let args = ProcessConfig {
+        program: "/bin/sh",
+        args: [~"-c", ~"echo foo; echo bar >&2; exit 3"],
+        env: None,
+        cwd: None,
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    };
+    let mut p = Process::new(args).expect("didn't create a proces?!");
+    let out = p.wait_with_output(Some(60000));
+    assert!(out.status.matches_exit_status(3));
+    assert_eq!(out.output, "foo\n".as_bytes().to_owned());
+    assert_eq!(out.error, "bar\n".as_bytes().to_owned());
+    assert!(!out.timed_out);
+}
+
+#[test]
+// FIXME(#10380)
+#[cfg(unix, not(target_os="android"))]
+fn wait_with_output_times_out() {
+    let io = ~[Ignored,
+               CreatePipe(false, true),
+               CreatePipe(false, true)];
+    let args = ProcessConfig {
+        program: "/bin/sh",
+        args: [~"-c", ~"echo foo; exec sleep 1000"],
+        env: None,
+        cwd: None,
+        io: io,
+        uid: None,
+        gid: None,
+        detach: false,
+    };
+    let mut p = Process::new(args).expect("didn't create a proces?!");
+    let out = p.wait_with_output(Some(100));
+    assert!(out.timed_out);
+    assert!(!out.status.success());
+    assert_eq!(out.output, "foo\n".as_bytes().to_owned());
+}
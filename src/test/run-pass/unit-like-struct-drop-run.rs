@@ -11,6 +11,7 @@
 // Make sure the destructor is run for unit-like structs.
 
 use std::task;
+use std::task::FailurePayload;
 
 struct Foo;
 
@@ -25,6 +26,6 @@ pub fn main() {
         let _b = Foo;
     };
 
-    let s = x.unwrap_err().move::<&'static str>().unwrap();
-    assert_eq!(s.as_slice(), "This failure should happen.");
+    let payload = x.unwrap_err().move::<FailurePayload>().unwrap();
+    assert_eq!(payload.msg_str(), Some("This failure should happen."));
 }